@@ -274,6 +274,7 @@ fn generate_lib_rs(config: &ChipTypesConfig) -> String {
     code.push_str("//!\n");
     code.push_str("//! - **Zero runtime cost**: All data is const, compiled into your binary\n");
     code.push_str("//! - **no_std and no allocations**: Perfect for embedded systems and WASM\n");
+    code.push_str("//!   (other than [`ChipType::candidates_for_size()`], which uses alloc)\n");
     code.push_str("//! - **Type safe**: Enum-based API prevents invalid Chip type references\n");
     code.push_str("//! - **Validated**: Build fails if JSON config is invalid\n");
     code.push_str("//!\n");
@@ -579,6 +580,10 @@ fn generate_chip_type_impl(config: &ChipTypesConfig) -> String {
     code.push_str(&generate_chip_pins_method(config));
     code.push_str("\n\n");
 
+    // Generate pinout_name
+    code.push_str(&generate_pinout_name_method(config));
+    code.push_str("\n\n");
+
     // Generate size_bytes
     code.push_str(&generate_size_bytes_method(config));
     code.push_str("\n\n");
@@ -587,6 +592,10 @@ fn generate_chip_type_impl(config: &ChipTypesConfig) -> String {
     code.push_str(&generate_num_addr_lines_method(config));
     code.push_str("\n\n");
 
+    // Generate num_data_lines
+    code.push_str(&generate_num_data_lines_method(config));
+    code.push_str("\n\n");
+
     // Generate address_pins
     code.push_str(&generate_address_pins_method(config));
     code.push_str("\n\n");
@@ -607,6 +616,10 @@ fn generate_chip_type_impl(config: &ChipTypesConfig) -> String {
     code.push_str(&generate_power_pins_method(config));
     code.push_str("\n\n");
 
+    // Generate size lookups
+    code.push_str(generate_size_lookup_methods());
+    code.push_str("\n\n");
+
     code.push_str("}\n");
 
     // Display impl
@@ -887,6 +900,36 @@ fn generate_chip_pins_method(config: &ChipTypesConfig) -> String {
     code
 }
 
+fn generate_pinout_name_method(config: &ChipTypesConfig) -> String {
+    let mut code = String::new();
+
+    code.push_str("    /// Get the Chip type name with its package's pin count, for display\n");
+    code.push_str("    ///\n");
+    code.push_str("    /// # Examples\n");
+    code.push_str("    ///\n");
+    code.push_str("    /// ```\n");
+    code.push_str("    /// use onerom_config::chip::ChipType;\n");
+    code.push_str("    ///\n");
+    code.push_str("    /// assert_eq!(ChipType::Chip2364.pinout_name(), \"2364 (24-pin)\");\n");
+    code.push_str("    /// assert_eq!(ChipType::Chip27128.pinout_name(), \"27128 (28-pin)\");\n");
+    code.push_str("    /// ```\n");
+    code.push_str("    pub const fn pinout_name(&self) -> &'static str {\n");
+    code.push_str("        match self {\n");
+
+    for (type_name, _chip_type) in get_sorted_chip_types(config) {
+        if let Some(chip_type) = config.chip_types.get(type_name) {
+            code.push_str(&format!(
+                "            ChipType::Chip{} => \"{} ({}-pin)\",\n",
+                type_name, type_name, chip_type.pins
+            ));
+        }
+    }
+
+    code.push_str("        }\n");
+    code.push_str("    }\n");
+    code
+}
+
 fn generate_size_bytes_method(config: &ChipTypesConfig) -> String {
     let mut code = String::new();
 
@@ -947,6 +990,38 @@ fn generate_num_addr_lines_method(config: &ChipTypesConfig) -> String {
     code
 }
 
+fn generate_num_data_lines_method(config: &ChipTypesConfig) -> String {
+    let mut code = String::new();
+
+    code.push_str("    /// Get number of data lines - 16 for Chip types with a word mode,\n");
+    code.push_str("    /// otherwise 8\n");
+    code.push_str("    ///\n");
+    code.push_str("    /// # Examples\n");
+    code.push_str("    ///\n");
+    code.push_str("    /// ```\n");
+    code.push_str("    /// use onerom_config::chip::ChipType;\n");
+    code.push_str("    ///\n");
+    code.push_str("    /// assert_eq!(ChipType::Chip2364.num_data_lines(), 8);\n");
+    code.push_str("    /// assert_eq!(ChipType::Chip27C400.num_data_lines(), 16);\n");
+    code.push_str("    /// ```\n");
+    code.push_str("    pub const fn num_data_lines(&self) -> u8 {\n");
+    code.push_str("        match self {\n");
+
+    for (type_name, _chip_type) in get_sorted_chip_types(config) {
+        if let Some(chip_type) = config.chip_types.get(type_name) {
+            code.push_str(&format!(
+                "            ChipType::Chip{} => {},\n",
+                type_name,
+                chip_type.data.len()
+            ));
+        }
+    }
+
+    code.push_str("        }\n");
+    code.push_str("    }\n");
+    code
+}
+
 fn generate_address_pins_method(config: &ChipTypesConfig) -> String {
     let mut code = String::new();
 
@@ -1172,6 +1247,31 @@ fn generate_programming_pins_method(config: &ChipTypesConfig) -> String {
     code
 }
 
+fn generate_size_lookup_methods() -> &'static str {
+    r#"    /// Get all Chip types of the given size in bytes, in [`CHIP_TYPES`]
+    /// order.  Several Chip types may share a size - for example the 2364
+    /// and 2764 - so this can be used to suggest types for a file.
+    pub fn candidates_for_size(size: usize) -> alloc::vec::Vec<ChipType> {
+        CHIP_TYPES
+            .iter()
+            .copied()
+            .filter(|chip| chip.size_bytes() == size)
+            .collect()
+    }
+
+    /// Get the Chip type of the given size in bytes, only if it is the only
+    /// Chip type of that size.  See [`Self::candidates_for_size()`].
+    pub fn from_size_bytes(size: usize) -> Option<ChipType> {
+        let mut candidates = CHIP_TYPES
+            .iter()
+            .filter(|chip| chip.size_bytes() == size);
+        match (candidates.next(), candidates.next()) {
+            (Some(chip), None) => Some(*chip),
+            _ => None,
+        }
+    }"#
+}
+
 fn generate_power_pins_method(config: &ChipTypesConfig) -> String {
     let mut code = String::new();
 
@@ -444,6 +444,9 @@ fn generate_hw_config_impl(configs: &[HwConfigData]) -> String {
     code.push_str(&generate_data_pins_method(configs));
     code.push_str("\n\n");
 
+    code.push_str(&generate_data_inverted_pins_method(configs));
+    code.push_str("\n\n");
+
     code.push_str(&generate_addr_pins_method(configs));
     code.push_str("\n\n");
 
@@ -718,6 +721,35 @@ fn generate_data_pins_method(configs: &[HwConfigData]) -> String {
     code
 }
 
+fn generate_data_inverted_pins_method(configs: &[HwConfigData]) -> String {
+    let mut code = String::new();
+
+    code.push_str("    /// Get the data line pins whose signal is inverted between the MCU and\n");
+    code.push_str("    /// the Chip socket.  Empty for most boards.\n");
+    code.push_str("    pub const fn data_inverted_pins(&self) -> &'static [u8] {\n");
+    code.push_str("        match self {\n");
+
+    for config in configs {
+        let pins_str = config
+            .config
+            .mcu
+            .pins
+            .data_inverted
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        code.push_str(&format!(
+            "            Board::{} => &[{}],\n",
+            config.variant_name, pins_str
+        ));
+    }
+
+    code.push_str("        }\n");
+    code.push_str("    }");
+    code
+}
+
 fn generate_addr_pins_method(configs: &[HwConfigData]) -> String {
     let mut code = String::new();
 
@@ -1033,6 +1065,13 @@ fn generate_chip_pin_methods(configs: &[HwConfigData]) -> String {
     code.push_str("            2 => self.bit_x2(),\n");
     code.push_str("            _ => 255,\n");
     code.push_str("        }\n");
+    code.push_str("    }\n\n");
+
+    code.push_str("    /// Get the maximum number of Chips in a multi Chip set - one selected by\n");
+    code.push_str("    /// CS1, plus one for each of X1 and X2 the board has.  Chips beyond this\n");
+    code.push_str("    /// have no line in [`Self::cs_bit_for_chip_in_set()`] to select them.\n");
+    code.push_str("    pub const fn max_chips_in_multi_set(&self) -> u8 {\n");
+    code.push_str("        1 + (self.pin_x1() != 255) as u8 + (self.pin_x2() != 255) as u8\n");
     code.push_str("    }");
 
     code
@@ -1064,18 +1103,45 @@ fn generate_jumper_methods(configs: &[HwConfigData]) -> String {
     code.push_str("        }\n");
     code.push_str("    }\n\n");
 
-    code.push_str("    /// Get X jumper pull direction (0=down, 1=up)\n");
-    code.push_str("    pub const fn x_jumper_pull(&self) -> u8 {\n");
+    code.push_str("    /// Get X1 jumper pull direction (0=down, 1=up)\n");
+    code.push_str("    pub const fn x1_jumper_pull(&self) -> u8 {\n");
+    code.push_str("        match self {\n");
+
+    for config in configs {
+        let pins = &config.config.mcu.pins;
+        code.push_str(&format!(
+            "            Board::{} => {},\n",
+            config.variant_name,
+            pins.x1_jumper_pull.unwrap_or(pins.x_jumper_pull)
+        ));
+    }
+
+    code.push_str("        }\n");
+    code.push_str("    }\n\n");
+
+    code.push_str("    /// Get X2 jumper pull direction (0=down, 1=up)\n");
+    code.push_str("    pub const fn x2_jumper_pull(&self) -> u8 {\n");
     code.push_str("        match self {\n");
 
     for config in configs {
+        let pins = &config.config.mcu.pins;
         code.push_str(&format!(
             "            Board::{} => {},\n",
-            config.variant_name, config.config.mcu.pins.x_jumper_pull
+            config.variant_name,
+            pins.x2_jumper_pull.unwrap_or(pins.x_jumper_pull)
         ));
     }
 
     code.push_str("        }\n");
+    code.push_str("    }\n\n");
+
+    code.push_str("    /// Get combined X jumper pull direction (0=down, 1=up)\n");
+    code.push_str("    ///\n");
+    code.push_str("    /// Only 1 if both X1 and X2 pull up.  Use [`Self::x1_jumper_pull()`] and\n");
+    code.push_str("    /// [`Self::x2_jumper_pull()`], which may pull in opposite directions.\n");
+    code.push_str("    #[deprecated(note = \"use x1_jumper_pull() and x2_jumper_pull()\")]\n");
+    code.push_str("    pub const fn x_jumper_pull(&self) -> u8 {\n");
+    code.push_str("        self.x1_jumper_pull() & self.x2_jumper_pull()\n");
     code.push_str("    }");
 
     code
@@ -1251,6 +1317,24 @@ fn generate_supports_chip_type_method(_configs: &[HwConfigData]) -> String {
     code.push_str("        let board_pins = self.chip_pins();\n");
     code.push_str("        let chip_pins = chip_type.chip_pins();\n");
     code.push_str("        chip_pins == board_pins\n");
+    code.push_str("    }\n\n");
+
+    code.push_str("    /// Get the Chip types this board can host, in\n");
+    code.push_str("    /// [`CHIP_TYPES`](crate::chip::CHIP_TYPES) order - those which fit the\n");
+    code.push_str("    /// board's socket (see [`Self::supports_chip_type()`]), in one of the\n");
+    code.push_str("    /// board's bit modes.\n");
+    code.push_str("    pub fn supported_chip_types(&self) -> alloc::vec::Vec<ChipType> {\n");
+    code.push_str("        crate::chip::CHIP_TYPES\n");
+    code.push_str("            .iter()\n");
+    code.push_str("            .copied()\n");
+    code.push_str("            .filter(|chip| {\n");
+    code.push_str("                self.supports_chip_type(*chip)\n");
+    code.push_str("                    && self\n");
+    code.push_str("                        .bit_modes()\n");
+    code.push_str("                        .iter()\n");
+    code.push_str("                        .any(|mode| chip.supports_bit_mode(*mode as u8))\n");
+    code.push_str("            })\n");
+    code.push_str("            .collect()\n");
     code.push_str("    }");
 
     code
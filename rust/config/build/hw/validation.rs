@@ -200,6 +200,10 @@ impl Chip {
 #[derive(Debug, Deserialize, Clone)]
 pub struct McuPins {
     pub data: Vec<u8>,
+    /// Data pins whose signal is inverted between the MCU and the Chip
+    /// socket, for example by an inverting buffer.  Must be data pins.
+    #[serde(default)]
+    pub data_inverted: Vec<u8>,
     pub addr: Vec<u8>,
     #[serde(default, deserialize_with = "deserialize_chip_map")]
     pub cs1: HashMap<String, u8>,
@@ -214,6 +218,12 @@ pub struct McuPins {
     #[serde(default, deserialize_with = "deserialize_chip_map")]
     pub oe: HashMap<String, u8>,
     pub x_jumper_pull: u8,
+    /// X1 jumper pull direction, if different to `x_jumper_pull`
+    #[serde(default)]
+    pub x1_jumper_pull: Option<u8>,
+    /// X2 jumper pull direction, if different to `x_jumper_pull`
+    #[serde(default)]
+    pub x2_jumper_pull: Option<u8>,
     pub sel: Vec<u8>,
     pub sel_jumper_pull: Vec<u8>,
     /// If a sel pin is connected to SWCLK, specify it here
@@ -489,6 +499,32 @@ pub fn validate_config(name: &str, config: &HwConfigJson) {
         );
     }
 
+    // Validate X jumper pulls
+    for (pull, field) in [
+        (Some(config.mcu.pins.x_jumper_pull), "x_jumper_pull"),
+        (config.mcu.pins.x1_jumper_pull, "x1_jumper_pull"),
+        (config.mcu.pins.x2_jumper_pull, "x2_jumper_pull"),
+    ] {
+        if let Some(pull) = pull
+            && pull > 1
+        {
+            panic!(
+                "{}: {} must be 0 (pull down) or 1 (pull up), found {}",
+                name, field, pull
+            );
+        }
+    }
+
+    // Validate any inverted data pins are data pins, listed once
+    for (ii, pin) in config.mcu.pins.data_inverted.iter().enumerate() {
+        if !config.mcu.pins.data.contains(pin) {
+            panic!("{}: data_inverted pin {} is not a data pin", name, pin);
+        }
+        if config.mcu.pins.data_inverted[..ii].contains(pin) {
+            panic!("{}: data_inverted pin {} listed more than once", name, pin);
+        }
+    }
+
     // Validate sel_jumper_pull
     if config.mcu.pins.sel_jumper_pull.len() != config.mcu.pins.sel.len() {
         panic!(
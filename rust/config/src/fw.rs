@@ -4,7 +4,10 @@
 
 //! One ROM Firmware objects
 
+use core::ops::RangeInclusive;
+
 use crate::Error;
+use crate::chip::ChipType;
 use crate::hw::Board;
 use crate::mcu::Variant as McuVariant;
 
@@ -50,8 +53,9 @@ impl FirmwareVersion {
         self.build
     }
 
-    // Create from a string like "1.2.3" or "v1.2.3.4"
-    pub fn try_from_str(s: &str) -> Result<Self, Error> {
+    /// Parse a version string like "1.2.3" or "v1.2.3.4".  The build
+    /// version is 0 if not specified.
+    pub fn parse(s: &str) -> Result<Self, Error> {
         let s = s.strip_prefix('v').unwrap_or(s);
         let mut parts = s.split('.');
 
@@ -87,6 +91,33 @@ impl FirmwareVersion {
 
         Ok(Self::new(major, minor, patch, build))
     }
+
+    /// Parse a version string - see [`Self::parse()`]
+    #[deprecated(note = "use FirmwareVersion::parse")]
+    pub fn try_from_str(s: &str) -> Result<Self, Error> {
+        Self::parse(s)
+    }
+}
+
+impl core::str::FromStr for FirmwareVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Formats as "major.minor.patch", with ".build" appended if the build
+/// version is non-zero.  This is the form [`FirmwareVersion::parse()`]
+/// accepts.
+impl core::fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if self.build != 0 {
+            write!(f, ".{}", self.build)?;
+        }
+        Ok(())
+    }
 }
 
 /// Chip serving algorithm.  Only valid for firmwares serving via CPU.  By
@@ -122,6 +153,43 @@ impl ServeAlg {
         }
     }
 
+    /// Whether this algorithm can be used to serve the given chip type,
+    /// based on the number of chip select lines it has.  `TwoCsOneAddr`
+    /// requires at least two CS lines, and `AddrOnCs` at least one - so pure
+    /// CE/OE chips can only be served using the default algorithm.
+    pub fn is_valid_for(&self, chip_type: &ChipType) -> bool {
+        let cs_lines = chip_type
+            .control_lines()
+            .iter()
+            .filter(|line| matches!(line.name, "cs1" | "cs2" | "cs3"))
+            .count();
+        match self {
+            ServeAlg::Default | ServeAlg::AddrOnAnyCs => true,
+            ServeAlg::TwoCsOneAddr => cs_lines >= 2,
+            ServeAlg::AddrOnCs => cs_lines >= 1,
+        }
+    }
+
+    /// Maximum number of serving algorithm parameters - the size of the
+    /// firmware's serve config structure.
+    pub const MAX_PARAMS: usize = 64;
+
+    /// The number of serving algorithm parameters this algorithm accepts, or
+    /// None if it takes none.
+    ///
+    /// Parameters are used by the PIO serving firmware, which applies them
+    /// to any set that has them, whatever its serving algorithm.  Their
+    /// format is left to the firmware, so any number up to
+    /// [`Self::MAX_PARAMS`] is accepted for every algorithm.
+    pub fn expected_param_count(&self) -> Option<RangeInclusive<usize>> {
+        match self {
+            ServeAlg::Default
+            | ServeAlg::TwoCsOneAddr
+            | ServeAlg::AddrOnCs
+            | ServeAlg::AddrOnAnyCs => Some(1..=Self::MAX_PARAMS),
+        }
+    }
+
     pub fn c_value(&self) -> &str {
         match self {
             ServeAlg::TwoCsOneAddr => "SERVE_TWO_CS_ONE_ADDR",
@@ -152,6 +220,8 @@ pub struct FirmwareProperties {
     mcu_variant: McuVariant,
     serve_alg: ServeAlg,
     boot_logging: bool,
+    #[serde(default)]
+    reserve_metadata_slack: usize,
 }
 
 impl FirmwareProperties {
@@ -176,9 +246,17 @@ impl FirmwareProperties {
             mcu_variant,
             serve_alg,
             boot_logging,
+            reserve_metadata_slack: 0,
         })
     }
 
+    /// Reserve extra bytes at the end of the metadata, so later in-place
+    /// edits (for example relabeling ROMs) don't require relayout.  The
+    /// metadata, including the slack, must still fit within its flash region.
+    pub fn set_reserve_metadata_slack(&mut self, slack: usize) {
+        self.reserve_metadata_slack = slack;
+    }
+
     /// Get the firmware version
     pub const fn version(&self) -> FirmwareVersion {
         self.version
@@ -203,6 +281,11 @@ impl FirmwareProperties {
     pub const fn mcu_variant(&self) -> McuVariant {
         self.mcu_variant
     }
+
+    /// Get the number of bytes of slack to reserve at the end of the metadata
+    pub const fn reserve_metadata_slack(&self) -> usize {
+        self.reserve_metadata_slack
+    }
 }
 
 #[cfg(test)]
@@ -212,6 +295,39 @@ mod tests {
     use super::*;
     use alloc::vec;
 
+    #[test]
+    fn test_version_parse() {
+        assert_eq!(
+            FirmwareVersion::parse("0.6.0").ok(),
+            Some(FirmwareVersion::new(0, 6, 0, 0))
+        );
+        assert_eq!(
+            FirmwareVersion::parse("v0.5.1.3").ok(),
+            Some(FirmwareVersion::new(0, 5, 1, 3))
+        );
+        for s in ["1.x", "1.2", "1.2.3.4.5", "", "v", "1..3"] {
+            assert!(
+                matches!(
+                    FirmwareVersion::parse(s),
+                    Err(Error::InvalidFirmwareVersion)
+                ),
+                "{s:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_version_display() {
+        use alloc::string::ToString;
+
+        assert_eq!(FirmwareVersion::new(0, 6, 0, 0).to_string(), "0.6.0");
+        assert_eq!(FirmwareVersion::new(0, 5, 1, 3).to_string(), "0.5.1.3");
+        for s in ["0.6.0", "v0.5.1.3"] {
+            let version = FirmwareVersion::parse(s).unwrap();
+            assert_eq!(version.to_string().parse().ok(), Some(version));
+        }
+    }
+
     #[test]
     fn test_version_equality() {
         let v1 = FirmwareVersion::new(1, 2, 3, 4);
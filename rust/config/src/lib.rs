@@ -24,6 +24,8 @@
 
 #![no_std]
 
+extern crate alloc;
+
 pub mod fw;
 pub mod hw;
 //pub mod image;
@@ -76,6 +76,31 @@ fn test_chip_27512_specs() {
     assert_eq!(addr[15], 1); // A15 on pin 1
 }
 
+#[test]
+fn test_chip_pins_and_pinout_name() {
+    for (chip, pins, pinout_name) in [
+        (ChipType::Chip2316, 24, "2316 (24-pin)"),
+        (ChipType::Chip2716, 24, "2716 (24-pin)"),
+        (ChipType::Chip6116, 24, "6116 (24-pin)"),
+        (ChipType::Chip2764, 28, "2764 (28-pin)"),
+        (ChipType::Chip23128, 28, "23128 (28-pin)"),
+        (ChipType::Chip27512, 28, "27512 (28-pin)"),
+        (ChipType::Chip27C010, 32, "27C010 (32-pin)"),
+        (ChipType::Chip27C400, 40, "27C400 (40-pin)"),
+    ] {
+        assert_eq!(chip.chip_pins(), pins, "{chip}");
+        assert_eq!(chip.pinout_name(), pinout_name);
+    }
+
+    // Every pinout name is the name and pin count
+    for chip in onerom_config::chip::CHIP_TYPES {
+        assert_eq!(
+            chip.pinout_name(),
+            format!("{} ({}-pin)", chip.name(), chip.chip_pins())
+        );
+    }
+}
+
 #[test]
 fn test_try_from_str() {
     assert_eq!(ChipType::try_from_str("2364"), Some(ChipType::Chip2364));
@@ -95,3 +120,36 @@ fn test_all_chip_types_parse() {
         );
     }
 }
+
+#[test]
+fn test_candidates_for_size() {
+    let candidates = ChipType::candidates_for_size(8192);
+    assert!(candidates.contains(&ChipType::Chip2364));
+    assert!(candidates.contains(&ChipType::Chip2764));
+    assert!(candidates.iter().all(|chip| chip.size_bytes() == 8192));
+
+    assert!(ChipType::candidates_for_size(8191).is_empty());
+}
+
+#[test]
+fn test_from_size_bytes() {
+    // Ambiguous sizes have no single answer
+    assert_eq!(ChipType::from_size_bytes(8192), None);
+    assert_eq!(ChipType::from_size_bytes(4096), None);
+    assert_eq!(ChipType::from_size_bytes(8191), None);
+
+    // Unique sizes do
+    assert_eq!(ChipType::from_size_bytes(262144), Some(ChipType::Chip27C020));
+    assert_eq!(ChipType::from_size_bytes(1048576), Some(ChipType::Chip27C080));
+}
+
+#[test]
+fn test_num_data_lines() {
+    assert_eq!(ChipType::Chip2364.num_data_lines(), 8);
+    assert_eq!(ChipType::Chip231024.num_data_lines(), 8);
+    assert_eq!(ChipType::Chip27C080.num_data_lines(), 8);
+
+    let chip = ChipType::Chip27C400;
+    assert_eq!(chip.num_data_lines(), 16);
+    assert_eq!(chip.num_data_lines() as usize, chip.data_pins().len());
+}
@@ -0,0 +1,74 @@
+// config/tests/hw_generated_tests.rs
+
+use onerom_config::chip::{ChipType, CHIP_TYPES};
+use onerom_config::hw::{BOARDS, Board};
+
+#[test]
+fn test_supported_chip_types_24_pin() {
+    let board = Board::Ice24UsbH;
+    let supported = board.supported_chip_types();
+
+    for chip in [ChipType::Chip2316, ChipType::Chip2332, ChipType::Chip2364, ChipType::Chip2716] {
+        assert!(supported.contains(&chip), "{} should be supported", chip);
+    }
+    for chip in [
+        ChipType::Chip2764,
+        ChipType::Chip27256,
+        ChipType::Chip27C080,
+        ChipType::Chip27C400,
+    ] {
+        assert!(!supported.contains(&chip), "{} should not be supported", chip);
+    }
+    assert!(supported.iter().all(|chip| chip.chip_pins() == 24));
+}
+
+#[test]
+fn test_supported_chip_types_matches_supports_chip_type() {
+    for board in [Board::Ice24UsbH, Board::Ice28A, Board::Fire40A] {
+        let supported = board.supported_chip_types();
+        assert!(!supported.is_empty());
+        for chip in CHIP_TYPES {
+            if supported.contains(chip) {
+                assert!(board.supports_chip_type(*chip));
+            }
+        }
+    }
+
+    // 28 pin boards host 28 pin Chips only
+    assert!(Board::Ice28A.supported_chip_types().contains(&ChipType::Chip27256));
+    assert!(!Board::Ice28A.supported_chip_types().contains(&ChipType::Chip2364));
+
+    // 16-bit Chip types are hosted by boards with a 16-bit mode
+    assert!(Board::Fire40A.supported_chip_types().contains(&ChipType::Chip27C400));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_x_jumper_pulls() {
+    // Existing boards pull X1 and X2 the same way
+    for board in [Board::Ice24D, Board::Ice24UsbH, Board::Ice28A, Board::Fire24C] {
+        assert_eq!(board.x1_jumper_pull(), board.x2_jumper_pull());
+        assert_eq!(board.x_jumper_pull(), board.x1_jumper_pull());
+    }
+    assert_eq!(Board::Ice24D.x1_jumper_pull(), 1);
+    assert_eq!(Board::Ice24UsbH.x1_jumper_pull(), 0);
+}
+
+#[test]
+fn test_max_chips_in_multi_set() {
+    // CS1, X1 and X2
+    assert_eq!(Board::Ice24UsbH.max_chips_in_multi_set(), 3);
+    assert_ne!(Board::Ice24UsbH.cs_bit_for_chip_in_set(ChipType::Chip2364, 2), 255);
+
+    // CS1 only
+    assert_eq!(Board::Ice24D.max_chips_in_multi_set(), 1);
+    assert_eq!(Board::Ice24D.cs_bit_for_chip_in_set(ChipType::Chip2364, 1), 255);
+}
+
+#[test]
+fn test_data_inverted_pins() {
+    // No current board inverts any data lines
+    for board in BOARDS {
+        assert!(board.data_inverted_pins().is_empty(), "{}", board.name());
+    }
+}
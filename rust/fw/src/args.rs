@@ -102,6 +102,14 @@ pub struct Args {
     )]
     pub out: Option<String>,
 
+    /// Validate only, without writing the firmware
+    #[clap(
+        long,
+        long_help = "Parse and fully validate the ROM configuration, and print the planned flash layout,\nwithout writing the output firmware binary",
+        action
+    )]
+    pub dry_run: bool,
+
     /// Verbose output
     #[clap(short, long, action)]
     pub verbose: bool,
@@ -211,8 +219,7 @@ impl Args {
             } else {
                 let latest = releases.latest();
                 debug!("Firmware version not specified, using latest: {}", latest);
-                self.fw =
-                    Some(FirmwareVersion::try_from_str(latest).map_err(Error::firmware_version)?);
+                self.fw = Some(FirmwareVersion::parse(latest).map_err(Error::firmware_version)?);
             }
         }
 
@@ -229,7 +236,7 @@ fn mcu_value_parser(s: &str) -> Result<McuVariant, String> {
 }
 
 fn firmware_value_parser(s: &str) -> Result<FirmwareVersion, String> {
-    FirmwareVersion::try_from_str(s).map_err(|_| "Invalid firmware version".to_string())
+    FirmwareVersion::parse(s).map_err(|_| "Invalid firmware version".to_string())
 }
 
 fn board_values() -> String {
@@ -278,3 +285,26 @@ async fn load_firmware_metadata(path: &str) -> Result<(FirmwareVersion, Board, M
 
     Ok((version, board, mcu))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dry_run_flag() {
+        let args = Args::try_parse_from([
+            "onerom-fw",
+            "--board",
+            "ice-24-h",
+            "--mcu",
+            "f411re",
+            "--dry-run",
+        ])
+        .expect("Failed to parse args");
+        assert!(args.dry_run);
+
+        let args = Args::try_parse_from(["onerom-fw", "--board", "ice-24-h", "--mcu", "f411re"])
+            .expect("Failed to parse args");
+        assert!(!args.dry_run);
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright (C) 2026 Piers Finlayson <piers@piers.rocks>
+//
+// MIT License
+
+//! Batch firmware generation
+//!
+//! Builds firmware for every config in a directory, for the same firmware
+//! version, board and MCU - for example, when producing release images.
+
+#[allow(unused_imports)]
+use log::{debug, error, info, trace, warn};
+use std::path::{Path, PathBuf};
+
+use onerom_config::fw::FirmwareProperties;
+use onerom_gen::Builder;
+
+use crate::{Error, create_firmware, get_rom_files, read_rom_config, validate_sizes};
+
+/// Outcome of building a single config
+#[derive(Debug)]
+pub struct BatchResult {
+    /// Config file built
+    pub config: PathBuf,
+
+    /// Firmware file written, and its size in bytes, or the error
+    pub result: Result<(PathBuf, usize), Error>,
+}
+
+/// Outcomes of building every config in a directory, in filename order
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    pub results: Vec<BatchResult>,
+}
+
+impl BatchSummary {
+    /// Number of configs which built successfully
+    pub fn succeeded(&self) -> usize {
+        self.results.iter().filter(|r| r.result.is_ok()).count()
+    }
+
+    /// Number of configs which failed to build
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.succeeded()
+    }
+}
+
+impl std::fmt::Display for BatchSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for result in &self.results {
+            match &result.result {
+                Ok((out, size)) => writeln!(
+                    f,
+                    "OK:     {} -> {} ({} bytes)",
+                    result.config.display(),
+                    out.display(),
+                    size
+                )?,
+                Err(e) => writeln!(
+                    f,
+                    "FAILED: {}\n  {}",
+                    result.config.display(),
+                    e.to_string().replace('\n', "\n  ")
+                )?,
+            }
+        }
+        write!(
+            f,
+            "{} succeeded, {} failed",
+            self.succeeded(),
+            self.failed()
+        )
+    }
+}
+
+/// Returns the firmware filename for a config, as used by Studio when saving,
+/// in the form `onerom-<config>-<board>-<mcu>.bin`.
+pub fn firmware_filename(config: &Path, fw_props: &FirmwareProperties) -> String {
+    let name = config
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    format!(
+        "onerom-{}-{}-{}.bin",
+        name.split('.').next().unwrap_or("unknown"),
+        fw_props.board().name(),
+        fw_props.mcu_variant().to_string().to_ascii_lowercase()
+    )
+}
+
+/// Builds firmware for every `.json` config in `config_dir`, writing each to
+/// `out_dir` named using [`firmware_filename()`].
+///
+/// A failure building one config doesn't stop the others - check the
+/// returned summary.  Licenses can't be proposed to a user in a batch, so
+/// configs requiring them fail unless `accept_licenses` is set.
+///
+/// Errors only if the config directory can't be read.
+pub fn build_config_dir(
+    config_dir: &Path,
+    out_dir: &Path,
+    fw_props: &FirmwareProperties,
+    firmware_data: &[u8],
+    accept_licenses: bool,
+) -> Result<BatchSummary, Error> {
+    let mut configs = std::fs::read_dir(config_dir)
+        .map_err(Error::read)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    configs.sort();
+
+    let mut summary = BatchSummary::default();
+    for config in configs {
+        debug!("Building config: {}", config.display());
+        let out = out_dir.join(firmware_filename(&config, fw_props));
+        let result = build_config(&config, &out, fw_props, firmware_data, accept_licenses)
+            .map(|size| (out, size));
+        if let Err(e) = &result {
+            warn!("Failed to build {}: {e}", config.display());
+        }
+        summary.results.push(BatchResult { config, result });
+    }
+
+    Ok(summary)
+}
+
+// Builds and writes the firmware for a single config, returning its size
+fn build_config(
+    config: &Path,
+    out: &Path,
+    fw_props: &FirmwareProperties,
+    firmware_data: &[u8],
+    accept_licenses: bool,
+) -> Result<usize, Error> {
+    let json = read_rom_config(&config.to_string_lossy())?;
+    let mut builder = Builder::from_json(
+        fw_props.version(),
+        fw_props.mcu_variant().family(),
+        &json,
+    )
+    .map_err(Error::parse)?;
+
+    for license in builder.licenses() {
+        if !accept_licenses {
+            return Err(Error::license_not_accepted());
+        }
+        builder.accept_license(&license).map_err(Error::license)?;
+    }
+
+    get_rom_files(&mut builder)?;
+
+    let (metadata, image_data) = builder.build(*fw_props).map_err(Error::build)?;
+    let metadata = Some(metadata);
+    let image_data = Some(image_data);
+
+    validate_sizes(fw_props, firmware_data, &metadata, &image_data)?;
+    create_firmware(
+        &out.to_string_lossy(),
+        firmware_data.to_vec(),
+        metadata,
+        image_data,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use onerom_config::fw::{FirmwareVersion, ServeAlg};
+    use onerom_config::hw::Board;
+    use onerom_config::mcu::Variant as McuVariant;
+
+    fn config_json(description: &str, file: &str) -> String {
+        format!(
+            r#"{{
+                "version": 1,
+                "description": "{description}",
+                "chip_sets": [{{
+                    "type": "single",
+                    "chips": [{{
+                        "file": "{file}",
+                        "type": "2364",
+                        "cs1": "active_low",
+                        "size_handling": "duplicate"
+                    }}]
+                }}]
+            }}"#
+        )
+    }
+
+    #[test]
+    fn test_build_config_dir() {
+        let dir = std::env::temp_dir().join(format!("onerom-batch-{}", std::process::id()));
+        let config_dir = dir.join("configs");
+        let out_dir = dir.join("out");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::create_dir_all(&out_dir).unwrap();
+
+        std::fs::write(config_dir.join("first.json"), config_json("First", "hex:42")).unwrap();
+        std::fs::write(config_dir.join("second.json"), config_json("Second", "hex:24")).unwrap();
+        // Invalid hex, so fails to load the ROM file
+        std::fs::write(config_dir.join("third.json"), config_json("Third", "hex:4")).unwrap();
+        // Ignored, as not a config
+        std::fs::write(config_dir.join("notes.txt"), "not a config").unwrap();
+
+        let fw_props = FirmwareProperties::new(
+            FirmwareVersion::new(0, 5, 1, 0),
+            Board::Ice24UsbH,
+            McuVariant::F411RE,
+            ServeAlg::Default,
+            false,
+        )
+        .unwrap();
+        let firmware_data = vec![0u8; 1024];
+
+        let summary = build_config_dir(&config_dir, &out_dir, &fw_props, &firmware_data, false)
+            .expect("Batch build failed");
+
+        assert_eq!(summary.results.len(), 3);
+        assert_eq!(summary.succeeded(), 2);
+        assert_eq!(summary.failed(), 1);
+
+        for (result, name) in summary.results.iter().zip(["first", "second"]) {
+            let (out, size) = result.result.as_ref().expect("Config should build");
+            assert_eq!(
+                out.file_name().unwrap().to_string_lossy(),
+                format!("onerom-{name}-{}-f411re.bin", Board::Ice24UsbH.name())
+            );
+            assert_eq!(std::fs::metadata(out).unwrap().len() as usize, *size);
+        }
+        assert!(summary.results[2].result.is_err());
+
+        let report = summary.to_string();
+        assert!(report.contains("FAILED: "));
+        assert!(report.ends_with("2 succeeded, 1 failed"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
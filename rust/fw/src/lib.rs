@@ -3,6 +3,7 @@
 // MIT License
 
 pub mod args;
+pub mod batch;
 pub mod error;
 pub mod net;
 
@@ -15,7 +16,7 @@ use std::io::Write;
 
 use onerom_config::fw::FirmwareProperties;
 use onerom_gen::builder::{Builder, FileData};
-use onerom_gen::{FIRMWARE_SIZE, MAX_METADATA_LEN};
+use onerom_gen::{BuildReport, FIRMWARE_SIZE, MAX_METADATA_LEN};
 
 use net::{fetch_rom_file, fetch_rom_file_async};
 
@@ -80,6 +81,51 @@ pub fn validate_sizes(
     Ok(())
 }
 
+/// Describes the flash layout a firmware would be written with - the
+/// firmware, and any metadata and chip set images from the build report.
+pub fn flash_layout(
+    fw_props: &FirmwareProperties,
+    firmware_size: usize,
+    report: Option<&BuildReport>,
+) -> String {
+    let flash_base = fw_props.board().mcu_family().get_flash_base() as usize;
+    let mut layout = format!("- Firmware:   {:#010X} {} bytes", flash_base, firmware_size);
+
+    let Some(report) = report else {
+        return layout;
+    };
+
+    layout.push_str(&format!(
+        "\n- Metadata:   {:#010X} {} bytes",
+        flash_base + FIRMWARE_SIZE,
+        report.metadata_len
+    ));
+    layout.push_str(&format!(
+        "\n- ROM images: {:#010X} {} bytes",
+        flash_base + FIRMWARE_SIZE + MAX_METADATA_LEN,
+        report.rom_images_len
+    ));
+    for (index, set) in report.chip_sets.iter().enumerate() {
+        let location = match set.data_ptr {
+            Some(ptr) => format!("{:#010X}", ptr),
+            None => "no image".to_string(),
+        };
+        layout.push_str(&format!(
+            "\n  - Set {}: {} {} bytes, {:?}",
+            index, location, set.image_size, set.serve_alg
+        ));
+        if !set.firmware_overrides.is_empty() {
+            layout.push_str(&format!(
+                ", overrides: {}",
+                set.firmware_overrides.join(", ")
+            ));
+        }
+    }
+    layout.push_str(&format!("\n- Total:      {} bytes", report.firmware_size));
+
+    layout
+}
+
 pub fn create_firmware(
     out_path: &str,
     firmware_data: Vec<u8>,
@@ -13,7 +13,7 @@ use onerom_config::fw::{FirmwareProperties, ServeAlg};
 use onerom_fw::Error;
 use onerom_fw::args::Args;
 use onerom_fw::net::{Releases, fetch_license};
-use onerom_fw::{create_firmware, get_rom_files, read_rom_config, validate_sizes};
+use onerom_fw::{create_firmware, flash_layout, get_rom_files, read_rom_config, validate_sizes};
 use onerom_gen::{Builder, License};
 
 fn main() {
@@ -68,6 +68,7 @@ async fn sub_main() -> Result<(), Error> {
     let fw_props = FirmwareProperties::new(version, board, mcu, ServeAlg::default(), true).unwrap();
 
     // Load the config file
+    let mut report = None;
     let (metadata, image_data, desc) = if let Some(rom_config_filename) = rom_config_filename {
         debug!("Using ROM config file: {}", rom_config_filename);
 
@@ -90,11 +91,12 @@ async fn sub_main() -> Result<(), Error> {
         get_rom_files(&mut builder)?;
 
         // Generate metadata/ROM images
-        let (m, i) = builder.build(fw_props).map_err(Error::build)?;
+        let (m, i, r) = builder.build_with_report(fw_props).map_err(Error::build)?;
         if !i.is_empty() {
             // Cannot have ROM image data without metadata
             assert!(!m.is_empty());
         }
+        report = Some(r);
 
         (Some(m), Some(i), Some(builder.description()))
     } else {
@@ -105,6 +107,21 @@ async fn sub_main() -> Result<(), Error> {
     // Check everything fits
     validate_sizes(&fw_props, &firmware_data, &metadata, &image_data)?;
 
+    // Dry run stops short of writing the firmware
+    if args.dry_run {
+        println!("---");
+        println!(
+            "Validated One ROM firmware (dry run, {} not written):\n---\n{}",
+            out_filename,
+            flash_layout(&fw_props, firmware_data.len(), report.as_ref())
+        );
+        if let Some(desc) = desc {
+            println!("---\n{desc}");
+        }
+        println!("---");
+        return Ok(());
+    }
+
     // Create the firmware file
     let size = create_firmware(out_filename, firmware_data, metadata, image_data)?;
 
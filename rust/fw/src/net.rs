@@ -237,6 +237,14 @@ impl Releases {
     }
 
     pub async fn from_network_async_url(url: &str) -> Result<Self, Error> {
+        let body = Self::manifest_json_async_url(url).await?;
+        Self::from_json(&body)
+    }
+
+    /// Fetches the releases manifest's JSON without parsing it, for callers
+    /// which store it, such as to cache it.  Use [`Self::from_json()`] to
+    /// parse it.
+    pub async fn manifest_json_async_url(url: &str) -> Result<String, Error> {
         debug!("Fetching releases manifest from {}", url);
         let response = reqwest::get(url).await.map_err(Error::network)?;
         if !response.status().is_success() {
@@ -245,8 +253,7 @@ impl Releases {
             });
         }
 
-        let body = response.text().await.map_err(Error::network)?;
-        Self::from_json(&body)
+        response.text().await.map_err(Error::network)
     }
 
     pub fn from_json(data: &str) -> Result<Releases, Error> {
@@ -406,7 +413,7 @@ impl Release {
     }
 
     pub fn firmware_version(&self) -> Result<FirmwareVersion, Error> {
-        FirmwareVersion::try_from_str(&self.version).map_err(|_| {
+        FirmwareVersion::parse(&self.version).map_err(|_| {
             debug!("Failed to parse firmware version from {:?}", self.version);
             Error::release_not_found()
         })
@@ -14,12 +14,16 @@ use onerom_config::fw::{FirmwareProperties, FirmwareVersion, ServeAlg};
 use onerom_config::mcu::Family;
 use onerom_config::chip::{ChipFunction, ChipType};
 
-use crate::image::{CsConfig, CsLogic, Location, Chip, ChipSet, ChipSetType, SizeHandling};
-use crate::meta::Metadata;
+use crate::image::{CsConfig, CsLogic, Half, Location, Chip, ChipSet, ChipSetType, SizeHandling, Provenance, ResolveServeAlg, builtin_image};
+use crate::meta::{Metadata, PAD_METADATA_BYTE, ROM_IMAGE_DATA_START};
 use crate::{Error, FIRMWARE_SIZE, MAX_METADATA_LEN, MIN_FIRMWARE_OVERRIDES_VERSION, Result};
 
 pub const MAX_SUPPORTED_FIRMWARE_VERSION: FirmwareVersion = FirmwareVersion::new(0, 6, 999, 0);
 
+/// Prefix of a Chip `file` referencing a built-in synthetic ROM image,
+/// generated by the builder rather than loaded - see [`builtin_image()`].
+pub const BUILTIN_FILE_PREFIX: &str = "@builtin:";
+
 pub(crate) use crate::firmware::*;
 
 /// Main Builder object
@@ -108,10 +112,20 @@ pub(crate) use crate::firmware::*;
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct Builder {
     version: FirmwareVersion,
+    mcu_family: Family,
     config: Config,
+    // Files' data, by file id.  Keyed (and so iterated) by id, so builds
+    // don't depend on the order files were added.
     files: BTreeMap<usize, Vec<u8>>,
     licenses: BTreeMap<usize, License>,
     file_id_map: BTreeMap<usize, usize>,
+    // Locations of Chips' images within files added with a manifest, by
+    // Chip index
+    #[serde(default)]
+    manifest_locations: BTreeMap<usize, Location>,
+    // Note to stamp into the metadata, if any
+    #[serde(default)]
+    note: Option<String>,
 }
 
 impl Builder {
@@ -122,28 +136,98 @@ impl Builder {
     /// - `mcu_family`: MCU family this config is for
     /// - `json`: JSON string
     pub fn from_json(version: FirmwareVersion, mcu_family: Family, json: &str) -> Result<Self> {
+        Self::check_version(version)?;
+
+        let config: Config = serde_json::from_str(json).map_err(|e| Error::InvalidConfig {
+            error: e.to_string(),
+        })?;
+
+        Self::from_config(version, mcu_family, config)
+    }
+
+    /// Create from a JSON config layered over a shared base JSON config
+    ///
+    /// Allows many similar configs, such as those sharing board defaults but
+    /// using different ROM files, to share a base config.  The overlay only
+    /// needs to contain what differs - see [`Config::merge_json()`] for how
+    /// the two are combined.  The combined config is validated as if it had
+    /// been passed to [`Self::from_json()`].
+    ///
+    /// Arguments:
+    /// - `version`: Firmware version this config is for
+    /// - `mcu_family`: MCU family this config is for
+    /// - `base`: Base JSON string
+    /// - `overlay`: Overlay JSON string
+    pub fn from_json_with_base(
+        version: FirmwareVersion,
+        mcu_family: Family,
+        base: &str,
+        overlay: &str,
+    ) -> Result<Self> {
+        Self::check_version(version)?;
+
+        let config = Config::merge_json(base, overlay)?;
+
+        Self::from_config(version, mcu_family, config)
+    }
+
+    /// Create from TOML config
+    ///
+    /// The config has the same structure as the JSON config, and is validated
+    /// identically.  See [`Self::from_json()`].
+    #[cfg(feature = "toml")]
+    pub fn from_toml(version: FirmwareVersion, mcu_family: Family, toml: &str) -> Result<Self> {
+        Self::check_version(version)?;
+
+        let config: Config = toml::from_str(toml).map_err(|e| Error::InvalidConfig {
+            error: e.to_string(),
+        })?;
+
+        Self::from_config(version, mcu_family, config)
+    }
+
+    /// Create from YAML config
+    ///
+    /// The config has the same structure as the JSON config, and is validated
+    /// identically.  See [`Self::from_json()`].
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(version: FirmwareVersion, mcu_family: Family, yaml: &str) -> Result<Self> {
+        Self::check_version(version)?;
+
+        let config: Config = serde_yaml::from_str(yaml).map_err(|e| Error::InvalidConfig {
+            error: e.to_string(),
+        })?;
+
+        Self::from_config(version, mcu_family, config)
+    }
+
+    fn check_version(version: FirmwareVersion) -> Result<()> {
         if version > MAX_SUPPORTED_FIRMWARE_VERSION {
             return Err(Error::FirmwareTooNew {
                 version,
                 maximum: MAX_SUPPORTED_FIRMWARE_VERSION,
             });
         }
+        Ok(())
+    }
 
-        let config: Config = serde_json::from_str(json).map_err(|e| Error::InvalidConfig {
-            error: e.to_string(),
-        })?;
-
+    // Validates a deserialized config and creates the builder from it
+    fn from_config(version: FirmwareVersion, mcu_family: Family, config: Config) -> Result<Self> {
         Self::validate_config(&version, &mcu_family, &config)?;
 
         let mut builder = Self {
             version,
+            mcu_family,
             config,
             files: BTreeMap::new(),
             licenses: BTreeMap::new(),
             file_id_map: BTreeMap::new(),
+            manifest_locations: BTreeMap::new(),
+            note: None,
         };
 
         builder.build_file_id_map();
+        builder.add_builtin_files();
 
         Ok(builder)
     }
@@ -153,6 +237,65 @@ impl Builder {
         &self.config
     }
 
+    /// Get the config's description
+    pub fn description(&self) -> &str {
+        &self.config.description
+    }
+
+    /// Replaces the config's description.  The description is written to
+    /// the metadata when built, if it fits - see [`Metadata::description()`].
+    pub fn set_description(&mut self, description: String) {
+        self.config.description = description;
+    }
+
+    /// Get the note to be written to the metadata, if any
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    /// Sets a free-text note to write to the metadata when built, such as a
+    /// build date, git hash or asset tag for the device being provisioned.
+    /// Readable later by analysing the device.  An empty note clears any
+    /// existing note.
+    ///
+    /// Returns [`Error::NoteTooLong`] if the note is longer than
+    /// [`crate::MAX_NOTE_LEN`] bytes - see [`Metadata::set_note()`].
+    pub fn set_note(&mut self, note: String) -> Result<()> {
+        Metadata::check_note(&note)?;
+        self.note = Some(note).filter(|note| !note.is_empty());
+        Ok(())
+    }
+
+    /// Checks whether this config will build against the given firmware
+    /// version and MCU family, without needing the firmware itself - for
+    /// example, to warn when a firmware release is selected, before it is
+    /// downloaded.
+    ///
+    /// Returns [`Error::FirmwareTooNew`] if the version is newer than this
+    /// crate supports, [`Error::WrongMcuFamily`] if the family differs from
+    /// the one the builder was created for, and [`Error::FirmwareTooOld`] if
+    /// the config uses features the version doesn't support - see
+    /// [`Config::unsupported_features()`].
+    pub fn check_firmware_compat(&self, version: &FirmwareVersion, family: Family) -> Result<()> {
+        Self::check_version(*version)?;
+
+        if family != self.mcu_family {
+            return Err(Error::WrongMcuFamily {
+                actual: family,
+                required: self.mcu_family,
+            });
+        }
+
+        if let Some(feature) = self.config.unsupported_features(version).first() {
+            return Err(Error::FirmwareTooOld {
+                version: *version,
+                minimum: feature.min_firmware_version(),
+            });
+        }
+
+        Ok(())
+    }
+
     fn validate_config(
         version: &FirmwareVersion,
         _mcu_family: &Family,
@@ -191,16 +334,20 @@ impl Builder {
                     });
                 }
 
-                if set.chips.len() > 3 && set.set_type == ChipSetType::Multi {
+                if set.chips.len() > ChipSetType::Multi.max_chips()
+                    && set.set_type == ChipSetType::Multi
+                {
                     return Err(Error::TooManyChips {
-                        expected: 3,
+                        expected: ChipSetType::Multi.max_chips(),
                         actual: set.chips.len(),
                     });
                 }
 
-                if set.chips.len() > 4 && set.set_type == ChipSetType::Banked {
+                if set.chips.len() > ChipSetType::Banked.max_chips()
+                    && set.set_type == ChipSetType::Banked
+                {
                     return Err(Error::TooManyChips {
-                        expected: 4,
+                        expected: ChipSetType::Banked.max_chips(),
                         actual: set.chips.len(),
                     });
                 }
@@ -209,6 +356,22 @@ impl Builder {
             for chip in set.chips.iter() {
                 let chip0 = &set.chips[0];
 
+                // Check any window offset is within the Chip's window
+                #[allow(clippy::collapsible_if)]
+                if let Some(offset) = set.window_offset {
+                    if offset >= chip.chip_type.size_bytes() {
+                        return Err(Error::InvalidConfig {
+                            error: format!(
+                                "Window offset {:#X} not within Chip {} window ({} is {:#X} bytes)",
+                                offset,
+                                chip_num,
+                                chip.chip_type.name(),
+                                chip.chip_type.size_bytes()
+                            ),
+                        });
+                    }
+                }
+
                 // Check filename specified for ROMs
                 if chip.file.is_empty() && chip.chip_type.chip_function() != ChipFunction::Ram {
                     return Err(Error::InvalidConfig {
@@ -227,6 +390,32 @@ impl Builder {
                     });
                 }
 
+                // Check an explicit CE/OE mode is only used for Chip types
+                // with CE/OE lines, and instead of individual CS lines
+                if chip.cs == Some(CsMode::CeOe) {
+                    if chip.cs1.is_some() || chip.cs2.is_some() || chip.cs3.is_some() {
+                        return Err(Error::InvalidConfig {
+                            error: format!(
+                                "Chip {} cs ce_oe cannot be combined with cs1, cs2 or cs3",
+                                chip_num
+                            ),
+                        });
+                    }
+                    if !chip
+                        .chip_type
+                        .control_lines()
+                        .iter()
+                        .any(|line| line.name == "ce")
+                    {
+                        return Err(Error::InvalidConfig {
+                            error: format!(
+                                "cs ce_oe specified for Chip type {} which does not use CE/OE",
+                                chip.chip_type.name()
+                            ),
+                        });
+                    }
+                }
+
                 // Check that required CS lines are specified
                 for line in chip.chip_type.control_lines() {
                     let cs = match line.name {
@@ -343,6 +532,35 @@ impl Builder {
                     }
                 }
 
+                // Check the set's serving algorithm, if specified, can be
+                // used with this chip's CS lines.  Any firmware overrides
+                // algorithm takes precedence.  Multi-ROM sets always use
+                // AddrOnAnyCs, so are skipped.
+                let serve_alg = set
+                    .firmware_overrides
+                    .as_ref()
+                    .and_then(|overrides| overrides.serve_alg)
+                    .or(set.serve_alg);
+                #[allow(clippy::collapsible_if)]
+                if let Some(serve_alg) = serve_alg {
+                    if set.set_type != ChipSetType::Multi
+                        && !serve_alg.is_valid_for(&chip.chip_type)
+                    {
+                        let reason = match serve_alg {
+                            ServeAlg::TwoCsOneAddr => format!(
+                                "requires at least 2 CS lines, but chip type {} has {}",
+                                chip.chip_type.name(),
+                                required_cs_lines.len()
+                            ),
+                            _ => format!(
+                                "requires a CS line, but chip type {} only has CE/OE",
+                                chip.chip_type.name()
+                            ),
+                        };
+                        return Err(Error::InvalidServeAlg { serve_alg, reason });
+                    }
+                }
+
                 if set.chips.len() == 1 {
                     // Check none are ignore
                     for line in &["cs1", "cs2", "cs3"] {
@@ -386,12 +604,72 @@ impl Builder {
                         });
                     }
 
+                    // Check any stride is non-zero
+                    if location.stride == Some(0) {
+                        return Err(Error::InvalidConfig {
+                            error: format!("Chip {} location stride must be non-zero", chip_num),
+                        });
+                    }
+
                     // Check for overflowing a usize (!)
                     if location.start.checked_add(location.length).is_none() {
                         return Err(Error::InvalidConfig {
                             error: format!("Chip {} location start + length overflows", chip_num),
                         });
                     }
+
+                    // Half is a shortcut for a location, so can't have both
+                    if chip.half.is_some() {
+                        return Err(Error::InvalidConfig {
+                            error: format!(
+                                "Chip {} cannot specify both half and location",
+                                chip_num
+                            ),
+                        });
+                    }
+                }
+
+                // Check CS2/CS3 bit overrides are only given for lines the
+                // Chip type has, and are within range
+                for (line, bit) in [("cs2", chip.cs2_bit), ("cs3", chip.cs3_bit)] {
+                    let Some(bit) = bit else {
+                        continue;
+                    };
+                    if !required_cs_lines.contains(line) {
+                        return Err(Error::InvalidCsBit {
+                            id: chip_num,
+                            reason: format!(
+                                "{}_bit specified for Chip type {} which does not use {}",
+                                line,
+                                chip.chip_type.name(),
+                                line.to_uppercase()
+                            ),
+                        });
+                    }
+                    if bit > 15 {
+                        return Err(Error::InvalidCsBit {
+                            id: chip_num,
+                            reason: format!("{}_bit {} is not within 0-15", line, bit),
+                        });
+                    }
+                }
+
+                // Size handling is not permitted when exact sizes are required
+                if (config.require_exact_sizes || chip.exact_size)
+                    && !matches!(chip.size_handling, SizeHandling::None)
+                {
+                    return Err(Error::SizeHandlingNotAllowed { id: chip_num });
+                }
+
+                // Check any built-in image exists
+                #[allow(clippy::collapsible_if)]
+                if let Some(name) = chip.builtin() {
+                    if builtin_image(name).is_none() {
+                        return Err(Error::UnknownBuiltin {
+                            id: chip_num,
+                            name: name.to_string(),
+                        });
+                    }
                 }
 
                 chip_num += 1;
@@ -471,7 +749,27 @@ impl Builder {
         }
     }
 
-    /// Get list of files that need to be loaded
+    // Generates the data for any Chips using built-in images, so these don't
+    // need to be loaded by the caller.  Must be called after the file ID map
+    // is built.
+    fn add_builtin_files(&mut self) {
+        let mut chip_id = 0;
+        for chip_set in self.config.chip_sets.iter() {
+            for chip in &chip_set.chips {
+                if let Some(name) = chip.builtin() {
+                    let file_id = self.file_id_map[&chip_id];
+                    // Names were checked when validating the config
+                    self.files
+                        .entry(file_id)
+                        .or_insert_with(|| builtin_image(name).unwrap());
+                }
+                chip_id += 1;
+            }
+        }
+    }
+
+    /// Get list of files that need to be loaded.  Excludes built-in images,
+    /// which the builder generates itself.
     pub fn file_specs(&self) -> Vec<FileSpec> {
         let mut specs = Vec::new();
         let mut seen_files: BTreeMap<(String, Option<String>), usize> = BTreeMap::new();
@@ -479,7 +777,7 @@ impl Builder {
 
         for (chip_set_num, chip_set) in self.config.chip_sets.iter().enumerate() {
             for rom in &chip_set.chips {
-                if rom.file.is_empty() {
+                if rom.file.is_empty() || rom.builtin().is_some() {
                     // No file to load for this ROM
                     rom_id += 1;
                     continue;
@@ -516,21 +814,207 @@ impl Builder {
     /// Add a loaded file - called multiple times, once for each file that
     /// has been loaded
     pub fn add_file(&mut self, file: FileData) -> Result<()> {
+        self.check_file_id(file.id)?;
+        self.files.insert(file.id, file.data);
+        Ok(())
+    }
+
+    /// Add a loaded file containing several Chip images back to back, such as
+    /// a single dump of a set of ROMs.
+    ///
+    /// The Chips sharing the file in the config are each given their image's
+    /// range within the file by the manifest, as if the Chip had configured
+    /// that [`Location`].  Each manifest entry's Chip must use this file, and
+    /// must not already take a location or half of it.
+    ///
+    /// Errors if any range extends beyond the end of the file, or if ranges
+    /// overlap, unless the manifest allows overlaps.
+    pub fn add_file_with_manifest(&mut self, file: FileData, manifest: &FileManifest) -> Result<()> {
+        self.check_file_id(file.id)?;
+
+        let chips: Vec<&ChipConfig> = self
+            .config
+            .chip_sets
+            .iter()
+            .flat_map(|chip_set| chip_set.chips.iter())
+            .collect();
+
+        let mut locations = BTreeMap::new();
+        for entry in &manifest.entries {
+            let id = entry.chip_index;
+            let bad_location = |reason: String| Error::BadLocation { id, reason };
+
+            if self.file_id_map.get(&id) != Some(&file.id) {
+                return Err(bad_location(format!(
+                    "Chip {id} does not use file {}",
+                    file.id
+                )));
+            }
+            let chip = chips[id];
+            if chip.location.is_some() || chip.half.is_some() {
+                return Err(bad_location(format!(
+                    "Chip {id} already has a location within file {}",
+                    file.id
+                )));
+            }
+
+            if entry
+                .offset
+                .checked_add(entry.length)
+                .is_none_or(|end| end > file.data.len())
+            {
+                return Err(bad_location(format!(
+                    "Range {:#X}+{:#X} extends beyond end of file {} of {:#X} bytes",
+                    entry.offset,
+                    entry.length,
+                    file.id,
+                    file.data.len()
+                )));
+            }
+
+            let location = Location {
+                start: entry.offset,
+                length: entry.length,
+                stride: None,
+            };
+            if locations.insert(id, location).is_some() {
+                return Err(bad_location(format!(
+                    "Chip {id} appears more than once in the manifest"
+                )));
+            }
+        }
+
+        if !manifest.allow_overlap {
+            let mut ranges: Vec<(usize, &Location)> =
+                locations.iter().map(|(&id, location)| (id, location)).collect();
+            ranges.sort_by_key(|(_, location)| location.start);
+            for pair in ranges.windows(2) {
+                let (_, first) = pair[0];
+                let (id, second) = pair[1];
+                if first.start + first.length > second.start {
+                    return Err(Error::BadLocation {
+                        id,
+                        reason: format!(
+                            "Range {:#X}+{:#X} overlaps range {:#X}+{:#X} in file {}",
+                            second.start, second.length, first.start, first.length, file.id
+                        ),
+                    });
+                }
+            }
+        }
+
+        self.manifest_locations.extend(locations);
+        self.add_file(file)
+    }
+
+    /// Remove a previously added file, for example to correct a mistakenly
+    /// added one.  Any locations given to Chips by the file's manifest are
+    /// also removed.
+    ///
+    /// Errors with [`Error::MissingFile`] if the file hasn't been added.
+    pub fn remove_file(&mut self, id: usize) -> Result<()> {
+        if self.files.remove(&id).is_none() {
+            return Err(Error::MissingFile { id });
+        }
+        self.remove_manifest_locations(id);
+        Ok(())
+    }
+
+    /// Replace the data of a previously added file, such as when the user
+    /// picks a different image for it.  The new data is treated as a plain
+    /// file - any locations given to Chips by the previous file's manifest
+    /// are removed.  To replace a file added with a manifest, use
+    /// [`Self::remove_file()`] followed by [`Self::add_file_with_manifest()`].
+    ///
+    /// Errors with [`Error::MissingFile`] if the file hasn't been added.
+    pub fn replace_file(&mut self, file: FileData) -> Result<()> {
+        let Some(data) = self.files.get_mut(&file.id) else {
+            return Err(Error::MissingFile { id: file.id });
+        };
+        *data = file.data;
+        self.remove_manifest_locations(file.id);
+        Ok(())
+    }
+
+    // Removes manifest locations for Chips using the given file
+    fn remove_manifest_locations(&mut self, file_id: usize) {
+        let file_id_map = &self.file_id_map;
+        self.manifest_locations
+            .retain(|chip_id, _| file_id_map.get(chip_id) != Some(&file_id));
+    }
+
+    /// Returns the size and checksum of a loaded file, or None if the file
+    /// hasn't been added.
+    pub fn file_provenance(&self, id: usize) -> Option<Provenance> {
+        self.files.get(&id).map(|data| Provenance::from_source(data))
+    }
+
+    /// Reads and adds a file from the local filesystem.  As [`Self::add_file()`],
+    /// but also checks up front that the file isn't too large for any Chip
+    /// using it, rather than leaving this to [`Self::build()`].
+    ///
+    /// Any extraction from an archive must already have been done - `path`
+    /// must point to the ROM image itself.
+    #[cfg(feature = "std")]
+    pub fn add_file_from_path(&mut self, id: usize, path: &std::path::Path) -> Result<()> {
+        self.check_file_id(id)?;
+
+        let data = std::fs::read(path).map_err(|e| Error::Io {
+            id,
+            message: format!("{}: {e}", path.display()),
+        })?;
+        self.check_file_size(id, data.len())?;
+
+        self.add_file(FileData { id, data })
+    }
+
+    // Checks a file id is in range and hasn't already been added
+    fn check_file_id(&self, id: usize) -> Result<()> {
         // Check if already added
-        if self.files.contains_key(&file.id) {
-            return Err(Error::DuplicateFile { id: file.id });
+        if self.files.contains_key(&id) {
+            return Err(Error::DuplicateFile { id });
         }
 
         // Validate id is in range
         let total_files = self.total_file_count();
-        if file.id >= total_files {
+        if id >= total_files {
             return Err(Error::InvalidFile {
-                id: file.id,
+                id,
                 total: total_files,
             });
         }
 
-        self.files.insert(file.id, file.data);
+        Ok(())
+    }
+
+    // Checks a file isn't too large for any Chip using it.  Chips which
+    // take a location within the file, or truncate it, accept any size here.
+    #[cfg(feature = "std")]
+    fn check_file_size(&self, id: usize, size: usize) -> Result<()> {
+        let chips = self
+            .config
+            .chip_sets
+            .iter()
+            .flat_map(|chip_set| chip_set.chips.iter())
+            .enumerate();
+        for (chip_id, chip) in chips {
+            if self.file_id_map.get(&chip_id) != Some(&id)
+                || chip.location.is_some()
+                || chip.half.is_some()
+                || matches!(chip.size_handling, SizeHandling::Truncate)
+            {
+                continue;
+            }
+
+            let expected_size = chip.chip_type.size_bytes();
+            if size > expected_size {
+                return Err(Error::ImageTooLarge {
+                    image_size: size,
+                    expected_size,
+                });
+            }
+        }
+
         Ok(())
     }
 
@@ -586,15 +1070,20 @@ impl Builder {
             }
         }
 
-        // Validate all ROM types are supported by this board
+        // Validate all ROM types fit this board's socket
         let board = props.board();
-        for set in self.config.chip_sets.iter() {
-            for rom in set.chips.iter() {
-                if !board.supports_chip_type(rom.chip_type) {
-                    return Err(Error::UnsupportedChipType {
-                        chip_type: rom.chip_type,
-                    });
-                }
+        let chip_types = self
+            .config
+            .chip_sets
+            .iter()
+            .flat_map(|set| set.chips.iter().map(|chip| chip.chip_type));
+        for (id, chip_type) in chip_types.enumerate() {
+            if chip_type.chip_pins() != board.chip_pins() {
+                return Err(Error::WrongChipPins {
+                    id,
+                    chip_type,
+                    board_pins: board.chip_pins(),
+                });
             }
         }
 
@@ -603,109 +1092,550 @@ impl Builder {
 
     /// Generate metadata and ROM images once all files loaded
     ///
-    /// Returns (metadata, Chip images)
+    /// Returns (metadata, Chip images).  The output depends only on the
+    /// config, the files' contents and `props` - it is byte-identical
+    /// whatever order files and licenses were added or validated in, so can
+    /// be cached or compared by hash.
     pub fn build(&self, props: FirmwareProperties) -> Result<(Vec<u8>, Vec<u8>)> {
-        if props.version() > MAX_SUPPORTED_FIRMWARE_VERSION {
-            return Err(Error::FirmwareTooNew {
-                version: props.version(),
-                maximum: MAX_SUPPORTED_FIRMWARE_VERSION,
+        let (metadata_len, rom_images_len) = self.output_sizes(&props)?;
+        let mut metadata_buf = vec![0u8; metadata_len];
+        let mut rom_data_buf = vec![0u8; rom_images_len];
+        self.build_into(props, &mut metadata_buf, &mut rom_data_buf)?;
+        Ok((metadata_buf, rom_data_buf))
+    }
+
+    /// Returns the sizes of the buffers needed by [`Self::build_into()`] -
+    /// (metadata, Chip images).  The metadata size includes any reserved
+    /// slack.
+    ///
+    /// Computed from the config alone, without processing any file data, so
+    /// is cheap enough to call before building - for example, to report the
+    /// expected size.  Runs the same missing file and Chip count validation
+    /// as [`Self::build()`], so errors if the builder isn't ready to build.
+    pub fn output_sizes(&self, props: &FirmwareProperties) -> Result<(usize, usize)> {
+        self.preflight(props)?;
+
+        let board = props.board();
+        let chip_sets = self.chip_sets(props)?;
+
+        // Chip sets have an image if they have a file, or are ROMs
+        let rom_images_len = chip_sets
+            .iter()
+            .filter(|chip_set| {
+                self.file_id_map.contains_key(&chip_set.chips()[0].index())
+                    || chip_set.chip_function() != ChipFunction::Ram
+            })
+            .map(|chip_set| chip_set.image_size(&board.mcu_family(), board.chip_pins()))
+            .sum();
+
+        let metadata = Self::new_metadata(&self.config, props, chip_sets, self.note())?;
+        let (metadata_len, _) = Self::metadata_output_sizes(&metadata, props);
+        Ok((metadata_len, rom_images_len))
+    }
+
+    /// Returns the Chip sets this config will build, without their images -
+    /// for example, to display each set's type, CS config and serving
+    /// algorithm with [`ChipSet::describe()`] before building.
+    ///
+    /// Computed from the config alone, so may be called before any files
+    /// are added, but otherwise the sets are created and validated exactly
+    /// as by [`Self::build()`].  The firmware properties are required to
+    /// resolve each set's serving algorithm and validate the sets.
+    pub fn chip_sets(&self, props: &FirmwareProperties) -> Result<Vec<ChipSet>> {
+        self.new_chip_sets(&self.config, props, false)
+    }
+
+    /// As [`Self::build()`], but writes the metadata and Chip images into
+    /// caller supplied buffers, rather than allocating them - see
+    /// [`Self::output_sizes()`] for the sizes required.  Larger buffers are
+    /// fine, and any bytes beyond those written are left untouched.
+    ///
+    /// Returns the number of bytes written to each - (metadata, Chip
+    /// images).  Returns [`Error::BufferTooSmall`] if either buffer is too
+    /// small.
+    pub fn build_into(
+        &self,
+        props: FirmwareProperties,
+        metadata_out: &mut [u8],
+        images_out: &mut [u8],
+    ) -> Result<(usize, usize)> {
+        let metadata = self.prepare(&props)?;
+        let (metadata_len, rom_images_len) = Self::metadata_output_sizes(&metadata, &props);
+
+        if metadata_out.len() < metadata_len {
+            return Err(Error::BufferTooSmall {
+                location: "Builder::build_into metadata",
+                expected: metadata_len,
+                actual: metadata_out.len(),
+            });
+        }
+        if images_out.len() < rom_images_len {
+            return Err(Error::BufferTooSmall {
+                location: "Builder::build_into images",
+                expected: rom_images_len,
+                actual: images_out.len(),
             });
         }
 
-        // Validate ready to build
-        self.build_validation(&props)?;
+        Self::write_outputs(
+            &metadata,
+            &props,
+            &mut metadata_out[..metadata_len],
+            &mut images_out[..rom_images_len],
+        )?;
+        Ok((metadata_len, rom_images_len))
+    }
+
+    /// As [`Self::build()`], but also returns a [`BuildReport`] describing
+    /// the flash layout of the generated metadata and images.
+    ///
+    /// Returns (metadata, Chip images, report)
+    pub fn build_with_report(
+        &self,
+        props: FirmwareProperties,
+    ) -> Result<(Vec<u8>, Vec<u8>, BuildReport)> {
+        let metadata = self.prepare(&props)?;
+        let (metadata_len, rom_images_len) = Self::metadata_output_sizes(&metadata, &props);
+
+        let mut metadata_buf = vec![0u8; metadata_len];
+        let mut rom_data_buf = vec![0u8; rom_images_len];
+        let report = Self::write_outputs(&metadata, &props, &mut metadata_buf, &mut rom_data_buf)?;
+
+        // Done - return the two buffers
+        Ok((metadata_buf, rom_data_buf, report))
+    }
+
+    /// Builds many images from the same config and files, each with its own
+    /// per-device tweaks, such as a serial number in the description - see
+    /// [`VariantSpec`].
+    ///
+    /// More efficient than building each variant separately, as the files are
+    /// validated once and shared by every variant, and variants making
+    /// identical tweaks are only built once.  Each variant's config is
+    /// validated as if it had been passed to [`Self::from_json()`].
+    ///
+    /// Returns (variant, metadata, Chip images) for each variant, in order.
+    /// See [`Self::build_variants_with_report()`] to find out which variants'
+    /// outputs are byte-identical.
+    pub fn build_variants(
+        &self,
+        base_props: FirmwareProperties,
+        variants: &[VariantSpec],
+    ) -> Result<Vec<(VariantSpec, Vec<u8>, Vec<u8>)>> {
+        self.build_variants_with_report(base_props, variants)
+            .map(|(outputs, _)| outputs)
+    }
+
+    /// As [`Self::build_variants()`], but also returns a [`VariantReport`]
+    /// noting which variants' outputs are byte-identical.
+    ///
+    /// Returns (outputs, report)
+    #[allow(clippy::type_complexity)]
+    pub fn build_variants_with_report(
+        &self,
+        base_props: FirmwareProperties,
+        variants: &[VariantSpec],
+    ) -> Result<(Vec<(VariantSpec, Vec<u8>, Vec<u8>)>, VariantReport)> {
+        // Files and licenses are shared, so only need checking once
+        self.preflight(&base_props)?;
+
+        let mut outputs: Vec<(VariantSpec, Vec<u8>, Vec<u8>)> = Vec::with_capacity(variants.len());
+        let mut identical_to = Vec::with_capacity(variants.len());
+        for (index, variant) in variants.iter().enumerate() {
+            // Reuse the outputs of any earlier variant making the same tweaks
+            let earlier = variants[..index]
+                .iter()
+                .position(|other| other.same_tweaks(variant));
+            let (metadata_buf, rom_data_buf) = match earlier {
+                Some(earlier) => (outputs[earlier].1.clone(), outputs[earlier].2.clone()),
+                None => {
+                    let config = variant.apply(&self.config)?;
+                    Self::validate_config(&self.version, &self.mcu_family, &config)?;
+
+                    let metadata = self.prepare_config(&config, &base_props)?;
+                    let (metadata_len, rom_images_len) =
+                        Self::metadata_output_sizes(&metadata, &base_props);
+                    let mut metadata_buf = vec![0u8; metadata_len];
+                    let mut rom_data_buf = vec![0u8; rom_images_len];
+                    Self::write_outputs(
+                        &metadata,
+                        &base_props,
+                        &mut metadata_buf,
+                        &mut rom_data_buf,
+                    )?;
+                    (metadata_buf, rom_data_buf)
+                }
+            };
+
+            // Different tweaks may still build identically - for example,
+            // labels aren't written to the metadata unless enabled
+            identical_to.push(outputs.iter().position(|(_, metadata, images)| {
+                *metadata == metadata_buf && *images == rom_data_buf
+            }));
+            outputs.push((variant.clone(), metadata_buf, rom_data_buf));
+        }
+
+        Ok((outputs, VariantReport { identical_to }))
+    }
+
+    // Validates the builder is ready to build, creates the Chip sets and
+    // checks the resulting metadata and Chip images will fit.
+    fn prepare(&self, props: &FirmwareProperties) -> Result<Metadata> {
+        self.preflight(props)?;
+        self.prepare_config(&self.config, props)
+    }
+
+    // As prepare(), for the given config rather than the builder's own, and
+    // without the preflight checks.  The config must have the same Chips as
+    // the builder's own, so they are matched to the same files.
+    fn prepare_config(&self, config: &Config, props: &FirmwareProperties) -> Result<Metadata> {
+        let chip_sets = self.new_chip_sets(config, props, true)?;
+
+        let metadata = Self::new_metadata(config, props, chip_sets, self.note())?;
+
+        // Any slack reserved for later in-place edits must still fit in the
+        // metadata region
+        let (metadata_len, rom_data_size) = Self::metadata_output_sizes(&metadata, props);
+        if metadata_len > MAX_METADATA_LEN {
+            return Err(Error::BufferTooSmall {
+                location: "Metadata",
+                expected: metadata_len,
+                actual: MAX_METADATA_LEN,
+            });
+        }
+
+        // Check the board has enough space
+        let mcu_variant = props.mcu_variant();
+        let flash_size = mcu_variant.flash_storage_bytes();
+        let rom_space = flash_size - FIRMWARE_SIZE - MAX_METADATA_LEN;
+        assert!(rom_space > 0);
+
+        // Figure out the ROM data size
+        if rom_data_size > rom_space {
+            return Err(Error::RomImagesExceedFlash {
+                required: rom_data_size,
+                available: rom_space,
+            });
+        }
+
+        Ok(metadata)
+    }
 
+    // Creates the Chip sets for the given config, applying every set and
+    // Chip option, and validates them for the board and firmware.  With
+    // `with_data` each Chip's image is created from its file, so the files
+    // must have been added.  Otherwise each Chip is a placeholder without an
+    // image, to report the sets or size the outputs before building.
+    fn new_chip_sets(
+        &self,
+        config: &Config,
+        props: &FirmwareProperties,
+        with_data: bool,
+    ) -> Result<Vec<ChipSet>> {
         // Build Chip and ChipSet objects together
         let mut chip_sets = Vec::new();
         let mut chip_id = 0;
 
-        for (set_id, chip_set_config) in self.config.chip_sets.iter().enumerate() {
+        for (set_id, chip_set_config) in config.chip_sets.iter().enumerate() {
             let mut set_roms = Vec::new();
 
             for chip_config in &chip_set_config.chips {
-                let data = if let Some(&file_id) = self.file_id_map.get(&chip_id) {
-                    // Has a file - use loaded data
-                    Some(self.files.get(&file_id).unwrap())
+                let mut rom = if with_data {
+                    self.new_chip(config, chip_set_config, chip_config, chip_id)?
                 } else {
-                    // No file (RAM chip) - use empty slice
-                    None
+                    Chip::placeholder(
+                        chip_id,
+                        chip_config.filename(),
+                        chip_config.label.clone(),
+                        &chip_config.chip_type,
+                        chip_config.cs_config(),
+                    )
                 };
-
-                let filename = chip_config.filename();
-
-                let rom = Chip::from_raw_rom_image(
-                    chip_id,
-                    filename,
-                    chip_config.label.clone(),
-                    data.map(|v| &**v),
-                    vec![0u8; chip_config.chip_type.size_bytes()],
-                    &chip_config.chip_type,
-                    CsConfig::new(chip_config.cs1, chip_config.cs2, chip_config.cs3),
-                    &chip_config.size_handling,
-                    chip_config.location,
-                )?;
+                rom.set_cs_bits(chip_config.cs2_bit, chip_config.cs3_bit)?;
+                if let Some(offset) = chip_set_config.window_offset {
+                    rom.set_window_offset(offset)?;
+                }
                 set_roms.push(rom);
                 chip_id += 1;
             }
 
-            let serve_alg = if let Some(alg) = chip_set_config.serve_alg {
-                alg
-            } else {
-                props.serve_alg()
-            };
-            let chip_set = ChipSet::new(
+            let serve_alg = ChipSet::resolve_serve_alg(
+                &chip_set_config.set_type,
+                chip_set_config.firmware_overrides.as_ref(),
+                chip_set_config.serve_alg,
+                props.serve_alg(),
+            )?;
+            let mut chip_set = ChipSet::new(
                 set_id,
                 chip_set_config.set_type.clone(),
                 serve_alg,
                 set_roms,
                 chip_set_config.firmware_overrides.clone(),
             )?;
+
+            if let Some(data_pin_map) = &config.data_pin_map {
+                chip_set.set_data_pin_map(&props.board(), data_pin_map.clone())?;
+            }
+
+            if let Some(no_chip_byte) = chip_set_config.no_chip_byte {
+                chip_set.set_no_chip_byte(no_chip_byte);
+            }
+
+            if let Some(addr_line_map) = &chip_set_config.addr_line_map {
+                chip_set.set_addr_line_map(addr_line_map.clone())?;
+            }
+
+            if let Some(boot_logging) = chip_set_config.boot_logging {
+                chip_set.set_boot_logging(boot_logging);
+            }
+
+            // Check the set is valid for this board and firmware, including
+            // the board's address map and any CS2/CS3 bit overrides
+            chip_set.validate(&props.board(), &props.version())?;
+
             chip_sets.push(chip_set);
         }
 
-        // Build Metadata
-        let metadata = Metadata::new(
+        Ok(chip_sets)
+    }
+
+    // Creates a Chip with its image from its file, or without one if it has
+    // no file (RAM).
+    fn new_chip(
+        &self,
+        config: &Config,
+        chip_set_config: &ChipSetConfig,
+        chip_config: &ChipConfig,
+        chip_id: usize,
+    ) -> Result<Chip> {
+        let data = if let Some(&file_id) = self.file_id_map.get(&chip_id) {
+            // Has a file - use loaded data
+            Some(self.files.get(&file_id).unwrap())
+        } else {
+            // No file (RAM chip) - use empty slice
+            None
+        };
+
+        let filename = chip_config.filename();
+
+        // Resolve any half into a location, now the file length is
+        // known
+        let location = match (chip_config.half, data) {
+            (Some(half), Some(data)) => Some(half.location(chip_id, data.len())?),
+            _ => chip_config
+                .location
+                .or_else(|| self.manifest_locations.get(&chip_id).copied()),
+        };
+
+        // In strict mode, or if this Chip is marked exact size, the
+        // file (or its located portion) must exactly match the Chip
+        // size
+        #[allow(clippy::collapsible_if)]
+        if config.require_exact_sizes || chip_config.exact_size {
+            if let Some(data) = data {
+                let actual = location.map_or(data.len(), |l| l.length);
+                let expected = chip_config.chip_type.size_bytes();
+                if actual != expected {
+                    return Err(Error::NotExactSize {
+                        id: chip_id,
+                        expected,
+                        actual,
+                    });
+                }
+            }
+        }
+
+        // Any window offset plus the file (or its located portion)
+        // must fit within the Chip's window
+        #[allow(clippy::collapsible_if)]
+        if let Some(offset) = chip_set_config.window_offset {
+            if let Some(data) = data {
+                let actual = location.map_or(data.len(), |l| l.length);
+                let window = chip_config.chip_type.size_bytes();
+                if offset + actual > window {
+                    return Err(Error::BadLocation {
+                        id: chip_id,
+                        reason: format!(
+                            "Window offset {:#X} plus image size {:#X} exceeds {} window of {:#X} bytes",
+                            offset,
+                            actual,
+                            chip_config.chip_type.name(),
+                            window
+                        ),
+                    });
+                }
+            }
+        }
+
+        Chip::from_raw_rom_image(
+            chip_id,
+            filename,
+            chip_config.label.clone(),
+            data.map(|v| &**v),
+            vec![0u8; chip_config.chip_type.size_bytes()],
+            &chip_config.chip_type,
+            chip_config.cs_config(),
+            &chip_config.size_handling,
+            location,
+            chip_config.fill_byte,
+            chip_config.strict_truncate,
+        )
+    }
+
+    // Checks the firmware version is supported and the builder is ready to
+    // build.
+    fn preflight(&self, props: &FirmwareProperties) -> Result<()> {
+        if props.version() > MAX_SUPPORTED_FIRMWARE_VERSION {
+            return Err(Error::FirmwareTooNew {
+                version: props.version(),
+                maximum: MAX_SUPPORTED_FIRMWARE_VERSION,
+            });
+        }
+
+        // Validate ready to build
+        self.build_validation(props)
+    }
+
+    // Creates the metadata for the given Chip sets, applying the config's
+    // metadata options and any note.
+    fn new_metadata(
+        config: &Config,
+        props: &FirmwareProperties,
+        chip_sets: Vec<ChipSet>,
+        note: Option<&str>,
+    ) -> Result<Metadata> {
+        let mut metadata = Metadata::new(
             props.board(),
             chip_sets,
             props.boot_logging(),
             props.board().mcu_pio(),
             props.version(),
         );
+        if props.boot_logging() || config.provenance {
+            metadata.set_provenance();
+        }
+        if config.labels {
+            metadata.set_labels();
+        }
+        metadata.set_description(config.description.clone());
+        if let Some(note) = note {
+            metadata.set_note(note.to_string())?;
+        }
+        if let Some(socket_order) = &config.socket_order {
+            metadata.set_socket_order(socket_order.clone())?;
+        }
+        Ok(metadata)
+    }
+
+    // Returns the metadata (including any reserved slack) and Chip images
+    // sizes for the given metadata.
+    fn metadata_output_sizes(metadata: &Metadata, props: &FirmwareProperties) -> (usize, usize) {
+        (
+            metadata.metadata_len() + props.reserve_metadata_slack(),
+            metadata.rom_images_size(),
+        )
+    }
 
-        // Get buffer sizes
+    // Writes the metadata and Chip images into buffers of exactly the sizes
+    // returned by `metadata_output_sizes()`, returning a report of where
+    // everything ended up.
+    fn write_outputs(
+        metadata: &Metadata,
+        props: &FirmwareProperties,
+        metadata_buf: &mut [u8],
+        rom_data_buf: &mut [u8],
+    ) -> Result<BuildReport> {
         let metadata_size = metadata.metadata_len();
-        let rom_data_size: usize = metadata.rom_images_size();
-        let set_count = metadata.total_set_count();
+        let mut rom_data_ptrs = vec![0u32; metadata.total_set_count()];
 
-        // Check the board has enough space
-        let mcu_variant = props.mcu_variant();
-        let flash_size = mcu_variant.flash_storage_bytes();
-        let rom_space = flash_size - FIRMWARE_SIZE - MAX_METADATA_LEN;
-        assert!(rom_space > 0);
+        // Write metadata, then pad any slack
+        metadata.write_all(&mut metadata_buf[..metadata_size], &mut rom_data_ptrs)?;
+        // Note rom_data_ptrs unused here - absolute flash addresses.
+        metadata_buf[metadata_size..].fill(PAD_METADATA_BYTE);
 
-        // Figure out the ROM data size
-        if rom_data_size > rom_space {
-            return Err(Error::BufferTooSmall {
-                location: "Flash",
-                expected: rom_data_size,
-                actual: rom_space,
-            });
-        }
+        // Write ROM data
+        metadata.write_roms(rom_data_buf)?;
 
-        // Allocate buffers
-        let mut metadata_buf = vec![0u8; metadata_size];
-        let mut rom_data_buf = vec![0u8; rom_data_size];
-        let mut rom_data_ptrs = vec![0u32; set_count];
+        // Report where everything ended up
+        let board = props.board();
+        let chip_image_start = board.mcu_family().get_flash_base() + ROM_IMAGE_DATA_START;
+        let chip_sets = metadata
+            .chip_sets()
+            .iter()
+            .zip(rom_data_ptrs.iter())
+            .map(|(chip_set, &offset)| {
+                // Chip sets without images (RAM) have no location
+                let (data_ptr, offset, image_size) = if offset == 0xFFFF_FFFF {
+                    (None, None, 0)
+                } else {
+                    (
+                        Some(chip_image_start + offset),
+                        Some(offset as usize),
+                        chip_set.image_size(&board.mcu_family(), board.chip_pins()),
+                    )
+                };
+                ChipSetReport {
+                    data_ptr,
+                    offset,
+                    image_size,
+                    serve_alg: chip_set.serve_alg().resolve(&chip_set.set_type),
+                    data_crc32: chip_set.data_crc32(),
+                    firmware_overrides: chip_set
+                        .firmware_overrides
+                        .as_ref()
+                        .map_or(Vec::new(), |overrides| {
+                            overrides
+                                .active_overrides()
+                                .into_iter()
+                                .map(String::from)
+                                .collect()
+                        }),
+                }
+            })
+            .collect();
+        Ok(BuildReport {
+            chip_sets,
+            firmware_size: ROM_IMAGE_DATA_START as usize + rom_data_buf.len(),
+            metadata_len: metadata_buf.len(),
+            rom_images_len: rom_data_buf.len(),
+        })
+    }
 
-        // Write metadata
-        metadata.write_all(&mut metadata_buf, &mut rom_data_ptrs)?;
-        // Note rom_data_ptrs unused here - absolute flash addresses.
+    /// Assembles the full image, as flashed to the device, from the
+    /// firmware, and the metadata and Chip images returned by
+    /// [`Self::build()`].
+    ///
+    /// The firmware is padded to [`FIRMWARE_SIZE`] and the metadata to
+    /// [`MAX_METADATA_LEN`], so the metadata and Chip images are at the
+    /// offsets the firmware expects.
+    ///
+    /// Returns [`Error::ImageTooLarge`] if the firmware or metadata exceed
+    /// their reserved space.
+    pub fn assemble_full_image(
+        &self,
+        firmware: &[u8],
+        metadata: &[u8],
+        rom_images: &[u8],
+    ) -> Result<Vec<u8>> {
+        if firmware.len() > FIRMWARE_SIZE {
+            return Err(Error::ImageTooLarge {
+                image_size: firmware.len(),
+                expected_size: FIRMWARE_SIZE,
+            });
+        }
+        if metadata.len() > MAX_METADATA_LEN {
+            return Err(Error::ImageTooLarge {
+                image_size: metadata.len(),
+                expected_size: MAX_METADATA_LEN,
+            });
+        }
 
-        // Write ROM data
-        metadata.write_roms(&mut rom_data_buf)?;
+        let mut image = vec![PAD_METADATA_BYTE; FIRMWARE_SIZE + MAX_METADATA_LEN];
+        image[..firmware.len()].copy_from_slice(firmware);
+        image[FIRMWARE_SIZE..FIRMWARE_SIZE + metadata.len()].copy_from_slice(metadata);
+        image.extend_from_slice(rom_images);
 
-        // Done - return the two buffers
-        Ok((metadata_buf, rom_data_buf))
+        Ok(image)
     }
 
     fn num_chip_sets(&self) -> usize {
@@ -909,6 +1839,196 @@ pub struct FileSpec {
     pub set_description: Option<String>,
 }
 
+impl FileSpec {
+    /// Returns the file size the builder expects for this file - the Chip
+    /// size, or half of it if the file is mirrored.
+    ///
+    /// Depending on the size handling, files of other sizes may also be
+    /// accepted - see [`Self::accepts_size()`].
+    ///
+    /// Where the Chip uses a location or half of the file, this is the size
+    /// of that portion, rather than of the whole file.
+    pub fn expected_size(&self) -> usize {
+        let chip_size = self.chip_type.size_bytes();
+        match self.size_handling {
+            SizeHandling::Mirror => chip_size / 2,
+            SizeHandling::None
+            | SizeHandling::Duplicate
+            | SizeHandling::Truncate
+            | SizeHandling::Pad => chip_size,
+        }
+    }
+
+    /// Returns whether a file of `size` bytes would be accepted by the
+    /// builder for this file, given the Chip type and size handling:
+    /// - `None` - exactly the Chip size
+    /// - `Duplicate` - smaller than the Chip size, and an exact divisor of it
+    /// - `Truncate` - larger than the Chip size
+    /// - `Pad` - smaller than the Chip size
+    /// - `Mirror` - exactly half the Chip size
+    ///
+    /// As with [`Self::expected_size()`], where the Chip uses a location or
+    /// half of the file, `size` is the size of that portion.
+    pub fn accepts_size(&self, size: usize) -> bool {
+        let chip_size = self.chip_type.size_bytes();
+        match self.size_handling {
+            SizeHandling::None => size == chip_size,
+            SizeHandling::Duplicate => {
+                size > 0 && size < chip_size && chip_size.is_multiple_of(size)
+            }
+            SizeHandling::Truncate => size > chip_size,
+            SizeHandling::Pad => size < chip_size,
+            SizeHandling::Mirror => size * 2 == chip_size,
+        }
+    }
+}
+
+/// Flash layout of the metadata and images generated by
+/// [`Builder::build_with_report()`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BuildReport {
+    /// Layout of each chip set, in config order
+    pub chip_sets: Vec<ChipSetReport>,
+
+    /// Total size of the flash image - the firmware and metadata regions,
+    /// followed by the chip images
+    pub firmware_size: usize,
+
+    /// Length of the generated metadata, including any reserved slack
+    pub metadata_len: usize,
+
+    /// Length of the generated chip images
+    pub rom_images_len: usize,
+}
+
+/// Flash layout of a single chip set, as part of a [`BuildReport`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ChipSetReport {
+    /// Absolute flash address of this chip set's image, as written to the
+    /// metadata.  None if the chip set has no image (RAM).
+    pub data_ptr: Option<u32>,
+
+    /// Byte offset of this chip set's image within the chip images buffer.
+    /// None if the chip set has no image (RAM).
+    pub offset: Option<usize>,
+
+    /// Size of this chip set's image in bytes
+    pub image_size: usize,
+
+    /// Serving algorithm the firmware will run for this chip set - never
+    /// [`ServeAlg::Default`]
+    pub serve_alg: ServeAlg,
+
+    /// CRC-32 of this chip set's logical ROM data, before it is arranged for
+    /// the board - see [`ChipSet::data_crc32()`].  The same for the same ROM
+    /// data on any board.  None if the chip set has no image (RAM).
+    #[serde(default)]
+    pub data_crc32: Option<u32>,
+
+    /// Names of the firmware overrides this chip set applies, if any - see
+    /// [`FirmwareConfig::active_overrides()`]
+    #[serde(default)]
+    pub firmware_overrides: Vec<String>,
+}
+
+/// Per-device tweaks applied to the builder's config by
+/// [`Builder::build_variants()`].  Anything not specified is as configured.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct VariantSpec {
+    /// Name identifying this variant, such as the device's serial number.
+    /// Not included in the build.
+    pub name: String,
+
+    /// Replacement for the config's description, written to the metadata -
+    /// see [`Builder::set_description()`]
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Replacement Chip labels, by Chip index.  Only written to the metadata
+    /// when labels or boot logging are enabled.
+    #[serde(default)]
+    pub labels: BTreeMap<usize, String>,
+
+    /// Replacement Chip fill bytes, by Chip index
+    #[serde(default)]
+    pub fill_bytes: BTreeMap<usize, u8>,
+
+    /// Replacement firmware overrides, by chip set index.  Replace any
+    /// configured for the chip set, rather than being merged with them.
+    #[serde(default)]
+    pub firmware_overrides: BTreeMap<usize, FirmwareConfig>,
+}
+
+impl VariantSpec {
+    // Whether the two variants make the same tweaks, so build identically
+    fn same_tweaks(&self, other: &Self) -> bool {
+        self.description == other.description
+            && self.labels == other.labels
+            && self.fill_bytes == other.fill_bytes
+            && self.firmware_overrides == other.firmware_overrides
+    }
+
+    // Returns a copy of the config with this variant's tweaks applied
+    fn apply(&self, config: &Config) -> Result<Config> {
+        let num_chips: usize = config.chip_sets.iter().map(|set| set.chips.len()).sum();
+        if let Some(id) = self
+            .labels
+            .keys()
+            .chain(self.fill_bytes.keys())
+            .find(|&&id| id >= num_chips)
+        {
+            return Err(Error::InvalidConfig {
+                error: format!("Variant {:?} refers to Chip {id}, which doesn't exist", self.name),
+            });
+        }
+        if let Some(id) = self
+            .firmware_overrides
+            .keys()
+            .find(|&&id| id >= config.chip_sets.len())
+        {
+            return Err(Error::InvalidConfig {
+                error: format!(
+                    "Variant {:?} refers to chip set {id}, which doesn't exist",
+                    self.name
+                ),
+            });
+        }
+
+        let mut config = config.clone();
+        if let Some(description) = &self.description {
+            config.description = description.clone();
+        }
+
+        let mut chip_id = 0;
+        for (set_id, set) in config.chip_sets.iter_mut().enumerate() {
+            if let Some(overrides) = self.firmware_overrides.get(&set_id) {
+                set.firmware_overrides = Some(overrides.clone());
+            }
+            for chip in set.chips.iter_mut() {
+                if let Some(label) = self.labels.get(&chip_id) {
+                    chip.label = Some(label.clone());
+                }
+                if let Some(&fill_byte) = self.fill_bytes.get(&chip_id) {
+                    chip.fill_byte = Some(fill_byte);
+                }
+                chip_id += 1;
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// Notes which outputs of [`Builder::build_variants_with_report()`] are
+/// byte-identical.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct VariantReport {
+    /// For each variant, in order, the index of the first earlier variant
+    /// whose metadata and Chip images are byte-identical to its own.  None
+    /// if its outputs are unique so far.
+    pub identical_to: Vec<Option<usize>>,
+}
+
 /// File data loaded by the caller, passed back to the builder.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct FileData {
@@ -919,6 +2039,33 @@ pub struct FileData {
     pub data: Vec<u8>,
 }
 
+/// Manifest describing where each Chip's image lies within a file containing
+/// several Chip images back to back.  Passed to
+/// [`Builder::add_file_with_manifest()`].
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct FileManifest {
+    /// The Chip images within the file
+    pub entries: Vec<ManifestEntry>,
+
+    /// Whether Chip images may overlap within the file.  Defaults to false,
+    /// as overlaps usually indicate a mistake in the manifest.
+    #[serde(default)]
+    pub allow_overlap: bool,
+}
+
+/// A single Chip image within a file, as part of a [`FileManifest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ManifestEntry {
+    /// Index of the Chip, counting Chips across all chip sets in config order
+    pub chip_index: usize,
+
+    /// Start of the Chip's image within the file
+    pub offset: usize,
+
+    /// Length of the Chip's image within the file
+    pub length: usize,
+}
+
 /// One ROM chip configuration format.
 /// 
 /// Used to indicate:
@@ -962,6 +2109,200 @@ pub struct Config {
     /// Optional categories for this configuration, to aid in grouping,
     /// sorting, and searching of configurations.
     pub categories: Option<Vec<String>>,
+
+    /// Optional override of the board's data pin mapping, D0-D7.  Must be a
+    /// permutation of the board's data pins.  Used for hardware variants
+    /// which reorder the data lines, such as a jumper swapping the data bus
+    /// nibbles.
+    pub data_pin_map: Option<Vec<u8>>,
+
+    /// Whether to record each ROM's source file size and checksum in the
+    /// metadata, so an analysed device reveals the exact source dumps.  Also
+    /// recorded whenever boot logging is enabled.  Requires 0.6.0 firmware
+    /// onwards, and is omitted if there is insufficient metadata space.
+    #[serde(default)]
+    pub provenance: bool,
+
+    /// Whether to record each ROM's label in the metadata, even when boot
+    /// logging is disabled, so an analysed device shows friendly names.
+    /// Applies to chip sets with at least one labelled ROM.  Requires 0.6.0
+    /// firmware onwards.
+    #[serde(default)]
+    pub labels: bool,
+
+    /// Whether every file must exactly match its Chip's size.  When set, any
+    /// Chip specifying `size_handling` is rejected, as is any file which
+    /// would need duplicating, padding or truncating.  Catches accidentally
+    /// wrong dumps.
+    #[serde(default)]
+    pub require_exact_sizes: bool,
+
+    /// Optional mapping from chip set index to the physical socket/flash slot
+    /// its image data is placed in, for hardware which addresses ROM sockets
+    /// in a different order to the configuration.  Entry `i` is the slot for
+    /// chip set `i`, and must be a permutation of the chip set indexes.
+    pub socket_order: Option<Vec<usize>>,
+}
+
+impl Config {
+    /// Returns the firmware features this configuration relies on, in
+    /// ascending order, without duplicates.
+    ///
+    /// Used to check whether a particular firmware release can serve this
+    /// configuration before attempting a build.
+    pub fn required_features(&self) -> Vec<ConfigFeature> {
+        let mut features = BTreeSet::new();
+        for set in self.chip_sets.iter() {
+            if set.firmware_overrides.is_some() {
+                features.insert(ConfigFeature::FirmwareOverrides);
+            }
+            if set.boot_logging.is_some() {
+                features.insert(ConfigFeature::BootLoggingOverrides);
+            }
+        }
+        features.into_iter().collect()
+    }
+
+    /// Returns the required features not supported by the given firmware
+    /// version.  An empty list means the firmware can serve this
+    /// configuration.
+    pub fn unsupported_features(&self, version: &FirmwareVersion) -> Vec<ConfigFeature> {
+        self.required_features()
+            .into_iter()
+            .filter(|feature| !feature.supported_by(version))
+            .collect()
+    }
+
+    /// Combines an overlay JSON config with a base JSON config.
+    ///
+    /// - Each top level field present in the overlay replaces the base's
+    ///   field.  Fields the overlay omits are inherited from the base, and a
+    ///   `null` field clears an optional base field.
+    /// - `chip_sets` (or `rom_sets`) are combined by index.  Overlay chip set
+    ///   `i` replaces base chip set `i` entirely, a `null` entry inherits base
+    ///   chip set `i` unchanged, and overlay chip sets beyond the end of the
+    ///   base's are appended.  Chip sets cannot be removed.
+    /// - All other arrays, such as `categories`, are replaced entirely.
+    ///
+    /// Neither config need be complete on its own - for example, the base may
+    /// omit `description` - but the combined config must be.
+    ///
+    /// For example, an overlay of `{"chip_sets": [null, {...}]}` keeps the
+    /// base's first chip set and replaces its second.
+    pub fn merge_json(base: &str, overlay: &str) -> Result<Self> {
+        let mut merged = Self::parse_json_object(base)?;
+        for (key, value) in Self::parse_json_object(overlay)? {
+            let value = if key == "chip_sets" {
+                Self::merge_chip_sets(merged.remove("chip_sets"), value)?
+            } else {
+                value
+            };
+            merged.insert(key, value);
+        }
+
+        serde_json::from_value(serde_json::Value::Object(merged)).map_err(|e| {
+            Error::InvalidConfig {
+                error: e.to_string(),
+            }
+        })
+    }
+
+    // Parses a, possibly partial, JSON config as an object, normalising the
+    // rom_sets alias so base and overlay chip sets line up.
+    fn parse_json_object(json: &str) -> Result<serde_json::Map<String, serde_json::Value>> {
+        let value = serde_json::from_str(json).map_err(|e| Error::InvalidConfig {
+            error: e.to_string(),
+        })?;
+        let serde_json::Value::Object(mut object) = value else {
+            return Err(Error::InvalidConfig {
+                error: "config must be a JSON object".to_string(),
+            });
+        };
+
+        if !object.contains_key("chip_sets")
+            && let Some(sets) = object.remove("rom_sets")
+        {
+            object.insert("chip_sets".to_string(), sets);
+        }
+
+        Ok(object)
+    }
+
+    // Combines base and overlay chip sets by index - see `merge_json()`.
+    fn merge_chip_sets(
+        base: Option<serde_json::Value>,
+        overlay: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        use serde_json::Value;
+
+        let mut sets = match base {
+            Some(Value::Array(sets)) => sets,
+            None => Vec::new(),
+            Some(_) => {
+                return Err(Error::InvalidConfig {
+                    error: "base chip_sets must be an array".to_string(),
+                });
+            }
+        };
+        let Value::Array(overlay) = overlay else {
+            return Err(Error::InvalidConfig {
+                error: "overlay chip_sets must be an array".to_string(),
+            });
+        };
+
+        for (index, set) in overlay.into_iter().enumerate() {
+            match set {
+                Value::Null if index < sets.len() => (),
+                Value::Null => {
+                    return Err(Error::InvalidConfig {
+                        error: format!(
+                            "overlay chip set {index} is null, but the base has no chip set {index}"
+                        ),
+                    });
+                }
+                set if index < sets.len() => sets[index] = set,
+                set => sets.push(set),
+            }
+        }
+
+        Ok(Value::Array(sets))
+    }
+}
+
+/// Firmware features a configuration may require
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub enum ConfigFeature {
+    /// Per chip set firmware overrides
+    FirmwareOverrides,
+
+    /// Per chip set boot logging overrides
+    BootLoggingOverrides,
+}
+
+impl ConfigFeature {
+    /// Human readable name of this feature
+    pub const fn name(&self) -> &'static str {
+        match self {
+            ConfigFeature::FirmwareOverrides => "firmware overrides",
+            ConfigFeature::BootLoggingOverrides => "per chip set boot logging",
+        }
+    }
+
+    /// Minimum firmware version supporting this feature
+    pub const fn min_firmware_version(&self) -> FirmwareVersion {
+        match self {
+            ConfigFeature::FirmwareOverrides | ConfigFeature::BootLoggingOverrides => {
+                MIN_FIRMWARE_OVERRIDES_VERSION
+            }
+        }
+    }
+
+    /// Whether the given firmware version supports this feature
+    pub fn supported_by(&self, version: &FirmwareVersion) -> bool {
+        *version >= self.min_firmware_version()
+    }
 }
 
 #[cfg(feature = "schemars")]
@@ -990,12 +2331,40 @@ pub struct ChipSetConfig {
     pub chips: Vec<ChipConfig>,
 
     /// Optional serving algorithm override for this chip set.  Only valid
-    /// when using CPU serving - Ice boards and Fire 24 A/B by default.
+    /// when using CPU serving - Ice boards and Fire 24 A/B by default.  Any
+    /// `serve_alg` in `firmware_overrides` takes precedence over this.
     pub serve_alg: Option<ServeAlg>,
 
     /// Optional firmware overrides when serving this chip set.  Takes
     /// precedence over any global configuration firmware overrides.
     pub firmware_overrides: Option<FirmwareConfig>,
+
+    /// Optional byte to serve for addresses where no chip is selected, or
+    /// where more than one chip select is active, in multi chip sets.  Used
+    /// for systems where the bus floats to a particular value.  Defaults to
+    /// 0xAA.
+    pub no_chip_byte: Option<u8>,
+
+    /// Optional override of the firmware's boot logging setting for this
+    /// chip set.  When enabled, this set's chips' filenames and signature are
+    /// included in the metadata, and logged at boot.  Used to log only the
+    /// chip sets of interest in large configurations.  Requires 0.6.0
+    /// firmware onwards.
+    pub boot_logging: Option<bool>,
+
+    /// Optional offset, in bytes, at which each chip's image starts within
+    /// the chip's address window.  Addresses below the offset read as the
+    /// chip's fill byte.  The offset plus the size of each image must fit
+    /// within the window, so images smaller than the chip must specify
+    /// `size_handling` - typically `"pad"`.
+    pub window_offset: Option<usize>,
+
+    /// Optional remapping of the chips' address lines, for adapters which
+    /// rewire the socket's address lines.  Entry `i` is the chip address
+    /// line the socket's A`i` is wired to - for example `[1, 0, 2, 3, ...]`
+    /// swaps A0 and A1.  Must be a permutation of every chip's address
+    /// lines.  Applied on top of the 2732's swapped A11 and A12.
+    pub addr_line_map: Option<Vec<usize>>,
 }
 
 /// Chip configuration structure
@@ -1004,7 +2373,11 @@ pub struct ChipSetConfig {
 pub struct ChipConfig {
     /// Filename or URL of any ROM image - filename is only valid if using a
     /// generator tool with local file access.  This is passed to the generator
-    /// tool to retrieve the ROM image.
+    /// tool to retrieve the ROM image.  Alternatively `"@builtin:<name>"`
+    /// references a synthetic image generated by the builder, such as
+    /// `"@builtin:ramp8k"` - see [`builtin_image()`].  Optional for RAM
+    /// Chips, where it provides the RAM's initial contents - without one,
+    /// RAM reads as [`PAD_RAM_BYTE`](crate::image::PAD_RAM_BYTE).
     #[serde(default)]
     pub file: String,
 
@@ -1029,12 +2402,41 @@ pub struct ChipConfig {
     /// Optional Chip Select 3 logic - only valid for Chip Types that have CS3
     pub cs3: Option<CsLogic>,
 
+    /// Optional explicit chip select mode, instead of `cs1`/`cs2`/`cs3`.
+    /// `ce_oe` is only valid for Chip Types with CE/OE lines.  Such Chips
+    /// use CE/OE even if this is omitted, as long as no CS lines are given.
+    #[serde(default)]
+    pub cs: Option<CsMode>,
+
+    /// Optional override of the address bit position CS2 is routed to, for
+    /// boards where it is wired to an unusual address bit.  Must be within
+    /// 0-15 and distinct from the CS1/X1/X2 bits.  Only valid for Chip Types
+    /// that have CS2.
+    pub cs2_bit: Option<u8>,
+
+    /// Optional override of the address bit position CS3 is routed to.  As
+    /// for `cs2_bit`, but only valid for Chip Types that have CS3.
+    pub cs3_bit: Option<u8>,
+
     /// Optional size handling configuration for this Chip.  Used to specify
     /// handling when the image supplied isn't the correct size for this Chip
     /// type.
     #[serde(default)]
     pub size_handling: SizeHandling,
 
+    /// Whether this file must exactly match the Chip size, regardless of the
+    /// configuration-wide `require_exact_sizes`.  Used to mark known-good
+    /// dumps as must-be-exact, while allowing size handling elsewhere.
+    /// Cannot be combined with `size_handling`.
+    #[serde(default)]
+    pub exact_size: bool,
+
+    /// Whether `truncate` size handling must only discard bytes equal to the
+    /// fill byte.  When set, a file whose discarded tail contains any other
+    /// data is rejected, rather than silently losing that data.
+    #[serde(default)]
+    pub strict_truncate: bool,
+
     /// Optional extract path within an archive (zip/tar) if the file pointed
     /// to is an archive.
     pub extract: Option<String>,
@@ -1047,10 +2449,47 @@ pub struct ChipConfig {
     /// Optional location within a larger image file.  Used to specify start
     /// offset and length within the file.  Useful when multiple ROM images
     /// are concatenated into a single file and one needs to be extracted.
+    /// An optional stride extracts every Nth byte instead, for images
+    /// interleaved byte-by-byte.
     pub location: Option<Location>,
+
+    /// Optional half of the file to use, for files containing two
+    /// concatenated images - `lower` for the first, `upper` for the second.
+    /// The file's length must be even.  Cannot be combined with `location`.
+    pub half: Option<Half>,
+
+    /// Optional byte to pad this image with when using `pad` size handling,
+    /// instead of 0xAA.  For example, 0xFF to match an unprogrammed EPROM.
+    /// Also served when no Chip in a multi-Chip set is selected, unless the
+    /// set's `no_chip_byte` is specified.
+    pub fill_byte: Option<u8>,
+}
+
+/// Explicit chip select mode for a Chip
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CsMode {
+    /// Chip is selected by its active low CE and OE lines, rather than CS
+    /// lines
+    CeOe,
 }
 
 impl ChipConfig {
+    // Returns the Chip's CS configuration.  CE/OE is used if requested, or
+    // if no CS lines are given.
+    fn cs_config(&self) -> CsConfig {
+        match self.cs {
+            Some(CsMode::CeOe) => CsConfig::CeOe,
+            None => CsConfig::new(self.cs1, self.cs2, self.cs3),
+        }
+    }
+
+    // Returns the name of the built-in image this Chip uses, if any.
+    fn builtin(&self) -> Option<&str> {
+        self.file.strip_prefix(BUILTIN_FILE_PREFIX)
+    }
+
     // Constructs the filename string for metadata.  Note label will be used
     // in metadata instead if specified.
     fn filename(&self) -> String {
@@ -1066,12 +2505,18 @@ impl ChipConfig {
             self.file.clone()
         };
 
-        // If location specified, append "|start=0x...,length=0x..."
+        // If location specified, append "|start=0x...,length=0x..." (plus
+        // ",stride=0x..." if specified), or if half specified, "|half=..."
         if let Some(location) = &self.location {
+            let stride = location
+                .stride
+                .map_or(String::new(), |stride| format!(",stride={:#X}", stride));
             format!(
-                "{}|start={:#X},length={:#X}",
-                filename_base, location.start, location.length
+                "{}|start={:#X},length={:#X}{}",
+                filename_base, location.start, location.length, stride
             )
+        } else if let Some(half) = &self.half {
+            format!("{}|half={}", filename_base, half.as_str())
         } else {
             filename_base
         }
@@ -0,0 +1,93 @@
+// Copyright (C) 2026 Piers Finlayson <piers@piers.rocks>
+//
+// MIT License
+
+//! Compressed storage of the ROM images region
+//!
+//! The mangled ROM images produced by [`crate::Builder::build()`] tend to be
+//! highly repetitive (padding, duplicated images, unused banks), so compress
+//! well.  This module provides a compact representation suitable for storing
+//! or transmitting builds.
+//!
+//! The compressed form is a small header followed by raw DEFLATE data:
+//!
+//! ```text
+//! Offset  Length  Contents
+//! 0       8       COMPRESSED_IMAGES_MAGIC
+//! 8       4       Uncompressed length (u32, little-endian)
+//! 12      ...     DEFLATE compressed ROM images
+//! ```
+//!
+//! The compressed form must never be flashed directly - call
+//! [`decompress_images()`] first.
+
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+use crate::{Error, Result};
+
+/// Magic bytes at the start of a compressed ROM images buffer
+pub const COMPRESSED_IMAGES_MAGIC: &[u8; 8] = b"ONEROMZ\0";
+
+/// Length of the header preceding the compressed data
+pub const COMPRESSED_IMAGES_HEADER_LEN: usize = 12;
+
+// miniz_oxide compression level - 10 is the maximum ("uber") level.  Build
+// outputs are small, so favour size over speed.
+const COMPRESSION_LEVEL: u8 = 10;
+
+/// Compresses a ROM images buffer, as returned by [`crate::Builder::build()`].
+pub fn compress_images(images: &[u8]) -> Vec<u8> {
+    let compressed = compress_to_vec(images, COMPRESSION_LEVEL);
+
+    let mut buf = Vec::with_capacity(COMPRESSED_IMAGES_HEADER_LEN + compressed.len());
+    buf.extend_from_slice(COMPRESSED_IMAGES_MAGIC);
+    buf.extend_from_slice(&(images.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&compressed);
+
+    buf
+}
+
+/// Returns true if the buffer looks like the output of [`compress_images()`].
+pub fn is_compressed_images(buf: &[u8]) -> bool {
+    buf.len() >= COMPRESSED_IMAGES_HEADER_LEN && buf.starts_with(COMPRESSED_IMAGES_MAGIC)
+}
+
+/// Returns the uncompressed length recorded in the header of a compressed ROM
+/// images buffer, without decompressing it.
+pub fn uncompressed_len(buf: &[u8]) -> Result<usize> {
+    if !is_compressed_images(buf) {
+        return Err(Error::InvalidCompressedImages {
+            reason: "Missing compressed images header".to_string(),
+        });
+    }
+
+    Ok(u32::from_le_bytes([buf[8], buf[9], buf[10], buf[11]]) as usize)
+}
+
+/// Decompresses a buffer created by [`compress_images()`], returning the
+/// original ROM images, ready to be flashed.
+pub fn decompress_images(buf: &[u8]) -> Result<Vec<u8>> {
+    let expected = uncompressed_len(buf)?;
+
+    let images = decompress_to_vec_with_limit(&buf[COMPRESSED_IMAGES_HEADER_LEN..], expected)
+        .map_err(|e| Error::InvalidCompressedImages {
+            reason: format!("Decompression failed: {:?}", e.status),
+        })?;
+
+    if images.len() != expected {
+        return Err(Error::InvalidCompressedImages {
+            reason: format!(
+                "Decompressed length {} does not match header length {}",
+                images.len(),
+                expected
+            ),
+        });
+    }
+
+    Ok(images)
+}
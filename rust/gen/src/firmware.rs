@@ -7,6 +7,7 @@
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
+use onerom_config::fw::ServeAlg;
 
 // Required for custom schemas implementations
 #[cfg(feature = "schemars")]
@@ -32,6 +33,13 @@ pub struct FirmwareConfig {
 
     /// Optional serving algorithm parameters
     pub serve_alg_params: Option<ServeAlgParams>,
+
+    /// Optional serving algorithm override.  Takes precedence over the chip
+    /// set's `serve_alg` - see [`crate::image::ChipSet::resolve_serve_alg()`].
+    /// Stored as the chip set's serving algorithm, rather than in the
+    /// firmware overrides structure.
+    #[serde(default)]
+    pub serve_alg: Option<ServeAlg>,
 }
 
 impl FirmwareConfig {
@@ -140,8 +148,25 @@ impl FirmwareConfig {
             led,
             swd,
             serve_alg_params: None, // Stored separately
+            serve_alg: None,        // Stored in the chip set
         })
     }
+
+    /// Returns the names of the override fields which are set, in field
+    /// order - for example `["led", "swd"]`.
+    pub fn active_overrides(&self) -> Vec<&'static str> {
+        [
+            ("ice", self.ice.is_some()),
+            ("fire", self.fire.is_some()),
+            ("led", self.led.is_some()),
+            ("swd", self.swd.is_some()),
+            ("serve_alg_params", self.serve_alg_params.is_some()),
+            ("serve_alg", self.serve_alg.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect()
+    }
 }
 
 /// Ice configuration structure
@@ -15,8 +15,10 @@
 //! into a final Chip image to be flashed to One ROM, at an offset pointed to by
 //! the metadata.
 
+use alloc::borrow::Cow;
 use alloc::format;
 use alloc::string::{String, ToString};
+use alloc::vec;
 use alloc::vec::Vec;
 use core::cmp::Ordering;
 
@@ -37,12 +39,25 @@ pub const PAD_BLANK_BYTE: u8 = 0xAA;
 /// Value to use when no Chip in portion of address space
 pub const PAD_NO_CHIP_BYTE: u8 = 0xAA;
 
-/// Value to return when a RAM Chip is read
+/// Value to return when a RAM Chip without an initial image is read
 pub const PAD_RAM_BYTE: u8 = 0x55;
 
+// CS3 state written for CE/OE Chips (`CS_CE_OE` in `sdrr/include/enums.h`).
+// CS1 and CS2 hold the CE and OE logic, which is always active low.  Firmware
+// never checks CS3 for CE/OE Chip types, so older firmware serves these Chips
+// unchanged, while the marker distinguishes them from CS1 active low Chips.
+pub(crate) const CS_STATE_CE_OE: u8 = 3;
+
 const CHIP_METADATA_LEN_NO_FILENAME: usize = 4;
 const CHIP_METADATA_LEN_WITH_FILENAME: usize = 8;
 
+// Values of the chip set's filenames field, in the extended (0.6.0+) chip
+// set structure.  Default means the chip set's chips include filenames if the
+// firmware has boot logging enabled.
+const CHIP_SET_FILENAMES_DEFAULT: u8 = 0;
+pub(crate) const CHIP_SET_FILENAMES_PRESENT: u8 = 1;
+pub(crate) const CHIP_SET_FILENAMES_ABSENT: u8 = 2;
+
 /// How to handle Chip images that are too small for the Chip type
 #[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -61,8 +76,14 @@ pub enum SizeHandling {
     /// exact match size-wise.
     Truncate,
 
-    /// Pads the image out with [`PAD_BLANK_BYTE`].
+    /// Pads the image out with the Chip's fill byte - [`PAD_BLANK_BYTE`]
+    /// unless overridden.
     Pad,
+
+    /// Mirrors the image into the upper half of the Chip, in reverse address
+    /// order - for ROM dumps stored as a half to be reflected.  Errors if the
+    /// image is not exactly half the Chip size.
+    Mirror,
 }
 
 /// Possible Chip Select line logic options
@@ -92,6 +113,104 @@ pub struct Location {
     /// Length of the image within the larger Chip image.  Must match the
     /// selected Chip type, or SizeHandling will be applied.
     pub length: usize,
+
+    /// Optional stride, in bytes, between each byte of the image within the
+    /// larger Chip image - for example, 2 to extract one Chip from a dump
+    /// interleaving two Chips byte-by-byte.  `length` is the number of bytes
+    /// extracted.  If not specified, the image is contiguous.
+    pub stride: Option<usize>,
+}
+
+impl Location {
+    /// Extracts this location from `source`, returning the image.
+    ///
+    /// `id` is the Chip index, used to identify the Chip in any error.
+    pub fn extract<'a>(&self, id: usize, source: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        let stride = self.stride.unwrap_or(1);
+        if stride == 0 {
+            return Err(Error::BadLocation {
+                id,
+                reason: "Location stride must be non-zero".to_string(),
+            });
+        }
+
+        // Bounds check - end is one past the last byte extracted, at
+        // start + (length - 1) * stride
+        let end = match self.length {
+            0 => Some(self.start),
+            length => (length - 1)
+                .checked_mul(stride)
+                .and_then(|span| self.start.checked_add(span))
+                .and_then(|last| last.checked_add(1)),
+        }
+        .ok_or(Error::BadLocation {
+            id,
+            reason: format!(
+                "Location overflow: start={:#X} length={:#X} stride={:#X}",
+                self.start, self.length, stride
+            ),
+        })?;
+
+        if end > source.len() {
+            return Err(Error::ImageTooSmall {
+                index: id,
+                expected: end,
+                actual: source.len(),
+            });
+        }
+
+        let source = &source[self.start..end];
+        if self.stride.is_none() {
+            Ok(Cow::Borrowed(source))
+        } else {
+            Ok(Cow::Owned(source.iter().step_by(stride).copied().collect()))
+        }
+    }
+}
+
+/// Half of a file containing two concatenated Chip images, such as NTSC and
+/// PAL variants, to use.  A shortcut for a [`Location`] splitting the file in
+/// two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Half {
+    /// First half of the file
+    Lower,
+
+    /// Second half of the file
+    Upper,
+}
+
+impl Half {
+    /// Returns the location of this half within a file of the given length.
+    /// Errors if the length is odd, as the file can't be split in two.
+    pub fn location(&self, id: usize, file_len: usize) -> Result<Location> {
+        if file_len % 2 != 0 {
+            return Err(Error::BadLocation {
+                id,
+                reason: format!("Cannot take half of odd length file: {:#X}", file_len),
+            });
+        }
+
+        let length = file_len / 2;
+        let start = match self {
+            Half::Lower => 0,
+            Half::Upper => length,
+        };
+        Ok(Location {
+            start,
+            length,
+            stride: None,
+        })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Half::Lower => "lower",
+            Half::Upper => "upper",
+        }
+    }
 }
 
 impl CsLogic {
@@ -112,6 +231,15 @@ impl CsLogic {
         }
     }
 
+    /// Returns a human readable name, such as "active low"
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CsLogic::ActiveLow => "active low",
+            CsLogic::ActiveHigh => "active high",
+            CsLogic::Ignore => "ignored",
+        }
+    }
+
     pub fn c_enum_val(&self) -> u8 {
         match self {
             CsLogic::ActiveLow => 0,
@@ -119,6 +247,39 @@ impl CsLogic {
             CsLogic::Ignore => 2,
         }
     }
+
+    /// Returns [`CsLogic::ActiveLow`] if `active_low`, otherwise
+    /// [`CsLogic::ActiveHigh`].
+    pub fn from_bool(active_low: bool) -> Self {
+        if active_low {
+            CsLogic::ActiveLow
+        } else {
+            CsLogic::ActiveHigh
+        }
+    }
+
+    /// Returns whether a line with this logic is active, given whether its
+    /// bit is set in the value read from the bus.  `invert` inverts the
+    /// result, for reads with inverted CS1/X1/X2 logic.
+    ///
+    /// [`CsLogic::Ignore`] lines are don't-care, so are always active.
+    pub fn is_active(&self, bit_set: bool, invert: bool) -> bool {
+        match self {
+            CsLogic::ActiveLow => bit_set == invert,
+            CsLogic::ActiveHigh => bit_set != invert,
+            CsLogic::Ignore => true,
+        }
+    }
+
+    /// Inverse of [`Self::c_enum_val()`]
+    pub(crate) fn from_c_enum_val(val: u8) -> Option<Self> {
+        match val {
+            0 => Some(CsLogic::ActiveLow),
+            1 => Some(CsLogic::ActiveHigh),
+            2 => Some(CsLogic::Ignore),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -149,6 +310,7 @@ impl CsConfig {
         }
     }
 
+    /// Returns CS1's logic.  For CE/OE, this is CE's logic - active low.
     pub fn cs1_logic(&self) -> CsLogic {
         match self {
             CsConfig::ChipSelect { cs1, .. } => *cs1,
@@ -156,6 +318,8 @@ impl CsConfig {
         }
     }
 
+    /// Returns CS2's logic, if used.  For CE/OE, this is OE's logic - active
+    /// low.
     pub fn cs2_logic(&self) -> Option<CsLogic> {
         match self {
             CsConfig::ChipSelect { cs2, .. } => *cs2,
@@ -163,6 +327,7 @@ impl CsConfig {
         }
     }
 
+    /// Returns CS3's logic, if used.  Never used for CE/OE.
     pub fn cs3_logic(&self) -> Option<CsLogic> {
         match self {
             CsConfig::ChipSelect { cs3, .. } => *cs3,
@@ -190,9 +355,65 @@ pub struct Chip {
 
     // Optional location within a larger Chip image
     location: Option<Location>,
+
+    // Size and checksum of the source file, if there was one
+    #[serde(default)]
+    provenance: Option<Provenance>,
+
+    // Optional overrides of the board's CS2/CS3 address bit positions
+    #[serde(default)]
+    cs2_bit: Option<u8>,
+    #[serde(default)]
+    cs3_bit: Option<u8>,
+
+    // Optional override of PAD_BLANK_BYTE, used when padding the image
+    #[serde(default)]
+    fill_byte: Option<u8>,
+}
+
+/// Size and CRC-32 of the source file a Chip image was built from.  Taken
+/// before any location slicing or size handling, so it identifies the exact
+/// source dump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Provenance {
+    /// Size of the source file in bytes
+    pub size: u32,
+
+    /// CRC-32 (IEEE) of the source file - see [`crc32()`]
+    pub checksum: u32,
+}
+
+impl Provenance {
+    /// Creates provenance information for a source file
+    pub fn from_source(source: &[u8]) -> Self {
+        Self {
+            size: source.len() as u32,
+            checksum: crc32(source),
+        }
+    }
+}
+
+/// Calculates the CRC-32 (IEEE 802.3, as used by zip and PNG) of the data.
+pub fn crc32(data: &[u8]) -> u32 {
+    !crc32_update(0xFFFF_FFFF, data)
+}
+
+// Updates a running CRC-32, before its final inversion, so data which isn't
+// contiguous can be covered.  Start with 0xFFFFFFFF.
+pub(crate) fn crc32_update(mut crc: u32, data: &[u8]) -> u32 {
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    crc
 }
 
 impl Chip {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         index: usize,
         filename: String,
@@ -201,6 +422,8 @@ impl Chip {
         cs_config: CsConfig,
         data: Option<Vec<u8>>,
         location: Option<Location>,
+        provenance: Option<Provenance>,
+        fill_byte: Option<u8>,
     ) -> Self {
         Self {
             index,
@@ -210,9 +433,26 @@ impl Chip {
             cs_config,
             data,
             location,
+            provenance,
+            cs2_bit: None,
+            cs3_bit: None,
+            fill_byte,
         }
     }
 
+    /// Returns a Chip without an image.  Used to size metadata without
+    /// processing file data - see `Builder::output_sizes()` - and to describe
+    /// a Chip's layout for [`Self::unscramble_image()`].
+    pub fn placeholder(
+        index: usize,
+        filename: String,
+        label: Option<String>,
+        chip_type: &ChipType,
+        cs_config: CsConfig,
+    ) -> Self {
+        Self::new(index, filename, label, chip_type, cs_config, None, None, None, None)
+    }
+
     /// Returns the index of the Chip in the configuration
     pub fn index(&self) -> usize {
         self.index
@@ -229,20 +469,111 @@ impl Chip {
         self.label.as_deref().unwrap_or(&self.filename)
     }
 
+    /// Returns the Chip's label, if specified.
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
     /// Returns the Chip type.
     pub fn chip_type(&self) -> &ChipType {
         &self.chip_type
     }
 
+    /// Overrides the address bit positions the board routes CS2 and CS3 to,
+    /// for boards where these lines are wired to unusual address bits.
+    /// `None` uses the board's position.  Positions must be within 0-15, and
+    /// are checked against the board by [`ChipSet::validate_cs_bits()`].
+    pub fn set_cs_bits(&mut self, cs2_bit: Option<u8>, cs3_bit: Option<u8>) -> Result<()> {
+        for (line, bit) in [("CS2", cs2_bit), ("CS3", cs3_bit)] {
+            #[allow(clippy::collapsible_if)]
+            if let Some(bit) = bit {
+                if bit > 15 {
+                    return Err(Error::InvalidCsBit {
+                        id: self.index,
+                        reason: format!("{} bit {} is not within 0-15", line, bit),
+                    });
+                }
+            }
+        }
+
+        self.cs2_bit = cs2_bit;
+        self.cs3_bit = cs3_bit;
+        Ok(())
+    }
+
+    /// Returns the address bit position of CS2 for this Chip - any override,
+    /// otherwise the board's.
+    pub fn bit_cs2(&self, board: &Board) -> u8 {
+        self.cs2_bit.unwrap_or_else(|| board.bit_cs2(self.chip_type))
+    }
+
+    /// Returns the address bit position of CS3 for this Chip - any override,
+    /// otherwise the board's.
+    pub fn bit_cs3(&self, board: &Board) -> u8 {
+        self.cs3_bit.unwrap_or_else(|| board.bit_cs3(self.chip_type))
+    }
+
+    /// Returns the byte used to pad this Chip's image - any override,
+    /// otherwise [`PAD_BLANK_BYTE`].
+    pub fn fill_byte(&self) -> u8 {
+        self.fill_byte.unwrap_or(PAD_BLANK_BYTE)
+    }
+
     pub fn has_data(&self) -> bool {
         self.data.is_some()
     }
 
+    /// Moves this Chip's image up by `offset` bytes within the Chip's
+    /// address window, so it responds at a non-zero offset - for example, to
+    /// shadow only part of the address space.  Addresses below the offset
+    /// read as the fill byte, and the top `offset` bytes of the image are
+    /// discarded, so the caller must ensure these are only padding.
+    ///
+    /// Has no effect on Chips without data.
+    pub fn set_window_offset(&mut self, offset: usize) -> Result<()> {
+        let size = self.chip_type.size_bytes();
+        if offset >= size {
+            return Err(Error::BadLocation {
+                id: self.index,
+                reason: format!(
+                    "Window offset {:#X} not within {} window of {:#X} bytes",
+                    offset,
+                    self.chip_type.name(),
+                    size
+                ),
+            });
+        }
+
+        let fill_byte = self.fill_byte();
+        if let Some(data) = &mut self.data {
+            data.copy_within(..size - offset, offset);
+            data[..offset].fill(fill_byte);
+        }
+        Ok(())
+    }
+
+    /// Returns the size and checksum of the source file this Chip was built
+    /// from, if any.
+    pub fn provenance(&self) -> Option<&Provenance> {
+        self.provenance.as_ref()
+    }
+
     /// Returns a [`Chip`] instance.
     ///
     /// Takes a raw Chip image (binary data, loaded from file) and processes it
     /// according to the specified size handling (none, duplicate, pad) to
     /// ensure it matches the expected size for the given Chip type.
+    ///
+    /// `fill_byte` overrides [`PAD_BLANK_BYTE`] when padding - for example,
+    /// 0xFF for EPROMs, whose unprogrammed state is all 1s.
+    ///
+    /// When truncating, `strict_truncate` rejects images whose discarded tail
+    /// is not entirely the fill byte, as this would drop real data.  When
+    /// false, any tail is silently discarded.
+    ///
+    /// RAM Chips may be given a source as their initial image, which is
+    /// processed exactly as for ROMs.  Without one, they have no data, and
+    /// read as [`PAD_RAM_BYTE`].
     #[allow(clippy::too_many_arguments)]
     pub fn from_raw_rom_image(
         index: usize,
@@ -254,11 +585,14 @@ impl Chip {
         cs_config: CsConfig,
         size_handling: &SizeHandling,
         location: Option<Location>,
+        fill_byte: Option<u8>,
+        strict_truncate: bool,
     ) -> Result<Self> {
         if source.is_none() {
             if chip_type.chip_function() == ChipFunction::Ram {
                 return Ok(Self::new(
-                    index, filename, label, chip_type, cs_config, None, location,
+                    index, filename, label, chip_type, cs_config, None, location, None,
+                    fill_byte,
                 ))
             } else {
                 // This is an internal error
@@ -267,33 +601,14 @@ impl Chip {
         }
 
         let source = source.unwrap();
+        let provenance = Provenance::from_source(source);
 
         // Slice source if location specified
-        let source = if let Some(loc) = location {
-            // Bounds check
-            let end = loc
-                .start
-                .checked_add(loc.length)
-                .ok_or(Error::BadLocation {
-                    id: index,
-                    reason: format!(
-                        "Location overflow: start={:#X} length={:#X}",
-                        loc.start, loc.length
-                    ),
-                })?;
-
-            if end > source.len() {
-                return Err(Error::ImageTooSmall {
-                    index,
-                    expected: end,
-                    actual: source.len(),
-                });
-            }
-
-            &source[loc.start..end]
-        } else {
-            source
+        let source = match location {
+            Some(loc) => loc.extract(index, source)?,
+            None => Cow::Borrowed(source),
         };
+        let source: &[u8] = &source;
 
         let expected_size = chip_type.size_bytes();
         if dest.len() < expected_size {
@@ -313,6 +628,12 @@ impl Chip {
                         // Copy source to dest as-is
                         dest[..expected_size].copy_from_slice(&source[..expected_size]);
                     }
+                    SizeHandling::Mirror => {
+                        return Err(Error::MirrorNotHalfSize {
+                            image_size: source.len(),
+                            expected_size,
+                        });
+                    }
                     _ => {
                         return Err(Error::RightSize {
                             size: expected_size,
@@ -347,10 +668,11 @@ impl Chip {
                         }
                     }
                     SizeHandling::Pad => {
-                        // Copy source to dest and pad the rest with 0xAA
+                        // Copy source to dest and pad the rest with the fill
+                        // byte
                         dest[..source.len()].copy_from_slice(source);
                         for byte in &mut dest[source.len()..expected_size] {
-                            *byte = PAD_BLANK_BYTE;
+                            *byte = fill_byte.unwrap_or(PAD_BLANK_BYTE);
                         }
                     }
                     SizeHandling::Truncate => {
@@ -359,14 +681,50 @@ impl Chip {
                             expected_size,
                         });
                     }
+                    SizeHandling::Mirror => {
+                        if source.len() * 2 != expected_size {
+                            return Err(Error::MirrorNotHalfSize {
+                                image_size: source.len(),
+                                expected_size,
+                            });
+                        }
+
+                        // Copy source to the lower half, and reflect it into
+                        // the upper half
+                        let half = source.len();
+                        dest[..half].copy_from_slice(source);
+                        for (byte, &src) in dest[half..expected_size]
+                            .iter_mut()
+                            .zip(source.iter().rev())
+                        {
+                            *byte = src;
+                        }
+                    }
                 }
             }
             Ordering::Greater => {
                 match size_handling {
                     SizeHandling::Truncate => {
+                        // In strict mode, only a tail consisting entirely of
+                        // the fill byte may be discarded
+                        let pad = fill_byte.unwrap_or(PAD_BLANK_BYTE);
+                        let tail = &source[expected_size..];
+                        if strict_truncate && tail.iter().any(|&b| b != pad) {
+                            return Err(Error::TruncationLosesData {
+                                id: index,
+                                dropped_bytes: tail.len(),
+                            });
+                        }
+
                         // Copy only up to expected size
                         dest[..expected_size].copy_from_slice(&source[..expected_size]);
                     }
+                    SizeHandling::Mirror => {
+                        return Err(Error::MirrorNotHalfSize {
+                            image_size: source.len(),
+                            expected_size,
+                        });
+                    }
                     _ => {
                         return Err(Error::ImageTooLarge {
                             image_size: source.len(),
@@ -378,7 +736,15 @@ impl Chip {
         }
 
         Ok(Self::new(
-            index, filename, label, chip_type, cs_config, Some(dest), location,
+            index,
+            filename,
+            label,
+            chip_type,
+            cs_config,
+            Some(dest),
+            location,
+            Some(provenance),
+            fill_byte,
         ))
     }
 
@@ -425,14 +791,17 @@ impl Chip {
     //
     // This transformation ensures that when the hardware reads a byte through its
     // data pins, it gets the correct bit values despite the non-standard connections.
-    fn byte_mangled(byte: u8, board: &Board) -> u8 {
+    //
+    // `data_pins` is the board's data pin mapping (D0-D7), or any override of
+    // it - see `ChipSet::data_pins()`.
+    //
+    // `inverted` is the mask of physical bits whose data lines are inverted
+    // between the MCU and the socket, XORed after reordering - see
+    // `ChipSet::data_inversion()`.
+    fn byte_mangled(byte: u8, data_pins: &[u8], inverted: u8) -> u8 {
         // Start with 0 result
         let mut result = 0;
 
-        // Retrieve data pin mapping - not physical pin to bit mapping, as that would be
-        // the wrong way round.
-        let data_pins = board.data_pins();
-
         // For each bit in the original byte
         #[allow(clippy::needless_range_loop)]
         for bit_pos in 0..8 {
@@ -442,20 +811,128 @@ impl Chip {
                 let mut new_pos = data_pins[bit_pos];
                 if new_pos > 15 {
                     // Fire rev A
-                    assert!(new_pos < 24);
+                    debug_assert!(new_pos < 24);
                     new_pos -= 16;
                 } else {
                     // All other boards
-                    assert!(new_pos < 8);
+                    debug_assert!(new_pos < 8);
                 }
                 // Set the bit in the result at its new position
                 result |= 1 << new_pos;
             }
         }
 
+        result ^ inverted
+    }
+
+    // Reverses `byte_mangled()`, recovering the logical byte from the byte as
+    // served on the physical data pins.
+    fn byte_unmangled(byte: u8, data_pins: &[u8], inverted: u8) -> u8 {
+        let byte = byte ^ inverted;
+        let mut result = 0;
+
+        for (bit_pos, &pin) in data_pins.iter().enumerate().take(8) {
+            let phys_pos = data_pin_bit(pin);
+            if (byte & (1 << phys_pos)) != 0 {
+                result |= 1 << bit_pos;
+            }
+        }
+
         result
     }
 
+    // As `byte_mangled()`, but for a 16-bit word served on D0-D15, for Chip
+    // types used in 16-bit mode.  `data_pins` must have at least 16 entries.
+    fn word_mangled(word: u16, data_pins: &[u8], inverted: u16) -> u16 {
+        let mut result = 0;
+
+        for (bit_pos, &pin) in data_pins.iter().enumerate().take(16) {
+            if (word & (1 << bit_pos)) != 0 {
+                debug_assert!(pin < 16, "Data pin {} out of range for 16-bit mode", pin);
+                result |= 1 << pin;
+            }
+        }
+
+        result ^ inverted
+    }
+
+    // Reverses `word_mangled()`.
+    fn word_unmangled(word: u16, data_pins: &[u8], inverted: u16) -> u16 {
+        let word = word ^ inverted;
+        let mut result = 0;
+
+        for (bit_pos, &pin) in data_pins.iter().enumerate().take(16) {
+            if (word & (1 << pin)) != 0 {
+                result |= 1 << bit_pos;
+            }
+        }
+
+        result
+    }
+
+    // Get the 16-bit word at the given word address, with both address and
+    // data transformations applied, for Chip types used in 16-bit mode.
+    //
+    // The Chip image is stored as it would be read in byte mode, so the low
+    // byte (D0-D7) of each word is at the even logical address and the high
+    // byte (D8-D15) at the following odd one.  The byte select line (A-1,
+    // logical address line 0) must already have been removed from
+    // `phys_pin_to_addr_map`, as in 16-bit mode that pin is D15.
+    fn get_word(
+        &self,
+        phys_pin_to_addr_map: &[Option<usize>],
+        address: usize,
+        board: &Board,
+        data_pins: &[u8],
+        inverted: u16,
+    ) -> Result<u16> {
+        let data = self.data.as_ref().expect("Shouldn't be called get_word on empty image");
+
+        let num_addr_lines = self.chip_type.num_addr_lines();
+        let transformed_address =
+            Self::address_to_logical(phys_pin_to_addr_map, address, board, num_addr_lines);
+        debug_assert!(
+            transformed_address & 1 == 0,
+            "Byte select line must not be mapped in 16-bit mode"
+        );
+
+        if transformed_address + 1 >= data.len() {
+            return Err(Error::AddressOutOfBounds {
+                address: transformed_address,
+                chip_size: data.len(),
+            });
+        }
+
+        let word = u16::from_le_bytes([data[transformed_address], data[transformed_address + 1]]);
+        Ok(Self::word_mangled(word, data_pins, inverted))
+    }
+
+    /// Reverses the address and data transformations applied by
+    /// [`ChipSet::get_byte()`] for a single Chip set containing this Chip,
+    /// recovering the logical Chip image from the physical image as stored in
+    /// flash - for example, to compare it against a known-good dump.
+    ///
+    /// Only this Chip's type, CS configuration and any CS bit overrides are
+    /// used - its own image data is ignored.  Use
+    /// [`ChipSet::from_flash_image()`] for multi and banked sets.  Errors if
+    /// the physical image isn't the size of a single Chip set's image on
+    /// this board.
+    pub fn unscramble_image(&self, board: &Board, physical: &[u8]) -> Result<Vec<u8>> {
+        let mut chip = Self::placeholder(
+            self.index,
+            self.filename.clone(),
+            self.label.clone(),
+            &self.chip_type,
+            self.cs_config.clone(),
+        );
+        chip.cs2_bit = self.cs2_bit;
+        chip.cs3_bit = self.cs3_bit;
+
+        let chip_set = ChipSet::new(0, ChipSetType::Single, ServeAlg::Default, vec![chip], None)?;
+        let mut images = chip_set.from_flash_image(physical, board, false)?;
+        Ok(images.remove(0))
+    }
+
     // Get byte at the given address with both address and data
     // transformations applied.
     //
@@ -468,12 +945,17 @@ impl Chip {
     // This ensures that when the hardware reads from a certain address
     // through its GPIO pins, it gets the correct byte value with bits
     // arranged according to its data pin connections.
+    //
+    // Errors if the logical address falls outside the Chip image, which
+    // indicates an inconsistent board pin map.
     fn get_byte(
         &self,
         phys_pin_to_addr_map: &[Option<usize>],
         address: usize,
         board: &Board,
-    ) -> u8 {
+        data_pins: &[u8],
+        inverted: u8,
+    ) -> Result<u8> {
         let data = self.data.as_ref().expect("Shouldn't be called get_byte on empty image");
 
         // We have been passed a physical address based on the hardware pins,
@@ -483,31 +965,16 @@ impl Chip {
         let transformed_address =
             Self::address_to_logical(phys_pin_to_addr_map, address, board, num_addr_lines);
 
-        // Sanity check that we did get a logical address, which must by
+        // Get the byte from the logical Chip address, which must by
         // definition fit within the actual Chip size.
-        if transformed_address >= data.len() {
-            panic!(
-                "Transformed address {} out of bounds for Chip image of size {}",
-                transformed_address,
-                data.len()
-            );
-        }
-
-        // Get the byte from the logical Chip address.
-        let byte = data
-            .get(transformed_address)
-            .copied()
-            .unwrap_or_else(|| {
-                panic!(
-                    "Address {} out of bounds for Chip image of size {}",
-                    transformed_address,
-                    data.len()
-                )
-            });
+        let byte = data.get(transformed_address).copied().ok_or(Error::AddressOutOfBounds {
+            address: transformed_address,
+            chip_size: data.len(),
+        })?;
 
         // Now transform the byte, as the physical data lines are not in the
         // expected order (0-7).
-        Self::byte_mangled(byte, board)
+        Ok(Self::byte_mangled(byte, data_pins, inverted))
     }
 
     // See `sdrr/include/enums.h`
@@ -536,6 +1003,34 @@ impl Chip {
             ChipType::Chip6116 => 20,
         }
     }
+
+    // Inverse of `chip_type_c_enum_val()`
+    pub(crate) fn chip_type_from_c_enum_val(val: u8) -> Option<ChipType> {
+        const CHIP_TYPES: [ChipType; 21] = [
+            ChipType::Chip2316,
+            ChipType::Chip2332,
+            ChipType::Chip2364,
+            ChipType::Chip23128,
+            ChipType::Chip23256,
+            ChipType::Chip23512,
+            ChipType::Chip2704,
+            ChipType::Chip2708,
+            ChipType::Chip2716,
+            ChipType::Chip2732,
+            ChipType::Chip2764,
+            ChipType::Chip27128,
+            ChipType::Chip27256,
+            ChipType::Chip27512,
+            ChipType::Chip231024,
+            ChipType::Chip27C010,
+            ChipType::Chip27C020,
+            ChipType::Chip27C040,
+            ChipType::Chip27C080,
+            ChipType::Chip27C400,
+            ChipType::Chip6116,
+        ];
+        CHIP_TYPES.get(val as usize).copied()
+    }
 }
 
 /// Type of Chip set
@@ -556,6 +1051,111 @@ pub enum ChipSetType {
     Multi,
 }
 
+impl ChipSetType {
+    /// Maximum number of Chips a set of this type can contain on a board with
+    /// both X1 and X2 available.  See [`BoardChipSetLimits`] for the limit on
+    /// a specific board.
+    pub const fn max_chips(&self) -> usize {
+        match self {
+            ChipSetType::Single => 1,
+            // Selected by X1/X2 jumpers
+            ChipSetType::Banked => 4,
+            // Selected by CS1/X1/X2
+            ChipSetType::Multi => 3,
+        }
+    }
+}
+
+/// Chip set limits imposed by a board's available X lines
+pub trait BoardChipSetLimits {
+    /// Maximum number of Chips a set of the given type can contain on this
+    /// board.
+    fn max_chips_in_set(&self, set_type: &ChipSetType) -> usize;
+}
+
+impl BoardChipSetLimits for Board {
+    fn max_chips_in_set(&self, set_type: &ChipSetType) -> usize {
+        let num_x = [self.pin_x1(), self.pin_x2()]
+            .iter()
+            .filter(|&&pin| pin != 255)
+            .count();
+        let max = match set_type {
+            ChipSetType::Single => 1,
+            ChipSetType::Banked => 1 << num_x,
+            ChipSetType::Multi => self.max_chips_in_multi_set() as usize,
+        };
+        max.min(set_type.max_chips())
+    }
+}
+
+/// Resolution of a serving algorithm to the one the firmware will run
+pub trait ResolveServeAlg {
+    /// Returns the concrete algorithm the firmware runs for a Chip set of the
+    /// given type - never [`ServeAlg::Default`].  Multi-ROM sets always run
+    /// [`ServeAlg::AddrOnAnyCs`], and [`ServeAlg::Default`] is
+    /// [`ServeAlg::AddrOnCs`] for single and banked sets.
+    fn resolve(&self, set_type: &ChipSetType) -> ServeAlg;
+}
+
+impl ResolveServeAlg for ServeAlg {
+    fn resolve(&self, set_type: &ChipSetType) -> ServeAlg {
+        match (set_type, self) {
+            (ChipSetType::Multi, _) => ServeAlg::AddrOnAnyCs,
+            (_, ServeAlg::Default) => ServeAlg::AddrOnCs,
+            (_, serve_alg) => *serve_alg,
+        }
+    }
+}
+
+/// Returns an image the size of the given Chip type, filled with a constant
+/// value.
+pub fn test_pattern_image(chip_type: &ChipType, value: u8) -> Vec<u8> {
+    vec![value; chip_type.size_bytes()]
+}
+
+/// Returns the built-in synthetic ROM image with the given name, or `None`
+/// if there is no such image.  Referenced from configs as
+/// `"@builtin:<name>"`.  Supported names, where `<n>` is the size in KB
+/// (1-1024):
+/// - `ramp<n>k` - each byte is the low 8 bits of its address
+/// - `blank<n>k` - all 0xFF, as an unprogrammed EPROM
+pub fn builtin_image(name: &str) -> Option<Vec<u8>> {
+    let (kind, size) = name.strip_suffix('k').and_then(|name| {
+        let split = name.find(|c: char| c.is_ascii_digit())?;
+        let kb = name[split..].parse::<usize>().ok()?;
+        (1..=1024).contains(&kb).then_some((&name[..split], kb * 1024))
+    })?;
+
+    match kind {
+        "ramp" => Some((0..size).map(|addr| addr as u8).collect()),
+        "blank" => Some(vec![0xFF; size]),
+        _ => None,
+    }
+}
+
+/// Returns the constant Chip `index` is filled with by
+/// [`ChipSet::diagnostic_multi()`] - 0x11 for Chip 0, 0x22 for Chip 1, etc.
+pub const fn diagnostic_fill_byte(index: usize) -> u8 {
+    ((index as u8) + 1) * 0x11
+}
+
+/// Returns the bank a banked Chip set serves at `address`, from the X1 and X2
+/// bits (`x1_bit`, `x2_bit`) and their jumper pull directions (0=down, 1=up).
+///
+/// A jumper pulling to GND selects by driving its X line low, so that line's
+/// logic is inverted.  X1 is bit 0 of the bank, X2 bit 1.
+pub const fn banked_chip_index(
+    address: usize,
+    x1_bit: u8,
+    x2_bit: u8,
+    x1_pull: u8,
+    x2_pull: u8,
+) -> usize {
+    let x1 = ((address >> x1_bit) & 1) ^ (x1_pull as usize ^ 1);
+    let x2 = ((address >> x2_bit) & 1) ^ (x2_pull as usize ^ 1);
+    x1 | (x2 << 1)
+}
+
 /// A set of Chips, where the set type is ChipSetType
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
@@ -574,6 +1174,34 @@ pub struct ChipSet {
 
     /// Optional firmware configuration overrides for this Chip set
     pub firmware_overrides: Option<FirmwareConfig>,
+
+    /// Optional override of the board's data pin mapping (D0-D7), used when
+    /// mangling bytes.  Set using [`ChipSet::set_data_pin_map()`].
+    #[serde(default)]
+    pub data_pin_map: Option<Vec<u8>>,
+
+    /// Optional override of the board's inverted data pins, used when
+    /// mangling bytes.  Set using [`ChipSet::set_data_inverted_pins()`].
+    #[serde(default)]
+    pub data_inverted_pins: Option<Vec<u8>>,
+
+    /// Optional remapping of the Chips' address lines, for adapters which
+    /// rewire the socket's address lines.  Entry `i` is the Chip address
+    /// line the socket's A`i` is wired to.  Set using
+    /// [`ChipSet::set_addr_line_map()`].
+    #[serde(default)]
+    pub addr_line_map: Option<Vec<usize>>,
+
+    /// Optional override of [`PAD_NO_CHIP_BYTE`], served when no Chip is
+    /// selected.  Set using [`ChipSet::set_no_chip_byte()`].
+    #[serde(default)]
+    pub no_chip_byte: Option<u8>,
+
+    /// Optional override of the firmware's boot logging setting for this
+    /// Chip set, controlling whether its Chips' filenames are included.  Set
+    /// using [`ChipSet::set_boot_logging()`].
+    #[serde(default)]
+    pub boot_logging: Option<bool>,
 }
 
 impl ChipSet {
@@ -581,7 +1209,8 @@ impl ChipSet {
     /// given Chips.
     ///
     /// The ID is an arbitrary index, usually the set ID from the config,
-    /// starting at 0.
+    /// starting at 0.  Any `serve_alg` in the firmware overrides replaces the
+    /// given serving algorithm - see [`Self::resolve_serve_alg()`].
     pub fn new(
         id: usize,
         set_type: ChipSetType,
@@ -602,6 +1231,13 @@ impl ChipSet {
             });
         }
 
+        if chips.len() > set_type.max_chips() {
+            return Err(Error::TooManyChips {
+                expected: set_type.max_chips(),
+                actual: chips.len(),
+            });
+        }
+
         if chips.len() == 1 && set_type != ChipSetType::Single {
             return Err(Error::TooFewChips {
                 expected: 2,
@@ -609,32 +1245,143 @@ impl ChipSet {
             });
         }
 
-        // Correct the serving algorithm if necessary - we accept any value
-        // if a multi-rom set, and correct it.  But we don't accept an invalid
-        // value for the other set types.
-        let serve_alg = match set_type {
+        // Chips in a multi set are served from the same socket's address and
+        // CS lines, so must all fit the same socket
+        if set_type == ChipSetType::Multi {
+            let first = &chips[0].chip_type;
+            if let Some(chip) = chips
+                .iter()
+                .find(|chip| chip.chip_type.chip_pins() != first.chip_pins())
+            {
+                return Err(Error::IncompatibleMultiChip {
+                    id: chip.index,
+                    reason: format!(
+                        "{} is a {} pin chip, but {} is a {} pin chip",
+                        chip.chip_type,
+                        chip.chip_type.chip_pins(),
+                        first,
+                        first.chip_pins()
+                    ),
+                });
+            }
+        }
+
+        let serve_alg =
+            Self::resolve_serve_alg(&set_type, firmware_overrides.as_ref(), None, serve_alg)?;
+        Self::check_firmware_overrides(serve_alg, firmware_overrides.as_ref())?;
+
+        Ok(Self {
+            id,
+            set_type,
+            serve_alg,
+            chips,
+            firmware_overrides,
+            data_pin_map: None,
+            data_inverted_pins: None,
+            addr_line_map: None,
+            no_chip_byte: None,
+            boot_logging: None,
+        })
+    }
+
+    /// Validates the Chip set without generating any images - checks CS
+    /// logic is consistent across the set, the serving algorithm, any
+    /// firmware overrides, and that the set is compatible with the given
+    /// board and firmware version.
+    ///
+    /// [`Builder::build()`](crate::builder::Builder::build) runs the same
+    /// checks, so this can be used to check a set will build up front.
+    pub fn validate(&self, board: &Board, version: &FirmwareVersion) -> Result<()> {
+        // 16-bit Chip types
+        self.validate_16_bit(board)?;
+
+        // CS logic
+        self.multi_cs_logic()?;
+        self.validate_addr_maps(board)?;
+        self.validate_cs_bits(board)?;
+        self.validate_multi_addressing(board)?;
+        if let Some(data_pin_map) = &self.data_pin_map {
+            Self::validate_data_pin_map(board, data_pin_map)?;
+        }
+        if let Some(data_inverted_pins) = &self.data_inverted_pins {
+            Self::validate_data_inverted_pins(board, data_inverted_pins)?;
+        }
+        if let Some(addr_line_map) = &self.addr_line_map {
+            self.validate_addr_line_map(addr_line_map)?;
+        }
+
+        // Serving algorithm and firmware overrides
+        Self::check_serve_alg(&self.set_type, self.serve_alg)?;
+        Self::check_firmware_overrides(self.serve_alg, self.firmware_overrides.as_ref())?;
+
+        // Firmware overrides and the boot logging override are stored in the
+        // extended chip set structure
+        if (self.firmware_overrides.is_some() || self.boot_logging.is_some())
+            && version < &MIN_FIRMWARE_OVERRIDES_VERSION
+        {
+            return Err(Error::FirmwareTooOld {
+                version: *version,
+                minimum: MIN_FIRMWARE_OVERRIDES_VERSION,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the serving algorithm for a Chip set, in order of
+    /// precedence:
+    /// - the `serve_alg` in the set's firmware overrides
+    /// - the `serve_alg` configured for the set
+    /// - `default`, usually the firmware's default for the board
+    ///
+    /// Multi-ROM sets are always served with [`ServeAlg::AddrOnAnyCs`],
+    /// whichever algorithm was chosen.  Returns [`Error::InvalidServeAlg`] if
+    /// the chosen algorithm can't be used for a single or banked set.
+    pub fn resolve_serve_alg(
+        set_type: &ChipSetType,
+        firmware_overrides: Option<&FirmwareConfig>,
+        serve_alg: Option<ServeAlg>,
+        default: ServeAlg,
+    ) -> Result<ServeAlg> {
+        let serve_alg = firmware_overrides
+            .and_then(|overrides| overrides.serve_alg)
+            .or(serve_alg)
+            .unwrap_or(default);
+        Self::check_serve_alg(set_type, serve_alg)
+    }
+
+    // Checks the serving algorithm is valid for the set type, returning the
+    // algorithm to use.  We accept any value if a multi-rom set, and correct
+    // it.  But we don't accept an invalid value for the other set types.
+    fn check_serve_alg(set_type: &ChipSetType, serve_alg: ServeAlg) -> Result<ServeAlg> {
+        match set_type {
             ChipSetType::Single | ChipSetType::Banked => {
                 if !matches!(
                     serve_alg,
                     ServeAlg::Default | ServeAlg::AddrOnCs | ServeAlg::TwoCsOneAddr
                 ) {
-                    return Err(Error::InvalidServeAlg { serve_alg });
+                    Err(Error::InvalidServeAlg {
+                        serve_alg,
+                        reason: "only valid for multi-ROM sets".to_string(),
+                    })
                 } else {
-                    serve_alg
+                    Ok(serve_alg)
                 }
             }
-            ChipSetType::Multi => ServeAlg::AddrOnAnyCs,
-        };
+            ChipSetType::Multi => Ok(ServeAlg::AddrOnAnyCs),
+        }
+    }
 
-        // Validate firmware overrides if present
+    // Checks any firmware overrides have at least one field, and any
+    // serve_alg_params have a number of parameters the serving algorithm
+    // accepts.
+    fn check_firmware_overrides(
+        serve_alg: ServeAlg,
+        firmware_overrides: Option<&FirmwareConfig>,
+    ) -> Result<()> {
         #[allow(clippy::collapsible_if)]
-        if let Some(ref overrides) = firmware_overrides {
-            if overrides.ice.is_none()
-                && overrides.fire.is_none()
-                && overrides.led.is_none()
-                && overrides.swd.is_none()
-                && overrides.serve_alg_params.is_none()
-            {
+        if let Some(overrides) = firmware_overrides {
+            if overrides.active_overrides().is_empty() {
                 return Err(Error::InvalidConfig {
                     error: "firmware_overrides specified but all fields are None".to_string(),
                 });
@@ -647,22 +1394,317 @@ impl ChipSet {
                         error: "serve_alg_params specified but params vec is empty".to_string(),
                     });
                 }
+
+                let count = params.params.len();
+                match serve_alg.expected_param_count() {
+                    Some(expected) if !expected.contains(&count) => {
+                        return Err(Error::InvalidConfig {
+                            error: format!(
+                                "serve_alg_params for serve algorithm {serve_alg:?} must have {} to {} params, found {count}",
+                                expected.start(),
+                                expected.end()
+                            ),
+                        });
+                    }
+                    Some(_) => (),
+                    None => {
+                        return Err(Error::InvalidConfig {
+                            error: format!(
+                                "serve algorithm {serve_alg:?} takes no serve_alg_params, found {count}"
+                            ),
+                        });
+                    }
+                }
             }
         }
+        Ok(())
+    }
 
-        Ok(Self {
-            id,
-            set_type,
-            serve_alg,
-            chips,
-            firmware_overrides,
-        })
+    /// Overrides the firmware's boot logging setting for this Chip set, so
+    /// only Chip sets of interest carry filenames.  Requires 0.6.0 firmware
+    /// onwards, as the override is stored in the extended Chip set structure.
+    pub fn set_boot_logging(&mut self, boot_logging: bool) {
+        self.boot_logging = Some(boot_logging);
+    }
+
+    /// Returns any override of the firmware's boot logging setting for this
+    /// Chip set.
+    pub fn boot_logging(&self) -> Option<bool> {
+        self.boot_logging
+    }
+
+    /// Remaps the address lines of this set's Chips, for adapters which
+    /// rewire the socket's address lines.  Entry `i` is the Chip address
+    /// line the socket's A`i` is wired to, so `[1, 0, 2, ...]` swaps A0 and
+    /// A1.  Applied on top of any Chip type specific handling, such as the
+    /// 2732's swapped A11 and A12.
+    ///
+    /// The map must be a permutation of every Chip's address lines.  Not
+    /// supported for 16-bit Chip sets.
+    pub fn set_addr_line_map(&mut self, addr_line_map: Vec<usize>) -> Result<()> {
+        self.validate_addr_line_map(&addr_line_map)?;
+        self.addr_line_map = Some(addr_line_map);
+        Ok(())
+    }
+
+    /// Checks an address line map is a permutation of the address lines of
+    /// every Chip in this set.
+    pub fn validate_addr_line_map(&self, addr_line_map: &[usize]) -> Result<()> {
+        for chip in &self.chips {
+            let num_addr_lines = chip.chip_type.num_addr_lines();
+            let reason = if self.is_16_bit() {
+                Some("Not supported for 16-bit Chip sets".to_string())
+            } else if addr_line_map.len() != num_addr_lines {
+                Some(format!(
+                    "Expected {} address lines for a {}, got {}",
+                    num_addr_lines,
+                    chip.chip_type.name(),
+                    addr_line_map.len()
+                ))
+            } else {
+                let mut lines = addr_line_map.to_vec();
+                lines.sort_unstable();
+                (!lines.iter().copied().eq(0..num_addr_lines)).then(|| {
+                    format!(
+                        "Address lines {:?} are not a permutation of 0-{}",
+                        addr_line_map,
+                        num_addr_lines - 1
+                    )
+                })
+            };
+
+            if let Some(reason) = reason {
+                return Err(Error::InvalidAddrLineMap {
+                    id: chip.index,
+                    reason,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    // Returns the physical pin to address line map for a Chip type in this
+    // set - the board's, with any Chip type specific handling and then any
+    // address line map applied.
+    fn chip_phys_pin_to_addr_map(&self, board: &Board, chip_type: &ChipType) -> Vec<Option<usize>> {
+        let mut phys_pin_to_addr_map =
+            handle_snowflake_chip_types(board.phys_pin_to_addr_map(), chip_type);
+        if let Some(addr_line_map) = &self.addr_line_map {
+            for addr_bit in phys_pin_to_addr_map.iter_mut().flatten() {
+                if let Some(&mapped) = addr_line_map.get(*addr_bit) {
+                    *addr_bit = mapped;
+                }
+            }
+        }
+        phys_pin_to_addr_map
+    }
+
+    /// Overrides the byte served when no Chip in the set is selected - for
+    /// example, for systems where the bus floats to a value other than
+    /// [`PAD_NO_CHIP_BYTE`].
+    pub fn set_no_chip_byte(&mut self, no_chip_byte: u8) {
+        self.no_chip_byte = Some(no_chip_byte);
+    }
+
+    /// Returns the byte served when no Chip in the set is selected (before
+    /// mangling) - any set override, otherwise the first Chip fill byte
+    /// override, otherwise [`PAD_NO_CHIP_BYTE`].
+    pub fn no_chip_byte(&self) -> u8 {
+        self.no_chip_byte
+            .or_else(|| self.chips.iter().find_map(|chip| chip.fill_byte))
+            .unwrap_or(PAD_NO_CHIP_BYTE)
+    }
+
+    /// Overrides the board's data pin mapping for this Chip set.  The map
+    /// must be a permutation of the board's data pins - for example, to
+    /// handle a jumper which swaps the data bus nibbles.
+    pub fn set_data_pin_map(&mut self, board: &Board, data_pin_map: Vec<u8>) -> Result<()> {
+        Self::validate_data_pin_map(board, &data_pin_map)?;
+        self.data_pin_map = Some(data_pin_map);
+        Ok(())
+    }
+
+    /// Checks a data pin map is a permutation of the board's data pins.
+    pub fn validate_data_pin_map(board: &Board, data_pin_map: &[u8]) -> Result<()> {
+        let board_pins = board.data_pins();
+        if data_pin_map.len() != board_pins.len() {
+            return Err(Error::InvalidDataPinMap {
+                reason: format!(
+                    "Expected {} data pins, got {}",
+                    board_pins.len(),
+                    data_pin_map.len()
+                ),
+            });
+        }
+
+        let mut expected = board_pins.to_vec();
+        let mut actual = data_pin_map.to_vec();
+        expected.sort_unstable();
+        actual.sort_unstable();
+        if expected != actual {
+            return Err(Error::InvalidDataPinMap {
+                reason: format!(
+                    "Data pins {:?} are not a permutation of board data pins {:?}",
+                    data_pin_map, board_pins
+                ),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the data pin mapping (D0-D7, or D0-D15 on 16-bit boards) to use
+    /// when mangling bytes for this Chip set - any override, otherwise the
+    /// board's.
+    pub fn data_pins<'a>(&'a self, board: &Board) -> &'a [u8] {
+        match &self.data_pin_map {
+            Some(map) => map,
+            None => board.data_pins(),
+        }
+    }
+
+    /// Overrides the board's inverted data pins for this Chip set - the MCU
+    /// pins whose data signal is inverted on its way to the socket, such as
+    /// by an inverting buffer.  Each must be one of the board's data pins.
+    pub fn set_data_inverted_pins(&mut self, board: &Board, pins: Vec<u8>) -> Result<()> {
+        Self::validate_data_inverted_pins(board, &pins)?;
+        self.data_inverted_pins = Some(pins);
+        Ok(())
+    }
+
+    /// Checks inverted data pins are all board data pins.
+    pub fn validate_data_inverted_pins(board: &Board, pins: &[u8]) -> Result<()> {
+        let board_pins = board.data_pins();
+        match pins.iter().find(|pin| !board_pins.contains(pin)) {
+            Some(pin) => Err(Error::InvalidDataPinMap {
+                reason: format!(
+                    "Inverted data pin {} is not one of board data pins {:?}",
+                    pin, board_pins
+                ),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the MCU pins whose data signal is inverted for this Chip set -
+    /// any override, otherwise the board's.  Empty for most boards.
+    pub fn data_inverted_pins<'a>(&'a self, board: &Board) -> &'a [u8] {
+        match &self.data_inverted_pins {
+            Some(pins) => pins,
+            None => board.data_inverted_pins(),
+        }
+    }
+
+    // Returns the mask of physical data bits to invert when serving, for
+    // XORing after reordering.  The low byte is the mask for 8-bit serving.
+    fn data_inversion(&self, board: &Board) -> u16 {
+        self.data_inverted_pins(board)
+            .iter()
+            .fold(0, |mask, &pin| mask | (1 << data_pin_bit(pin)))
+    }
+
+    /// Creates a diagnostic multi Chip set, for verifying CS/X wiring on
+    /// multi-socket boards.
+    ///
+    /// Each Chip is filled with a distinct constant - see
+    /// [`diagnostic_fill_byte()`] - so reading any address from a socket
+    /// reveals which logical Chip it maps to.  All Chips use the given CS1
+    /// logic, as required for multi Chip sets.
+    pub fn diagnostic_multi(
+        id: usize,
+        chip_type: &ChipType,
+        num_chips: usize,
+        cs1: CsLogic,
+    ) -> Result<Self> {
+        let mut chips = Vec::with_capacity(num_chips);
+        for index in 0..num_chips {
+            let image = test_pattern_image(chip_type, diagnostic_fill_byte(index));
+            let chip = Chip::from_raw_rom_image(
+                index,
+                format!("diagnostic-{index}"),
+                None,
+                Some(&image),
+                vec![0u8; chip_type.size_bytes()],
+                chip_type,
+                CsConfig::new(Some(cs1), None, None),
+                &SizeHandling::None,
+                None,
+                None,
+                false,
+            )?;
+            chips.push(chip);
+        }
+
+        Self::new(id, ChipSetType::Multi, ServeAlg::AddrOnAnyCs, chips, None)
     }
 
     pub fn has_data(&self) -> bool {
         self.chips[0].has_data()
     }
 
+    /// Returns the CRC-32 of this Chip set's logical image data - each Chip's
+    /// image in turn, before any board specific address and data line
+    /// mangling.  So the same ROM data gives the same CRC whichever board it
+    /// is built for.  None if the Chip set has no data (RAM).
+    pub fn data_crc32(&self) -> Option<u32> {
+        if !self.has_data() {
+            return None;
+        }
+        let crc = self
+            .chips
+            .iter()
+            .filter_map(|chip| chip.data.as_deref())
+            .fold(0xFFFF_FFFF, crc32_update);
+        Some(!crc)
+    }
+
+    /// Returns whether this Chip set is served 16 bits wide - i.e. it
+    /// contains a Chip type supporting 16-bit mode, such as the 27C400.
+    pub fn is_16_bit(&self) -> bool {
+        self.chips.iter().any(|chip| chip.chip_type.supports_bit_mode(16))
+    }
+
+    // Checks the board has as many data lines as each Chip type needs, and
+    // that any 16-bit Chip types are in a single Chip set.
+    fn validate_16_bit(&self, board: &Board) -> Result<()> {
+        let available = self.data_pins(board).len();
+        if let Some(chip) = self
+            .chips
+            .iter()
+            .find(|chip| chip.chip_type.num_data_lines() as usize > available)
+        {
+            return Err(Error::TooFewDataLines {
+                chip_type: chip.chip_type,
+                required: chip.chip_type.num_data_lines(),
+                available,
+            });
+        }
+
+        if self.is_16_bit() && self.set_type != ChipSetType::Single {
+            return Err(Error::InvalidConfig {
+                error: "16-bit Chip types are only supported in single Chip sets".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    // The physical pin to address line map for a 16-bit Chip set, indexed by
+    // word address.  The byte select line (A-1, logical line 0) is dropped,
+    // as in 16-bit mode that pin is D15.
+    fn word_phys_pin_to_addr_map(&self, board: &Board) -> Vec<Option<usize>> {
+        let chip_type = &self.chips[0].chip_type;
+        let mut phys_pin_to_addr_map = self.chip_phys_pin_to_addr_map(board, chip_type);
+        Self::truncate_phys_pin_to_addr_map(&mut phys_pin_to_addr_map, chip_type.num_addr_lines());
+        for item in phys_pin_to_addr_map.iter_mut() {
+            if *item == Some(0) {
+                *item = None;
+            }
+        }
+        phys_pin_to_addr_map
+    }
+
     pub fn multi_cs_logic(&self) -> Result<CsLogic> {
         let first_cs1 = self.chips[0].cs_config.cs1_logic();
         if self.chips.len() == 1 {
@@ -708,14 +1750,66 @@ impl ChipSet {
         }
     }
 
+    /// Returns a one-line human readable summary of the Chip set, such as
+    /// "Banked set #1: 4× 2364, CS1 active low, AddrOnCs".
+    ///
+    /// Chips of differing types are listed individually, such as
+    /// "2316+2332".  Only CS1 is described for multi-Chip sets, as the other
+    /// CS lines are ignored.
+    pub fn describe(&self) -> String {
+        let set_type = match self.set_type {
+            ChipSetType::Single => "Single",
+            ChipSetType::Banked => "Banked",
+            ChipSetType::Multi => "Multi",
+        };
+
+        let first_type = &self.chips[0].chip_type;
+        let chips = if self.chips.len() == 1 {
+            first_type.to_string()
+        } else if self.chips.iter().all(|chip| &chip.chip_type == first_type) {
+            format!("{}× {first_type}", self.chips.len())
+        } else {
+            self.chips
+                .iter()
+                .map(|chip| chip.chip_type.name())
+                .collect::<Vec<_>>()
+                .join("+")
+        };
+
+        let cs = match &self.chips[0].cs_config {
+            CsConfig::CeOe => "CE/OE".to_string(),
+            CsConfig::ChipSelect { cs1, cs2, cs3 } => {
+                let mut cs = format!("CS1 {}", cs1.as_str());
+                if self.set_type != ChipSetType::Multi {
+                    for (name, logic) in [("CS2", *cs2), ("CS3", *cs3)] {
+                        if let Some(logic) = logic.filter(|logic| *logic != CsLogic::Ignore) {
+                            cs.push_str(&format!(", {name} {}", logic.as_str()));
+                        }
+                    }
+                }
+                cs
+            }
+        };
+
+        format!(
+            "{set_type} set #{}: {chips}, {cs}, {:?}",
+            self.id, self.serve_alg
+        )
+    }
+
     /// Returns the ChipFunction for this set
     pub fn chip_function(&self) -> ChipFunction {
         self.chips[0].chip_type.chip_function()
     }
 
     /// Returns the size of the data required for this Chip set, in bytes.
+    ///
+    /// 16-bit Chip sets store two bytes (low, then high) per word address, so
+    /// the image is the size of the Chip itself.
     pub fn image_size(&self, family: &McuFamily, chip_pins: u8) -> usize {
-        if family == &McuFamily::Rp2350 {
+        if self.is_16_bit() {
+            self.chips[0].chip_type.size_bytes()
+        } else if family == &McuFamily::Rp2350 {
             // RP2350 can address full 64KB space for each Chip set
             65536
         } else {
@@ -745,13 +1839,357 @@ impl ChipSet {
         }
     }
 
+    /// Checks that a physical pin to address line map, once truncated to the
+    /// Chip type's address lines, covers every address line from 0 to
+    /// `num_addr_lines - 1` exactly once.
+    ///
+    /// A board map which is missing an address line (or maps one twice) would
+    /// otherwise silently alias addresses within the Chip image.
+    ///
+    /// `id` is the Chip index, used to identify the Chip in any error.
+    pub fn validate_phys_pin_to_addr_map(
+        id: usize,
+        chip_type: &ChipType,
+        phys_pin_to_addr_map: &[Option<usize>],
+    ) -> Result<()> {
+        let num_addr_lines = chip_type.num_addr_lines();
+        let mut map = phys_pin_to_addr_map.to_vec();
+        Self::truncate_phys_pin_to_addr_map(&mut map, num_addr_lines);
+
+        let mut seen = vec![false; num_addr_lines];
+        for addr_bit in map.iter().flatten() {
+            if seen[*addr_bit] {
+                return Err(Error::DuplicateAddressLine {
+                    id,
+                    chip_type: *chip_type,
+                    addr_bit: *addr_bit,
+                });
+            }
+            seen[*addr_bit] = true;
+        }
+
+        if let Some(addr_bit) = seen.iter().position(|&s| !s) {
+            return Err(Error::MissingAddressLine {
+                id,
+                chip_type: *chip_type,
+                addr_bit,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Validates the board's physical pin to address line map for every Chip
+    /// in this set.  See [`Self::validate_phys_pin_to_addr_map()`].
+    pub fn validate_addr_maps(&self, board: &Board) -> Result<()> {
+        for chip in &self.chips {
+            let phys_pin_to_addr_map = self.chip_phys_pin_to_addr_map(board, &chip.chip_type);
+            Self::validate_phys_pin_to_addr_map(
+                chip.index,
+                &chip.chip_type,
+                &phys_pin_to_addr_map,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Validates any CS2/CS3 bit position overrides for every Chip in this
+    /// set - see [`Chip::set_cs_bits()`].  Overridden positions must not
+    /// clash with each other, or with the board's CS1, X1 or X2 bits.
+    pub fn validate_cs_bits(&self, board: &Board) -> Result<()> {
+        for chip in &self.chips {
+            if chip.cs2_bit.is_none() && chip.cs3_bit.is_none() {
+                continue;
+            }
+
+            let reserved = [
+                ("CS1", board.bit_cs1(chip.chip_type)),
+                ("X1", board.bit_x1()),
+                ("X2", board.bit_x2()),
+            ];
+            for (line, bit) in [("CS2", chip.cs2_bit), ("CS3", chip.cs3_bit)] {
+                let Some(bit) = bit else {
+                    continue;
+                };
+                if let Some((other, _)) = reserved.iter().find(|(_, b)| *b == bit) {
+                    return Err(Error::InvalidCsBit {
+                        id: chip.index,
+                        reason: format!("{} bit {} is already used by {}", line, bit, other),
+                    });
+                }
+            }
+
+            if chip.bit_cs2(board) == chip.bit_cs3(board) {
+                return Err(Error::InvalidCsBit {
+                    id: chip.index,
+                    reason: format!("CS2 and CS3 both use bit {}", chip.bit_cs2(board)),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates that every Chip in a multi set can be addressed alongside
+    /// the others.  Each Chip is selected by CS1, X1 or X2, according to its
+    /// position in the set (see [`Board::cs_bit_for_chip_in_set()`]), with
+    /// the other two inactive.  So every Chip must use the same CS1 bit, and
+    /// no Chip's address lines may use a selection bit, or fall outside the
+    /// 64KB image.  Other set types always pass.
+    ///
+    /// Errors with [`Error::TooManyChips`] if the set has more Chips than
+    /// the board has selection lines for (see
+    /// [`Board::max_chips_in_multi_set()`]).
+    pub fn validate_multi_addressing(&self, board: &Board) -> Result<()> {
+        if self.set_type != ChipSetType::Multi {
+            return Ok(());
+        }
+
+        let max_chips = board.max_chips_in_multi_set() as usize;
+        if self.chips.len() > max_chips {
+            return Err(Error::TooManyChips {
+                expected: max_chips,
+                actual: self.chips.len(),
+            });
+        }
+
+        let first = self.chips[0].chip_type;
+        let select_bits = [
+            ("CS1", board.bit_cs1(first)),
+            ("X1", board.bit_x1()),
+            ("X2", board.bit_x2()),
+        ];
+
+        for (index, chip) in self.chips.iter().enumerate() {
+            let bit = board.cs_bit_for_chip_in_set(chip.chip_type, index);
+            if bit > 15 {
+                return Err(Error::IncompatibleMultiChip {
+                    id: chip.index,
+                    reason: format!(
+                        "board has no {} line to select it",
+                        select_bits[index].0
+                    ),
+                });
+            }
+
+            let cs1 = board.bit_cs1(chip.chip_type);
+            if cs1 != select_bits[0].1 {
+                return Err(Error::IncompatibleMultiChip {
+                    id: chip.index,
+                    reason: format!(
+                        "{} uses CS1 bit {}, but {} uses bit {}",
+                        chip.chip_type, cs1, first, select_bits[0].1
+                    ),
+                });
+            }
+
+            let mut phys_pin_to_addr_map = self.chip_phys_pin_to_addr_map(board, &chip.chip_type);
+            Self::truncate_phys_pin_to_addr_map(
+                &mut phys_pin_to_addr_map,
+                chip.chip_type.num_addr_lines(),
+            );
+            for (pin, addr_bit) in phys_pin_to_addr_map.iter().enumerate() {
+                let Some(addr_bit) = addr_bit else {
+                    continue;
+                };
+                if pin > 15 {
+                    return Err(Error::IncompatibleMultiChip {
+                        id: chip.index,
+                        reason: format!("A{} is on bit {}, outside the 64KB image", addr_bit, pin),
+                    });
+                }
+                if let Some((line, _)) = select_bits.iter().find(|(_, b)| *b as usize == pin) {
+                    return Err(Error::IncompatibleMultiChip {
+                        id: chip.index,
+                        reason: format!("A{} is on bit {}, used by {}", addr_bit, pin, line),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Gets a byte from the chip set at the given address (as far as the MCU is
     /// concerned) and returns the byte, ready for the MCU to serve.
-    pub fn get_byte(&self, address: usize, board: &Board, invert_cs1_x: bool) -> u8 {
+    ///
+    /// The layout is independent of the serving algorithm for Single and
+    /// Banked sets.  `TwoCsOneAddr` differs from the other algorithms only in
+    /// how often the firmware polls the CS lines - it indexes the image with
+    /// the same raw address/CS lines value, and no address bit is latched as
+    /// a CS, so it is served from the default layout.
+    ///
+    /// Errors with [`Error::AddressOutOfBounds`] if the address is outside
+    /// this Chip set's image on the board (see [`Self::image_size()`]), or the
+    /// board's pin map takes it outside the Chip image.
+    pub fn get_byte(&self, address: usize, board: &Board, invert_cs1_x: bool) -> Result<u8> {
+        let size = self.image_size(&board.mcu_family(), board.chip_pins());
+        if address >= size {
+            return Err(Error::AddressOutOfBounds {
+                address,
+                chip_size: size,
+            });
+        }
+
+        let inverted = self.data_inversion(board);
+
+        // RAM with an initial image is served like ROM
         if (!self.has_data()) && (self.chip_function() == ChipFunction::Ram) {
-            return Chip::byte_mangled(PAD_RAM_BYTE, board)
+            return Ok(Chip::byte_mangled(
+                PAD_RAM_BYTE,
+                self.data_pins(board),
+                inverted as u8,
+            ));
         }
 
+        if self.is_16_bit() {
+            // Each word address is served as two bytes, low byte first
+            let word = self.chips[0].get_word(
+                &self.word_phys_pin_to_addr_map(board),
+                address >> 1,
+                board,
+                self.data_pins(board),
+                inverted,
+            )?;
+            return Ok(word.to_le_bytes()[address & 1]);
+        }
+
+        match self.select_chip(address, board, invert_cs1_x) {
+            Some((chip_index, phys_pin_to_addr_map, chip_address)) => {
+                self.chips[chip_index].get_byte(
+                    &phys_pin_to_addr_map,
+                    chip_address,
+                    board,
+                    self.data_pins(board),
+                    inverted as u8,
+                )
+            }
+            // No Chip is selected, or more than one would be, so this part
+            // of the address space is set to the no Chip value
+            None => Ok(Chip::byte_mangled(
+                self.no_chip_byte(),
+                self.data_pins(board),
+                inverted as u8,
+            )),
+        }
+    }
+
+    /// Generates this Chip set's complete flash image, as written by
+    /// [`crate::Metadata::write_roms()`] - every address passed through
+    /// [`Self::get_byte()`].
+    pub fn to_flash_image(&self, board: &Board, invert_cs1_x: bool) -> Result<Vec<u8>> {
+        let size = self.image_size(&board.mcu_family(), board.chip_pins());
+        (0..size)
+            .map(|address| self.get_byte(address, board, invert_cs1_x))
+            .collect()
+    }
+
+    /// Simulates the firmware serving this Chip set over a recorded bus
+    /// trace, such as one captured from a real system with a logic analyser.
+    ///
+    /// Each bus value is the state of the address and CS lines as read by the
+    /// MCU - see [`Self::get_byte()`] - and is masked to the Chip set's image
+    /// size.  Returns the byte served for each, in order, in logical D0-D7
+    /// bit order so it can be compared directly with the real ROM's captured
+    /// outputs.
+    pub fn serve_trace(&self, bus_values: &[u16], board: &Board) -> Result<Vec<u8>> {
+        let size = self.image_size(&board.mcu_family(), board.chip_pins());
+        let inverted = self.data_inversion(board) as u8;
+        bus_values
+            .iter()
+            .map(|&value| {
+                let byte = self.get_byte(value as usize & (size - 1), board, false)?;
+                Ok(Chip::byte_unmangled(byte, self.data_pins(board), inverted))
+            })
+            .collect()
+    }
+
+    /// Reverses [`Self::to_flash_image()`], recovering the logical image of
+    /// each Chip in the set from a flash image.
+    ///
+    /// This Chip set provides the layout - set type, Chip types, CS
+    /// configuration and any data pin map - and its own Chip data is ignored.
+    /// Returns one image per Chip, each the size of that Chip type.  Errors if
+    /// the flash image is the wrong size, or doesn't contain every byte of
+    /// every Chip.
+    pub fn from_flash_image(
+        &self,
+        bytes: &[u8],
+        board: &Board,
+        invert_cs1_x: bool,
+    ) -> Result<Vec<Vec<u8>>> {
+        let size = self.image_size(&board.mcu_family(), board.chip_pins());
+        if bytes.len() != size {
+            return Err(Error::InvalidFlashImage {
+                reason: format!("Expected {} bytes, got {}", size, bytes.len()),
+            });
+        }
+
+        let mut images: Vec<Vec<u8>> = self
+            .chips
+            .iter()
+            .map(|chip| vec![0u8; chip.chip_type.size_bytes()])
+            .collect();
+        let mut recovered: Vec<Vec<bool>> = images.iter().map(|i| vec![false; i.len()]).collect();
+        let inverted = self.data_inversion(board);
+
+        if self.is_16_bit() {
+            let phys_pin_to_addr_map = self.word_phys_pin_to_addr_map(board);
+            let num_addr_lines = self.chips[0].chip_type.num_addr_lines();
+            for (address, word) in bytes.chunks_exact(2).enumerate() {
+                let logical =
+                    Chip::address_to_logical(&phys_pin_to_addr_map, address, board, num_addr_lines);
+                let word = Chip::word_unmangled(
+                    u16::from_le_bytes([word[0], word[1]]),
+                    self.data_pins(board),
+                    inverted,
+                );
+                images[0][logical..logical + 2].copy_from_slice(&word.to_le_bytes());
+                recovered[0][logical] = true;
+                recovered[0][logical + 1] = true;
+            }
+        } else {
+            for (address, &byte) in bytes.iter().enumerate() {
+                let Some((chip_index, phys_pin_to_addr_map, chip_address)) =
+                    self.select_chip(address, board, invert_cs1_x)
+                else {
+                    continue;
+                };
+
+                let chip_type = &self.chips[chip_index].chip_type;
+                let logical = Chip::address_to_logical(
+                    &phys_pin_to_addr_map,
+                    chip_address,
+                    board,
+                    chip_type.num_addr_lines(),
+                );
+                images[chip_index][logical] =
+                    Chip::byte_unmangled(byte, self.data_pins(board), inverted as u8);
+                recovered[chip_index][logical] = true;
+            }
+        }
+
+        for (index, recovered) in recovered.iter().enumerate() {
+            if let Some(logical) = recovered.iter().position(|&r| !r) {
+                return Err(Error::InvalidFlashImage {
+                    reason: format!(
+                        "Chip {} address {:#06X} not present in flash image",
+                        index, logical
+                    ),
+                });
+            }
+        }
+
+        Ok(images)
+    }
+
+    // Works out which Chip, if any, responds to the given address (as far as
+    // the MCU is concerned).  Returns the index of the Chip, the physical pin
+    // to address map to use for it, and the address to pass to the Chip.
+    fn select_chip(
+        &self,
+        address: usize,
+        board: &Board,
+        invert_cs1_x: bool,
+    ) -> Option<(usize, Vec<Option<usize>>, usize)> {
         // Hard-coded assumption that X1/X2 (STM32F4) are pins 14/15 for
         // single chip sets and banked chip sets.  However, for RP2350 they may
         // be other pins.
@@ -760,19 +2198,19 @@ impl ChipSet {
                 match board.mcu_family() {
                     McuFamily::Rp2350 => {
                         // Single Chip set: uses entire 64KB space
-                        assert!(
+                        debug_assert!(
                             address < 65536,
                             "Address out of bounds for RP235X single Chip set"
                         );
                     }
                     McuFamily::Stm32f4 => {
                         if board.chip_pins() == 24 {
-                            assert!(
+                            debug_assert!(
                                 address < 16384,
                                 "Address out of bounds for STM32F4 single 24 pin Chip"
                             );
                         } else {
-                            assert!(
+                            debug_assert!(
                                 address < 65536,
                                 "Address out of bounds for STM32F4 single 28 pin Chip"
                             );
@@ -782,15 +2220,16 @@ impl ChipSet {
                 (0, address)
             } else {
                 // Banked mode: use X1/X2 to select Chip
-                assert!(address < 65536, "Address out of bounds for banked Chip set");
+                debug_assert!(address < 65536, "Address out of bounds for banked Chip set");
                 let x1_pin = board.bit_x1();
                 let x2_pin = board.bit_x2();
-                let bank = if board.x_jumper_pull() == 1 {
-                    ((address >> x1_pin) & 1) | (((address >> x2_pin) & 1) << 1)
-                } else {
-                    // Invert the logic if the jumpers pull to GND
-                    (!(address >> x1_pin) & 1) | ((!((address >> x2_pin) & 1)) << 1)
-                };
+                let bank = banked_chip_index(
+                    address,
+                    x1_pin,
+                    x2_pin,
+                    board.x1_jumper_pull(),
+                    board.x2_jumper_pull(),
+                );
                 let mask = !(1 << x1_pin) & !(1 << x2_pin);
                 let masked_address = address & mask;
                 let chip_index = bank % self.chips.len(); // Wrap around
@@ -801,18 +2240,16 @@ impl ChipSet {
             };
 
             let num_addr_lines = self.chips[chip_index].chip_type.num_addr_lines();
-            let mut phys_pin_to_addr_map = handle_snowflake_chip_types(
-                board.phys_pin_to_addr_map(),
-                &self.chips[chip_index].chip_type,
-            );
+            let mut phys_pin_to_addr_map =
+                self.chip_phys_pin_to_addr_map(board, &self.chips[chip_index].chip_type);
             Self::truncate_phys_pin_to_addr_map(&mut phys_pin_to_addr_map, num_addr_lines);
 
-            return self.chips[chip_index].get_byte(&phys_pin_to_addr_map, masked_address, board);
+            return Some((chip_index, phys_pin_to_addr_map, masked_address));
         }
 
         // Multiple Chips: check CS line states to select responding Chip.  This
         // code can handle any X1/X2 positions - but the above can't.
-        assert!(address < 65536, "Address out of bounds for multi-Chip set");
+        debug_assert!(address < 65536, "Address out of bounds for multi-Chip set");
 
         for (index, chip_in_set) in self.chips.iter().enumerate() {
             // Get the physical addr and data pin mappings.  We have to
@@ -820,41 +2257,22 @@ impl ChipSet {
             // a different type (size).
             let num_addr_lines = chip_in_set.chip_type.num_addr_lines();
             let mut phys_pin_to_addr_map =
-                handle_snowflake_chip_types(board.phys_pin_to_addr_map(), &chip_in_set.chip_type);
+                self.chip_phys_pin_to_addr_map(board, &chip_in_set.chip_type);
             Self::truncate_phys_pin_to_addr_map(&mut phys_pin_to_addr_map, num_addr_lines);
 
             // All of CS1/X1/X2 have to have the same active low/high status
             // so we retrieve that from CS1 (as X1/X2 aren't specifically
             // configured in the chip sets).
-            let pins_active_high = chip_in_set.cs_config.cs1_logic() == CsLogic::ActiveHigh;
+            let pins_logic =
+                CsLogic::from_bool(chip_in_set.cs_config.cs1_logic() != CsLogic::ActiveHigh);
+            let is_pin_active =
+                |pin: u8| pins_logic.is_active((address & (1 << pin)) != 0, invert_cs1_x);
 
             // Get the CS pin that controls this chip's selection
             let cs_pin = board.cs_bit_for_chip_in_set(chip_in_set.chip_type, index);
-            assert!(cs_pin <= 15, "Internal error: CS pin is > 15");
-
-            fn is_pin_active(
-                active_high: bool,
-                invert_cs1_x: bool,
-                address: usize,
-                pin: u8,
-            ) -> bool {
-                if !invert_cs1_x {
-                    if active_high {
-                        (address & (1 << pin)) != 0
-                    } else {
-                        (address & (1 << pin)) == 0
-                    }
-                } else {
-                    // Invert the logic for this read
-                    if active_high {
-                        (address & (1 << pin)) == 0
-                    } else {
-                        (address & (1 << pin)) != 0
-                    }
-                }
-            }
+            debug_assert!(cs_pin <= 15, "Internal error: CS pin is > 15");
 
-            let cs_active = is_pin_active(pins_active_high, invert_cs1_x, address, cs_pin);
+            let cs_active = is_pin_active(cs_pin);
 
             if cs_active {
                 // Verify exactly one CS pin is active
@@ -862,9 +2280,9 @@ impl ChipSet {
                 let x1_pin = board.bit_x1();
                 let x2_pin = board.bit_x2();
 
-                let cs1_is_active = is_pin_active(pins_active_high, invert_cs1_x, address, cs1_pin);
-                let x1_is_active = is_pin_active(pins_active_high, invert_cs1_x, address, x1_pin);
-                let x2_is_active = is_pin_active(pins_active_high, invert_cs1_x, address, x2_pin);
+                let cs1_is_active = is_pin_active(cs1_pin);
+                let x1_is_active = is_pin_active(x1_pin);
+                let x2_is_active = is_pin_active(x2_pin);
 
                 let active_count = [cs1_is_active, x1_is_active, x2_is_active]
                     .iter()
@@ -872,61 +2290,36 @@ impl ChipSet {
                     .count();
 
                 if active_count == 1 && self.check_chip_cs_requirements(chip_in_set, address, board) {
-                    return chip_in_set.get_byte(&phys_pin_to_addr_map, address, board);
+                    return Some((index, phys_pin_to_addr_map, address));
                 }
             }
         }
 
-        // No Chip is selected, so this part of the address space is set to blank value
-        Chip::byte_mangled(PAD_NO_CHIP_BYTE, board)
+        // No Chip is selected
+        None
     }
 
     fn check_chip_cs_requirements(&self, chip_in_set: &Chip, address: usize, board: &Board) -> bool {
         let cs_config = &chip_in_set.cs_config;
-        let chip_type = chip_in_set.chip_type;
 
-        // Check CS2 if specified
-        if let Some(cs2_logic) = cs_config.cs2_logic() {
-            match cs2_logic {
-                CsLogic::Ignore => {
-                    // CS2 state doesn't matter
-                }
-                CsLogic::ActiveLow => {
-                    let cs2_pin = board.bit_cs2(chip_type);
-                    let cs2_active = (address & (1 << cs2_pin)) == 0;
-                    if !cs2_active {
-                        return false;
-                    }
-                }
-                CsLogic::ActiveHigh => {
-                    let cs2_pin = board.bit_cs2(chip_type);
-                    let cs2_active = (address & (1 << cs2_pin)) != 0;
-                    if cs2_active {
-                        return false;
-                    }
+        // Check CS2 if specified - its state doesn't matter if ignored
+        match cs_config.cs2_logic() {
+            None | Some(CsLogic::Ignore) => {}
+            Some(cs2_logic) => {
+                let cs2_pin = chip_in_set.bit_cs2(board);
+                if !cs2_logic.is_active((address & (1 << cs2_pin)) != 0, false) {
+                    return false;
                 }
             }
         }
 
         // Check CS3 if specified
-        if let Some(cs3_logic) = cs_config.cs3_logic() {
-            match cs3_logic {
-                CsLogic::Ignore => {
-                    // CS3 state doesn't matter
-                }
-                CsLogic::ActiveLow => {
-                    let cs3_pin = board.bit_cs3(chip_type);
-                    let cs3_active = (address & (1 << cs3_pin)) == 0;
-                    if !cs3_active {
-                        return false;
-                    }
-                }
-                CsLogic::ActiveHigh => {
-                    let cs3_pin = board.bit_cs3(chip_type);
-                    let cs3_active = (address & (1 << cs3_pin)) != 0;
-                    if cs3_active {
-                        return false;
-                    }
+        match cs_config.cs3_logic() {
+            None | Some(CsLogic::Ignore) => {}
+            Some(cs3_logic) => {
+                let cs3_pin = chip_in_set.bit_cs3(board);
+                if !cs3_logic.is_active((address & (1 << cs3_pin)) != 0, false) {
+                    return false;
                 }
             }
         }
@@ -984,11 +2377,14 @@ impl ChipSet {
 
     /// Returns the length of metadata required for all of the chips.  This
     /// includes all chip structs, plus the array of pointers to them.
-    pub fn chips_metadata_len(&self, include_filenames: bool) -> usize {
+    ///
+    /// Each chip struct includes a filename pointer if either filenames (for
+    /// boot logging) or labels are included.
+    pub fn chips_metadata_len(&self, include_filenames: bool, include_labels: bool) -> usize {
         let num_chips = self.chips.len();
 
         // Size of all chip metadata structs
-        let chip_metadata_len = if include_filenames {
+        let chip_metadata_len = if include_filenames || include_labels {
             CHIP_METADATA_LEN_WITH_FILENAME
         } else {
             CHIP_METADATA_LEN_NO_FILENAME
@@ -1009,11 +2405,12 @@ impl ChipSet {
         chip_filename_ptrs: &[u32],
         chip_metadata_ptrs: &mut [u32],
         include_filenames: bool,
+        include_labels: bool,
     ) -> Result<usize> {
         let num_chips = self.chips.len();
 
         // Check enough buffer space
-        let expected_len = self.chips_metadata_len(include_filenames);
+        let expected_len = self.chips_metadata_len(include_filenames, include_labels);
         if buf.len() < expected_len {
             return Err(Error::BufferTooSmall {
                 location: "write_chip_metadata1",
@@ -1042,16 +2439,25 @@ impl ChipSet {
             buf[offset] = chip.chip_type_c_enum_val();
             offset += 1;
 
-            // Write the CS states
-            buf[offset] = chip.cs_config.cs1_logic().c_enum_val();
-            offset += 1;
-            buf[offset] = chip.cs_config.cs2_logic().map_or(2, |cs| cs.c_enum_val());
-            offset += 1;
-            buf[offset] = chip.cs_config.cs3_logic().map_or(2, |cs| cs.c_enum_val());
-            offset += 1;
+            // Write the CS states.  CE/OE Chips are marked as such in the
+            // otherwise unused CS3 state.
+            let cs_states = match &chip.cs_config {
+                CsConfig::ChipSelect { cs1, cs2, cs3 } => [
+                    cs1.c_enum_val(),
+                    cs2.map_or(2, |cs| cs.c_enum_val()),
+                    cs3.map_or(2, |cs| cs.c_enum_val()),
+                ],
+                CsConfig::CeOe => [
+                    CsLogic::ActiveLow.c_enum_val(),
+                    CsLogic::ActiveLow.c_enum_val(),
+                    CS_STATE_CE_OE,
+                ],
+            };
+            buf[offset..offset + 3].copy_from_slice(&cs_states);
+            offset += 3;
 
-            // Add filename if required
-            if include_filenames {
+            // Add filename (or label) if required
+            if include_filenames || include_labels {
                 let chip_filename_ptr = chip_filename_ptrs
                     .get(chip.index())
                     .copied()
@@ -1115,6 +2521,9 @@ impl ChipSet {
         version: &FirmwareVersion,
         serve_config_ptr: Option<u32>,
         firmware_overrides_ptr: Option<u32>,
+        provenance_ptr: Option<u32>,
+        signature: Option<[u8; 4]>,
+        include_labels: bool,
     ) -> Result<usize> {
         // Check enough buffer space
         let expected_len = Self::chip_set_metadata_len(version);
@@ -1146,8 +2555,8 @@ impl ChipSet {
         buf[offset] = num_chips;
         offset += 1;
 
-        // Write the serving algorithm
-        let algorithm = self.serve_alg().c_enum_value();
+        // Write the serving algorithm, as the firmware will run it
+        let algorithm = self.serve_alg().resolve(&self.set_type).c_enum_value();
         buf[offset] = algorithm;
         offset += 1;
 
@@ -1177,9 +2586,29 @@ impl ChipSet {
             buf[offset..offset + 4].copy_from_slice(&fw_ptr.to_le_bytes());
             offset += 4;
 
+            // Write provenance pointer.  0 when absent, as this was padding
+            // before provenance was added.
+            let provenance_ptr = provenance_ptr.unwrap_or(0);
+            buf[offset..offset + 4].copy_from_slice(&provenance_ptr.to_le_bytes());
+            offset += 4;
+
+            // Write signature.  0s when absent, as above.
+            buf[offset..offset + 4].copy_from_slice(&signature.unwrap_or([0u8; 4]));
+            offset += 4;
+
+            // Write whether this set's chips include filenames.  Labels are
+            // written to the filename slot, so are present if included.
+            buf[offset] = match self.boot_logging {
+                _ if include_labels => CHIP_SET_FILENAMES_PRESENT,
+                None => CHIP_SET_FILENAMES_DEFAULT,
+                Some(true) => CHIP_SET_FILENAMES_PRESENT,
+                Some(false) => CHIP_SET_FILENAMES_ABSENT,
+            };
+            offset += 1;
+
             // Write padding to reach 64 bytes
-            buf[offset..offset + 40].copy_from_slice(&[0u8; 40]);
-            offset += 40;
+            buf[offset..offset + 31].copy_from_slice(&[0u8; 31]);
+            offset += 31;
 
             assert_eq!(
                 offset, CHIP_SET_FIRMWARE_OVERRIDES_METADATA_LEN,
@@ -1195,6 +2624,29 @@ impl ChipSet {
         Ok(offset)
     }
 
+    /// Returns a short human readable signature of this Chip set's logical
+    /// content - 4 uppercase hex characters, derived from the CRC-32 of each
+    /// Chip's image in turn.  Deterministic, so identical content always has
+    /// the same signature.  None for Chip sets without data (RAM).
+    pub fn signature(&self) -> Option<[u8; 4]> {
+        if !self.has_data() {
+            return None;
+        }
+
+        let mut content = Vec::new();
+        for chip in &self.chips {
+            if let Some(data) = &chip.data {
+                content.extend_from_slice(data);
+            }
+        }
+        let crc = crc32(&content);
+        let folded = ((crc >> 16) ^ (crc & 0xFFFF)) as u16;
+
+        let mut signature = [0u8; 4];
+        signature.copy_from_slice(format!("{:04X}", folded).as_bytes());
+        Some(signature)
+    }
+
     pub fn chip_set_metadata_len(version: &FirmwareVersion) -> usize {
         if *version >= MIN_FIRMWARE_OVERRIDES_VERSION {
             CHIP_SET_METADATA_LEN_EXTRA_INFO
@@ -1208,10 +2660,16 @@ impl ChipSet {
     }
 }
 
+// Returns the bit a data pin occupies in the served byte.  Fire rev A has its
+// data lines on pins 16-23, which are served from the bottom byte.
+pub(crate) const fn data_pin_bit(pin: u8) -> u8 {
+    if pin > 15 { pin - 16 } else { pin }
+}
+
 // Handle Chip Types which do not have a standard address layout.  Currently,
 // the only know Chip type needing special handling is the 2732, which has
 // swapped A11 and A12 lines.
-fn handle_snowflake_chip_types(
+pub(crate) fn handle_snowflake_chip_types(
     phys_pin_to_addr_map: &[Option<usize>],
     chip_type: &ChipType,
 ) -> Vec<Option<usize>> {
@@ -7,31 +7,53 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub mod builder;
+#[cfg(feature = "compress")]
+pub mod compress;
 pub mod firmware;
 pub mod image;
 pub mod meta;
+pub mod pin_map;
+pub mod validation;
 
-pub use builder::{Builder, Config, FileData, FileSpec, License, ChipConfig, ChipSetConfig};
-pub use image::{CsConfig, CsLogic, Chip, ChipSet, ChipSetType, SizeHandling};
-pub use image::{PAD_BLANK_BYTE, PAD_NO_CHIP_BYTE};
-pub use meta::{MAX_METADATA_LEN, Metadata, PAD_METADATA_BYTE};
+pub use builder::{Builder, Config, ConfigFeature, FileData, FileSpec, License, ChipConfig, ChipSetConfig};
+pub use builder::{BuildReport, ChipSetReport, CsMode, FileManifest, ManifestEntry};
+pub use builder::{VariantReport, VariantSpec};
+pub use image::{BoardChipSetLimits, CsConfig, CsLogic, Chip, ChipSet, ChipSetType, Half, SizeHandling};
+pub use image::{PAD_BLANK_BYTE, PAD_NO_CHIP_BYTE, diagnostic_fill_byte, test_pattern_image};
+pub use image::{Provenance, ResolveServeAlg, banked_chip_index, builtin_image, crc32};
+pub use meta::{MAX_METADATA_LEN, MAX_NOTE_LEN, Metadata, PAD_METADATA_BYTE};
+pub use meta::{ParsedChip, ParsedChipSet, ParsedMetadata};
+pub use pin_map::{BoardPinMap, ChipPinMap, PinMapTable, PinMapping};
+pub use validation::{RuleDescription, validation_rules};
 
 use alloc::string::String;
 use onerom_config::fw::{FirmwareVersion, ServeAlg};
 use onerom_config::mcu::Family;
 use onerom_config::chip::ChipType;
 
-/// Version of metadata produced by this version of the crate
-pub const METADATA_VERSION: u32 = 1;
-const METADATA_VERSION_STR: &str = "1";
+/// Version of metadata produced by this version of the crate.  Version 2
+/// adds a CRC32 over the metadata, and is only produced for firmware from
+/// [`MIN_METADATA_CRC_VERSION`] onwards - older firmware requires version 1.
+pub const METADATA_VERSION: u32 = 2;
+const METADATA_VERSION_STR: &str = "2";
 
 /// Firmware size reserved at the start of flash, before metadata
 pub const FIRMWARE_SIZE: usize = 48 * 1024; // 48KB
 
 pub const MIN_FIRMWARE_OVERRIDES_VERSION: FirmwareVersion = FirmwareVersion::new(0, 6, 0, 0);
 
+/// Provenance records are referenced from the extended chip set structure,
+/// so require 0.6.0 firmware onwards.
+pub const MIN_PROVENANCE_VERSION: FirmwareVersion = FirmwareVersion::new(0, 6, 0, 0);
+
+/// Earlier firmware rejects metadata versions other than 1, so the metadata
+/// CRC (metadata version 2) requires 0.6.3 firmware onwards.
+pub const MIN_METADATA_CRC_VERSION: FirmwareVersion = FirmwareVersion::new(0, 6, 3, 0);
+
 /// Error type
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub enum Error {
@@ -51,6 +73,10 @@ pub enum Error {
         image_size: usize,
         expected_size: usize,
     },
+    MirrorNotHalfSize {
+        image_size: usize,
+        expected_size: usize,
+    },
     BufferTooSmall {
         location: &'static str,
         expected: usize,
@@ -73,6 +99,7 @@ pub enum Error {
     },
     InvalidServeAlg {
         serve_alg: ServeAlg,
+        reason: String,
     },
     InconsistentCsLogic {
         first: CsLogic,
@@ -107,6 +134,24 @@ pub enum Error {
         id: usize,
         reason: String,
     },
+    SizeHandlingNotAllowed {
+        id: usize,
+    },
+    NotExactSize {
+        id: usize,
+        expected: usize,
+        actual: usize,
+    },
+    MissingAddressLine {
+        id: usize,
+        chip_type: ChipType,
+        addr_bit: usize,
+    },
+    DuplicateAddressLine {
+        id: usize,
+        chip_type: ChipType,
+        addr_bit: usize,
+    },
     UnsupportedFrequency {
         frequency_mhz: u32,
     },
@@ -122,11 +167,346 @@ pub enum Error {
         actual: Family,
         required: Family,
     },
+    InvalidCompressedImages {
+        reason: String,
+    },
+    InvalidDataPinMap {
+        reason: String,
+    },
+    InvalidFlashImage {
+        reason: String,
+    },
+    MetadataCrcMismatch {
+        expected: u32,
+        actual: u32,
+    },
+    InvalidSocketOrder {
+        reason: String,
+    },
+    InvalidCsBit {
+        id: usize,
+        reason: String,
+    },
+    IncompatibleMultiChip {
+        id: usize,
+        reason: String,
+    },
+    AddressOutOfBounds {
+        address: usize,
+        chip_size: usize,
+    },
+    TooFewDataLines {
+        chip_type: ChipType,
+        required: u8,
+        available: usize,
+    },
+    RomImagesExceedFlash {
+        required: usize,
+        available: usize,
+    },
+    Io {
+        id: usize,
+        message: String,
+    },
+    UnknownBuiltin {
+        id: usize,
+        name: String,
+    },
     Base64,
     Base16,
+    WrongChipPins {
+        id: usize,
+        chip_type: ChipType,
+        board_pins: u8,
+    },
+    TruncationLosesData {
+        id: usize,
+        dropped_bytes: usize,
+    },
+    NoteTooLong {
+        len: usize,
+        max: usize,
+    },
+    InvalidAddrLineMap {
+        id: usize,
+        reason: String,
+    },
 }
 type Result<T> = core::result::Result<T, Error>;
 
+/// Numeric code and variant name for each [`Error`] variant, as returned by
+/// [`Error::code()`].  Codes are stable, so can be relied on across an FFI
+/// boundary - new variants are given new codes, and codes are never reused.
+pub const ERROR_CODES: &[(u32, &str)] = &[
+    (1, "RightSize"),
+    (2, "ImageTooSmall"),
+    (3, "ImageTooLarge"),
+    (4, "DuplicationNotExactDivisor"),
+    (5, "MirrorNotHalfSize"),
+    (6, "BufferTooSmall"),
+    (7, "NoChips"),
+    (8, "TooManyChips"),
+    (9, "TooFewChips"),
+    (10, "MissingCsConfig"),
+    (11, "MissingPointer"),
+    (12, "InvalidServeAlg"),
+    (13, "InconsistentCsLogic"),
+    (14, "InvalidConfig"),
+    (15, "UnsupportedConfigVersion"),
+    (16, "DuplicateFile"),
+    (17, "InvalidFile"),
+    (18, "MissingFile"),
+    (19, "UnsupportedChipType"),
+    (20, "InvalidLicense"),
+    (21, "UnvalidatedLicense"),
+    (22, "BadLocation"),
+    (23, "SizeHandlingNotAllowed"),
+    (24, "NotExactSize"),
+    (25, "MissingAddressLine"),
+    (26, "DuplicateAddressLine"),
+    (27, "UnsupportedFrequency"),
+    (28, "FirmwareTooOld"),
+    (29, "FirmwareTooNew"),
+    (30, "WrongMcuFamily"),
+    (31, "InvalidCompressedImages"),
+    (32, "InvalidDataPinMap"),
+    (33, "InvalidFlashImage"),
+    (34, "MetadataCrcMismatch"),
+    (35, "InvalidSocketOrder"),
+    (36, "InvalidCsBit"),
+    (37, "IncompatibleMultiChip"),
+    (38, "AddressOutOfBounds"),
+    (39, "TooFewDataLines"),
+    (40, "RomImagesExceedFlash"),
+    (41, "Io"),
+    (42, "UnknownBuiltin"),
+    (43, "Base64"),
+    (44, "Base16"),
+    (45, "WrongChipPins"),
+    (46, "TruncationLosesData"),
+    (47, "NoteTooLong"),
+    (48, "InvalidAddrLineMap"),
+];
+
+impl Error {
+    /// Returns the error's numeric code - see [`ERROR_CODES`].  Allows C
+    /// callers to branch on the error without parsing its text.
+    pub const fn code(&self) -> u32 {
+        match self {
+            Error::RightSize { .. } => 1,
+            Error::ImageTooSmall { .. } => 2,
+            Error::ImageTooLarge { .. } => 3,
+            Error::DuplicationNotExactDivisor { .. } => 4,
+            Error::MirrorNotHalfSize { .. } => 5,
+            Error::BufferTooSmall { .. } => 6,
+            Error::NoChips => 7,
+            Error::TooManyChips { .. } => 8,
+            Error::TooFewChips { .. } => 9,
+            Error::MissingCsConfig { .. } => 10,
+            Error::MissingPointer { .. } => 11,
+            Error::InvalidServeAlg { .. } => 12,
+            Error::InconsistentCsLogic { .. } => 13,
+            Error::InvalidConfig { .. } => 14,
+            Error::UnsupportedConfigVersion { .. } => 15,
+            Error::DuplicateFile { .. } => 16,
+            Error::InvalidFile { .. } => 17,
+            Error::MissingFile { .. } => 18,
+            Error::UnsupportedChipType { .. } => 19,
+            Error::InvalidLicense { .. } => 20,
+            Error::UnvalidatedLicense { .. } => 21,
+            Error::BadLocation { .. } => 22,
+            Error::SizeHandlingNotAllowed { .. } => 23,
+            Error::NotExactSize { .. } => 24,
+            Error::MissingAddressLine { .. } => 25,
+            Error::DuplicateAddressLine { .. } => 26,
+            Error::UnsupportedFrequency { .. } => 27,
+            Error::FirmwareTooOld { .. } => 28,
+            Error::FirmwareTooNew { .. } => 29,
+            Error::WrongMcuFamily { .. } => 30,
+            Error::InvalidCompressedImages { .. } => 31,
+            Error::InvalidDataPinMap { .. } => 32,
+            Error::InvalidFlashImage { .. } => 33,
+            Error::MetadataCrcMismatch { .. } => 34,
+            Error::InvalidSocketOrder { .. } => 35,
+            Error::InvalidCsBit { .. } => 36,
+            Error::IncompatibleMultiChip { .. } => 37,
+            Error::AddressOutOfBounds { .. } => 38,
+            Error::TooFewDataLines { .. } => 39,
+            Error::RomImagesExceedFlash { .. } => 40,
+            Error::Io { .. } => 41,
+            Error::UnknownBuiltin { .. } => 42,
+            Error::Base64 => 43,
+            Error::Base16 => 44,
+            Error::WrongChipPins { .. } => 45,
+            Error::TruncationLosesData { .. } => 46,
+            Error::NoteTooLong { .. } => 47,
+            Error::InvalidAddrLineMap { .. } => 48,
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::RightSize { size } => write!(
+                f,
+                "ROM image is already the expected {size} bytes, size handling must not be set"
+            ),
+            Error::ImageTooSmall { index, expected, actual } => write!(
+                f,
+                "ROM image at index {index} is {actual} bytes but chip expects {expected}"
+            ),
+            Error::ImageTooLarge { image_size, expected_size } => write!(
+                f,
+                "ROM image is {image_size} bytes but chip expects {expected_size}"
+            ),
+            Error::DuplicationNotExactDivisor { image_size, expected_size } => write!(
+                f,
+                "ROM image of {image_size} bytes cannot be duplicated to fill {expected_size} bytes"
+            ),
+            Error::MirrorNotHalfSize { image_size, expected_size } => write!(
+                f,
+                "ROM image of {image_size} bytes cannot be mirrored to fill {expected_size} bytes, it must be exactly half that size"
+            ),
+            Error::BufferTooSmall { location, expected, actual } => write!(
+                f,
+                "Buffer for {location} is {actual} bytes but {expected} are required"
+            ),
+            Error::NoChips => write!(f, "Chip set contains no chips"),
+            Error::TooManyChips { expected, actual } => write!(
+                f,
+                "Too many chips: {actual} supplied but at most {expected} supported"
+            ),
+            Error::TooFewChips { expected, actual } => write!(
+                f,
+                "Too few chips: {actual} supplied but at least {expected} required"
+            ),
+            Error::MissingCsConfig { line } => {
+                write!(f, "Missing chip select configuration for {line}")
+            }
+            Error::MissingPointer { id } => write!(f, "Missing metadata pointer for chip {id}"),
+            Error::InvalidServeAlg { serve_alg, reason } => {
+                write!(f, "Invalid serve algorithm {serve_alg:?}: {reason}")
+            }
+            Error::InconsistentCsLogic { first, other } => write!(
+                f,
+                "Inconsistent chip select logic within chip set: {first:?} and {other:?}"
+            ),
+            Error::InvalidConfig { error } => write!(f, "Invalid configuration: {error}"),
+            Error::UnsupportedConfigVersion { version } => {
+                write!(f, "Unsupported configuration version {version}")
+            }
+            Error::DuplicateFile { id } => write!(f, "File {id} has already been added"),
+            Error::InvalidFile { id, total } => write!(
+                f,
+                "File {id} is out of range, configuration has {total} files"
+            ),
+            Error::MissingFile { id } => write!(f, "File {id} has not been added"),
+            Error::UnsupportedChipType { chip_type } => {
+                write!(f, "Chip type {chip_type} is not supported")
+            }
+            Error::InvalidLicense { id } => write!(f, "Invalid license {id}"),
+            Error::UnvalidatedLicense { id } => {
+                write!(f, "License {id} has not been accepted")
+            }
+            Error::BadLocation { id, reason } => {
+                write!(f, "Bad location for file {id}: {reason}")
+            }
+            Error::SizeHandlingNotAllowed { id } => write!(
+                f,
+                "Size handling is not allowed for chip {id}"
+            ),
+            Error::NotExactSize { id, expected, actual } => write!(
+                f,
+                "File {id} is {actual} bytes but must be exactly {expected}"
+            ),
+            Error::MissingAddressLine { id, chip_type, addr_bit } => write!(
+                f,
+                "Board is missing address line A{addr_bit} required by chip {id} ({chip_type})"
+            ),
+            Error::DuplicateAddressLine { id, chip_type, addr_bit } => write!(
+                f,
+                "Address line A{addr_bit} is mapped more than once for chip {id} ({chip_type})"
+            ),
+            Error::UnsupportedFrequency { frequency_mhz } => {
+                write!(f, "Unsupported frequency {frequency_mhz}MHz")
+            }
+            Error::FirmwareTooOld { version, minimum } => write!(
+                f,
+                "Firmware version {version} is too old, at least {minimum} is required"
+            ),
+            Error::FirmwareTooNew { version, maximum } => write!(
+                f,
+                "Firmware version {version} is too new, at most {maximum} is supported"
+            ),
+            Error::WrongMcuFamily { actual, required } => write!(
+                f,
+                "Wrong MCU family {actual}, {required} is required"
+            ),
+            Error::InvalidCompressedImages { reason } => {
+                write!(f, "Invalid compressed images: {reason}")
+            }
+            Error::InvalidDataPinMap { reason } => write!(f, "Invalid data pin map: {reason}"),
+            Error::InvalidFlashImage { reason } => write!(f, "Invalid flash image: {reason}"),
+            Error::MetadataCrcMismatch { expected, actual } => write!(
+                f,
+                "Metadata CRC mismatch: header records {expected:#010X} but metadata is {actual:#010X}"
+            ),
+            Error::InvalidSocketOrder { reason } => write!(f, "Invalid socket order: {reason}"),
+            Error::InvalidCsBit { id, reason } => {
+                write!(f, "Invalid chip select bit for chip {id}: {reason}")
+            }
+            Error::IncompatibleMultiChip { id, reason } => {
+                write!(f, "Chip {id} cannot be served in a multi chip set: {reason}")
+            }
+            Error::AddressOutOfBounds { address, chip_size } => write!(
+                f,
+                "Address {address:#X} out of bounds for image of {chip_size} bytes"
+            ),
+            Error::TooFewDataLines {
+                chip_type,
+                required,
+                available,
+            } => write!(
+                f,
+                "Chip type {chip_type} needs {required} data lines, but the board has {available}"
+            ),
+            Error::RomImagesExceedFlash { required, available } => write!(
+                f,
+                "ROM images need {required} bytes of flash, but the MCU only has {available} available"
+            ),
+            Error::Io { id, message } => write!(f, "I/O error for file {id}: {message}"),
+            Error::UnknownBuiltin { id, name } => {
+                write!(f, "Unknown builtin image \"{name}\" for file {id}")
+            }
+            Error::Base64 => write!(f, "Invalid base64 data"),
+            Error::Base16 => write!(f, "Invalid base16 data"),
+            Error::WrongChipPins {
+                id,
+                chip_type,
+                board_pins,
+            } => write!(
+                f,
+                "Chip {id} is a {} part, but the board has a {board_pins}-pin socket",
+                chip_type.pinout_name()
+            ),
+            Error::TruncationLosesData { id, dropped_bytes } => write!(
+                f,
+                "Truncating file {id} would discard {dropped_bytes} bytes which are not all the fill byte"
+            ),
+            Error::NoteTooLong { len, max } => {
+                write!(f, "Note is {len} bytes, but must be at most {max} bytes")
+            }
+            Error::InvalidAddrLineMap { id, reason } => {
+                write!(f, "Invalid address line map for Chip {id}: {reason}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 pub fn crate_version() -> &'static str {
     env!("CARGO_PKG_VERSION")
 }
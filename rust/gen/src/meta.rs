@@ -6,16 +6,24 @@
 //!
 //!
 
+use alloc::format;
+use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
 
-use onerom_config::chip::ChipFunction;
-use onerom_config::fw::FirmwareVersion;
+use onerom_config::chip::{ChipFunction, ChipType};
+use onerom_config::fw::{FirmwareVersion, ServeAlg};
 use onerom_config::hw::Board;
 
 use crate::builder::{FireServeMode, FirmwareConfig, ServeAlgParams};
-use crate::image::{ChipSet, ChipSetType};
-use crate::{Error, FIRMWARE_SIZE, METADATA_VERSION, MIN_FIRMWARE_OVERRIDES_VERSION, Result};
+use crate::image::{
+    CHIP_SET_FILENAMES_ABSENT, CHIP_SET_FILENAMES_PRESENT, CS_STATE_CE_OE, Chip, ChipSet,
+    ChipSetType, CsLogic, Provenance, crc32_update,
+};
+use crate::{
+    Error, FIRMWARE_SIZE, METADATA_VERSION, MIN_FIRMWARE_OVERRIDES_VERSION,
+    MIN_METADATA_CRC_VERSION, MIN_PROVENANCE_VERSION, Result,
+};
 
 pub const PAD_METADATA_BYTE: u8 = 0xFF;
 
@@ -25,7 +33,7 @@ const HEADER_MAGIC: &[u8; 16] = b"ONEROM_METADATA\0";
 const METADATA_START: u32 = FIRMWARE_SIZE as u32;
 
 // ROM images start at 64KB from the start of flash.
-const ROM_IMAGE_DATA_START: u32 = 65536;
+pub(crate) const ROM_IMAGE_DATA_START: u32 = 65536;
 
 /// Metadata max length
 pub const MAX_METADATA_LEN: usize = 16384;
@@ -34,10 +42,34 @@ const METADATA_HEADER_LEN: usize = 256; // onerom_metadata_header_t
 
 const METADATA_CHIP_SET_OFFSET: usize = 24; // Offset of chip_set pointer in header
 
+const METADATA_LEN_OFFSET: usize = 36; // Offset of metadata length in header (version 2)
+
+const METADATA_CRC_OFFSET: usize = 40; // Offset of metadata CRC32 in header (version 2)
+
+const METADATA_DESCRIPTION_OFFSET: usize = 44; // Offset of description in header
+
+// Length of the header's description field, including the null terminator.
+// Previously reserved, so 0xFF in metadata without a description.
+const METADATA_DESCRIPTION_LEN: usize = 128;
+
+const METADATA_NOTE_OFFSET: usize = 172; // Offset of note in header
+
+// Length of the header's note field, including the null terminator.
+// Previously reserved, so 0xFF in metadata without a note.
+const METADATA_NOTE_LEN: usize = 64;
+
+/// Maximum length of a metadata note, in bytes - see
+/// [`Metadata::set_note()`].
+pub const MAX_NOTE_LEN: usize = METADATA_NOTE_LEN - 1;
+
+// Metadata version without a CRC, for firmware before MIN_METADATA_CRC_VERSION
+const METADATA_VERSION_NO_CRC: u32 = 1;
+
 pub(crate) const CHIP_SET_METADATA_LEN: usize = 16; // sdrr_rom_set_t
 pub(crate) const CHIP_SET_METADATA_LEN_EXTRA_INFO: usize = 64; // sdrr_rom_set_t
 pub(crate) const CHIP_SET_FIRMWARE_OVERRIDES_METADATA_LEN: usize = 64; // 0.6.0 onwards
 pub(crate) const CHIP_SET_SERVE_CONFIG_METADATA_LEN: usize = 64; // 0.6.0 onwards
+pub(crate) const CHIP_PROVENANCE_METADATA_LEN: usize = 8; // Per chip, 0.6.0 onwards
 
 /// Metadata for One ROM firmware
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -47,6 +79,17 @@ pub struct Metadata {
     filenames: bool,
     pio: bool,
     firmware_version: FirmwareVersion,
+    #[serde(default)]
+    provenance: bool,
+    #[serde(default)]
+    labels: bool,
+    // Physical flash slot for each chip set's image data, indexed by chip set
+    #[serde(default)]
+    socket_order: Option<Vec<usize>>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    note: Option<String>,
 }
 
 impl Metadata {
@@ -63,7 +106,51 @@ impl Metadata {
             filenames,
             pio,
             firmware_version,
+            provenance: false,
+            labels: false,
+            socket_order: None,
+            description: None,
+            note: None,
+        }
+    }
+
+    /// Places each chip set's image data at the physical flash slot the
+    /// hardware expects, rather than in chip set order.  `socket_order[i]`
+    /// is the slot for chip set `i`, and must be a permutation of the chip
+    /// set indexes.  The metadata continues to list chip sets in their
+    /// original order, with each data pointer referencing the remapped slot.
+    pub fn set_socket_order(&mut self, socket_order: Vec<usize>) -> Result<()> {
+        if socket_order.len() != self.chip_sets.len() {
+            return Err(Error::InvalidSocketOrder {
+                reason: format!(
+                    "Expected {} entries, got {}",
+                    self.chip_sets.len(),
+                    socket_order.len()
+                ),
+            });
+        }
+
+        let mut seen = vec![false; socket_order.len()];
+        for &slot in &socket_order {
+            if slot >= seen.len() || seen[slot] {
+                return Err(Error::InvalidSocketOrder {
+                    reason: format!("{:?} is not a permutation of the chip sets", socket_order),
+                });
+            }
+            seen[slot] = true;
+        }
+
+        self.socket_order = Some(socket_order);
+        Ok(())
+    }
+
+    // Chip set indexes in the order their image data is laid out in flash
+    fn sets_in_flash_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.chip_sets.len()).collect();
+        if let Some(socket_order) = &self.socket_order {
+            order.sort_by_key(|&ii| socket_order[ii]);
         }
+        order
     }
 
     pub fn set_pio(&mut self) {
@@ -74,10 +161,122 @@ impl Metadata {
         self.pio
     }
 
+    // Whether a chip set's chips include filenames - the chip set's boot
+    // logging override, if any, otherwise the firmware's setting
+    fn chip_set_filenames(&self, chip_set: &ChipSet) -> bool {
+        chip_set.boot_logging().unwrap_or(self.filenames)
+    }
+
+    // Whether a chip set's chips include labels in the filename slot, when
+    // they would otherwise have no filenames.  Only sets with at least one
+    // labelled chip, and only where the firmware records per set whether
+    // filenames are present.
+    fn chip_set_labels(&self, chip_set: &ChipSet) -> bool {
+        self.labels
+            && self.firmware_version >= MIN_FIRMWARE_OVERRIDES_VERSION
+            && !self.chip_set_filenames(chip_set)
+            && chip_set.chips().iter().any(|chip| chip.label().is_some())
+    }
+
+    // Whether a chip set's chips have their filename slot populated, with
+    // either filenames or labels
+    fn chip_set_names(&self, chip_set: &ChipSet) -> bool {
+        self.chip_set_filenames(chip_set) || self.chip_set_labels(chip_set)
+    }
+
+    // Whether any chip set's chips include filenames (or labels)
+    fn any_filenames(&self) -> bool {
+        self.chip_sets.iter().any(|set| self.chip_set_names(set))
+    }
+
+    /// Requests that Chip labels are written to the metadata filename slot,
+    /// even for Chip sets without boot logging, so analysis tools can show
+    /// friendly names.  Requires 0.6.0 firmware onwards.
+    pub fn set_labels(&mut self) {
+        self.labels = true;
+    }
+
+    /// Requests that the config's description is written to the metadata
+    /// header, so analysis tools can show it.  It is only written if it
+    /// fits - see [`Self::description()`].
+    pub fn set_description(&mut self, description: String) {
+        self.description = Some(description);
+    }
+
+    /// The description which will be written to the metadata header, if
+    /// any - one was requested, isn't empty, and fits in the header's 127
+    /// byte (plus null terminator) description field.
+    pub fn description(&self) -> Option<&str> {
+        self.description
+            .as_deref()
+            .filter(|description| !description.is_empty())
+            .filter(|description| description.len() < METADATA_DESCRIPTION_LEN)
+            .filter(|description| !description.contains('\0'))
+    }
+
+    /// Sets a free-text note to write to the metadata header, such as a
+    /// build date, git hash or asset tag, so analysis tools can show it.
+    ///
+    /// Returns [`Error::NoteTooLong`] if the note is longer than
+    /// [`MAX_NOTE_LEN`] bytes, and [`Error::InvalidConfig`] if it contains a
+    /// null character.  An empty note clears any existing note.
+    pub fn set_note(&mut self, note: String) -> Result<()> {
+        Self::check_note(&note)?;
+        self.note = Some(note).filter(|note| !note.is_empty());
+        Ok(())
+    }
+
+    /// Checks a note will fit in the metadata header - see
+    /// [`Self::set_note()`].
+    pub fn check_note(note: &str) -> Result<()> {
+        if note.len() > MAX_NOTE_LEN {
+            return Err(Error::NoteTooLong {
+                len: note.len(),
+                max: MAX_NOTE_LEN,
+            });
+        }
+        if note.contains('\0') {
+            return Err(Error::InvalidConfig {
+                error: "Note must not contain null characters".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// The note which will be written to the metadata header, if any.
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
+    /// Requests that each Chip's source file size and checksum are written
+    /// to the metadata.  They are only written if the firmware version
+    /// supports them, and there is space - see [`Self::provenance()`].
+    pub fn set_provenance(&mut self) {
+        self.provenance = true;
+    }
+
+    /// Whether provenance records will be written - requested, supported by
+    /// the firmware version, and fitting within [`MAX_METADATA_LEN`].
+    pub fn provenance(&self) -> bool {
+        self.provenance
+            && self.firmware_version >= MIN_PROVENANCE_VERSION
+            && self.base_metadata_len() + self.provenance_records_len() <= MAX_METADATA_LEN
+    }
+
     const fn header_len(&self) -> usize {
         METADATA_HEADER_LEN
     }
 
+    // Version of the metadata written, which depends on what the firmware
+    // supports
+    fn version(&self) -> u32 {
+        if self.firmware_version >= MIN_METADATA_CRC_VERSION {
+            METADATA_VERSION
+        } else {
+            METADATA_VERSION_NO_CRC
+        }
+    }
+
     const fn abs_metadata_start(&self) -> u32 {
         self.board.mcu_family().get_flash_base() + METADATA_START
     }
@@ -95,10 +294,8 @@ impl Metadata {
         // - All ROM set entries (16 bytes) - sdrr_chip_set_t
         // - Array of pointers to ROMs in each set (4 bytes per ROM)
         // - Each ROM entry (4-8 bytes) - sdrr_chip_info_t
-        let len = self.header_len()
-            + self.filenames_metadata_len()
-            + self.firmware_overrides_len()
-            + self.sets_len();
+        // - Provenance records (8 bytes per ROM), if enabled
+        let len = self.base_metadata_len() + self.provenance_len();
 
         if len > MAX_METADATA_LEN {
             panic!(
@@ -110,10 +307,35 @@ impl Metadata {
         len
     }
 
+    // Length of metadata excluding provenance records
+    fn base_metadata_len(&self) -> usize {
+        self.header_len()
+            + self.filenames_metadata_len()
+            + self.firmware_overrides_len()
+            + self.sets_len()
+    }
+
+    fn provenance_records_len(&self) -> usize {
+        self.total_chip_count() * CHIP_PROVENANCE_METADATA_LEN
+    }
+
+    fn provenance_len(&self) -> usize {
+        if self.provenance() {
+            self.provenance_records_len()
+        } else {
+            0
+        }
+    }
+
     pub fn total_set_count(&self) -> usize {
         self.chip_sets.len()
     }
 
+    /// Returns the chip sets, in metadata order
+    pub fn chip_sets(&self) -> &[ChipSet] {
+        &self.chip_sets
+    }
+
     // Total number of ROMs across all sets
     fn total_chip_count(&self) -> usize {
         self.chip_sets.iter().map(|rs| rs.chips().len()).sum()
@@ -121,15 +343,13 @@ impl Metadata {
 
     // Total length, including null terminators, of all filenames
     fn filenames_metadata_len(&self) -> usize {
-        let len = if !self.filenames {
-            0
-        } else {
-            self.chip_sets
-                .iter()
-                .flat_map(|rs| rs.chips())
-                .map(|rom| rom.filename().len() + 1)
-                .sum()
-        };
+        let len = self
+            .chip_sets
+            .iter()
+            .filter(|rs| self.chip_set_names(rs))
+            .flat_map(|rs| rs.chips())
+            .map(|rom| rom.filename().len() + 1)
+            .sum::<usize>();
         if len % 4 != 0 {
             // Align to 4 bytes
             len + (4 - (len % 4))
@@ -146,7 +366,10 @@ impl Metadata {
     fn sets_len(&self) -> usize {
         let mut total = 0;
         for set in &self.chip_sets {
-            total += set.chips_metadata_len(self.filenames);
+            total += set.chips_metadata_len(
+                self.chip_set_filenames(set),
+                self.chip_set_labels(set),
+            );
             total += set.chips().len() * 4;
         }
 
@@ -184,7 +407,7 @@ impl Metadata {
 
         // Write the filenames.
         let mut filename_ptrs = vec![0xFF_u32; self.total_chip_count()];
-        if self.filenames {
+        if self.any_filenames() {
             // Store off the offset where filenames start
             let filename_offset = offset;
 
@@ -193,9 +416,15 @@ impl Metadata {
             offset += filename_len;
 
             // Need to correct filename pointers to be absolute addresses.
-            // We need to add filename_offset plus the flash base
-            for ptr in filename_ptrs.iter_mut() {
-                *ptr += (filename_offset as u32) + self.abs_metadata_start();
+            // We need to add filename_offset plus the flash base.  Chips in
+            // sets without filenames have no pointer to correct.
+            for rom in self
+                .chip_sets
+                .iter()
+                .filter(|rs| self.chip_set_names(rs))
+                .flat_map(|rs| rs.chips())
+            {
+                filename_ptrs[rom.index()] += (filename_offset as u32) + self.abs_metadata_start();
             }
 
             if filename_len % 4 != 0 {
@@ -235,6 +464,15 @@ impl Metadata {
             }
         }
 
+        // Write each set's array of provenance records, one per chip
+        let mut provenance_ptrs = vec![None; self.chip_sets.len()];
+        if self.provenance() {
+            for (ii, chip_set) in self.chip_sets.iter().enumerate() {
+                provenance_ptrs[ii] = Some(offset as u32 + self.abs_metadata_start());
+                offset += Self::write_provenance(chip_set, &mut buf[offset..])?;
+            }
+        }
+
         // Pre-compute where the ROM set image data will live for each rom set
         // now, so we can fill in the pointers in each set.  This is from
         // the start of flash + 64KB.  We also set up a vec to hold offsets
@@ -243,7 +481,8 @@ impl Metadata {
         let mut rom_data_ptrs = vec![0u32; self.chip_sets.len()];
         let mut rom_data_ptr = self.abs_chip_image_start();
         let mut rtn_chip_data_ptr = 0;
-        for (ii, set) in self.chip_sets.iter().enumerate() {
+        for ii in self.sets_in_flash_order() {
+            let set = &self.chip_sets[ii];
             if !set.has_data() && (set.chip_function() == ChipFunction::Ram) {
                 // No ROM data for RAM chip sets
                 rom_data_ptrs[ii] = 0xFFFF_FFFF;
@@ -269,7 +508,8 @@ impl Metadata {
                 &mut buf[offset..],
                 &filename_ptrs,
                 &mut rom_metadata_ptrs,
-                self.filenames,
+                self.chip_set_filenames(chip_set),
+                self.chip_set_labels(chip_set),
             )?;
 
             // Now update this set's array of ROM pointers
@@ -303,18 +543,132 @@ impl Metadata {
                 &self.firmware_version,
                 serve_config_ptrs[ii],
                 firmware_overrides_ptrs[ii],
+                provenance_ptrs[ii],
+                // Signatures are only logged at boot, so only written if boot
+                // logging is enabled for this set
+                if self.chip_set_filenames(chip_set) {
+                    chip_set.signature()
+                } else {
+                    None
+                },
+                self.chip_set_labels(chip_set),
             )?;
         }
 
-        // Finally, update the pointer to the first ROM set in the header.
+        // Update the pointer to the first ROM set in the header.
         self.update_chip_set_ptr(&mut buf[..], first_chip_set_ptr)?;
 
+        // Finally, record the length and CRC, which cover everything written
+        if self.version() != METADATA_VERSION_NO_CRC {
+            buf[METADATA_LEN_OFFSET..METADATA_LEN_OFFSET + 4]
+                .copy_from_slice(&(offset as u32).to_le_bytes());
+            Self::write_crc(&mut buf[..offset])?;
+        }
+
+        Ok(offset)
+    }
+
+    /// Writes the CRC32 of version 2 metadata into its header, covering the
+    /// length recorded in the header, less the CRC itself.
+    ///
+    /// [`Self::write_all()`] writes the CRC, so this is only needed if the
+    /// metadata is modified afterwards.  Returns
+    /// [`Error::UnsupportedConfigVersion`] for version 1 metadata, which has
+    /// no CRC.
+    pub fn write_crc(buf: &mut [u8]) -> Result<()> {
+        match Self::crcs(buf)? {
+            Some((_, actual)) => {
+                buf[METADATA_CRC_OFFSET..METADATA_CRC_OFFSET + 4]
+                    .copy_from_slice(&actual.to_le_bytes());
+                Ok(())
+            }
+            None => Err(Error::UnsupportedConfigVersion {
+                version: METADATA_VERSION_NO_CRC,
+            }),
+        }
+    }
+
+    /// Verifies the CRC32 of metadata, which starts at the beginning of
+    /// `buf`.
+    ///
+    /// Returns `Ok(true)` if the CRC matches, `Ok(false)` for version 1
+    /// metadata, which has no CRC, and [`Error::MetadataCrcMismatch`] if the
+    /// metadata has been corrupted.
+    pub fn verify_crc(buf: &[u8]) -> Result<bool> {
+        match Self::crcs(buf)? {
+            Some((expected, actual)) if expected != actual => {
+                Err(Error::MetadataCrcMismatch { expected, actual })
+            }
+            Some(_) => Ok(true),
+            None => Ok(false),
+        }
+    }
+
+    // Returns the CRC recorded in the header, and the CRC of the metadata it
+    // covers, or None for version 1 metadata
+    fn crcs(buf: &[u8]) -> Result<Option<(u32, u32)>> {
+        let reader = MetadataReader { buf, start: 0 };
+
+        reader.check_len(0, METADATA_HEADER_LEN)?;
+        if &buf[..HEADER_MAGIC.len()] != HEADER_MAGIC {
+            return Err(Error::InvalidFlashImage {
+                reason: "Metadata header magic not found".to_string(),
+            });
+        }
+
+        match reader.u32_at(16)? {
+            METADATA_VERSION_NO_CRC => return Ok(None),
+            METADATA_VERSION => (),
+            version => return Err(Error::UnsupportedConfigVersion { version }),
+        }
+
+        let len = reader.u32_at(METADATA_LEN_OFFSET)? as usize;
+        if len < METADATA_HEADER_LEN {
+            return Err(Error::InvalidFlashImage {
+                reason: format!("Metadata length {len} is shorter than its header"),
+            });
+        }
+        reader.check_len(0, len)?;
+
+        let expected = reader.u32_at(METADATA_CRC_OFFSET)?;
+        let crc = crc32_update(0xFFFF_FFFF, &buf[..METADATA_CRC_OFFSET]);
+        let crc = crc32_update(crc, &buf[METADATA_CRC_OFFSET + 4..len]);
+
+        Ok(Some((expected, !crc)))
+    }
+
+    // Writes a provenance record for each chip in the set:
+    // - Source file size (u32)
+    // - Source file CRC-32 (u32)
+    //
+    // Chips without a source file (RAM) have both set to 0xFFFFFFFF.
+    fn write_provenance(chip_set: &ChipSet, buf: &mut [u8]) -> Result<usize> {
+        let expected_len = chip_set.chips().len() * CHIP_PROVENANCE_METADATA_LEN;
+        if buf.len() < expected_len {
+            return Err(Error::BufferTooSmall {
+                location: "write_provenance",
+                expected: expected_len,
+                actual: buf.len(),
+            });
+        }
+
+        let mut offset = 0;
+        for chip in chip_set.chips() {
+            let (size, checksum) = chip
+                .provenance()
+                .map_or((0xFFFF_FFFF, 0xFFFF_FFFF), |p| (p.size, p.checksum));
+            buf[offset..offset + 4].copy_from_slice(&size.to_le_bytes());
+            offset += 4;
+            buf[offset..offset + 4].copy_from_slice(&checksum.to_le_bytes());
+            offset += 4;
+        }
+
         Ok(offset)
     }
 
     // Writes all ROM filenames to provided buffer.
     fn write_filenames(&self, buf: &mut [u8], ptrs: &mut [u32]) -> Result<usize> {
-        if !self.filenames {
+        if !self.any_filenames() {
             return Ok(0);
         }
 
@@ -338,8 +692,15 @@ impl Metadata {
             });
         }
 
-        for (ii, rom) in self.chip_sets.iter().flat_map(|rs| rs.chips()).enumerate() {
+        let roms = self.chip_sets.iter().flat_map(|rs| {
+            let filenames = self.chip_set_names(rs);
+            rs.chips().iter().map(move |rom| (rom, filenames))
+        });
+        for (ii, (rom, filenames)) in roms.enumerate() {
             assert_eq!(ii, rom.index());
+            if !filenames {
+                continue;
+            }
 
             // Get the filename and its length
             let name_bytes = rom.filename().as_bytes();
@@ -372,7 +733,7 @@ impl Metadata {
         offset += len;
 
         let len = 4;
-        buf[offset..offset + len].copy_from_slice(&METADATA_VERSION.to_le_bytes());
+        buf[offset..offset + len].copy_from_slice(&self.version().to_le_bytes());
         offset += len;
 
         let len = 1;
@@ -389,8 +750,51 @@ impl Metadata {
         buf[offset..offset + len].copy_from_slice(&0xFFFFFFFF_u32.to_le_bytes());
         offset += len;
 
-        let len = 228;
-        buf[offset..offset + len].copy_from_slice(&[0xFFu8; 228]);
+        // Version of this crate, so tools can tell what generated the image
+        for part in [
+            env!("CARGO_PKG_VERSION_MAJOR"),
+            env!("CARGO_PKG_VERSION_MINOR"),
+            env!("CARGO_PKG_VERSION_PATCH"),
+        ] {
+            let len = 2;
+            let part = part.parse::<u16>().unwrap_or(0xFFFF);
+            buf[offset..offset + len].copy_from_slice(&part.to_le_bytes());
+            offset += len;
+        }
+
+        let len = 2;
+        buf[offset..offset + len].copy_from_slice(&[0u8; 2]);
+        offset += len;
+
+        // Metadata length and CRC (version 2) are written last, by write_all()
+        let len = 8;
+        buf[offset..offset + len].copy_from_slice(&[0xFFu8; 8]);
+        offset += len;
+
+        // Null terminated description, if any, padded with 0xFF
+        let len = METADATA_DESCRIPTION_LEN;
+        assert_eq!(offset, METADATA_DESCRIPTION_OFFSET);
+        buf[offset..offset + len].fill(0xFF);
+        if let Some(description) = self.description() {
+            let description = description.as_bytes();
+            buf[offset..offset + description.len()].copy_from_slice(description);
+            buf[offset + description.len()] = 0;
+        }
+        offset += len;
+
+        // Null terminated note, if any, padded with 0xFF
+        let len = METADATA_NOTE_LEN;
+        assert_eq!(offset, METADATA_NOTE_OFFSET);
+        buf[offset..offset + len].fill(0xFF);
+        if let Some(note) = self.note() {
+            let note = note.as_bytes();
+            buf[offset..offset + note.len()].copy_from_slice(note);
+            buf[offset + note.len()] = 0;
+        }
+        offset += len;
+
+        let len = METADATA_HEADER_LEN - offset;
+        buf[offset..offset + len].fill(0xFF);
         offset += len;
 
         // Final sanity check
@@ -423,8 +827,55 @@ impl Metadata {
             .sum()
     }
 
-    /// Write all ROM images to buffer
+    /// Write all ROM images to buffer.
+    ///
+    /// With the `rayon` feature, each Chip set's image is generated in
+    /// parallel - they occupy disjoint regions of the buffer, so the output
+    /// is identical to [`Self::write_roms_sequential()`].
     pub fn write_roms(&self, buf: &mut [u8]) -> Result<()> {
+        let images = self.rom_images(buf)?;
+
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            images
+                .into_par_iter()
+                .try_for_each(|(chip_set, flip_cs1_x, image)| {
+                    self.write_rom(chip_set, flip_cs1_x, image)
+                })?;
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        for (chip_set, flip_cs1_x, image) in images {
+            self.write_rom(chip_set, flip_cs1_x, image)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write all ROM images to buffer, one Chip set at a time.
+    pub fn write_roms_sequential(&self, buf: &mut [u8]) -> Result<()> {
+        for (chip_set, flip_cs1_x, image) in self.rom_images(buf)? {
+            self.write_rom(chip_set, flip_cs1_x, image)?;
+        }
+        Ok(())
+    }
+
+    // Fill a Chip set's image by calling get_byte for each address
+    fn write_rom(&self, chip_set: &ChipSet, flip_cs1_x: bool, image: &mut [u8]) -> Result<()> {
+        for (addr, byte) in image.iter_mut().enumerate() {
+            *byte = chip_set.get_byte(addr, &self.board, flip_cs1_x)?;
+        }
+        Ok(())
+    }
+
+    // Splits the buffer into each Chip set's image, in flash order, along
+    // with the set and whether to flip the sense of CS1/X1/X2.
+    #[allow(clippy::type_complexity)]
+    fn rom_images<'a>(
+        &'a self,
+        buf: &'a mut [u8],
+    ) -> Result<Vec<(&'a ChipSet, bool, &'a mut [u8])>> {
         // Validate buffer size
         if buf.len() < self.rom_images_size() {
             return Err(Error::BufferTooSmall {
@@ -434,8 +885,11 @@ impl Metadata {
             });
         }
 
-        let mut offset = 0;
-        for chip_set in &self.chip_sets {
+        let mut images = Vec::new();
+        let mut rest = buf;
+        for ii in self.sets_in_flash_order() {
+            let chip_set = &self.chip_sets[ii];
+
             // Don't write a ROM image for RAM chip sets
             if !chip_set.has_data() && chip_set.chip_function() == ChipFunction::Ram {
                 continue;
@@ -461,16 +915,12 @@ impl Metadata {
             };
 
             let size = chip_set.image_size(&self.board.mcu_family(), self.board.chip_pins());
-
-            // Fill buffer by calling get_byte for each address
-            for addr in 0..size {
-                buf[offset + addr] = chip_set.get_byte(addr, &self.board, flip_cs1_x);
-            }
-
-            offset += size;
+            let (image, remaining) = core::mem::take(&mut rest).split_at_mut(size);
+            images.push((chip_set, flip_cs1_x, image));
+            rest = remaining;
         }
 
-        Ok(())
+        Ok(images)
     }
 
     /// Serialize FirmwareConfig into the 64-byte onerom_firmware_overrides_t structure
@@ -674,4 +1124,363 @@ impl Metadata {
         }
         total
     }
+
+    /// Parses metadata written by [`Self::write_all()`] back into structured
+    /// form, following the header's chip set pointer, and each chip set's
+    /// chip, filename and provenance pointers.
+    ///
+    /// `buf` starts at the metadata, which is located at `flash_base` plus
+    /// [`FIRMWARE_SIZE`].  `boot_logging` is the firmware's boot logging
+    /// setting, which determines whether Chips include filenames in chip
+    /// sets without their own boot logging override (all chip sets before
+    /// 0.6.0) - this is recorded by the firmware, not in the metadata.
+    ///
+    /// Returns [`Error::UnsupportedConfigVersion`] if the metadata version is
+    /// not supported, and [`Error::BufferTooSmall`] if the metadata is
+    /// truncated.
+    pub fn parse(buf: &[u8], flash_base: u32, boot_logging: bool) -> Result<ParsedMetadata> {
+        let reader = MetadataReader {
+            buf,
+            start: flash_base + METADATA_START,
+        };
+
+        reader.check_len(0, METADATA_HEADER_LEN)?;
+        if &buf[..HEADER_MAGIC.len()] != HEADER_MAGIC {
+            return Err(Error::InvalidFlashImage {
+                reason: "Metadata header magic not found".to_string(),
+            });
+        }
+
+        // Version 2 only adds the CRC, which isn't checked here - see
+        // Self::verify_crc()
+        let version = reader.u32_at(16)?;
+        if version != METADATA_VERSION && version != METADATA_VERSION_NO_CRC {
+            return Err(Error::UnsupportedConfigVersion { version });
+        }
+
+        let chip_set_count = buf[20] as usize;
+        let generator_version = [
+            reader.u16_at(28)?,
+            reader.u16_at(30)?,
+            reader.u16_at(32)?,
+        ];
+        let generator_version = if generator_version == [0xFFFF; 3] {
+            None
+        } else {
+            Some(generator_version)
+        };
+
+        let description = reader.description()?;
+        let note = reader.note()?;
+
+        let mut chip_sets = Vec::with_capacity(chip_set_count);
+        if chip_set_count > 0 {
+            let mut offset = reader.offset_of(reader.u32_at(METADATA_CHIP_SET_OFFSET)?)?;
+            for _ in 0..chip_set_count {
+                let (chip_set, len) = reader.chip_set_at(offset, boot_logging)?;
+                chip_sets.push(chip_set);
+                offset += len;
+            }
+        }
+
+        Ok(ParsedMetadata {
+            version,
+            generator_version,
+            description,
+            note,
+            chip_sets,
+        })
+    }
+}
+
+/// Metadata parsed by [`Metadata::parse()`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ParsedMetadata {
+    /// Version of the metadata format
+    pub version: u32,
+
+    /// Version (major, minor, patch) of the crate which generated the
+    /// metadata, if recorded
+    pub generator_version: Option<[u16; 3]>,
+
+    /// Description of the config the metadata was built from, if recorded
+    pub description: Option<String>,
+
+    /// Note stamped when the metadata was built, if recorded - see
+    /// [`Metadata::set_note()`]
+    pub note: Option<String>,
+
+    /// Chip sets, in metadata order
+    pub chip_sets: Vec<ParsedChipSet>,
+}
+
+/// Chip set parsed by [`Metadata::parse()`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ParsedChipSet {
+    /// Absolute address of the chip set's image data, None for chip sets
+    /// without data (RAM)
+    pub data_ptr: Option<u32>,
+
+    /// Size of the chip set's image data in bytes
+    pub size: u32,
+
+    /// Serving algorithm the firmware runs.  This is resolved for the set
+    /// type when written, so is never [`ServeAlg::Default`].
+    pub serve_alg: ServeAlg,
+
+    /// CS1 logic shared by all Chips in multi and banked sets
+    /// ([`CsLogic::Ignore`] for single sets)
+    pub multi_cs_logic: CsLogic,
+
+    /// Chips in the set
+    pub chips: Vec<ParsedChip>,
+
+    /// Absolute address of any serving algorithm parameters (0.6.0 onwards)
+    pub serve_config_ptr: Option<u32>,
+
+    /// Absolute address of any firmware overrides (0.6.0 onwards)
+    pub firmware_overrides_ptr: Option<u32>,
+
+    /// Signature of the chip set's content, if written (0.6.0 onwards)
+    pub signature: Option<[u8; 4]>,
+
+    /// Whether the chip set's Chips include filenames, if recorded by the
+    /// chip set (0.6.0 onwards) rather than following the firmware's boot
+    /// logging setting
+    pub filenames: Option<bool>,
+}
+
+/// Chip parsed by [`Metadata::parse()`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ParsedChip {
+    /// Type of the Chip
+    pub chip_type: ChipType,
+
+    /// CS1 logic
+    pub cs1: CsLogic,
+
+    /// CS2 logic, None if unused
+    pub cs2: Option<CsLogic>,
+
+    /// CS3 logic, None if unused
+    pub cs3: Option<CsLogic>,
+
+    /// Whether the Chip is selected by CE/OE rather than CS lines.  If so,
+    /// `cs1` and `cs2` are the CE and OE logic.
+    pub ce_oe: bool,
+
+    /// Filename (or label), if included
+    pub filename: Option<String>,
+
+    /// Size and checksum of the Chip's source file, if included
+    pub provenance: Option<Provenance>,
+}
+
+// Reads metadata fields, converting absolute pointers to offsets within the
+// metadata buffer
+struct MetadataReader<'a> {
+    buf: &'a [u8],
+    start: u32,
+}
+
+impl MetadataReader<'_> {
+    fn check_len(&self, offset: usize, len: usize) -> Result<()> {
+        if offset + len > self.buf.len() {
+            return Err(Error::BufferTooSmall {
+                location: "Metadata::parse",
+                expected: offset + len,
+                actual: self.buf.len(),
+            });
+        }
+        Ok(())
+    }
+
+    fn u16_at(&self, offset: usize) -> Result<u16> {
+        self.check_len(offset, 2)?;
+        Ok(u16::from_le_bytes([self.buf[offset], self.buf[offset + 1]]))
+    }
+
+    fn u32_at(&self, offset: usize) -> Result<u32> {
+        self.check_len(offset, 4)?;
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(&self.buf[offset..offset + 4]);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn offset_of(&self, ptr: u32) -> Result<usize> {
+        let offset = ptr.checked_sub(self.start).ok_or_else(|| Error::InvalidFlashImage {
+            reason: format!("Pointer {:#010X} is before the metadata", ptr),
+        })? as usize;
+        self.check_len(offset, 1)?;
+        Ok(offset)
+    }
+
+    fn string_at(&self, ptr: u32) -> Result<String> {
+        let offset = self.offset_of(ptr)?;
+        let len = self.buf[offset..]
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(Error::BufferTooSmall {
+                location: "Metadata::parse",
+                expected: self.buf.len() + 1,
+                actual: self.buf.len(),
+            })?;
+        String::from_utf8(self.buf[offset..offset + len].to_vec()).map_err(|_| {
+            Error::InvalidFlashImage {
+                reason: format!("String at {:#010X} is not valid UTF-8", ptr),
+            }
+        })
+    }
+
+    // Parses the header's description, if present.  Metadata generated
+    // before descriptions were added has 0xFF padding here.
+    fn description(&self) -> Result<Option<String>> {
+        self.header_string(
+            METADATA_DESCRIPTION_OFFSET,
+            METADATA_DESCRIPTION_LEN,
+            "Description",
+        )
+    }
+
+    // Parses the header's note, if present.  Metadata generated before notes
+    // were added has 0xFF padding here.
+    fn note(&self) -> Result<Option<String>> {
+        self.header_string(METADATA_NOTE_OFFSET, METADATA_NOTE_LEN, "Note")
+    }
+
+    // Parses a null terminated string field in the header, None if the field
+    // is 0xFF padding.
+    fn header_string(&self, offset: usize, len: usize, name: &str) -> Result<Option<String>> {
+        self.check_len(offset, len)?;
+        let field = &self.buf[offset..offset + len];
+        if field[0] == 0xFF {
+            return Ok(None);
+        }
+
+        let len = field
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| Error::InvalidFlashImage {
+                reason: format!("{name} is not null terminated"),
+            })?;
+        String::from_utf8(field[..len].to_vec())
+            .map(Some)
+            .map_err(|_| Error::InvalidFlashImage {
+                reason: format!("{name} is not valid UTF-8"),
+            })
+    }
+
+    // Parses the chip set structure at the given offset, returning it and
+    // its length
+    fn chip_set_at(&self, offset: usize, boot_logging: bool) -> Result<(ParsedChipSet, usize)> {
+        self.check_len(offset, CHIP_SET_METADATA_LEN)?;
+        let data_ptr = self.u32_at(offset)?;
+        let size = self.u32_at(offset + 4)?;
+        let chip_array_ptr = self.u32_at(offset + 8)?;
+        let num_chips = self.buf[offset + 12] as usize;
+        let serve_alg = match self.buf[offset + 13] {
+            0 => ServeAlg::TwoCsOneAddr,
+            1 => ServeAlg::AddrOnCs,
+            2 => ServeAlg::AddrOnAnyCs,
+            other => {
+                return Err(Error::InvalidFlashImage {
+                    reason: format!("Invalid serving algorithm {}", other),
+                });
+            }
+        };
+        let multi_cs_logic = self.cs_logic(self.buf[offset + 14])?;
+        let extended = self.buf[offset + 15] == 1;
+
+        let (len, serve_config_ptr, firmware_overrides_ptr, provenance_ptr, signature, filenames) =
+            if extended {
+                self.check_len(offset, CHIP_SET_METADATA_LEN_EXTRA_INFO)?;
+                let optional = |ptr: u32, absent: u32| (ptr != absent).then_some(ptr);
+                let mut signature = [0u8; 4];
+                signature.copy_from_slice(&self.buf[offset + 28..offset + 32]);
+                let filenames = match self.buf[offset + 32] {
+                    CHIP_SET_FILENAMES_PRESENT => Some(true),
+                    CHIP_SET_FILENAMES_ABSENT => Some(false),
+                    _ => None,
+                };
+                (
+                    CHIP_SET_METADATA_LEN_EXTRA_INFO,
+                    optional(self.u32_at(offset + 16)?, 0xFFFF_FFFF),
+                    optional(self.u32_at(offset + 20)?, 0xFFFF_FFFF),
+                    optional(self.u32_at(offset + 24)?, 0),
+                    (signature != [0u8; 4]).then_some(signature),
+                    filenames,
+                )
+            } else {
+                (CHIP_SET_METADATA_LEN, None, None, None, None, None)
+            };
+        let include_filenames = filenames.unwrap_or(boot_logging);
+
+        let chip_array_offset = self.offset_of(chip_array_ptr)?;
+        let mut chips = Vec::with_capacity(num_chips);
+        for ii in 0..num_chips {
+            let chip_offset = self.offset_of(self.u32_at(chip_array_offset + ii * 4)?)?;
+            self.check_len(chip_offset, 4)?;
+            let chip_type =
+                Chip::chip_type_from_c_enum_val(self.buf[chip_offset]).ok_or_else(|| {
+                    Error::InvalidFlashImage {
+                        reason: format!("Invalid Chip type {}", self.buf[chip_offset]),
+                    }
+                })?;
+            let cs1 = self.cs_logic(self.buf[chip_offset + 1])?;
+            let cs2 = self.cs_logic(self.buf[chip_offset + 2])?;
+            let ce_oe = self.buf[chip_offset + 3] == CS_STATE_CE_OE;
+            let cs3 = if ce_oe {
+                CsLogic::Ignore
+            } else {
+                self.cs_logic(self.buf[chip_offset + 3])?
+            };
+
+            let filename = if include_filenames {
+                Some(self.string_at(self.u32_at(chip_offset + 4)?)?)
+            } else {
+                None
+            };
+
+            let provenance = match provenance_ptr {
+                Some(ptr) => {
+                    let record = self.offset_of(ptr)? + ii * CHIP_PROVENANCE_METADATA_LEN;
+                    let size = self.u32_at(record)?;
+                    let checksum = self.u32_at(record + 4)?;
+                    (size != 0xFFFF_FFFF || checksum != 0xFFFF_FFFF)
+                        .then_some(Provenance { size, checksum })
+                }
+                None => None,
+            };
+
+            chips.push(ParsedChip {
+                chip_type,
+                cs1,
+                // Unused CS2/CS3 lines are written as ignored
+                cs2: (cs2 != CsLogic::Ignore).then_some(cs2),
+                cs3: (cs3 != CsLogic::Ignore).then_some(cs3),
+                ce_oe,
+                filename,
+                provenance,
+            });
+        }
+
+        let chip_set = ParsedChipSet {
+            data_ptr: (data_ptr != 0xFFFF_FFFF).then_some(data_ptr),
+            size,
+            serve_alg,
+            multi_cs_logic,
+            chips,
+            serve_config_ptr,
+            firmware_overrides_ptr,
+            signature,
+            filenames,
+        };
+        Ok((chip_set, len))
+    }
+
+    fn cs_logic(&self, val: u8) -> Result<CsLogic> {
+        CsLogic::from_c_enum_val(val).ok_or_else(|| Error::InvalidFlashImage {
+            reason: format!("Invalid CS logic {}", val),
+        })
+    }
 }
@@ -0,0 +1,212 @@
+// Copyright (C) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT License
+
+//! Board pin map tables
+//!
+//! Collects a board's address, data, X and Chip control line mappings in one
+//! place, to help debug images served with the wrong bytes.  Each line shows
+//! both the MCU GPIO pin and the bit it occupies in the Chip image - the
+//! address bit indexing the image, or the data bit within each byte - which
+//! can differ, for example on RP2350 boards with data lines on GPIOs 16-23.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use onerom_config::chip::ChipType;
+use onerom_config::hw::Board;
+
+use crate::image::{data_pin_bit, handle_snowflake_chip_types};
+
+/// A single line's mapping
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PinMapping {
+    /// Line name, such as "A0", "D7", "X1" or "CS1"
+    pub name: String,
+
+    /// MCU GPIO pin the line is connected to
+    pub gpio: u8,
+
+    /// Bit the line occupies in the Chip image - the address bit for address,
+    /// X and control lines, or the bit within the served byte (or word) for
+    /// data lines
+    pub bit: u8,
+}
+
+/// Mappings specific to a Chip type
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ChipPinMap {
+    /// Chip type
+    pub chip_type: ChipType,
+
+    /// CS1/CS2/CS3/CE/OE lines this Chip type uses
+    pub control: Vec<PinMapping>,
+
+    /// Address lines mapped differently to the board's schematic layout for
+    /// this Chip type - such as A11 and A12, which are swapped on the 2732
+    pub addr_overrides: Vec<PinMapping>,
+}
+
+/// A board's full pin map - see [`BoardPinMap::pin_map_table()`].
+///
+/// Displays as a readable table.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PinMapTable {
+    /// Board
+    pub board: Board,
+
+    /// Address lines, A0 upwards, as laid out in the board's schematics
+    pub addr: Vec<PinMapping>,
+
+    /// Data lines, D0 upwards
+    pub data: Vec<PinMapping>,
+
+    /// X1 and X2 lines, if the board has them
+    pub x: Vec<PinMapping>,
+
+    /// Mappings for each Chip type the board supports
+    pub chip_types: Vec<ChipPinMap>,
+}
+
+/// Collects a board's pin mappings
+pub trait BoardPinMap {
+    /// Returns the board's address, data, X and Chip control line mappings,
+    /// for each Chip type it supports.
+    fn pin_map_table(&self) -> PinMapTable;
+}
+
+impl BoardPinMap for Board {
+    fn pin_map_table(&self) -> PinMapTable {
+        let board_addr_map = self.phys_pin_to_addr_map();
+        let addr = addr_mappings(self.addr_pins(), board_addr_map);
+
+        let data = self
+            .data_pins()
+            .iter()
+            .enumerate()
+            .map(|(line, &gpio)| PinMapping {
+                name: format!("D{line}"),
+                gpio,
+                bit: data_pin_bit(gpio),
+            })
+            .collect();
+
+        let x = [
+            ("X1", self.pin_x1(), self.bit_x1()),
+            ("X2", self.pin_x2(), self.bit_x2()),
+        ]
+        .into_iter()
+        .filter(|&(_, gpio, _)| gpio != 255)
+        .map(|(name, gpio, bit)| PinMapping {
+            name: name.into(),
+            gpio,
+            bit,
+        })
+        .collect();
+
+        let chip_types = self
+            .supported_chip_types()
+            .into_iter()
+            .map(|chip_type| {
+                let control = [
+                    ("CS1", self.pin_cs1(chip_type), self.bit_cs1(chip_type)),
+                    ("CS2", self.pin_cs2(chip_type), self.bit_cs2(chip_type)),
+                    ("CS3", self.pin_cs3(chip_type), self.bit_cs3(chip_type)),
+                    ("CE", self.pin_ce(chip_type), self.bit_ce(chip_type)),
+                    ("OE", self.pin_oe(chip_type), self.bit_oe(chip_type)),
+                ]
+                .into_iter()
+                .filter(|&(_, gpio, _)| gpio != 255)
+                .map(|(name, gpio, bit)| PinMapping {
+                    name: name.into(),
+                    gpio,
+                    bit,
+                })
+                .collect();
+
+                // Only list the address lines this Chip type moves
+                let chip_addr_map = handle_snowflake_chip_types(board_addr_map, &chip_type);
+                let addr_overrides = addr_mappings(self.addr_pins(), &chip_addr_map)
+                    .into_iter()
+                    .zip(addr.iter())
+                    .filter(|(chip_line, board_line)| chip_line != *board_line)
+                    .map(|(chip_line, _)| chip_line)
+                    .collect();
+
+                ChipPinMap {
+                    chip_type,
+                    control,
+                    addr_overrides,
+                }
+            })
+            .collect();
+
+        PinMapTable {
+            board: *self,
+            addr,
+            data,
+            x,
+            chip_types,
+        }
+    }
+}
+
+// Maps each address line to its GPIO pin and its bit in the image address,
+// given a physical pin (image address bit) to address line map.
+fn addr_mappings(addr_pins: &[u8], phys_pin_to_addr_map: &[Option<usize>]) -> Vec<PinMapping> {
+    addr_pins
+        .iter()
+        .enumerate()
+        .filter_map(|(line, &gpio)| {
+            let bit = phys_pin_to_addr_map
+                .iter()
+                .position(|&addr_line| addr_line == Some(line))?;
+            Some(PinMapping {
+                name: format!("A{line}"),
+                gpio,
+                bit: bit as u8,
+            })
+        })
+        .collect()
+}
+
+impl fmt::Display for PinMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<4} GPIO {:>2} -> bit {:>2}",
+            self.name, self.gpio, self.bit
+        )
+    }
+}
+
+impl fmt::Display for PinMapTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Board: {}", self.board.name())?;
+        for (title, lines) in [
+            ("Address lines (bit in image address)", &self.addr),
+            ("Data lines (bit in served byte)", &self.data),
+            ("X lines (bit in image address)", &self.x),
+        ] {
+            if lines.is_empty() {
+                continue;
+            }
+            writeln!(f, "{title}:")?;
+            for line in lines {
+                writeln!(f, "  {line}")?;
+            }
+        }
+        for chip in &self.chip_types {
+            writeln!(f, "{}:", chip.chip_type)?;
+            for line in &chip.control {
+                writeln!(f, "  {line}")?;
+            }
+            for line in &chip.addr_overrides {
+                writeln!(f, "  {line} (overrides board layout)")?;
+            }
+        }
+        Ok(())
+    }
+}
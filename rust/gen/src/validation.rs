@@ -0,0 +1,253 @@
+// Copyright (C) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT License
+
+//! Describes the validation rules the builder enforces.
+//!
+//! Intended for documentation, and to help users understand why a config or
+//! its files were rejected.
+
+use alloc::vec::Vec;
+
+/// A validation rule enforced when parsing a config, loading files or
+/// building.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct RuleDescription {
+    /// Short identifier for the rule
+    pub name: &'static str,
+
+    /// What the rule checks
+    pub description: &'static str,
+
+    /// Name of the [`crate::Error`] variant returned when the rule fails
+    pub error: &'static str,
+}
+
+const fn rule(
+    name: &'static str,
+    error: &'static str,
+    description: &'static str,
+) -> RuleDescription {
+    RuleDescription {
+        name,
+        description,
+        error,
+    }
+}
+
+const RULES: &[RuleDescription] = &[
+    // Config
+    rule(
+        "config_version",
+        "UnsupportedConfigVersion",
+        "The config's version must be supported by this crate",
+    ),
+    rule(
+        "config_fields",
+        "InvalidConfig",
+        "The config must be valid JSON, and its fields consistent - for example each ROM \
+         specifies a file, Chips in a banked set are the same type, a location isn't empty, \
+         doesn't have a zero stride and isn't combined with half, a window offset is within \
+         each Chip's window, and 16-bit Chip types are in single sets",
+    ),
+    rule(
+        "builtin_name",
+        "UnknownBuiltin",
+        "A file referencing a built-in image must name a known built-in",
+    ),
+    rule(
+        "note_length",
+        "NoteTooLong",
+        "Any metadata note must fit in the metadata header",
+    ),
+    // Chip sets
+    rule(
+        "set_not_empty",
+        "NoChips",
+        "Each chip set must contain at least one Chip",
+    ),
+    rule(
+        "set_max_chips",
+        "TooManyChips",
+        "Single sets must contain exactly one Chip, and multi and banked sets no more than \
+         their maximum",
+    ),
+    rule(
+        "set_min_chips",
+        "TooFewChips",
+        "Multi and banked sets must contain at least two Chips",
+    ),
+    rule(
+        "serve_alg",
+        "InvalidServeAlg",
+        "A set's serving algorithm must suit its set type, and its Chips' CS lines",
+    ),
+    rule(
+        "socket_order",
+        "InvalidSocketOrder",
+        "A socket order must be a permutation of the chip set indexes",
+    ),
+    // Chip select lines
+    rule(
+        "cs_lines_specified",
+        "MissingCsConfig",
+        "Every CS line the Chip type uses must have its logic specified",
+    ),
+    rule(
+        "cs_consistency",
+        "InconsistentCsLogic",
+        "All Chips in a multi or banked set must share the same CS logic",
+    ),
+    rule(
+        "cs_bits",
+        "InvalidCsBit",
+        "CS2/CS3 bit overrides must be for lines the Chip type has, within range, and not \
+         clash with other lines",
+    ),
+    // Hardware
+    rule(
+        "chip_type_supported",
+        "WrongChipPins",
+        "Every Chip type must have as many pins as the board's socket",
+    ),
+    rule(
+        "data_lines",
+        "TooFewDataLines",
+        "The board must have as many data lines as each Chip type uses - 16 for 16-bit Chip \
+         types such as the 27C400",
+    ),
+    rule(
+        "addr_map_complete",
+        "MissingAddressLine",
+        "The board must map every address line the Chip type uses",
+    ),
+    rule(
+        "addr_map_unique",
+        "DuplicateAddressLine",
+        "The board must map each address line only once",
+    ),
+    rule(
+        "flash_space",
+        "RomImagesExceedFlash",
+        "All of the ROM images must fit in the MCU's flash, after the firmware and metadata",
+    ),
+    rule(
+        "multi_chip_addressing",
+        "IncompatibleMultiChip",
+        "Chips in a multi set must fit the same socket, be selectable by CS1, X1 or X2, and \
+         not use those lines as address lines",
+    ),
+    rule(
+        "data_pin_map",
+        "InvalidDataPinMap",
+        "A data pin map must be a permutation of the board's data pins",
+    ),
+    rule(
+        "addr_line_map",
+        "InvalidAddrLineMap",
+        "A Chip set's address line map must be a permutation of each of its Chips' address \
+         lines, and isn't supported for 16-bit Chip sets",
+    ),
+    // Firmware version
+    rule(
+        "firmware_too_new",
+        "FirmwareTooNew",
+        "The firmware version must be no newer than this crate supports",
+    ),
+    rule(
+        "firmware_too_old",
+        "FirmwareTooOld",
+        "Firmware overrides, per-set boot logging and other extended features require \
+         0.6.0 firmware onwards",
+    ),
+    rule(
+        "mcu_family",
+        "WrongMcuFamily",
+        "Firmware must be for the MCU family the config was loaded for",
+    ),
+    // Files
+    rule(
+        "file_id",
+        "InvalidFile",
+        "A loaded file's id must be one of the config's file specs",
+    ),
+    rule(
+        "file_unique",
+        "DuplicateFile",
+        "Each file must only be loaded once",
+    ),
+    rule(
+        "files_loaded",
+        "MissingFile",
+        "Every file must be loaded before building",
+    ),
+    rule(
+        "file_readable",
+        "Io",
+        "A file loaded from the filesystem must be readable",
+    ),
+    rule(
+        "licenses_valid",
+        "InvalidLicense",
+        "A validated license must be one the config requires",
+    ),
+    rule(
+        "licenses_validated",
+        "UnvalidatedLicense",
+        "Every license must be validated before building",
+    ),
+    // Size handling
+    rule(
+        "location",
+        "BadLocation",
+        "A location (including any stride) must not overflow, a file split in half must have \
+         an even length, and a window offset plus the file's size must fit within the Chip's \
+         window",
+    ),
+    rule(
+        "image_too_small",
+        "ImageTooSmall",
+        "A file, or its location, must be large enough for the Chip, unless padded",
+    ),
+    rule(
+        "image_too_large",
+        "ImageTooLarge",
+        "A file must not be larger than the Chip, unless truncated",
+    ),
+    rule(
+        "size_handling_needed",
+        "RightSize",
+        "Size handling must only be specified for files which aren't the Chip's size",
+    ),
+    rule(
+        "duplicate_divisor",
+        "DuplicationNotExactDivisor",
+        "A duplicated file's size must exactly divide the Chip's size",
+    ),
+    rule(
+        "mirror_half",
+        "MirrorNotHalfSize",
+        "A mirrored file must be exactly half the Chip's size",
+    ),
+    rule(
+        "strict_truncate",
+        "TruncationLosesData",
+        "With strict truncation, a truncated file's discarded tail must be entirely the fill byte",
+    ),
+    rule(
+        "size_handling_allowed",
+        "SizeHandlingNotAllowed",
+        "Size handling must not be specified when exact sizes are required",
+    ),
+    rule(
+        "exact_size",
+        "NotExactSize",
+        "When exact sizes are required, each file must exactly match its Chip's size",
+    ),
+];
+
+/// Returns the validation rules the builder enforces, each with the
+/// [`crate::Error`] variant returned when it fails.
+pub fn validation_rules() -> Vec<RuleDescription> {
+    RULES.to_vec()
+}
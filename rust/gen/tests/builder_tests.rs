@@ -44,6 +44,8 @@
 //! - [x] Exact size match (no size_handling needed)
 //! - [x] Duplicate (smaller file, exact divisor)
 //! - [x] Pad (smaller file)
+//! - [x] Pad with a per-ROM fill byte
+//! - [x] Mirror (half size file, reflected into upper half)
 //! - [x] Error cases (too large, wrong divisor, unnecessary size_handling)
 //!
 //! ## Phase 6: Multi-ROM Sets ✓ COMPLETE
@@ -58,6 +60,7 @@
 //! - [x] Use board pin maps to verify correctness
 //! - [x] Test address mapping
 //! - [x] Test data bit reordering
+//! - [x] Multi set custom no-chip byte for unmapped addresses
 //!
 //! ## Phase 8: Edge Cases ✓ COMPLETE
 //! - [x] 32 ROM sets (stress test)
@@ -151,6 +154,244 @@
 //! ## Phase 19: Descriptions
 //!
 //! ## Phase 20: Firmware overrides
+//!
+//! ## Phase 21: Compressed ROM Images
+//! - [x] Round trip compress/decompress of a built image
+//!
+//! ## Phase 22: Address Line Map Validation
+//! - [x] Board map missing an address line (should error)
+//!
+//! ## Phase 23: Diagnostic Multi Chip Set
+//! - [x] Each socket yields a distinct constant identifying its logical Chip
+//!
+//! ## Phase 24: Chip Set Size Limits
+//! - [x] Per-board maximum Chips for each set type
+//! - [x] Over-large sets rejected (should error)
+//!
+//! ## Phase 25: Data Pin Map Override
+//! - [x] Nibble-swapping data pin map swaps served nibbles
+//! - [x] Non-permutation data pin map (should error)
+//!
+//! ## Phase 26: Source File Provenance
+//! - [x] Source size and CRC-32 recorded per ROM and read back
+//! - [x] Not written for pre-0.6.0 firmware
+//!
+//! ## Phase 27: Require Exact Sizes
+//! - [x] Exact-size build passes in strict mode
+//! - [x] File needing padding (should error)
+//! - [x] size_handling specified in strict mode (should error)
+//!
+//! ## Phase 28: Flash Image Round Trip
+//! - [x] Single, multi and banked sets recovered from their flash images
+//!
+//! ## Phase 29: Socket Order Remapping
+//! - [x] Set data placed at remapped slots, metadata pointers follow
+//! - [x] Non-permutation socket order (should error)
+//!
+//! ## Phase 30: Chip Set Signatures
+//! - [x] Signature stable for identical content, differs when content changes
+//! - [x] Not written without boot logging
+//!
+//! ## Phase 31: CS2/CS3 Bit Position Overrides
+//! - [x] Multi set selection uses overridden CS2/CS3 bits across address space
+//! - [x] Single set builds with overridden bits
+//! - [x] Out of range or clashing bits (should error)
+//!
+//! ## Phase 32: Bus Trace Serving
+//! - [x] Served bytes follow a recorded bus trace, including unmapped values
+//!
+//! ## Phase 33: Per-ROM Exact Size
+//! - [x] Exact-marked ROM of the right size passes alongside a padded sibling
+//! - [x] Exact-marked ROM of the wrong size (should error)
+//!
+//! ## Phase 34: Adding Files From Paths (`std` feature)
+//! - [x] File read from path builds as if added directly
+//! - [x] Oversized, missing, duplicate and out of range files (should error)
+//!
+//! ## Phase 35: Per-Set Boot Logging
+//! - [x] Only the set with boot logging carries filenames and signature
+//! - [x] Pre-0.6.0 firmware (should error)
+//!
+//! ## Phase 36: Metadata Slack
+//! - [x] Slack pads the metadata, which still parses, with ROM images unmoved
+//! - [x] Slack beyond the metadata region (should error)
+//!
+//! ## Phase 37: Build Report
+//! - [x] Report matches the metadata's data pointers and buffer lengths
+//!
+//! ## Phase 38: Serve Algorithm vs Chip CS Lines
+//! - [x] TwoCsOneAddr on a single CS chip (should error at parse time)
+//! - [x] AddrOnCs on a CE/OE only chip (should error at parse time)
+//! - [x] TwoCsOneAddr on a multiple CS chip is accepted
+//!
+//! ## Phase 39: Built-in Test ROMs
+//! - [x] Built-in ramp image needs no file, and matches the pattern
+//! - [x] Unknown built-in name (should error)
+//!
+//! ## Phase 40: Chip Set Validation
+//! - [x] Valid set passes `ChipSet::validate()` without building
+//! - [x] Inconsistent CS logic (should error)
+//! - [x] Firmware overrides with pre-0.6.0 firmware (should error)
+//!
+//! ## Phase 41: Parallel ROM Image Generation
+//! - [x] Parallel and sequential generation of 16 Chip sets are identical
+//!
+//! ## Phase 42: Labels Without Boot Logging
+//! - [x] Label surfaces at the filename pointer with boot logging disabled
+//!
+//! ## Phase 43: TwoCsOneAddr Serving
+//! - [x] TwoCsOneAddr image matches the default layout across the whole image
+//! - [x] Every byte reads back where the firmware looks it up
+//!
+//! ## Phase 44: Half of a Dual-Image File
+//! - [x] Lower and upper halves select the right bytes
+//! - [x] Odd length file (should error)
+//! - [x] Both half and location (should error)
+//!
+//! ## Phase 45: Validation Rules
+//! - [x] Every error produced by validation appears in the rule list
+//!
+//! ## Phase 46: Round Trip via sdrr-fw-parser
+//! - [x] Parser recovers the chip sets, Chip types and CS states built
+//!
+//! ## Phase 47: Full Image Assembly
+//! - [x] Firmware, metadata and Chip images placed at their flash offsets
+//! - [x] Oversized firmware rejected
+//!
+//! ## Phase 48: Window Offset
+//! - [x] Addresses below the offset read as pad, the rest serve shifted data
+//! - [x] Offset plus image size exceeding the window rejected
+//!
+//! ## Phase 49: Config Round Trip via sdrr-fw-parser
+//! - [x] Single, banked and multi sets, with boot logging on and off
+//!
+//! ## Phase 50: TOML and YAML Configs
+//! - [x] Equivalent JSON, TOML and YAML configs produce identical metadata
+//!
+//! ## Phase 51: Metadata Parsing
+//! - [x] Parsing built metadata recovers the config, across set types,
+//!   firmware versions and boot logging
+//! - [x] Bad version and truncated metadata rejected
+//!
+//! ## Phase 52: Firmware Compatibility Checks
+//! - [x] Configs using overrides rejected for firmware before 0.6.0
+//! - [x] Wrong MCU family and unsupported versions rejected
+//!
+//! ## Phase 53: 16-bit Chip Types
+//! - [x] 27C400 served as little-endian words, per the board pin map
+//! - [x] 16-bit Chips rejected on 8-bit boards and in multi-Chip sets
+//!
+//! ## Phase 54: CS Logic Helpers
+//! - [x] `CsLogic::is_active()` for every logic, bit and invert combination
+//!
+//! ## Phase 55: Strided Locations
+//! - [x] Even bytes of an interleaved dump extracted into a 2364
+//! - [x] Overflowing and zero strides rejected
+//!
+//! ## Phase 56: Firmware Override Reporting
+//! - [x] Build report lists each set's active firmware overrides
+//!
+//! ## Phase 57: Building Into Caller Buffers
+//! - [x] Exact fit and oversized buffers match `build()`
+//! - [x] Too small buffers rejected
+//!
+//! ## Phase 58: Output Size Pre-flight
+//! - [x] Output sizes match built buffers, with and without filenames,
+//!   labels, overrides and RAM sets
+//! - [x] Missing files rejected
+//!
+//! ## Phase 59: RAM Initial Images
+//! - [x] RAM served from an initial image, typed as RAM in metadata
+//!
+//! ## Phase 60: Error Messages
+//! - [x] Errors display human readable messages naming ids and sizes
+//!
+//! ## Phase 61: Metadata CRC
+//! - [x] Metadata CRC written for 0.6.3 firmware onwards, and detects corruption
+//!
+//! ## Phase 62: Mixed Multi Chip Sets
+//! - [x] Compatible mixed Chip types served, impossible mixes rejected
+//!
+//! ## Phase 63: Chip Image Unscrambling
+//! - [x] Built ROM image unscrambled back to the source data
+//!
+//! ## Phase 64: Out of Bounds Addresses
+//! - [x] Addresses outside a Chip set's image return an error, not a panic
+//!
+//! ## Phase 65: Independent X1/X2 Jumper Pulls
+//! - [x] Banks selected with X1 and X2 pulling in opposite directions
+//!
+//! ## Phase 66: Serve Algorithm Parameter Validation
+//! - [x] serve_alg_params checked against the serving algorithm
+//!
+//! ## Phase 67: Config Layering
+//! - [x] Overlay config merged onto a shared base config
+//!
+//! ## Phase 68: File Spec Sizes
+//! - [x] File specs report the sizes each size handling accepts
+//!
+//! ## Phase 69: Build Report Data CRCs
+//! - [x] Chip set data CRCs reported, and the same across boards
+//!
+//! ## Phase 70: File Manifests
+//! - [x] Concatenated file split between Chips by a manifest
+//!
+//! ## Phase 71: Board Pin Map Tables
+//! - [x] Board pin map collects address, data, X and Chip control lines
+//!
+//! ## Phase 72: Chip Data Lines
+//! - [x] Chip types needing more data lines than the board has rejected
+//!
+//! ## Phase 73: Removing and Replacing Files
+//! - [x] Replaced file changes the built image, and a removed file is missing
+//!
+//! ## Phase 74: Chip Set Descriptions
+//! - [x] Single, banked and multi Chip sets described in one line
+//!
+//! ## Phase 75: Explicit CE/OE Chips
+//! - [x] CE/OE Chips marked in metadata, distinct from CS1 active low
+//!
+//! ## Phase 76: Board Multi Set Limits
+//! - [x] Multi sets larger than the board can select rejected cleanly
+//!
+//! ## Phase 77: Serving Algorithm Precedence
+//! - [x] Overrides, then config, then default serving algorithm used, with multi sets coerced
+//!
+//! ## Phase 78: Flash Capacity
+//! - [x] ROM images exceeding the MCU's flash rejected, and fitting ones built
+//!
+//! ## Phase 79: Config Descriptions
+//! - [x] Description set on the builder round trips through the metadata
+//!
+//! ## Phase 80: Reproducible Builds
+//! - [x] Build output is byte-identical whatever order files are added in
+//!
+//! ## Phase 81: Error Codes
+//! - [x] Each error variant has a unique, stable numeric code
+//!
+//! ## Phase 82: Variant Builds
+//! - [x] Variants build with their own tweaks, sharing files, and identical outputs are reported
+//!
+//! ## Phase 83: Chip Pin Counts
+//! - [x] Chip types with the wrong pin count for the board are rejected precisely
+//!
+//! ## Phase 84: Strict Truncation
+//! - [x] Strict truncation allows an all-fill tail, and rejects a tail containing data
+//!
+//! ## Phase 85: Chip Set Introspection
+//! - [x] Parsed Chip sets are reported, with their types, before files are added
+//!
+//! ## Phase 86: Inverted Data Lines
+//! - [x] A board with an inverted D7 serves it inverted, and reads back correctly
+//!
+//! ## Phase 87: Metadata Notes
+//! - [x] Note set on the builder round trips through the metadata, and overlong notes are rejected
+//!
+//! ## Phase 88: Address Line Maps
+//! - [x] A Chip set's address line map permutes the image, and must be a permutation
+//!
+//! ## Phase 89: Serving Algorithm Resolution
+//! - [x] Default resolves to the concrete algorithm in the metadata and build report
 
 #[cfg(test)]
 mod tests {
@@ -1837,6 +2078,56 @@ mod tests {
         println!("  - 3KB file padded to fill 8KB ROM");
     }
 
+    // ========================================================================
+    // TEST 129: Pad Size Handling with Fill Byte
+    // ========================================================================
+
+    #[test]
+    fn test_phase5_pad_with_fill_byte() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 5 pad with fill byte",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "test.rom",
+                    "type": "2364",
+                    "cs1": "active_low",
+                    "size_handling": "pad",
+                    "fill_byte": 255
+                }]
+            }]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+
+        // Create 3KB file for 8KB ROM
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(3072, 0x55),
+            })
+            .expect("Failed to add file");
+
+        let props = default_fw_props();
+        let (_metadata_buf, rom_images_buf) =
+            builder.build(props).expect("Build should succeed with pad");
+
+        // Verify the source data is intact
+        for addr in 0..3072 {
+            let b = read_rom_byte(&rom_images_buf, addr, props.board());
+            assert_eq!(b, 0x55, "Mismatch at address {:#X}", addr);
+        }
+
+        // Verify the tail is padded with the fill byte, not 0xAA
+        for addr in 3072..8192 {
+            let b = read_rom_byte(&rom_images_buf, addr, props.board());
+            assert_eq!(b, 0xFF, "Pad mismatch at address {:#X}", addr);
+        }
+
+        println!("✓ Phase 5 Test 129: Pad uses per-ROM fill byte");
+    }
+
     // ========================================================================
     // TEST 14: Error - File Too Large
     // ========================================================================
@@ -2725,14 +3016,11 @@ mod tests {
         );
     }
 
-    // Helper: Check if CS line is active at given address
+    // Helper: Check if CS line is active at given address, using the same
+    // logic as the generator
     fn is_cs_active(gpio_value: u16, cs_pin: u8, active_low: bool) -> bool {
-        let bit_value = (1 << cs_pin) & gpio_value;
-        if active_low {
-            bit_value == 0
-        } else {
-            bit_value != 0
-        }
+        let bit_set = ((1 << cs_pin) & gpio_value) != 0;
+        CsLogic::from_bool(active_low).is_active(bit_set, false)
     }
 
     // ========================================================================
@@ -2948,7 +3236,8 @@ mod tests {
         let cs1_active_low = true;
 
         // We need to know which way X1/X2 are pulled when selected
-        let x_dirn = board.x_jumper_pull();
+        let x1_dirn = board.x1_jumper_pull() as u16;
+        let x2_dirn = board.x2_jumper_pull() as u16;
 
         let mut errors = 0;
         let max_errors_to_report = 10;
@@ -2965,10 +3254,8 @@ mod tests {
             let expected_byte = {
                 let rom_offset = (address as usize) & 0x1FFF; // Lower 13 bits for 8KB ROM
 
-                let mut bank = ((x2_bit << 1) | x1_bit) as usize;
-                if x_dirn == 0 {
-                    bank = 3 - bank;
-                }
+                // A jumper pulled low inverts its line
+                let mut bank = (((x2_bit ^ x2_dirn ^ 1) << 1) | (x1_bit ^ x1_dirn ^ 1)) as usize;
 
                 if bank < rom_data.len() {
                     bank = bank % rom_data.len(); // Wrap around
@@ -3009,6 +3296,77 @@ mod tests {
         println!("  - Validated X1/X2 bit values select correct ROM");
     }
 
+    // ========================================================================
+    // TEST 122: Multi ROM Set Custom No-Chip Byte
+    // ========================================================================
+
+    #[test]
+    fn test_phase7_multi_chip_set_no_chip_byte() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 7 multi ROM set no-chip byte test",
+            "chip_sets": [{
+                "type": "multi",
+                "no_chip_byte": 255,
+                "chips": [
+                    { "file": "rom0.bin", "type": "2364", "cs1": "active_low" },
+                    { "file": "rom1.bin", "type": "2364", "cs1": "active_low" }
+                ]
+            }]
+        }"#;
+        let no_chip_byte = 0xFF;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        let rom_data = [
+            create_test_rom_data(8192, 0x11),
+            create_test_rom_data(8192, 0x22),
+        ];
+        for (id, data) in rom_data.iter().enumerate() {
+            builder
+                .add_file(FileData {
+                    id,
+                    data: data.clone(),
+                })
+                .expect("Failed to add file");
+        }
+
+        let props = default_fw_props();
+        let board = props.board();
+        let (_metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+
+        let cs1_pin = board.pin_cs1(onerom_config::chip::ChipType::Chip2364);
+        let x1_pin = board.pin_x1();
+        let x2_pin = board.pin_x2();
+
+        let mut unmapped = 0;
+        for address in 0..65536u32 {
+            let address_u16 = address as u16;
+            let cs1_active = is_cs_active(address_u16, cs1_pin, true);
+            let x1_active = is_cs_active(address_u16, x1_pin, true);
+            let x2_active = is_cs_active(address_u16, x2_pin, true);
+
+            // X2 has no ROM in this set, so selecting it is unmapped too
+            let expected_byte = match (cs1_active, x1_active, x2_active) {
+                (true, false, false) => rom_data[0][0],
+                (false, true, false) => rom_data[1][0],
+                _ => {
+                    unmapped += 1;
+                    no_chip_byte
+                }
+            };
+
+            let actual_byte = read_rom_byte_abs(&rom_images_buf, address as usize, board);
+            assert_eq!(
+                actual_byte, expected_byte,
+                "Mismatch at addr=0x{:04X} (CS1={}, X1={}, X2={})",
+                address, cs1_active, x1_active, x2_active
+            );
+        }
+        assert!(unmapped > 0, "Expected some unmapped addresses");
+
+        println!("✓ Phase 7 Test 5: Multi ROM set custom no-chip byte passed");
+    }
+
     // ========================================================================
     // PHASE 11: JSON Parsing and Validation Errors
     // ========================================================================
@@ -5601,6 +5959,77 @@ mod tests {
         );
     }
 
+    // ========================================================================
+    // TEST 127: Verify Mirror Reflects Data Into Upper Half
+    // ========================================================================
+
+    #[test]
+    fn test_phase18_mirror_reflects_upper_half() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Verify mirror reflects upper half",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "test.rom",
+                    "type": "2364",
+                    "cs1": "active_low",
+                    "size_handling": "mirror"
+                }]
+            }]
+        }"#;
+
+        let build = |data: Vec<u8>| {
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+            builder
+                .add_file(FileData { id: 0, data })
+                .expect("Failed to add file");
+            builder.build(default_fw_props())
+        };
+
+        // 4KB of test data with a pattern which differs when reversed
+        let test_data: Vec<u8> = (0..4096).map(|i| (i * 7 + (i >> 8)) as u8).collect();
+
+        let board = default_fw_props().board();
+        let (_metadata_buf, rom_images_buf) = build(test_data.clone()).expect("Build failed");
+
+        for addr in 0..8192 {
+            let expected = if addr < 4096 {
+                test_data[addr]
+            } else {
+                test_data[8191 - addr]
+            };
+            let actual = read_rom_byte(&rom_images_buf, addr, board);
+            assert_eq!(
+                actual, expected,
+                "Mirror mismatch at 0x{:04X}: expected 0x{:02X}, got 0x{:02X}",
+                addr, expected, actual
+            );
+        }
+
+        // Anything other than exactly half the Chip size is rejected
+        for size in [2048, 6144, 8192, 16384] {
+            let result = build(create_test_rom_data(size, 0x42));
+            assert!(
+                matches!(
+                    result,
+                    Err(Error::MirrorNotHalfSize {
+                        image_size,
+                        expected_size: 8192
+                    }) if image_size == size
+                ),
+                "Expected MirrorNotHalfSize error for {} bytes, got {:?}",
+                size,
+                result
+            );
+        }
+
+        println!("✓ Phase 18 Test 7: Mirror reflects data into upper half");
+    }
+
     // ========================================================================
     // TEST 85: Set with 4 multi-set ROMs (Should Fail - Max is 3)
     // ========================================================================
@@ -6085,6 +6514,9 @@ mod tests {
         extra_info: u8,
         serve_config_ptr: u32,
         firmware_overrides_ptr: u32,
+        provenance_ptr: u32,
+        signature: [u8; 4],
+        filenames: u8,
     }
 
     impl ExtendedRomSetStruct {
@@ -6116,12 +6548,30 @@ mod tests {
                 buf[offset + 23],
             ]);
 
-            // pad2: 40 bytes at offset 24-63 (skip)
+            // provenance_ptr: 4 bytes at offset 24-27
+            let provenance_ptr = u32::from_le_bytes([
+                buf[offset + 24],
+                buf[offset + 25],
+                buf[offset + 26],
+                buf[offset + 27],
+            ]);
+
+            // signature: 4 bytes at offset 28-31
+            let mut signature = [0u8; 4];
+            signature.copy_from_slice(&buf[offset + 28..offset + 32]);
+
+            // filenames: 1 byte at offset 32
+            let filenames = buf[offset + 32];
+
+            // pad2: 31 bytes at offset 33-63 (skip)
 
             Self {
                 extra_info,
                 serve_config_ptr,
                 firmware_overrides_ptr,
+                provenance_ptr,
+                signature,
+                filenames,
             }
         }
     }
@@ -7865,4 +8315,6231 @@ mod tests {
         println!("  - Set 2: Ice 168MHz (OC), Fire 250MHz (no OC, Stock VREG)");
         println!("  - Set 3: No overrides");
     }
+
+    // ========================================================================
+    // PHASE 21: Compressed ROM Images
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 107: Compress/decompress round trip
+    // ========================================================================
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn test_phase21_compress_round_trip() {
+        use onerom_gen::compress::{compress_images, decompress_images, uncompressed_len};
+
+        let json = r#"{
+            "version": 1,
+            "description": "Compressed images test",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "test.rom",
+                    "type": "2364",
+                    "cs1": "active_low"
+                }]
+            }]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+
+        // Highly repetitive image, which should compress well
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x42),
+            })
+            .expect("Failed to add file");
+
+        let (_metadata_buf, rom_images_buf) =
+            builder.build(default_fw_props()).expect("Build failed");
+
+        let compressed = compress_images(&rom_images_buf);
+        assert!(
+            compressed.len() < rom_images_buf.len(),
+            "Compressed size {} should be smaller than uncompressed size {}",
+            compressed.len(),
+            rom_images_buf.len()
+        );
+        assert_eq!(
+            uncompressed_len(&compressed).expect("Missing header"),
+            rom_images_buf.len()
+        );
+
+        let decompressed = decompress_images(&compressed).expect("Decompression failed");
+        assert_eq!(
+            decompressed, rom_images_buf,
+            "Decompressed images should match original"
+        );
+
+        // Uncompressed buffers must be rejected
+        assert!(decompress_images(&rom_images_buf).is_err());
+
+        println!("✓ Phase 21 Test 107: Compress/decompress round trip");
+        println!(
+            "  - {} bytes compressed to {} bytes",
+            rom_images_buf.len(),
+            compressed.len()
+        );
+    }
+
+    // ========================================================================
+    // PHASE 22: Address Line Map Validation
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 108: Board map missing an address line
+    // ========================================================================
+
+    #[test]
+    fn test_phase22_addr_map_missing_line() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, Error};
+
+        let board = Board::Ice24UsbH;
+        let chip_type = ChipType::Chip2364;
+
+        // The unmodified board map must be valid
+        ChipSet::validate_phys_pin_to_addr_map(0, &chip_type, board.phys_pin_to_addr_map())
+            .expect("Board map should be valid for 2364");
+
+        // Remove address line 5 from the map
+        let mut map = board.phys_pin_to_addr_map().to_vec();
+        let pin = map
+            .iter()
+            .position(|&x| x == Some(5))
+            .expect("Board should map A5");
+        map[pin] = None;
+
+        let result = ChipSet::validate_phys_pin_to_addr_map(3, &chip_type, &map);
+        match result {
+            Err(Error::MissingAddressLine {
+                id,
+                chip_type: err_chip_type,
+                addr_bit,
+            }) => {
+                assert_eq!(id, 3, "Error should name the Chip");
+                assert_eq!(err_chip_type, chip_type);
+                assert_eq!(addr_bit, 5, "Error should name the missing bit");
+            }
+            other => panic!("Expected MissingAddressLine error, got {:?}", other),
+        }
+
+        // Map A4 twice instead
+        map[pin] = Some(4);
+        let result = ChipSet::validate_phys_pin_to_addr_map(3, &chip_type, &map);
+        assert!(
+            matches!(result, Err(Error::DuplicateAddressLine { addr_bit: 4, .. })),
+            "Expected DuplicateAddressLine error, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 22 Test 108: Board map missing an address line");
+    }
+
+    // ========================================================================
+    // PHASE 23: Diagnostic Multi Chip Set
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 109: Diagnostic multi set reveals socket to Chip mapping
+    // ========================================================================
+
+    #[test]
+    fn test_phase23_diagnostic_multi_set() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, PAD_NO_CHIP_BYTE, diagnostic_fill_byte};
+
+        let board = Board::Ice24UsbH;
+        let chip_set = ChipSet::diagnostic_multi(0, &ChipType::Chip2364, 3, CsLogic::ActiveLow)
+            .expect("Failed to create diagnostic multi set");
+        assert_eq!(chip_set.chips.len(), 3);
+        assert_eq!(chip_set.serve_alg, ServeAlg::AddrOnAnyCs);
+
+        // Each Chip must have a distinct fill byte
+        assert_eq!(diagnostic_fill_byte(0), 0x11);
+        assert_eq!(diagnostic_fill_byte(1), 0x22);
+        assert_eq!(diagnostic_fill_byte(2), 0x33);
+
+        let cs1_pin = board.pin_cs1(ChipType::Chip2364);
+        let x1_pin = board.pin_x1();
+        let x2_pin = board.pin_x2();
+
+        let mut errors = 0;
+        let max_errors_to_report = 10;
+
+        // Check all 64KB addresses, so every CS-active combination is covered
+        for address in 0..65536u32 {
+            let address_u16 = address as u16;
+
+            // All CS lines are active low in this test
+            let cs1_active = is_cs_active(address_u16, cs1_pin, true);
+            let x1_active = is_cs_active(address_u16, x1_pin, true);
+            let x2_active = is_cs_active(address_u16, x2_pin, true);
+
+            let active_count = [cs1_active, x1_active, x2_active]
+                .iter()
+                .filter(|&&x| x)
+                .count();
+
+            let physical_byte = chip_set.get_byte(address as usize, &board, false).unwrap();
+            let actual_byte = unscramble_physical_byte(physical_byte, board);
+
+            let expected_byte = if active_count == 1 {
+                if cs1_active {
+                    diagnostic_fill_byte(0)
+                } else if x1_active {
+                    diagnostic_fill_byte(1)
+                } else {
+                    diagnostic_fill_byte(2)
+                }
+            } else {
+                PAD_NO_CHIP_BYTE
+            };
+
+            if actual_byte != expected_byte {
+                errors += 1;
+                if errors <= max_errors_to_report {
+                    println!(
+                        "  Mismatch at addr=0x{:04X} (CS1={}, X1={}, X2={}): expected 0x{:02X}, got 0x{:02X}",
+                        address, cs1_active, x1_active, x2_active, expected_byte, actual_byte
+                    );
+                }
+            }
+        }
+
+        if errors > max_errors_to_report {
+            println!("  ... and {} more errors", errors - max_errors_to_report);
+        }
+
+        assert_eq!(
+            errors, 0,
+            "Found {} byte mismatches in diagnostic multi set",
+            errors
+        );
+
+        println!("✓ Phase 23 Test 109: Diagnostic multi set");
+        println!("  - Verified each CS-active combination yields a distinct constant");
+    }
+
+    // ========================================================================
+    // PHASE 24: Chip Set Size Limits
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 110: Maximum Chips per set type
+    // ========================================================================
+
+    #[test]
+    fn test_phase24_max_chips_in_set() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{BoardChipSetLimits, ChipSet, ChipSetType, Error};
+
+        let board = Board::Ice24UsbH;
+        assert_ne!(board.pin_x1(), 255, "X1 pin must be defined");
+        assert_ne!(board.pin_x2(), 255, "X2 pin must be defined");
+
+        assert_eq!(board.max_chips_in_set(&ChipSetType::Single), 1);
+        assert_eq!(board.max_chips_in_set(&ChipSetType::Banked), 4);
+        assert_eq!(board.max_chips_in_set(&ChipSetType::Multi), 3);
+
+        // Multi set at the limit is accepted, one more is rejected
+        ChipSet::diagnostic_multi(0, &ChipType::Chip2364, 3, CsLogic::ActiveLow)
+            .expect("3 Chip multi set should be accepted");
+        let result = ChipSet::diagnostic_multi(0, &ChipType::Chip2364, 4, CsLogic::ActiveLow);
+        assert!(
+            matches!(
+                result,
+                Err(Error::TooManyChips {
+                    expected: 3,
+                    actual: 4
+                })
+            ),
+            "Expected TooManyChips error, got {:?}",
+            result
+        );
+
+        // Banked set of 5 is rejected
+        let mut chips = ChipSet::diagnostic_multi(0, &ChipType::Chip2364, 3, CsLogic::ActiveLow)
+            .expect("Failed to create Chips")
+            .chips;
+        chips.extend(
+            ChipSet::diagnostic_multi(0, &ChipType::Chip2364, 2, CsLogic::ActiveLow)
+                .expect("Failed to create Chips")
+                .chips,
+        );
+        let result = ChipSet::new(0, ChipSetType::Banked, ServeAlg::Default, chips, None);
+        assert!(
+            matches!(
+                result,
+                Err(Error::TooManyChips {
+                    expected: 4,
+                    actual: 5
+                })
+            ),
+            "Expected TooManyChips error, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 24 Test 110: Maximum Chips per set type");
+    }
+
+    // ========================================================================
+    // PHASE 25: Data Pin Map Override
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 111: Nibble-swapping data pin map
+    // ========================================================================
+
+    #[test]
+    fn test_phase25_data_pin_map_nibble_swap() {
+        use onerom_gen::Error;
+
+        let board = Board::Ice24UsbH;
+        let data_pins = board.data_pins();
+
+        // Swap the data bus nibbles
+        let swapped: Vec<u8> = (0..8).map(|bit| data_pins[(bit + 4) % 8]).collect();
+
+        let chip_json = r#"[{
+            "file": "test.rom",
+            "type": "2364",
+            "cs1": "active_low"
+        }]"#;
+        let default_json = format!(
+            r#"{{
+                "version": 1,
+                "description": "Phase 25 default data pins",
+                "chip_sets": [{{ "type": "single", "chips": {chip_json} }}]
+            }}"#
+        );
+        let swapped_json = format!(
+            r#"{{
+                "version": 1,
+                "description": "Phase 25 nibble-swapped data pins",
+                "data_pin_map": {:?},
+                "chip_sets": [{{ "type": "single", "chips": {chip_json} }}]
+            }}"#,
+            swapped
+        );
+
+        // Distinct data in each address, covering every byte value
+        let rom_data: Vec<u8> = (0..8192).map(|i| (i & 0xFF) as u8).collect();
+
+        let build = |json: &str| {
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+            builder
+                .add_file(FileData {
+                    id: 0,
+                    data: rom_data.clone(),
+                })
+                .expect("Failed to add file");
+            builder.build(default_fw_props()).expect("Build failed").1
+        };
+
+        let default_images = build(&default_json);
+        let swapped_images = build(&swapped_json);
+        assert_eq!(default_images.len(), swapped_images.len());
+
+        for (addr, (&default_byte, &swapped_byte)) in
+            default_images.iter().zip(swapped_images.iter()).enumerate()
+        {
+            let default_byte = unscramble_physical_byte(default_byte, board);
+            let swapped_byte = unscramble_physical_byte(swapped_byte, board);
+            assert_eq!(
+                swapped_byte,
+                default_byte.rotate_left(4),
+                "Nibbles not swapped at address 0x{:04X}",
+                addr
+            );
+        }
+
+        // A map which isn't a permutation of the board's data pins is rejected
+        let mut invalid = swapped.clone();
+        invalid[0] = invalid[1];
+        let invalid_json = format!(
+            r#"{{
+                "version": 1,
+                "description": "Phase 25 invalid data pins",
+                "data_pin_map": {:?},
+                "chip_sets": [{{ "type": "single", "chips": {chip_json} }}]
+            }}"#,
+            invalid
+        );
+        let mut builder =
+            Builder::from_json(FW_VER, MCU_FAM, &invalid_json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: rom_data.clone(),
+            })
+            .expect("Failed to add file");
+        let result = builder.build(default_fw_props());
+        assert!(
+            matches!(result, Err(Error::InvalidDataPinMap { .. })),
+            "Expected InvalidDataPinMap error, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 25 Test 111: Nibble-swapping data pin map");
+    }
+
+    // ========================================================================
+    // PHASE 26: Source File Provenance
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 112: Provenance round trip
+    // ========================================================================
+
+    #[test]
+    fn test_phase26_provenance_round_trip() {
+        use onerom_gen::crc32;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 26 provenance test",
+            "provenance": true,
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{
+                        "file": "full.rom",
+                        "type": "2364",
+                        "cs1": "active_low"
+                    }]
+                },
+                {
+                    "type": "single",
+                    "chips": [{
+                        "file": "half.rom",
+                        "type": "2364",
+                        "cs1": "active_low",
+                        "size_handling": "duplicate"
+                    }]
+                }
+            ]
+        }"#;
+
+        let files: Vec<Vec<u8>> = vec![
+            (0..8192).map(|i| (i * 7) as u8).collect(),
+            (0..4096).map(|i| (i * 13) as u8).collect(),
+        ];
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        for (id, data) in files.iter().enumerate() {
+            builder
+                .add_file(FileData {
+                    id,
+                    data: data.clone(),
+                })
+                .expect("Failed to add file");
+        }
+
+        let props = default_fw_props_060();
+        let flash_base = props.board().mcu_family().get_flash_base();
+        let metadata_flash_start = flash_base + METADATA_FLASH_OFFSET;
+        let (metadata_buf, _rom_images_buf) = builder.build(props).expect("Build failed");
+
+        let header = MetadataHeader::parse(&metadata_buf);
+        let chip_sets_offset = (header.chip_sets_ptr - metadata_flash_start) as usize;
+
+        for (ii, data) in files.iter().enumerate() {
+            let ext_chip_set =
+                ExtendedRomSetStruct::parse(&metadata_buf, chip_sets_offset + ii * 64);
+            assert_ne!(
+                ext_chip_set.provenance_ptr, 0,
+                "Set {} should have a provenance pointer",
+                ii
+            );
+
+            // One 8 byte record per chip - source size, then CRC-32
+            let offset = (ext_chip_set.provenance_ptr - metadata_flash_start) as usize;
+            let record = &metadata_buf[offset..offset + 8];
+            let size = u32::from_le_bytes([record[0], record[1], record[2], record[3]]);
+            let checksum = u32::from_le_bytes([record[4], record[5], record[6], record[7]]);
+
+            assert_eq!(size as usize, data.len(), "Set {} source size mismatch", ii);
+            assert_eq!(checksum, crc32(data), "Set {} source checksum mismatch", ii);
+        }
+
+        // Known CRC-32 check value
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        // Not written for pre-0.6.0 firmware
+        let (metadata_buf_051, _) = builder
+            .build(fw_props_with_logging())
+            .expect("Build failed");
+        let (metadata_buf_no_prov, _) = {
+            let json = json.replace(r#""provenance": true,"#, "");
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, &json).expect("Failed to parse JSON");
+            for (id, data) in files.iter().enumerate() {
+                builder
+                    .add_file(FileData {
+                        id,
+                        data: data.clone(),
+                    })
+                    .expect("Failed to add file");
+            }
+            builder.build(fw_props_with_logging()).expect("Build failed")
+        };
+        assert_eq!(
+            metadata_buf_051.len(),
+            metadata_buf_no_prov.len(),
+            "Provenance should not be written for pre-0.6.0 firmware"
+        );
+
+        println!("✓ Phase 26 Test 112: Provenance round trip");
+    }
+
+    // ========================================================================
+    // PHASE 27: Require Exact Sizes
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 113: Exact-size build passes in strict mode
+    // ========================================================================
+
+    #[test]
+    fn test_phase27_exact_size_strict_pass() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 27 strict exact size",
+            "require_exact_sizes": true,
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "test.rom",
+                    "type": "2364",
+                    "cs1": "active_low"
+                }]
+            }]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x42),
+            })
+            .expect("Failed to add file");
+
+        builder
+            .build(default_fw_props())
+            .expect("Exact-size build should pass in strict mode");
+
+        println!("✓ Phase 27 Test 113: Exact-size build passes in strict mode");
+    }
+
+    // ========================================================================
+    // TEST 114: Builds needing size handling fail in strict mode
+    // ========================================================================
+
+    #[test]
+    fn test_phase27_strict_rejects_size_handling() {
+        use onerom_gen::Error;
+
+        // A file needing padding is rejected
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 27 strict too small",
+            "require_exact_sizes": true,
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "test.rom",
+                    "type": "2364",
+                    "cs1": "active_low"
+                }]
+            }]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(6000, 0x42),
+            })
+            .expect("Failed to add file");
+
+        let result = builder.build(default_fw_props());
+        assert!(
+            matches!(
+                result,
+                Err(Error::NotExactSize {
+                    id: 0,
+                    expected: 8192,
+                    actual: 6000
+                })
+            ),
+            "Expected NotExactSize error, got {:?}",
+            result
+        );
+
+        // Specifying size_handling is itself rejected
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 27 strict with size handling",
+            "require_exact_sizes": true,
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "test.rom",
+                    "type": "2364",
+                    "cs1": "active_low",
+                    "size_handling": "pad"
+                }]
+            }]
+        }"#;
+
+        let result = Builder::from_json(FW_VER, MCU_FAM, json);
+        assert!(
+            matches!(result, Err(Error::SizeHandlingNotAllowed { id: 0 })),
+            "Expected SizeHandlingNotAllowed error, got {:?}",
+            result.err()
+        );
+
+        println!("✓ Phase 27 Test 114: Builds needing size handling fail in strict mode");
+    }
+
+    // ========================================================================
+    // PHASE 28: Flash Image Round Trip
+    // ========================================================================
+
+    // Helper: Create a single Chip from logical data, for building ChipSets
+    // directly
+    fn create_chip(
+        index: usize,
+        chip_type: onerom_config::chip::ChipType,
+        data: &[u8],
+    ) -> onerom_gen::Chip {
+        use onerom_gen::{Chip, CsConfig, SizeHandling};
+
+        Chip::from_raw_rom_image(
+            index,
+            format!("chip{}.rom", index),
+            None,
+            Some(data),
+            vec![0u8; chip_type.size_bytes()],
+            &chip_type,
+            CsConfig::new(Some(CsLogic::ActiveLow), None, None),
+            &SizeHandling::None,
+            None,
+            None,
+            false,
+        )
+        .expect("Failed to create Chip")
+    }
+
+    // ========================================================================
+    // TEST 115: Forward and reverse flash image transforms
+    // ========================================================================
+
+    #[test]
+    fn test_phase28_flash_image_round_trip() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, ChipSetType};
+
+        let board = Board::Ice24UsbH;
+
+        // Distinct, non-repeating data for each Chip
+        let logical: Vec<Vec<u8>> = (0..3u32)
+            .map(|chip| {
+                (0..8192u32)
+                    .map(|i| ((i * 31 + chip * 97) ^ (i >> 8)) as u8)
+                    .collect()
+            })
+            .collect();
+
+        let sets = [
+            (ChipSetType::Single, ChipType::Chip2732, 1),
+            (ChipSetType::Single, ChipType::Chip2364, 1),
+            (ChipSetType::Multi, ChipType::Chip2364, 3),
+            (ChipSetType::Banked, ChipType::Chip2364, 2),
+        ];
+
+        for (set_type, chip_type, num_chips) in sets {
+            let size = chip_type.size_bytes();
+            let chips = (0..num_chips)
+                .map(|index| create_chip(index, chip_type, &logical[index][..size]))
+                .collect();
+            let chip_set = ChipSet::new(0, set_type.clone(), ServeAlg::Default, chips, None)
+                .expect("Failed to create Chip set");
+
+            let flash_image = chip_set.to_flash_image(&board, false).unwrap();
+            assert_eq!(
+                flash_image.len(),
+                chip_set.image_size(&board.mcu_family(), board.chip_pins())
+            );
+
+            let recovered = chip_set
+                .from_flash_image(&flash_image, &board, false)
+                .expect("Failed to reverse flash image");
+            assert_eq!(recovered.len(), num_chips);
+            for (index, image) in recovered.iter().enumerate() {
+                assert_eq!(
+                    image.as_slice(),
+                    &logical[index][..size],
+                    "{:?} set Chip {} not recovered",
+                    set_type,
+                    index
+                );
+            }
+
+            // Wrong size flash images are rejected
+            assert!(
+                chip_set
+                    .from_flash_image(&flash_image[1..], &board, false)
+                    .is_err()
+            );
+        }
+
+        println!("✓ Phase 28 Test 115: Forward and reverse flash image transforms");
+    }
+
+    // ========================================================================
+    // PHASE 29: Socket Order Remapping
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 116: Set data placed at remapped slots
+    // ========================================================================
+
+    #[test]
+    fn test_phase29_socket_order_remap() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 29 socket order test",
+            "socket_order": [2, 0, 1],
+            "chip_sets": [
+                { "type": "single", "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }] },
+                { "type": "single", "chips": [{ "file": "b.rom", "type": "2364", "cs1": "active_low" }] },
+                { "type": "single", "chips": [{ "file": "c.rom", "type": "2364", "cs1": "active_low" }] }
+            ]
+        }"#;
+        let socket_order = [2usize, 0, 1];
+        let fills = [0x11u8, 0x22, 0x33];
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        for (id, &fill) in fills.iter().enumerate() {
+            builder
+                .add_file(FileData {
+                    id,
+                    data: create_test_rom_data(8192, fill),
+                })
+                .expect("Failed to add file");
+        }
+
+        let props = default_fw_props();
+        let board = props.board();
+        let flash_base = board.mcu_family().get_flash_base();
+        let metadata_flash_start = flash_base + METADATA_FLASH_OFFSET;
+        let (metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+
+        let header = MetadataHeader::parse(&metadata_buf);
+        let chip_sets_offset = (header.chip_sets_ptr - metadata_flash_start) as usize;
+        let slot_size = 16384;
+
+        for (ii, &fill) in fills.iter().enumerate() {
+            // Metadata still lists sets in config order
+            let chip_set =
+                RomSetStruct::parse(&metadata_buf, chip_sets_offset + ii * CHIP_SET_METADATA_LEN);
+            let slot = socket_order[ii];
+            let expected_data_ptr = flash_base + 65536 + (slot * slot_size) as u32;
+            assert_eq!(
+                chip_set.data_ptr, expected_data_ptr,
+                "Set {} data pointer should reference slot {}",
+                ii, slot
+            );
+
+            // The slot contains this set's data
+            let offset = (chip_set.data_ptr - flash_base - 65536) as usize;
+            let byte = read_rom_byte_abs(&rom_images_buf, offset, board);
+            assert_eq!(byte, fill, "Slot {} should contain set {} data", slot, ii);
+        }
+
+        // Non-permutations are rejected
+        let json = json.replace("[2, 0, 1]", "[2, 0, 2]");
+        let mut builder =
+            Builder::from_json(FW_VER, MCU_FAM, &json).expect("Failed to parse JSON");
+        for (id, &fill) in fills.iter().enumerate() {
+            builder
+                .add_file(FileData {
+                    id,
+                    data: create_test_rom_data(8192, fill),
+                })
+                .expect("Failed to add file");
+        }
+        let result = builder.build(default_fw_props());
+        assert!(
+            matches!(result, Err(Error::InvalidSocketOrder { .. })),
+            "Expected InvalidSocketOrder error, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 29 Test 116: Set data placed at remapped slots");
+    }
+
+    // ========================================================================
+    // PHASE 30: Chip Set Signatures
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 117: Signature stable for identical content
+    // ========================================================================
+
+    #[test]
+    fn test_phase30_chip_set_signature() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 30 signature test",
+            "chip_sets": [
+                { "type": "single", "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }] },
+                { "type": "single", "chips": [{ "file": "b.rom", "type": "2364", "cs1": "active_low" }] },
+                { "type": "single", "chips": [{ "file": "c.rom", "type": "2364", "cs1": "active_low" }] }
+            ]
+        }"#;
+
+        // Sets 0 and 1 have identical content, set 2 differs by one byte
+        let same: Vec<u8> = (0..8192).map(|i| (i * 3) as u8).collect();
+        let mut different = same.clone();
+        different[1234] ^= 0x01;
+        let files = [same.clone(), same, different];
+
+        let props_060_logging = || {
+            FirmwareProperties::new(
+                FirmwareVersion::new(0, 6, 0, 0),
+                Board::Ice24UsbH,
+                McuVariant::F411RE,
+                ServeAlg::Default,
+                true, // boot_logging enabled
+            )
+            .unwrap()
+        };
+
+        let build = |props: FirmwareProperties| {
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+            for (id, data) in files.iter().enumerate() {
+                builder
+                    .add_file(FileData {
+                        id,
+                        data: data.clone(),
+                    })
+                    .expect("Failed to add file");
+            }
+            let flash_base = props.board().mcu_family().get_flash_base();
+            let metadata_flash_start = flash_base + METADATA_FLASH_OFFSET;
+            let (metadata_buf, _) = builder.build(props).expect("Build failed");
+
+            let header = MetadataHeader::parse(&metadata_buf);
+            let chip_sets_offset = (header.chip_sets_ptr - metadata_flash_start) as usize;
+            (0..files.len())
+                .map(|ii| {
+                    ExtendedRomSetStruct::parse(&metadata_buf, chip_sets_offset + ii * 64)
+                        .signature
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let signatures = build(props_060_logging());
+        for signature in &signatures {
+            assert!(
+                signature.iter().all(|c| c.is_ascii_hexdigit()),
+                "Signature {:?} should be 4 hex characters",
+                signature
+            );
+        }
+        assert_eq!(
+            signatures[0], signatures[1],
+            "Identical content should have identical signatures"
+        );
+        assert_ne!(
+            signatures[0], signatures[2],
+            "Different content should have different signatures"
+        );
+
+        // Deterministic across builds
+        assert_eq!(signatures, build(props_060_logging()));
+
+        // Not written without boot logging
+        let signatures = build(default_fw_props_060());
+        assert!(
+            signatures.iter().all(|s| *s == [0u8; 4]),
+            "Signatures should not be written without boot logging"
+        );
+
+        println!("✓ Phase 30 Test 117: Signature stable for identical content");
+    }
+
+    // ========================================================================
+    // PHASE 31: CS2/CS3 Bit Position Overrides
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 118: Selection uses overridden CS2/CS3 bits
+    // ========================================================================
+
+    #[test]
+    fn test_phase31_cs_bit_override_selection() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{Chip, ChipSet, ChipSetType, CsConfig, PAD_NO_CHIP_BYTE, SizeHandling};
+
+        let board = Board::Ice24UsbH;
+        let chip_type = ChipType::Chip2316;
+        let fills = [0x11u8, 0x22];
+
+        // Board defaults for a 2316 are CS2 on bit 12 and CS3 on bit 9.  Swap
+        // them, and check each line in turn with the other ignored.
+        let (cs2_bit, cs3_bit) = (Some(9), Some(12));
+        let cases = [
+            (Some(CsLogic::ActiveLow), Some(CsLogic::Ignore), 9),
+            (Some(CsLogic::Ignore), Some(CsLogic::ActiveLow), 12),
+        ];
+
+        for (cs2, cs3, override_bit) in cases {
+            let chips = fills
+                .iter()
+                .enumerate()
+                .map(|(index, &fill)| {
+                    let mut chip = Chip::from_raw_rom_image(
+                        index,
+                        format!("chip{}.rom", index),
+                        None,
+                        Some(&create_test_rom_data(chip_type.size_bytes(), fill)),
+                        vec![0u8; chip_type.size_bytes()],
+                        &chip_type,
+                        CsConfig::new(Some(CsLogic::ActiveLow), cs2, cs3),
+                        &SizeHandling::None,
+                        None,
+                        None,
+                        false,
+                    )
+                    .expect("Failed to create Chip");
+                    chip.set_cs_bits(cs2_bit, cs3_bit)
+                        .expect("Failed to set CS bits");
+                    chip
+                })
+                .collect();
+            let chip_set = ChipSet::new(0, ChipSetType::Multi, ServeAlg::Default, chips, None)
+                .expect("Failed to create Chip set");
+            chip_set
+                .validate_cs_bits(&board)
+                .expect("CS bit overrides should be valid");
+
+            let select_bits = [
+                board.bit_cs1(chip_type),
+                board.bit_x1(),
+                board.bit_x2(),
+            ];
+            for address in 0..65536usize {
+                // CS1/X1/X2 are active low - exactly one must be active, and
+                // the overridden line must be active too
+                let active: Vec<usize> = select_bits
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &bit)| address & (1 << bit) == 0)
+                    .map(|(index, _)| index)
+                    .collect();
+                let expected = if active.len() == 1
+                    && active[0] < fills.len()
+                    && address & (1 << override_bit) == 0
+                {
+                    fills[active[0]]
+                } else {
+                    PAD_NO_CHIP_BYTE
+                };
+
+                let byte = unscramble_physical_byte(
+                    chip_set.get_byte(address, &board, false).unwrap(),
+                    board,
+                );
+                assert_eq!(
+                    byte, expected,
+                    "Address {:#06X} with override bit {} served wrong byte",
+                    address, override_bit
+                );
+            }
+        }
+
+        println!("✓ Phase 31 Test 118: Selection uses overridden CS2/CS3 bits");
+    }
+
+    // ========================================================================
+    // TEST 119: Builds with overridden CS2/CS3 bits, rejecting invalid ones
+    // ========================================================================
+
+    #[test]
+    fn test_phase31_cs_bit_override_build() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 31 CS bit override test",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "test.rom",
+                    "type": "2316",
+                    "cs1": "active_low",
+                    "cs2": "active_high",
+                    "cs3": "active_low",
+                    "cs2_bit": 9,
+                    "cs3_bit": 12
+                }]
+            }]
+        }"#;
+
+        let build = |json: &str| {
+            let mut builder = Builder::from_json(FW_VER, MCU_FAM, json)?;
+            builder.add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(2048, 0x42),
+            })?;
+            builder.build(default_fw_props())
+        };
+
+        build(json).expect("Build with swapped CS2/CS3 bits should pass");
+
+        // Out of range
+        let result = build(&json.replace(r#""cs2_bit": 9"#, r#""cs2_bit": 16"#));
+        assert!(
+            matches!(result, Err(Error::InvalidCsBit { id: 0, .. })),
+            "Expected InvalidCsBit error, got {:?}",
+            result
+        );
+
+        // Clashes with CS1, X1 and the other CS line
+        for replacement in [r#""cs2_bit": 10"#, r#""cs2_bit": 14"#, r#""cs2_bit": 12"#] {
+            let result = build(&json.replace(r#""cs2_bit": 9"#, replacement));
+            assert!(
+                matches!(result, Err(Error::InvalidCsBit { id: 0, .. })),
+                "Expected InvalidCsBit error for {}, got {:?}",
+                replacement,
+                result
+            );
+        }
+
+        // Not valid for Chip types without the line
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 31 CS bit override test",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "test.rom",
+                    "type": "2364",
+                    "cs1": "active_low",
+                    "cs2_bit": 9
+                }]
+            }]
+        }"#;
+        let result = Builder::from_json(FW_VER, MCU_FAM, json);
+        assert!(
+            matches!(result, Err(Error::InvalidCsBit { id: 0, .. })),
+            "Expected InvalidCsBit error, got {:?}",
+            result.err()
+        );
+
+        println!("✓ Phase 31 Test 119: Builds with overridden CS2/CS3 bits");
+    }
+
+    // ========================================================================
+    // PHASE 32: Bus Trace Serving
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 120: Served bytes follow a recorded bus trace
+    // ========================================================================
+
+    #[test]
+    fn test_phase32_serve_trace() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, PAD_NO_CHIP_BYTE, diagnostic_fill_byte};
+
+        let board = Board::Ice24UsbH;
+        let chip_type = ChipType::Chip2364;
+        let chip_set = ChipSet::diagnostic_multi(0, &chip_type, 3, CsLogic::ActiveLow)
+            .expect("Failed to create diagnostic Chip set");
+
+        // CS1/X1/X2 are active low - clear exactly one to select a Chip
+        let cs1 = 1u16 << board.bit_cs1(chip_type);
+        let x1 = 1u16 << board.bit_x1();
+        let x2 = 1u16 << board.bit_x2();
+        let idle = 0xFFFFu16;
+
+        let trace = [
+            (idle & !cs1, diagnostic_fill_byte(0)),
+            (idle & !x1, diagnostic_fill_byte(1)),
+            (idle & !x2, diagnostic_fill_byte(2)),
+            // Nothing selected
+            (idle, PAD_NO_CHIP_BYTE),
+            // More than one selected
+            (idle & !cs1 & !x1, PAD_NO_CHIP_BYTE),
+            (0x0000, PAD_NO_CHIP_BYTE),
+            // Other address lines don't affect selection
+            (0x1234 | x1 | x2, diagnostic_fill_byte(0)),
+        ];
+
+        let bus_values: Vec<u16> = trace.iter().map(|&(value, _)| value).collect();
+        let expected: Vec<u8> = trace.iter().map(|&(_, byte)| byte).collect();
+
+        let served = chip_set.serve_trace(&bus_values, &board).unwrap();
+        assert_eq!(served, expected, "Served bytes don't match trace");
+
+        // Each served byte matches the flash image, once unscrambled
+        let flash_image = chip_set.to_flash_image(&board, false).unwrap();
+        for (&value, &byte) in bus_values.iter().zip(served.iter()) {
+            assert_eq!(
+                unscramble_physical_byte(flash_image[value as usize], board),
+                byte,
+                "Bus value {:#06X} served differently to flash image",
+                value
+            );
+        }
+
+        println!("✓ Phase 32 Test 120: Served bytes follow a recorded bus trace");
+    }
+
+    // ========================================================================
+    // PHASE 33: Per-ROM Exact Size
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 121: Exact-marked ROMs must match their Chip size
+    // ========================================================================
+
+    #[test]
+    fn test_phase33_per_rom_exact_size() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 33 per-ROM exact size",
+            "chip_sets": [
+                { "type": "single", "chips": [{ "file": "exact.rom", "type": "2364", "cs1": "active_low", "exact_size": true }] },
+                { "type": "single", "chips": [{ "file": "padded.rom", "type": "2364", "cs1": "active_low", "size_handling": "pad" }] }
+            ]
+        }"#;
+
+        let build = |exact_len: usize| {
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+            builder
+                .add_file(FileData {
+                    id: 0,
+                    data: create_test_rom_data(exact_len, 0x42),
+                })
+                .expect("Failed to add file");
+            builder
+                .add_file(FileData {
+                    id: 1,
+                    data: create_test_rom_data(4000, 0x24),
+                })
+                .expect("Failed to add file");
+            builder.build(default_fw_props())
+        };
+
+        // Correctly sized exact ROM passes, while its sibling is padded
+        build(8192).expect("Exact-size ROM with padded sibling should build");
+
+        // Wrongly sized exact ROM fails
+        let result = build(6000);
+        assert!(
+            matches!(
+                result,
+                Err(Error::NotExactSize {
+                    id: 0,
+                    expected: 8192,
+                    actual: 6000
+                })
+            ),
+            "Expected NotExactSize error, got {:?}",
+            result
+        );
+
+        // Combining exact_size with size_handling is rejected
+        let json = json.replace(
+            r#""exact_size": true"#,
+            r#""exact_size": true, "size_handling": "pad""#,
+        );
+        let result = Builder::from_json(FW_VER, MCU_FAM, &json);
+        assert!(
+            matches!(result, Err(Error::SizeHandlingNotAllowed { id: 0 })),
+            "Expected SizeHandlingNotAllowed error, got {:?}",
+            result.err()
+        );
+
+        println!("✓ Phase 33 Test 121: Exact-marked ROMs must match their Chip size");
+    }
+
+    // ========================================================================
+    // PHASE 34: Adding Files From Paths
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 123: Files added from paths, with early errors
+    // ========================================================================
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_phase34_add_file_from_path() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 34 add file from path",
+            "chip_sets": [
+                { "type": "single", "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }] },
+                { "type": "single", "chips": [{ "file": "b.rom", "type": "2364", "cs1": "active_low", "size_handling": "truncate" }] }
+            ]
+        }"#;
+
+        let dir = std::env::temp_dir().join(format!("onerom-gen-path-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let exact = dir.join("exact.rom");
+        let large = dir.join("large.rom");
+        std::fs::write(&exact, create_test_rom_data(8192, 0x42)).unwrap();
+        std::fs::write(&large, create_test_rom_data(16384, 0x24)).unwrap();
+
+        // Correctly sized file, and an oversized one for a truncating Chip
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file_from_path(0, &exact)
+            .expect("Failed to add file from path");
+        builder
+            .add_file_from_path(1, &large)
+            .expect("Oversized file should be accepted when truncating");
+        builder
+            .build(default_fw_props())
+            .expect("Build with files from paths failed");
+
+        // Oversized file for a Chip which doesn't truncate is rejected before
+        // building
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        let result = builder.add_file_from_path(0, &large);
+        assert!(
+            matches!(
+                result,
+                Err(Error::ImageTooLarge {
+                    image_size: 16384,
+                    expected_size: 8192
+                })
+            ),
+            "Expected ImageTooLarge error, got {:?}",
+            result
+        );
+
+        // Missing file surfaces the IO error
+        let result = builder.add_file_from_path(0, &dir.join("missing.rom"));
+        assert!(
+            matches!(result, Err(Error::Io { id: 0, .. })),
+            "Expected Io error, got {:?}",
+            result
+        );
+
+        // Duplicate and out of range ids
+        builder
+            .add_file_from_path(0, &exact)
+            .expect("Failed to add file from path");
+        let result = builder.add_file_from_path(0, &exact);
+        assert!(
+            matches!(result, Err(Error::DuplicateFile { id: 0 })),
+            "Expected DuplicateFile error, got {:?}",
+            result
+        );
+        let result = builder.add_file_from_path(2, &exact);
+        assert!(
+            matches!(result, Err(Error::InvalidFile { id: 2, total: 2 })),
+            "Expected InvalidFile error, got {:?}",
+            result
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        println!("✓ Phase 34 Test 123: Files added from paths, with early errors");
+    }
+
+    // ========================================================================
+    // PHASE 35: Per-Set Boot Logging
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 124: Only the set with boot logging carries filenames
+    // ========================================================================
+
+    #[test]
+    fn test_phase35_per_set_boot_logging() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 35 per-set boot logging",
+            "chip_sets": [
+                { "type": "single", "chips": [{ "file": "quiet.rom", "type": "2364", "cs1": "active_low" }] },
+                { "type": "single", "boot_logging": true, "chips": [{ "file": "logged.rom", "type": "2364", "cs1": "active_low" }] }
+            ]
+        }"#;
+
+        let build = |props: FirmwareProperties| {
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+            for id in 0..2 {
+                builder
+                    .add_file(FileData {
+                        id,
+                        data: create_test_rom_data(8192, 0x42),
+                    })
+                    .expect("Failed to add file");
+            }
+            builder.build(props)
+        };
+
+        // Global boot logging disabled
+        let props = default_fw_props_060();
+        let flash_base = props.board().mcu_family().get_flash_base();
+        let metadata_flash_start = flash_base + METADATA_FLASH_OFFSET;
+        let (metadata_buf, _) = build(props).expect("Build failed");
+
+        let header = MetadataHeader::parse(&metadata_buf);
+        let chip_sets_offset = (header.chip_sets_ptr - metadata_flash_start) as usize;
+
+        let mut chip_offsets = Vec::new();
+        for ii in 0..2 {
+            let set_offset = chip_sets_offset + ii * 64;
+            let chip_set = RomSetStruct::parse(&metadata_buf, set_offset);
+            let extended = ExtendedRomSetStruct::parse(&metadata_buf, set_offset);
+
+            // Follow the set's chip pointer array to its only chip
+            let array_offset = (chip_set.chips_ptr - metadata_flash_start) as usize;
+            let chip_ptr = u32::from_le_bytes(
+                metadata_buf[array_offset..array_offset + 4].try_into().unwrap(),
+            );
+            chip_offsets.push((chip_ptr - metadata_flash_start) as usize);
+
+            if ii == 0 {
+                assert_eq!(extended.filenames, 0, "Set 0 should follow the firmware");
+                assert_eq!(extended.signature, [0u8; 4], "Set 0 should have no signature");
+            } else {
+                assert_eq!(extended.filenames, 1, "Set 1 should carry filenames");
+                assert!(
+                    extended.signature.iter().all(|c| c.is_ascii_hexdigit()),
+                    "Set 1 should have a signature"
+                );
+            }
+        }
+
+        // Set 0 uses the compact chip struct, immediately followed by set 1's
+        assert_eq!(
+            chip_offsets[1] - chip_offsets[0],
+            CHIP_INFO_METADATA_LEN,
+            "Set 0 should use the compact chip struct"
+        );
+
+        // Set 1's chip struct carries its filename
+        let chip = RomInfoStruct::parse_with_filename(&metadata_buf, chip_offsets[1]);
+        let filename_offset = (chip.filename_ptr.unwrap() - metadata_flash_start) as usize;
+        assert_eq!(
+            parse_null_terminated_string(&metadata_buf, filename_offset),
+            "logged.rom"
+        );
+
+        // Requires the extended chip set structure
+        let result = build(default_fw_props());
+        assert!(
+            matches!(result, Err(Error::FirmwareTooOld { .. })),
+            "Expected FirmwareTooOld error, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 35 Test 124: Only the set with boot logging carries filenames");
+    }
+
+    // ========================================================================
+    // PHASE 36: Metadata Slack
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 125: Slack pads the metadata region
+    // ========================================================================
+
+    #[test]
+    fn test_phase36_reserve_metadata_slack() {
+        use onerom_gen::{Error, MAX_METADATA_LEN, PAD_METADATA_BYTE};
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 36 metadata slack",
+            "chip_sets": [
+                { "type": "single", "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }] },
+                { "type": "single", "chips": [{ "file": "b.rom", "type": "2364", "cs1": "active_low" }] }
+            ]
+        }"#;
+
+        let build = |props: FirmwareProperties| {
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+            for id in 0..2 {
+                builder
+                    .add_file(FileData {
+                        id,
+                        data: create_test_rom_data(8192, 0x11 * (id as u8 + 1)),
+                    })
+                    .expect("Failed to add file");
+            }
+            builder.build(props)
+        };
+
+        let props = fw_props_with_logging();
+        let metadata_flash_start = props.board().mcu_family().get_flash_base()
+            + METADATA_FLASH_OFFSET;
+        let (plain_metadata, plain_images) = build(props).expect("Build failed");
+
+        let slack = 1024;
+        let mut props = fw_props_with_logging();
+        props.set_reserve_metadata_slack(slack);
+        let (metadata_buf, rom_images_buf) = build(props).expect("Build with slack failed");
+
+        // Slack is padding on the end of otherwise identical metadata
+        assert_eq!(metadata_buf.len(), plain_metadata.len() + slack);
+        assert_eq!(&metadata_buf[..plain_metadata.len()], &plain_metadata[..]);
+        assert!(
+            metadata_buf[plain_metadata.len()..]
+                .iter()
+                .all(|&b| b == PAD_METADATA_BYTE),
+            "Slack should be padding"
+        );
+        assert_eq!(rom_images_buf, plain_images);
+
+        // Metadata still parses, with ROM images at their usual location,
+        // after the whole metadata region
+        let header = MetadataHeader::parse(&metadata_buf);
+        header.validate_basic();
+        let chip_sets_offset = (header.chip_sets_ptr - metadata_flash_start) as usize;
+        for ii in 0..2 {
+            let chip_set = RomSetStruct::parse(
+                &metadata_buf,
+                chip_sets_offset + ii * CHIP_SET_METADATA_LEN,
+            );
+            let expected_ptr = metadata_flash_start + MAX_METADATA_LEN as u32 + ii as u32 * 16384;
+            assert_eq!(chip_set.data_ptr, expected_ptr, "Set {} data pointer", ii);
+        }
+
+        // Slack must fit within the metadata region
+        let mut props = fw_props_with_logging();
+        props.set_reserve_metadata_slack(MAX_METADATA_LEN);
+        let result = build(props);
+        assert!(
+            matches!(result, Err(Error::BufferTooSmall { location: "Metadata", .. })),
+            "Expected BufferTooSmall error, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 36 Test 125: Slack pads the metadata region");
+    }
+
+    // ========================================================================
+    // PHASE 37: Build Report
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 126: Report matches the generated layout
+    // ========================================================================
+
+    #[test]
+    fn test_phase37_build_report() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 37 build report",
+            "socket_order": [1, 0],
+            "chip_sets": [
+                { "type": "single", "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }] },
+                { "type": "single", "chips": [{ "file": "b.rom", "type": "2364", "cs1": "active_low" }] }
+            ]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        for id in 0..2 {
+            builder
+                .add_file(FileData {
+                    id,
+                    data: create_test_rom_data(8192, 0x11 * (id as u8 + 1)),
+                })
+                .expect("Failed to add file");
+        }
+
+        let props = default_fw_props();
+        let flash_base = props.board().mcu_family().get_flash_base();
+        let metadata_flash_start = flash_base + METADATA_FLASH_OFFSET;
+        let (metadata_buf, rom_images_buf, report) =
+            builder.build_with_report(props).expect("Build failed");
+
+        // Plain build produces the same buffers
+        let (plain_metadata, plain_images) = builder.build(props).expect("Build failed");
+        assert_eq!(metadata_buf, plain_metadata);
+        assert_eq!(rom_images_buf, plain_images);
+
+        assert_eq!(report.metadata_len, metadata_buf.len());
+        assert_eq!(report.rom_images_len, rom_images_buf.len());
+        assert_eq!(report.firmware_size, 65536 + rom_images_buf.len());
+        assert_eq!(report.chip_sets.len(), 2);
+
+        let header = MetadataHeader::parse(&metadata_buf);
+        let chip_sets_offset = (header.chip_sets_ptr - metadata_flash_start) as usize;
+        for (ii, chip_set_report) in report.chip_sets.iter().enumerate() {
+            let chip_set = RomSetStruct::parse(
+                &metadata_buf,
+                chip_sets_offset + ii * CHIP_SET_METADATA_LEN,
+            );
+            assert_eq!(chip_set_report.data_ptr, Some(chip_set.data_ptr), "Set {}", ii);
+            assert_eq!(chip_set_report.image_size, chip_set.size as usize, "Set {}", ii);
+            assert_eq!(chip_set_report.serve_alg, props.serve_alg(), "Set {}", ii);
+
+            // Offset locates this set's image within the images buffer
+            let offset = chip_set_report.offset.expect("Set should have an image");
+            assert_eq!(offset as u32, chip_set.data_ptr - flash_base - 65536);
+            let byte = read_rom_byte_abs(&rom_images_buf, offset, props.board());
+            assert_eq!(byte, 0x11 * (ii as u8 + 1), "Set {} image at reported offset", ii);
+        }
+
+        // Socket order places set 1 first
+        assert_eq!(report.chip_sets[1].offset, Some(0));
+
+        println!("✓ Phase 37 Test 126: Report matches the generated layout");
+    }
+
+    // ========================================================================
+    // PHASE 38: Serve Algorithm vs Chip CS Lines
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 128: Incompatible serve algorithms are rejected by from_json
+    // ========================================================================
+
+    #[test]
+    fn test_phase38_serve_alg_vs_cs_lines() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::Error;
+
+        let json_for = |chip: &str, serve_alg: &str| {
+            format!(
+                r#"{{
+                    "version": 1,
+                    "description": "Phase 38 serve algorithm vs CS lines",
+                    "chip_sets": [{{
+                        "type": "single",
+                        "serve_alg": "{serve_alg}",
+                        "chips": [{chip}]
+                    }}]
+                }}"#
+            )
+        };
+
+        let chip_2364 = r#"{ "file": "a.rom", "type": "2364", "cs1": "active_low" }"#;
+        let chip_27128 = r#"{ "file": "a.rom", "type": "27128" }"#;
+        let chip_2332 =
+            r#"{ "file": "a.rom", "type": "2332", "cs1": "active_low", "cs2": "active_high" }"#;
+
+        // TwoCsOneAddr on a single CS chip
+        let result = Builder::from_json(FW_VER, MCU_FAM, &json_for(chip_2364, "two_cs_one_addr"));
+        assert!(
+            matches!(
+                result,
+                Err(Error::InvalidServeAlg {
+                    serve_alg: ServeAlg::TwoCsOneAddr,
+                    ..
+                })
+            ),
+            "Expected InvalidServeAlg for TwoCsOneAddr on 2364, got {:?}",
+            result.err()
+        );
+
+        // AddrOnCs on a CE/OE only chip
+        let result = Builder::from_json(FW_VER, MCU_FAM, &json_for(chip_27128, "addr_on_cs"));
+        assert!(
+            matches!(
+                result,
+                Err(Error::InvalidServeAlg {
+                    serve_alg: ServeAlg::AddrOnCs,
+                    ..
+                })
+            ),
+            "Expected InvalidServeAlg for AddrOnCs on 27128, got {:?}",
+            result.err()
+        );
+
+        // Compatible control - 2332 has two CS lines
+        let builder = Builder::from_json(FW_VER, MCU_FAM, &json_for(chip_2332, "two_cs_one_addr"))
+            .expect("TwoCsOneAddr should be accepted for 2332");
+        assert_eq!(builder.config().chip_sets[0].serve_alg, Some(ServeAlg::TwoCsOneAddr));
+
+        assert!(ServeAlg::AddrOnCs.is_valid_for(&ChipType::Chip2364));
+        assert!(!ServeAlg::TwoCsOneAddr.is_valid_for(&ChipType::Chip2364));
+        assert!(!ServeAlg::AddrOnCs.is_valid_for(&ChipType::Chip27128));
+        assert!(ServeAlg::Default.is_valid_for(&ChipType::Chip27128));
+
+        println!("✓ Phase 38 Test 128: Incompatible serve algorithms rejected at parse time");
+    }
+
+    // ========================================================================
+    // PHASE 39: Built-in Test ROMs
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 130: Built-in ramp image
+    // ========================================================================
+
+    #[test]
+    fn test_phase39_builtin_ramp() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 39 built-in ramp",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "@builtin:ramp8k", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+
+        let builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+
+        // Nothing for the caller to load
+        assert!(builder.file_specs().is_empty());
+
+        let props = default_fw_props();
+        builder
+            .build_validation(&props)
+            .expect("Built-in image should count as loaded");
+        let (_metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+
+        let pattern = onerom_gen::builtin_image("ramp8k").expect("ramp8k should exist");
+        assert_eq!(pattern.len(), 8192);
+        for addr in 0..8192 {
+            let b = read_rom_byte(&rom_images_buf, addr, props.board());
+            assert_eq!(b, addr as u8, "Mismatch at address {:#X}", addr);
+            assert_eq!(b, pattern[addr]);
+        }
+
+        println!("✓ Phase 39 Test 130: Built-in ramp matches the pattern");
+    }
+
+    // ========================================================================
+    // TEST 131: Error - Unknown built-in image
+    // ========================================================================
+
+    #[test]
+    fn test_phase39_unknown_builtin() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 39 unknown built-in",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "@builtin:sawtooth8k", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+
+        let result = Builder::from_json(FW_VER, MCU_FAM, json);
+        match result {
+            Err(Error::UnknownBuiltin { id, name }) => {
+                assert_eq!(id, 0);
+                assert_eq!(name, "sawtooth8k");
+            }
+            other => panic!("Expected UnknownBuiltin, got {:?}", other.err()),
+        }
+
+        println!("✓ Phase 39 Test 131: Unknown built-in rejected");
+    }
+
+    // ========================================================================
+    // PHASE 40: Chip Set Validation
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 132: ChipSet::validate() as a standalone pre-build check
+    // ========================================================================
+
+    #[test]
+    fn test_phase40_chip_set_validate() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::firmware::{FirmwareConfig, LedConfig};
+        use onerom_gen::{Chip, ChipSet, ChipSetType, CsConfig, Error, SizeHandling};
+
+        let board = default_fw_props().board();
+        let old_version = FirmwareVersion::new(0, 5, 1, 0);
+        let new_version = FirmwareVersion::new(0, 6, 0, 0);
+        let chip_type = ChipType::Chip2364;
+
+        // Valid multi set
+        let chips = vec![
+            create_chip(0, chip_type, &create_test_rom_data(8192, 0x11)),
+            create_chip(1, chip_type, &create_test_rom_data(8192, 0x22)),
+        ];
+        let chip_set = ChipSet::new(0, ChipSetType::Multi, ServeAlg::Default, chips, None)
+            .expect("Failed to create ChipSet");
+        chip_set
+            .validate(&board, &old_version)
+            .expect("Valid set should validate");
+
+        // Inconsistent CS1 logic across the set
+        let active_high = Chip::from_raw_rom_image(
+            1,
+            "chip1.rom".to_string(),
+            None,
+            Some(&create_test_rom_data(8192, 0x22)),
+            vec![0u8; chip_type.size_bytes()],
+            &chip_type,
+            CsConfig::new(Some(CsLogic::ActiveHigh), None, None),
+            &SizeHandling::None,
+            None,
+            None,
+            false,
+        )
+        .expect("Failed to create Chip");
+        let chips = vec![
+            create_chip(0, chip_type, &create_test_rom_data(8192, 0x11)),
+            active_high,
+        ];
+        let chip_set = ChipSet::new(0, ChipSetType::Multi, ServeAlg::Default, chips, None)
+            .expect("Failed to create ChipSet");
+        let result = chip_set.validate(&board, &old_version);
+        assert!(
+            matches!(result, Err(Error::InconsistentCsLogic { .. })),
+            "Expected InconsistentCsLogic, got {:?}",
+            result
+        );
+
+        // Firmware overrides require 0.6.0 firmware onwards
+        let overrides = FirmwareConfig {
+            ice: None,
+            fire: None,
+            led: Some(LedConfig { enabled: false }),
+            swd: None,
+            serve_alg_params: None,
+            serve_alg: None,
+        };
+        let chips = vec![create_chip(0, chip_type, &create_test_rom_data(8192, 0x11))];
+        let chip_set = ChipSet::new(
+            0,
+            ChipSetType::Single,
+            ServeAlg::Default,
+            chips,
+            Some(overrides),
+        )
+        .expect("Failed to create ChipSet");
+        let result = chip_set.validate(&board, &old_version);
+        assert!(
+            matches!(result, Err(Error::FirmwareTooOld { .. })),
+            "Expected FirmwareTooOld, got {:?}",
+            result
+        );
+        chip_set
+            .validate(&board, &new_version)
+            .expect("Overrides should validate with 0.6.0 firmware");
+
+        println!("✓ Phase 40 Test 132: ChipSet::validate() checks without building");
+    }
+
+    // ========================================================================
+    // PHASE 41: Parallel ROM Image Generation
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 133: Parallel and sequential ROM images are identical
+    // ========================================================================
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_phase41_parallel_rom_images() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, ChipSetType, Metadata};
+        use std::time::Instant;
+
+        let board = default_fw_props().board();
+        let chip_type = ChipType::Chip2364;
+
+        // 16 Chip sets, alternating single and 2 chip multi sets, each with
+        // distinct data so any misplaced image would be caught.
+        let chip_sets: Vec<ChipSet> = (0..16)
+            .map(|ii| {
+                let data = |n: usize| -> Vec<u8> {
+                    (0..8192).map(|addr| (addr ^ (ii * 16 + n)) as u8).collect()
+                };
+                let (set_type, chips) = if ii % 2 == 0 {
+                    (ChipSetType::Single, vec![create_chip(ii, chip_type, &data(0))])
+                } else {
+                    let chips = vec![
+                        create_chip(ii * 2, chip_type, &data(0)),
+                        create_chip(ii * 2 + 1, chip_type, &data(1)),
+                    ];
+                    (ChipSetType::Multi, chips)
+                };
+                ChipSet::new(ii, set_type, ServeAlg::Default, chips, None)
+                    .expect("Failed to create ChipSet")
+            })
+            .collect();
+
+        let pio = board.mcu_pio();
+        let metadata = Metadata::new(board, chip_sets, false, pio, FW_VER);
+        let size = metadata.rom_images_size();
+        assert!(size > 0, "Expected non-empty ROM images");
+
+        let mut parallel = vec![0u8; size];
+        let start = Instant::now();
+        metadata
+            .write_roms(&mut parallel)
+            .expect("Parallel write_roms failed");
+        let parallel_time = start.elapsed();
+
+        let mut sequential = vec![0u8; size];
+        let start = Instant::now();
+        metadata
+            .write_roms_sequential(&mut sequential)
+            .expect("Sequential write_roms failed");
+        let sequential_time = start.elapsed();
+
+        println!(
+            "  ROM images: {} bytes, parallel {:?}, sequential {:?}",
+            size, parallel_time, sequential_time
+        );
+        assert!(
+            parallel == sequential,
+            "Parallel and sequential ROM images differ"
+        );
+
+        println!("✓ Phase 41 Test 133: Parallel ROM images match sequential");
+    }
+
+    // ========================================================================
+    // PHASE 42: Labels Without Boot Logging
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 134: Label surfaces at the filename pointer without boot logging
+    // ========================================================================
+
+    #[test]
+    fn test_phase42_labels_without_boot_logging() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 42 labels without boot logging",
+            "labels": true,
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "901227-03.bin",
+                    "label": "KERNAL",
+                    "type": "2364",
+                    "cs1": "active_low"
+                }]
+            }]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x42),
+            })
+            .expect("Failed to add file");
+
+        // Boot logging disabled
+        let props = default_fw_props_060();
+        let flash_base = props.board().mcu_family().get_flash_base();
+        let metadata_flash_start = flash_base + METADATA_FLASH_OFFSET;
+        let (metadata_buf, _) = builder.build(props).expect("Build failed");
+
+        let header = MetadataHeader::parse(&metadata_buf);
+        let set_offset = (header.chip_sets_ptr - metadata_flash_start) as usize;
+        let chip_set = RomSetStruct::parse(&metadata_buf, set_offset);
+        let extended = ExtendedRomSetStruct::parse(&metadata_buf, set_offset);
+        assert_eq!(extended.filenames, 1, "Set should carry filenames");
+        assert_eq!(
+            extended.signature, [0u8; 4],
+            "Signature is only written with boot logging"
+        );
+
+        // Follow the set's chip pointer array to its only chip
+        let array_offset = (chip_set.chips_ptr - metadata_flash_start) as usize;
+        let chip_ptr =
+            u32::from_le_bytes(metadata_buf[array_offset..array_offset + 4].try_into().unwrap());
+        let chip_offset = (chip_ptr - metadata_flash_start) as usize;
+
+        // The chip struct's filename pointer references the label
+        let chip = RomInfoStruct::parse_with_filename(&metadata_buf, chip_offset);
+        let filename_offset = (chip.filename_ptr.unwrap() - metadata_flash_start) as usize;
+        assert_eq!(
+            parse_null_terminated_string(&metadata_buf, filename_offset),
+            "KERNAL"
+        );
+
+        println!("✓ Phase 42 Test 134: Label surfaces at the filename pointer");
+    }
+
+    // ========================================================================
+    // PHASE 43: TwoCsOneAddr Serving
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 135: TwoCsOneAddr image layout
+    // ========================================================================
+
+    #[test]
+    fn test_phase43_two_cs_one_addr_images() {
+        let build = |serve_alg: &str| {
+            let json = format!(
+                r#"{{
+                "version": 1,
+                "description": "Phase 43 TwoCsOneAddr images",
+                "chip_sets": [{{
+                    "type": "single",
+                    "serve_alg": "{serve_alg}",
+                    "chips": [{{
+                        "file": "random.rom",
+                        "type": "2332",
+                        "cs1": "active_low",
+                        "cs2": "active_low"
+                    }}]
+                }}]
+            }}"#
+            );
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, &json).expect("Failed to parse JSON");
+
+            // Random test data using a simple PRNG for reproducibility
+            let mut seed = 0x12345678u32;
+            let test_data: Vec<u8> = (0..4096)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                    (seed >> 24) as u8
+                })
+                .collect();
+            builder
+                .add_file(FileData {
+                    id: 0,
+                    data: test_data.clone(),
+                })
+                .expect("Failed to add file");
+
+            let (_metadata_buf, rom_images_buf) =
+                builder.build(default_fw_props()).expect("Build failed");
+            (test_data, rom_images_buf)
+        };
+
+        let board = default_fw_props().board();
+        let (test_data, default_buf) = build("default");
+        let (_, two_cs_buf) = build("two_cs_one_addr");
+
+        // Sweep the whole image - every address/CS lines value the firmware
+        // can look up - and check the layout is identical to the default
+        assert_eq!(two_cs_buf.len(), 16384, "Expected a 16KB STM32F4 24 pin image");
+        assert_eq!(default_buf.len(), two_cs_buf.len());
+        let mismatches = (0..two_cs_buf.len())
+            .filter(|&addr| default_buf[addr] != two_cs_buf[addr])
+            .count();
+        assert_eq!(
+            mismatches, 0,
+            "Found {} bytes differing from the default layout",
+            mismatches
+        );
+
+        // With both CS lines active (low), every byte is where the firmware
+        // reads it
+        let errors = (0..test_data.len())
+            .filter(|&addr| read_rom_byte(&two_cs_buf, addr, board) != test_data[addr])
+            .count();
+        assert_eq!(errors, 0, "Found {} byte mismatches when reading back", errors);
+
+        println!("✓ Phase 43 Test 135: TwoCsOneAddr images match the firmware's lookup");
+    }
+
+    // ========================================================================
+    // PHASE 44: Half of a Dual-Image File
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 136: Lower and upper halves select the right bytes
+    // ========================================================================
+
+    #[test]
+    fn test_phase44_half_selection() {
+        let build = |half: &str, data: Vec<u8>| {
+            let json = format!(
+                r#"{{
+                "version": 1,
+                "description": "Phase 44 half selection",
+                "chip_sets": [{{
+                    "type": "single",
+                    "chips": [{{
+                        "file": "kernal-ntsc-pal.bin",
+                        "type": "2364",
+                        "cs1": "active_low",
+                        "half": "{half}"
+                    }}]
+                }}]
+            }}"#
+            );
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, &json).expect("Failed to parse JSON");
+            builder
+                .add_file(FileData { id: 0, data })
+                .expect("Failed to add file");
+            builder.build(default_fw_props())
+        };
+
+        // 16KB file: NTSC variant (0x11) followed by PAL variant (0x22)
+        let mut data = vec![0x11u8; 8192];
+        data.extend_from_slice(&[0x22u8; 8192]);
+        let board = default_fw_props().board();
+
+        for (half, expected) in [("lower", 0x11), ("upper", 0x22)] {
+            let (_metadata_buf, rom_images_buf) =
+                build(half, data.clone()).expect("Build should succeed with half");
+            for addr in 0..8192 {
+                assert_eq!(
+                    read_rom_byte(&rom_images_buf, addr, board),
+                    expected,
+                    "Wrong byte for {} half at 0x{:04X}",
+                    half,
+                    addr
+                );
+            }
+        }
+
+        println!("✓ Phase 44 Test 136: Lower and upper halves select the right bytes");
+    }
+
+    // ========================================================================
+    // TEST 137: Invalid half usage
+    // ========================================================================
+
+    #[test]
+    fn test_phase44_half_errors() {
+        use onerom_gen::Error;
+
+        // Odd length file can't be split in two
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 44 odd length half",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "odd.bin", "type": "2364", "cs1": "active_low", "half": "upper" }]
+            }]
+        }"#;
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: vec![0x11u8; 16385],
+            })
+            .expect("Failed to add file");
+        let result = builder.build(default_fw_props());
+        assert!(
+            matches!(result, Err(Error::BadLocation { id: 0, .. })),
+            "Expected BadLocation error, got {:?}",
+            result
+        );
+
+        // Half and location together
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 44 half and location",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "both.bin",
+                    "type": "2364",
+                    "cs1": "active_low",
+                    "half": "lower",
+                    "location": { "start": 0, "length": 8192 }
+                }]
+            }]
+        }"#;
+        let result = Builder::from_json(FW_VER, MCU_FAM, json);
+        assert!(
+            matches!(result, Err(Error::InvalidConfig { .. })),
+            "Expected InvalidConfig error, got {:?}",
+            result.err()
+        );
+
+        println!("✓ Phase 44 Test 137: Invalid half usage rejected");
+    }
+
+    // ========================================================================
+    // PHASE 45: Validation Rules
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 138: Every validation error appears in the rule list
+    // ========================================================================
+
+    #[test]
+    fn test_phase45_validation_rules() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, ChipSetType, Error, Metadata, validation_rules};
+
+        let rules = validation_rules();
+
+        // Rule names are unique
+        let mut names: Vec<_> = rules.iter().map(|rule| rule.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), rules.len(), "Duplicate rule names");
+
+        // Variant name of an error, from its Debug output
+        let variant = |error: &Error| {
+            format!("{:?}", error)
+                .split([' ', '{', '('])
+                .next()
+                .unwrap()
+                .to_string()
+        };
+
+        let config = |chip_sets: &str| {
+            format!(
+                r#"{{ "version": 1, "description": "Phase 45 validation", "chip_sets": [{chip_sets}] }}"#
+            )
+        };
+        let single = |chip: &str| config(&format!(r#"{{ "type": "single", "chips": [{chip}] }}"#));
+        let build = |json: &str, files: Vec<Vec<u8>>| {
+            let mut builder = Builder::from_json(FW_VER, MCU_FAM, json)?;
+            for (id, data) in files.into_iter().enumerate() {
+                builder.add_file(FileData { id, data })?;
+            }
+            builder.build(default_fw_props_060()).map(|_| ())
+        };
+
+        let chip_2364 = r#"{ "file": "a.rom", "type": "2364", "cs1": "active_low" }"#;
+        let mut errors = vec![
+            // Config
+            Builder::from_json(FW_VER, MCU_FAM, "not json").err(),
+            Builder::from_json(FW_VER, MCU_FAM, r#"{ "version": 99, "description": "", "chip_sets": [] }"#)
+                .err(),
+            Builder::from_json(FirmwareVersion::new(99, 0, 0, 0), MCU_FAM, &single(chip_2364)).err(),
+            Builder::from_json(FW_VER, MCU_FAM, &config(r#"{ "type": "single", "chips": [] }"#)).err(),
+            Builder::from_json(
+                FW_VER,
+                MCU_FAM,
+                &config(&format!(r#"{{ "type": "single", "chips": [{chip_2364}, {chip_2364}] }}"#)),
+            )
+            .err(),
+            Builder::from_json(
+                FW_VER,
+                MCU_FAM,
+                &single(r#"{ "file": "@builtin:nonsense", "type": "2364", "cs1": "active_low" }"#),
+            )
+            .err(),
+            Builder::from_json(
+                FW_VER,
+                MCU_FAM,
+                &single(r#"{ "file": "a.rom", "type": "2332", "cs1": "active_low" }"#),
+            )
+            .err(),
+            Builder::from_json(
+                FW_VER,
+                MCU_FAM,
+                &config(&format!(
+                    r#"{{ "type": "single", "serve_alg": "two_cs_one_addr", "chips": [{chip_2364}] }}"#
+                )),
+            )
+            .err(),
+            Builder::from_json(
+                FW_VER,
+                MCU_FAM,
+                &single(r#"{ "file": "a.rom", "type": "2364", "cs1": "active_low", "exact_size": true, "size_handling": "pad" }"#),
+            )
+            .err(),
+            // Files
+            build(&single(chip_2364), vec![]).err(),
+            build(&single(chip_2364), vec![vec![0; 8192], vec![0; 8192]]).err(),
+            build(&single(chip_2364), vec![vec![0; 4096]]).err(),
+            build(&single(chip_2364), vec![vec![0; 16384]]).err(),
+            build(
+                &single(r#"{ "file": "a.rom", "type": "2364", "cs1": "active_low", "half": "lower" }"#),
+                vec![vec![0; 16385]],
+            )
+            .err(),
+            build(
+                &single(r#"{ "file": "a.rom", "type": "2364", "cs1": "active_low", "exact_size": true }"#),
+                vec![vec![0; 4096]],
+            )
+            .err(),
+        ];
+
+        // Chip sets
+        let chip_type = ChipType::Chip2364;
+        let chips = vec![create_chip(0, chip_type, &create_test_rom_data(8192, 0x11))];
+        errors.push(ChipSet::new(0, ChipSetType::Multi, ServeAlg::Default, chips, None).err());
+        let chips = vec![
+            create_chip(0, chip_type, &create_test_rom_data(8192, 0x11)),
+            create_chip(1, ChipType::Chip2764, &create_test_rom_data(8192, 0x11)),
+        ];
+        errors.push(ChipSet::new(0, ChipSetType::Multi, ServeAlg::Default, chips, None).err());
+        let chips = vec![create_chip(0, chip_type, &create_test_rom_data(8192, 0x11))];
+        let chip_set = ChipSet::new(0, ChipSetType::Single, ServeAlg::Default, chips, None)
+            .expect("Failed to create ChipSet");
+        let board = default_fw_props().board();
+        let pio = board.mcu_pio();
+        let mut metadata = Metadata::new(board, vec![chip_set], false, pio, FW_VER);
+        errors.push(metadata.set_socket_order(vec![1]).err());
+
+        let mut produced = Vec::new();
+        for error in errors {
+            let error = error.expect("Expected a validation error");
+            let name = variant(&error);
+            assert!(
+                rules.iter().any(|rule| rule.error == name),
+                "{} ({:?}) is missing from the validation rules",
+                name,
+                error
+            );
+            produced.push(name);
+        }
+        println!("  - Checked {} validation errors: {:?}", produced.len(), produced);
+
+        println!("✓ Phase 45 Test 138: Every validation error appears in the rule list");
+    }
+
+    // ========================================================================
+    // PHASE 46: Round Trip via sdrr-fw-parser
+    // ========================================================================
+
+    // Helper: Parses a build's output with sdrr-fw-parser, as it would parse
+    // a device's flash.  In place of real firmware, the firmware region holds
+    // a minimal sdrr_info header (`sdrr_info_t`), pointing at the metadata as
+    // the firmware does.
+    fn parse_built_flash(
+        builder: &Builder,
+        props: &FirmwareProperties,
+        metadata_buf: &[u8],
+        rom_images_buf: &[u8],
+    ) -> sdrr_fw_parser::SdrrInfo {
+        use onerom_gen::FIRMWARE_SIZE;
+        use sdrr_fw_parser::readers::MemoryReader;
+        use sdrr_fw_parser::{Parser, SDRR_INFO_FW_OFFSET};
+        use std::future::Future;
+        use std::task::{Context, Poll, Waker};
+
+        const BUILD_DATE_OFFSET: usize = 0x400;
+        const HW_REV_OFFSET: usize = 0x420;
+
+        let flash_base = props.board().mcu_family().get_flash_base();
+        let mut firmware = vec![0xFFu8; HW_REV_OFFSET + 0x20];
+
+        // Strings referenced by the header
+        let mut write_string = |offset: usize, s: &str| {
+            firmware[offset..offset + s.len()].copy_from_slice(s.as_bytes());
+            firmware[offset + s.len()] = 0;
+        };
+        write_string(BUILD_DATE_OFFSET, "round trip");
+        write_string(HW_REV_OFFSET, props.board().name());
+
+        // sdrr_info_t, all little endian
+        let version = props.version();
+        let mut header = Vec::new();
+        header.extend_from_slice(b"SDRR");
+        for field in [version.major(), version.minor(), version.patch(), version.build()] {
+            header.extend_from_slice(&field.to_le_bytes());
+        }
+        header.extend_from_slice(&(flash_base + BUILD_DATE_OFFSET as u32).to_le_bytes());
+        header.extend_from_slice(&[0u8; 8]); // commit
+        header.extend_from_slice(&(flash_base + HW_REV_OFFSET as u32).to_le_bytes());
+        header.extend_from_slice(&2u16.to_le_bytes()); // stm_line = F411
+        header.extend_from_slice(&4u16.to_le_bytes()); // stm_storage = E
+        header.extend_from_slice(&100u16.to_le_bytes()); // freq
+        header.extend_from_slice(&[0u8; 10]); // flags, through count_rom_access, and pad
+        header.extend_from_slice(&(flash_base + FIRMWARE_SIZE as u32).to_le_bytes()); // metadata
+        header.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes()); // pins_ptr
+        header.extend_from_slice(&[0u8; 4]); // boot_config
+        header.extend_from_slice(&0xFFFF_FFFFu32.to_le_bytes()); // extra_ptr
+        header.extend_from_slice(&[0u8; 4]); // _post
+        assert_eq!(header.len(), 64, "sdrr_info_t is 64 bytes");
+        let offset = SDRR_INFO_FW_OFFSET as usize;
+        firmware[offset..offset + header.len()].copy_from_slice(&header);
+
+        let flash = builder
+            .assemble_full_image(&firmware, metadata_buf, rom_images_buf)
+            .expect("Failed to assemble full image");
+
+        // The memory reader completes immediately, so a single poll suffices
+        let mut reader = MemoryReader::new(flash, flash_base);
+        let mut parser = Parser::new(&mut reader);
+        let mut parse = std::pin::pin!(parser.parse_flash());
+        match parse.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(result) => result.expect("Failed to parse flash"),
+            Poll::Pending => panic!("Parsing flash unexpectedly pending"),
+        }
+    }
+
+    // ========================================================================
+    // TEST 139: Parser recovers the built chip sets
+    // ========================================================================
+
+    #[test]
+    fn test_phase46_parser_round_trip() {
+        use sdrr_fw_parser::{SdrrCsState, SdrrRomType, SdrrServe};
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 46 round trip",
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "kernal.rom", "type": "2364", "cs1": "active_low" }]
+                },
+                {
+                    "type": "single",
+                    "chips": [{ "file": "char.rom", "type": "2332", "cs1": "active_high", "cs2": "active_low" }]
+                },
+                {
+                    "type": "multi",
+                    "chips": [
+                        { "file": "basic.rom", "type": "2364", "cs1": "active_low" },
+                        { "file": "editor.rom", "type": "2364", "cs1": "active_low" }
+                    ]
+                }
+            ]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        for (id, size) in [(0, 8192), (1, 4096), (2, 8192), (3, 8192)] {
+            builder
+                .add_file(FileData {
+                    id,
+                    data: create_test_rom_data(size, id as u8),
+                })
+                .expect("Failed to add file");
+        }
+
+        let props = default_fw_props_060();
+        let (metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+        let info = parse_built_flash(&builder, &props, &metadata_buf, &rom_images_buf);
+
+        assert!(info.metadata_present, "Parser should find the metadata");
+        assert_eq!(info.rom_sets.len(), 3, "Chip set count mismatch");
+
+        let expected = [
+            vec![(SdrrRomType::Rom2364, SdrrCsState::ActiveLow, SdrrCsState::NotUsed)],
+            vec![(SdrrRomType::Rom2332, SdrrCsState::ActiveHigh, SdrrCsState::ActiveLow)],
+            vec![
+                (SdrrRomType::Rom2364, SdrrCsState::ActiveLow, SdrrCsState::NotUsed),
+                (SdrrRomType::Rom2364, SdrrCsState::ActiveLow, SdrrCsState::NotUsed),
+            ],
+        ];
+        for (ii, (set, expected)) in info.rom_sets.iter().zip(expected.iter()).enumerate() {
+            assert_eq!(set.rom_count as usize, expected.len(), "Set {} Chip count", ii);
+            assert_eq!(set.roms.len(), expected.len(), "Set {} parsed Chips", ii);
+            for (rom, (rom_type, cs1, cs2)) in set.roms.iter().zip(expected.iter()) {
+                assert_eq!(rom.rom_type, *rom_type, "Set {} Chip type", ii);
+                assert_eq!(rom.cs1_state, *cs1, "Set {} CS1", ii);
+                assert_eq!(rom.cs2_state, *cs2, "Set {} CS2", ii);
+            }
+        }
+        assert_eq!(info.rom_sets[2].serve, SdrrServe::AddrOnAnyCs);
+
+        println!("✓ Phase 46 Test 139: Parser recovers the built chip sets");
+    }
+
+    // ========================================================================
+    // PHASE 47: Full Image Assembly
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 140: Regions placed at their flash offsets
+    // ========================================================================
+
+    #[test]
+    fn test_phase47_assemble_full_image() {
+        use onerom_gen::{FIRMWARE_SIZE, MAX_METADATA_LEN};
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 47 full image",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "kernal.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x42),
+            })
+            .expect("Failed to add file");
+        let (metadata_buf, rom_images_buf) = builder.build(default_fw_props_060()).expect("Build failed");
+
+        let firmware = create_test_rom_data(1000, 0x5A);
+        let image = builder
+            .assemble_full_image(&firmware, &metadata_buf, &rom_images_buf)
+            .expect("Failed to assemble full image");
+
+        assert_eq!(image.len(), FIRMWARE_SIZE + MAX_METADATA_LEN + rom_images_buf.len());
+        assert_eq!(&image[..firmware.len()], &firmware[..]);
+        assert!(
+            image[firmware.len()..FIRMWARE_SIZE].iter().all(|&b| b == 0xFF),
+            "Firmware should be padded with 0xFF"
+        );
+        assert_eq!(
+            &image[FIRMWARE_SIZE..FIRMWARE_SIZE + metadata_buf.len()],
+            &metadata_buf[..]
+        );
+        assert!(
+            image[FIRMWARE_SIZE + metadata_buf.len()..FIRMWARE_SIZE + MAX_METADATA_LEN]
+                .iter()
+                .all(|&b| b == 0xFF),
+            "Metadata should be padded with 0xFF"
+        );
+        assert_eq!(&image[FIRMWARE_SIZE + MAX_METADATA_LEN..], &rom_images_buf[..]);
+
+        // Firmware filling its reservation exactly is fine
+        let firmware = vec![0u8; FIRMWARE_SIZE];
+        assert!(builder
+            .assemble_full_image(&firmware, &metadata_buf, &rom_images_buf)
+            .is_ok());
+
+        println!("✓ Phase 47 Test 140: Regions placed at their flash offsets");
+    }
+
+    // ========================================================================
+    // TEST 141: Oversized firmware rejected
+    // ========================================================================
+
+    #[test]
+    fn test_phase47_firmware_too_large() {
+        use onerom_gen::{Error, FIRMWARE_SIZE};
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 47 oversized firmware",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "kernal.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x42),
+            })
+            .expect("Failed to add file");
+        let (metadata_buf, rom_images_buf) = builder.build(default_fw_props_060()).expect("Build failed");
+
+        let firmware = vec![0u8; FIRMWARE_SIZE + 1];
+        let result = builder.assemble_full_image(&firmware, &metadata_buf, &rom_images_buf);
+        assert!(
+            matches!(
+                result,
+                Err(Error::ImageTooLarge {
+                    image_size,
+                    expected_size: FIRMWARE_SIZE,
+                }) if image_size == FIRMWARE_SIZE + 1
+            ),
+            "Expected ImageTooLarge, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 47 Test 141: Oversized firmware rejected");
+    }
+
+    // ========================================================================
+    // PHASE 48: Window Offset
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 142: Chip data shifted within its window
+    // ========================================================================
+
+    #[test]
+    fn test_phase48_window_offset() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 48 window offset",
+            "chip_sets": [{
+                "type": "single",
+                "window_offset": 4096,
+                "chips": [{
+                    "file": "test.rom",
+                    "type": "2364",
+                    "cs1": "active_low",
+                    "size_handling": "pad",
+                    "fill_byte": 255
+                }]
+            }]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+
+        // 4KB file for the upper half of an 8KB ROM
+        let data: Vec<u8> = (0..4096).map(|i| (i % 251) as u8).collect();
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: data.clone(),
+            })
+            .expect("Failed to add file");
+
+        let props = default_fw_props();
+        let (_metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+
+        // Addresses below the offset read as pad
+        for addr in 0..4096 {
+            let b = read_rom_byte(&rom_images_buf, addr, props.board());
+            assert_eq!(b, 0xFF, "Pad mismatch at address {:#X}", addr);
+        }
+
+        // The rest serve the file, shifted up by the offset
+        for addr in 4096..8192 {
+            let b = read_rom_byte(&rom_images_buf, addr, props.board());
+            assert_eq!(b, data[addr - 4096], "Mismatch at address {:#X}", addr);
+        }
+
+        println!("✓ Phase 48 Test 142: Chip data shifted within its window");
+    }
+
+    // ========================================================================
+    // TEST 143: Window offset must fit the window
+    // ========================================================================
+
+    #[test]
+    fn test_phase48_window_offset_too_large() {
+        use onerom_gen::Error;
+
+        let config = |offset: usize| {
+            format!(
+                r#"{{
+                    "version": 1,
+                    "description": "Phase 48 window offset too large",
+                    "chip_sets": [{{
+                        "type": "single",
+                        "window_offset": {offset},
+                        "chips": [{{
+                            "file": "test.rom",
+                            "type": "2364",
+                            "cs1": "active_low",
+                            "size_handling": "pad"
+                        }}]
+                    }}]
+                }}"#
+            )
+        };
+
+        // Offset beyond the window is rejected up front
+        let result = Builder::from_json(FW_VER, MCU_FAM, &config(8192));
+        assert!(
+            matches!(result, Err(Error::InvalidConfig { .. })),
+            "Expected InvalidConfig, got {:?}",
+            result.err()
+        );
+
+        // Offset plus file size beyond the window is rejected at build
+        let mut builder =
+            Builder::from_json(FW_VER, MCU_FAM, &config(4096)).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(6144, 0x55),
+            })
+            .expect("Failed to add file");
+        let result = builder.build(default_fw_props());
+        assert!(
+            matches!(result, Err(Error::BadLocation { id: 0, .. })),
+            "Expected BadLocation, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 48 Test 143: Window offset must fit the window");
+    }
+
+    // ========================================================================
+    // PHASE 49: Config Round Trip via sdrr-fw-parser
+    // ========================================================================
+
+    // Helper: Builds the config, loading a file of each given size in turn,
+    // then parses the output with sdrr-fw-parser and checks the parsed chip
+    // sets match the config - Chip types, CS logic, serving algorithms and,
+    // with boot logging, filenames.
+    fn assert_config_round_trip(json: &str, file_sizes: &[usize], props: FirmwareProperties) {
+        use onerom_gen::ChipSetType;
+        use sdrr_fw_parser::{SdrrCsState, SdrrServe};
+
+        let mut builder = Builder::from_json(props.version(), MCU_FAM, json)
+            .expect("Failed to parse JSON");
+        for (id, &size) in file_sizes.iter().enumerate() {
+            builder
+                .add_file(FileData {
+                    id,
+                    data: create_test_rom_data(size, id as u8),
+                })
+                .expect("Failed to add file");
+        }
+        let (metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+        let info = parse_built_flash(&builder, &props, &metadata_buf, &rom_images_buf);
+
+        let cs_state = |logic: Option<CsLogic>| match logic {
+            Some(CsLogic::ActiveLow) => SdrrCsState::ActiveLow,
+            Some(CsLogic::ActiveHigh) => SdrrCsState::ActiveHigh,
+            Some(CsLogic::Ignore) | None => SdrrCsState::NotUsed,
+        };
+
+        let config = builder.config();
+        assert_eq!(info.rom_sets.len(), config.chip_sets.len(), "Chip set count mismatch");
+        for (ii, (set, set_config)) in info.rom_sets.iter().zip(&config.chip_sets).enumerate() {
+            assert_eq!(set.roms.len(), set_config.chips.len(), "Set {} Chip count", ii);
+
+            let serve_alg = match set_config.set_type {
+                ChipSetType::Multi => ServeAlg::AddrOnAnyCs,
+                _ => set_config.serve_alg.unwrap_or(props.serve_alg()),
+            };
+            let serve = match set.serve {
+                SdrrServe::TwoCsOneAddr => 0,
+                SdrrServe::AddrOnCs => 1,
+                SdrrServe::AddrOnAnyCs => 2,
+            };
+            assert_eq!(serve, serve_alg.c_enum_value(), "Set {} serving algorithm", ii);
+
+            let boot_logging = set_config.boot_logging.unwrap_or(props.boot_logging());
+            for (jj, (rom, chip)) in set.roms.iter().zip(&set_config.chips).enumerate() {
+                assert_eq!(
+                    rom.rom_type.to_string(),
+                    chip.chip_type.name(),
+                    "Set {} Chip {} type",
+                    ii,
+                    jj
+                );
+                assert_eq!(rom.cs1_state, cs_state(chip.cs1), "Set {} Chip {} CS1", ii, jj);
+                assert_eq!(rom.cs2_state, cs_state(chip.cs2), "Set {} Chip {} CS2", ii, jj);
+                assert_eq!(rom.cs3_state, cs_state(chip.cs3), "Set {} Chip {} CS3", ii, jj);
+
+                let expected_filename = boot_logging.then(|| chip.file.clone());
+                assert_eq!(rom.filename, expected_filename, "Set {} Chip {} filename", ii, jj);
+            }
+        }
+    }
+
+    // ========================================================================
+    // TEST 144: Single, banked and multi sets survive the round trip
+    // ========================================================================
+
+    #[test]
+    fn test_phase49_config_round_trip() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 49 config round trip",
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "kernal.rom", "type": "2364", "cs1": "active_low" }]
+                },
+                {
+                    "type": "single",
+                    "serve_alg": "two_cs_one_addr",
+                    "chips": [{ "file": "char.rom", "type": "2332", "cs1": "active_high", "cs2": "active_low" }]
+                },
+                {
+                    "type": "banked",
+                    "chips": [
+                        { "file": "bank0.rom", "type": "2364", "cs1": "active_low" },
+                        { "file": "bank1.rom", "type": "2364", "cs1": "active_low" }
+                    ]
+                },
+                {
+                    "type": "multi",
+                    "chips": [
+                        { "file": "basic.rom", "type": "2364", "cs1": "active_high" },
+                        { "file": "editor.rom", "type": "2364", "cs1": "active_high" }
+                    ]
+                }
+            ]
+        }"#;
+        let file_sizes = [8192, 4096, 8192, 8192, 8192, 8192];
+
+        for boot_logging in [false, true] {
+            let props = FirmwareProperties::new(
+                FW_VER,
+                Board::Ice24UsbH,
+                McuVariant::F411RE,
+                ServeAlg::Default,
+                boot_logging,
+            )
+            .unwrap();
+            assert_config_round_trip(json, &file_sizes, props);
+        }
+
+        println!("✓ Phase 49 Test 144: Single, banked and multi sets survive the round trip");
+    }
+
+    // ========================================================================
+    // PHASE 50: TOML and YAML Configs
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 145: Equivalent configs produce identical metadata
+    // ========================================================================
+
+    #[cfg(all(feature = "toml", feature = "yaml"))]
+    #[test]
+    fn test_phase50_toml_yaml_configs() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 50 config formats",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "char.rom",
+                    "type": "2332",
+                    "cs1": "active_low",
+                    "cs2": "active_high",
+                    "size_handling": "pad"
+                }]
+            }]
+        }"#;
+
+        let toml = r#"
+            version = 1
+            description = "Phase 50 config formats"
+
+            [[chip_sets]]
+            type = "single"
+
+            [[chip_sets.chips]]
+            file = "char.rom"
+            type = "2332"
+            cs1 = "active_low"
+            cs2 = "active_high"
+            size_handling = "pad"
+        "#;
+
+        let yaml = r#"
+version: 1
+description: Phase 50 config formats
+chip_sets:
+  - type: single
+    chips:
+      - file: char.rom
+        type: "2332"
+        cs1: active_low
+        cs2: active_high
+        size_handling: pad
+"#;
+
+        let builders = [
+            Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON"),
+            Builder::from_toml(FW_VER, MCU_FAM, toml).expect("Failed to parse TOML"),
+            Builder::from_yaml(FW_VER, MCU_FAM, yaml).expect("Failed to parse YAML"),
+        ];
+
+        let props = default_fw_props_060();
+        let outputs: Vec<(Vec<u8>, Vec<u8>)> = builders
+            .into_iter()
+            .map(|mut builder| {
+                builder
+                    .add_file(FileData {
+                        id: 0,
+                        data: create_test_rom_data(2048, 0x42),
+                    })
+                    .expect("Failed to add file");
+                builder.build(props).expect("Build failed")
+            })
+            .collect();
+
+        for (format, output) in ["TOML", "YAML"].iter().zip(&outputs[1..]) {
+            assert_eq!(output.0, outputs[0].0, "{} metadata differs from JSON", format);
+            assert_eq!(output.1, outputs[0].1, "{} ROM images differ from JSON", format);
+        }
+
+        // Same validation applies
+        let bad_toml = toml.replace("size_handling = \"pad\"", "size_handling = \"bogus\"");
+        assert!(Builder::from_toml(FW_VER, MCU_FAM, &bad_toml).is_err());
+
+        println!("✓ Phase 50 Test 145: Equivalent configs produce identical metadata");
+    }
+
+    // ========================================================================
+    // PHASE 51: Metadata Parsing
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 146: Parsing inverts building
+    // ========================================================================
+
+    #[test]
+    fn test_phase51_parse_inverts_build() {
+        use onerom_gen::{ChipSetType, Metadata, crc32};
+
+        let sets = [
+            r#"{ "type": "single", "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }] }"#,
+            r#"{ "type": "single", "chips": [{ "file": "b.rom", "type": "2332", "cs1": "active_high", "cs2": "active_low" }] }"#,
+            r#"{ "type": "single", "chips": [{ "file": "c.rom", "type": "2316", "cs1": "active_low", "cs2": "active_high", "cs3": "active_low" }] }"#,
+            r#"{ "type": "banked", "chips": [
+                { "file": "d.rom", "type": "2364", "cs1": "active_high" },
+                { "file": "e.rom", "type": "2364", "cs1": "active_high" }
+            ] }"#,
+            r#"{ "type": "multi", "chips": [
+                { "file": "f.rom", "type": "2364", "cs1": "active_low" },
+                { "file": "g.rom", "type": "2364", "cs1": "active_low" },
+                { "file": "h.rom", "type": "2364", "cs1": "active_low" }
+            ] }"#,
+            r#"{ "type": "multi", "chips": [
+                { "file": "i.rom", "type": "2332", "cs1": "active_low", "cs2": "ignore" },
+                { "file": "j.rom", "type": "2332", "cs1": "active_low", "cs2": "ignore" }
+            ] }"#,
+        ];
+
+        // Individual sets, and all sets together
+        let mut configs: Vec<Vec<&str>> = sets.iter().map(|set| vec![*set]).collect();
+        configs.push(sets.to_vec());
+
+        let cs_logic = |logic: Option<CsLogic>| logic.filter(|&l| l != CsLogic::Ignore);
+
+        for version in [FirmwareVersion::new(0, 5, 1, 0), FW_VER] {
+            for boot_logging in [false, true] {
+                for chip_sets in &configs {
+                    let json = format!(
+                        r#"{{ "version": 1, "description": "Phase 51", "provenance": true, "chip_sets": [{}] }}"#,
+                        chip_sets.join(",")
+                    );
+                    let mut builder = Builder::from_json(version, MCU_FAM, &json)
+                        .expect("Failed to parse JSON");
+                    // Each Chip has its own file, so file IDs follow Chip order
+                    let files: Vec<Vec<u8>> = builder
+                        .config()
+                        .chip_sets
+                        .iter()
+                        .flat_map(|set| &set.chips)
+                        .enumerate()
+                        .map(|(id, chip)| create_test_rom_data(chip.chip_type.size_bytes(), id as u8))
+                        .collect();
+                    for (id, data) in files.iter().enumerate() {
+                        builder
+                            .add_file(FileData {
+                                id,
+                                data: data.clone(),
+                            })
+                            .expect("Failed to add file");
+                    }
+
+                    let props = FirmwareProperties::new(
+                        version,
+                        Board::Ice24UsbH,
+                        McuVariant::F411RE,
+                        ServeAlg::Default,
+                        boot_logging,
+                    )
+                    .unwrap();
+                    let flash_base = props.board().mcu_family().get_flash_base();
+                    let (metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+
+                    let parsed = Metadata::parse(&metadata_buf, flash_base, boot_logging)
+                        .expect("Failed to parse metadata");
+
+                    let config = builder.config();
+                    assert_eq!(parsed.version, 1);
+                    assert!(parsed.generator_version.is_some());
+                    assert_eq!(parsed.chip_sets.len(), config.chip_sets.len());
+
+                    let mut data_ptr = flash_base + 65536;
+                    let mut file_id = 0;
+                    for (set, set_config) in parsed.chip_sets.iter().zip(&config.chip_sets) {
+                        let size = match set_config.set_type {
+                            ChipSetType::Single => 16384,
+                            _ => 65536,
+                        };
+                        assert_eq!(set.data_ptr, Some(data_ptr), "{}", json);
+                        assert_eq!(set.size, size, "{}", json);
+                        data_ptr += size;
+
+                        let serve_alg = match set_config.set_type {
+                            ChipSetType::Multi => ServeAlg::AddrOnAnyCs,
+                            _ => ServeAlg::AddrOnCs,
+                        };
+                        assert_eq!(set.serve_alg, serve_alg, "{}", json);
+                        assert_eq!(set.chips.len(), set_config.chips.len(), "{}", json);
+
+                        let extended = version >= FW_VER;
+                        assert_eq!(set.signature.is_some(), extended && boot_logging, "{}", json);
+
+                        for (chip, chip_config) in set.chips.iter().zip(&set_config.chips) {
+                            assert_eq!(chip.chip_type, chip_config.chip_type, "{}", json);
+                            assert_eq!(Some(chip.cs1), chip_config.cs1, "{}", json);
+                            assert_eq!(chip.cs2, cs_logic(chip_config.cs2), "{}", json);
+                            assert_eq!(chip.cs3, cs_logic(chip_config.cs3), "{}", json);
+                            assert_eq!(
+                                chip.filename,
+                                boot_logging.then(|| chip_config.file.clone()),
+                                "{}",
+                                json
+                            );
+
+                            // Provenance requires 0.6.0
+                            let data = &files[file_id];
+                            let provenance = chip.provenance.map(|p| (p.size, p.checksum));
+                            let expected = extended.then(|| (data.len() as u32, crc32(data)));
+                            assert_eq!(provenance, expected, "{}", json);
+                            file_id += 1;
+                        }
+                    }
+                    assert_eq!(
+                        (data_ptr - flash_base - 65536) as usize,
+                        rom_images_buf.len(),
+                        "{}",
+                        json
+                    );
+                }
+            }
+        }
+
+        println!("✓ Phase 51 Test 146: Parsing inverts building");
+    }
+
+    // ========================================================================
+    // TEST 147: Bad version and truncation rejected
+    // ========================================================================
+
+    #[test]
+    fn test_phase51_parse_errors() {
+        use onerom_gen::{Error, Metadata};
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 51 parse errors",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x42),
+            })
+            .expect("Failed to add file");
+        let props = default_fw_props_060();
+        let flash_base = props.board().mcu_family().get_flash_base();
+        let (metadata_buf, _rom_images_buf) = builder.build(props).expect("Build failed");
+        assert!(Metadata::parse(&metadata_buf, flash_base, false).is_ok());
+
+        // Unsupported version
+        let mut bad_version = metadata_buf.clone();
+        bad_version[16..20].copy_from_slice(&3u32.to_le_bytes());
+        let result = Metadata::parse(&bad_version, flash_base, false);
+        assert!(
+            matches!(result, Err(Error::UnsupportedConfigVersion { version: 3 })),
+            "Expected UnsupportedConfigVersion, got {:?}",
+            result
+        );
+
+        // Truncated within the header, and within the chip set structure
+        for len in [100, metadata_buf.len() - 4] {
+            let result = Metadata::parse(&metadata_buf[..len], flash_base, false);
+            assert!(
+                matches!(result, Err(Error::BufferTooSmall { .. })),
+                "Expected BufferTooSmall for {} bytes, got {:?}",
+                len,
+                result
+            );
+        }
+
+        // Bad magic
+        let mut bad_magic = metadata_buf.clone();
+        bad_magic[0] = b'X';
+        assert!(matches!(
+            Metadata::parse(&bad_magic, flash_base, false),
+            Err(Error::InvalidFlashImage { .. })
+        ));
+
+        println!("✓ Phase 51 Test 147: Bad version and truncation rejected");
+    }
+
+    // ========================================================================
+    // PHASE 52: Firmware Compatibility Checks
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 148: Overrides require 0.6.0 firmware
+    // ========================================================================
+
+    #[test]
+    fn test_phase52_firmware_compat_too_old() {
+        use onerom_gen::Error;
+
+        let old_version = FirmwareVersion::new(0, 5, 1, 0);
+        for overrides in [
+            r#""firmware_overrides": { "led": { "enabled": false } }"#,
+            r#""boot_logging": true"#,
+        ] {
+            let json = format!(
+                r#"{{
+                    "version": 1,
+                    "description": "Phase 52 too old",
+                    "chip_sets": [{{
+                        "type": "single",
+                        "chips": [{{ "file": "a.rom", "type": "2364", "cs1": "active_low" }}],
+                        {overrides}
+                    }}]
+                }}"#
+            );
+            let builder = Builder::from_json(FW_VER, MCU_FAM, &json).expect("Failed to parse JSON");
+
+            assert!(builder.check_firmware_compat(&FW_VER, MCU_FAM).is_ok());
+
+            let result = builder.check_firmware_compat(&old_version, MCU_FAM);
+            assert!(
+                matches!(
+                    result,
+                    Err(Error::FirmwareTooOld { version, minimum })
+                        if version == old_version && minimum == FW_VER
+                ),
+                "Expected FirmwareTooOld for {}, got {:?}",
+                overrides,
+                result
+            );
+        }
+
+        // Configs without overrides are fine with older firmware
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 52 no overrides",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+        let builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        assert!(builder.check_firmware_compat(&old_version, MCU_FAM).is_ok());
+
+        println!("✓ Phase 52 Test 148: Overrides require 0.6.0 firmware");
+    }
+
+    // ========================================================================
+    // TEST 149: Wrong MCU family and unsupported versions rejected
+    // ========================================================================
+
+    #[test]
+    fn test_phase52_firmware_compat_wrong_family() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 52 wrong family",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+        let builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+
+        let result = builder.check_firmware_compat(&FW_VER, McuFamily::Rp2350);
+        assert!(
+            matches!(
+                result,
+                Err(Error::WrongMcuFamily {
+                    actual: McuFamily::Rp2350,
+                    required: McuFamily::Stm32f4,
+                })
+            ),
+            "Expected WrongMcuFamily, got {:?}",
+            result
+        );
+
+        let result = builder.check_firmware_compat(&FirmwareVersion::new(0, 7, 0, 0), MCU_FAM);
+        assert!(
+            matches!(result, Err(Error::FirmwareTooNew { .. })),
+            "Expected FirmwareTooNew, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 52 Test 149: Wrong MCU family and unsupported versions rejected");
+    }
+
+    // ========================================================================
+    // PHASE 53: 16-bit Chip Types
+    // ========================================================================
+
+    // Helper: Distinct logical data for a 16-bit Chip image
+    fn create_16_bit_rom_data(size: usize) -> Vec<u8> {
+        (0..size)
+            .map(|i| (i ^ (i >> 8) ^ (i >> 16)).wrapping_mul(7) as u8)
+            .collect()
+    }
+
+    // ========================================================================
+    // TEST 150: 27C400 served as words, per the board pin map
+    // ========================================================================
+
+    #[test]
+    fn test_phase53_27c400_word_ordering() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 53 27C400",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "test.rom", "type": "27C400" }]
+            }]
+        }"#;
+
+        let rom_data = create_16_bit_rom_data(524288);
+        let mut builder =
+            Builder::from_json(FW_VER, McuFamily::Rp2350, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: rom_data.clone(),
+            })
+            .expect("Failed to add file");
+
+        let props = FirmwareProperties::new(
+            FW_VER,
+            Board::Fire40A,
+            McuVariant::RP2350B,
+            ServeAlg::Default,
+            false,
+        )
+        .unwrap();
+        let board = props.board();
+        let (_metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+
+        // The whole Chip is stored, two bytes per word address
+        assert_eq!(rom_images_buf.len(), 524288);
+
+        // Physical word address for a logical word address.  Logical address
+        // line 0 is A-1 (byte select), which is D15 in 16-bit mode, so word
+        // bit n is address line n + 1.
+        let phys_pin_to_addr_map = board.phys_pin_to_addr_map();
+        let data_pins = board.data_pins();
+        assert_eq!(data_pins.len(), 16);
+        let word_to_phys = |word: usize| -> usize {
+            phys_pin_to_addr_map
+                .iter()
+                .enumerate()
+                .filter_map(|(pin, line)| match line {
+                    Some(line) if *line >= 1 && (word & (1 << (line - 1))) != 0 => {
+                        Some(1 << pin)
+                    }
+                    _ => None,
+                })
+                .sum()
+        };
+
+        for word in (0..262144).step_by(97).chain([0, 1, 0x20000, 262143]) {
+            let phys = word_to_phys(word);
+            let served =
+                u16::from_le_bytes([rom_images_buf[phys * 2], rom_images_buf[phys * 2 + 1]]);
+
+            // Reverse the data pin mapping
+            let mut actual = 0u16;
+            for (bit, &pin) in data_pins.iter().enumerate() {
+                if (served & (1 << pin)) != 0 {
+                    actual |= 1 << bit;
+                }
+            }
+
+            let expected = u16::from_le_bytes([rom_data[word * 2], rom_data[word * 2 + 1]]);
+            assert_eq!(
+                actual, expected,
+                "Word 0x{:05X} (physical 0x{:05X}) mismatch",
+                word, phys
+            );
+        }
+
+        println!("✓ Phase 53 Test 150: 27C400 served as words, per the board pin map");
+    }
+
+    // ========================================================================
+    // TEST 151: 16-bit Chips rejected on 8-bit boards and in multi-Chip sets
+    // ========================================================================
+
+    #[test]
+    fn test_phase53_16_bit_validation() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, ChipSetType, Error};
+
+        let rom_data = create_16_bit_rom_data(ChipType::Chip27C400.size_bytes());
+
+        let chip_set = ChipSet::new(
+            0,
+            ChipSetType::Single,
+            ServeAlg::Default,
+            vec![create_chip(0, ChipType::Chip27C400, &rom_data)],
+            None,
+        )
+        .expect("Failed to create Chip set");
+        assert!(chip_set.is_16_bit());
+
+        // Fine on a board with 16 data lines, and reverses cleanly
+        let board = Board::Fire40A;
+        chip_set
+            .validate(&board, &FW_VER)
+            .expect("16-bit Chip set should validate");
+        let flash_image = chip_set.to_flash_image(&board, false).unwrap();
+        let recovered = chip_set
+            .from_flash_image(&flash_image, &board, false)
+            .expect("Failed to reverse flash image");
+        assert_eq!(recovered, vec![rom_data.clone()]);
+
+        // Rejected on a board with 8 data lines
+        let result = chip_set.validate(&Board::Ice24UsbH, &FW_VER);
+        assert!(
+            matches!(
+                result,
+                Err(Error::TooFewDataLines {
+                    chip_type: ChipType::Chip27C400,
+                    required: 16,
+                    available: 8,
+                })
+            ),
+            "Expected TooFewDataLines, got {:?}",
+            result
+        );
+
+        // Rejected in multi-Chip sets
+        let chip_set = ChipSet::new(
+            0,
+            ChipSetType::Banked,
+            ServeAlg::Default,
+            vec![
+                create_chip(0, ChipType::Chip27C400, &rom_data),
+                create_chip(1, ChipType::Chip27C400, &rom_data),
+            ],
+            None,
+        )
+        .expect("Failed to create Chip set");
+        let result = chip_set.validate(&board, &FW_VER);
+        assert!(
+            matches!(result, Err(Error::InvalidConfig { .. })),
+            "Expected InvalidConfig, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 53 Test 151: 16-bit Chips rejected on 8-bit boards and in multi-Chip sets");
+    }
+
+    // ========================================================================
+    // PHASE 54: CS Logic Helpers
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 152: CsLogic::is_active() for every combination
+    // ========================================================================
+
+    #[test]
+    fn test_phase54_cs_logic_is_active() {
+        assert_eq!(CsLogic::from_bool(true), CsLogic::ActiveLow);
+        assert_eq!(CsLogic::from_bool(false), CsLogic::ActiveHigh);
+
+        // (logic, bit set, invert, expected active)
+        let cases = [
+            (CsLogic::ActiveLow, false, false, true),
+            (CsLogic::ActiveLow, true, false, false),
+            (CsLogic::ActiveLow, false, true, false),
+            (CsLogic::ActiveLow, true, true, true),
+            (CsLogic::ActiveHigh, false, false, false),
+            (CsLogic::ActiveHigh, true, false, true),
+            (CsLogic::ActiveHigh, false, true, true),
+            (CsLogic::ActiveHigh, true, true, false),
+            (CsLogic::Ignore, false, false, true),
+            (CsLogic::Ignore, true, false, true),
+            (CsLogic::Ignore, false, true, true),
+            (CsLogic::Ignore, true, true, true),
+        ];
+
+        for (logic, bit_set, invert, expected) in cases {
+            assert_eq!(
+                logic.is_active(bit_set, invert),
+                expected,
+                "{:?} bit_set={} invert={}",
+                logic,
+                bit_set,
+                invert
+            );
+        }
+
+        // The test helper agrees with the generator
+        for cs_pin in [0u8, 7, 13, 15] {
+            for active_low in [true, false] {
+                assert_eq!(is_cs_active(0, cs_pin, active_low), active_low);
+                assert_eq!(is_cs_active(1 << cs_pin, cs_pin, active_low), !active_low);
+            }
+        }
+
+        println!("✓ Phase 54 Test 152: CsLogic::is_active() for every combination");
+    }
+
+    // ========================================================================
+    // PHASE 55: Strided Locations
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 153: Even bytes of an interleaved dump extracted into a 2364
+    // ========================================================================
+
+    #[test]
+    fn test_phase55_location_stride_interleaved() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 55 interleaved",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "interleaved.rom",
+                    "type": "2364",
+                    "cs1": "active_low",
+                    "location": { "start": 0, "length": 8192, "stride": 2 }
+                }]
+            }]
+        }"#;
+
+        // Even bytes are the wanted Chip, odd bytes another
+        let even: Vec<u8> = (0..8192u32).map(|i| ((i * 13) ^ (i >> 7)) as u8).collect();
+        let interleaved: Vec<u8> = even.iter().flat_map(|&b| [b, !b]).collect();
+        assert_eq!(interleaved.len(), 16384);
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: interleaved,
+            })
+            .expect("Failed to add file");
+
+        let props = default_fw_props();
+        let (_metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+
+        for (addr, &expected) in even.iter().enumerate() {
+            assert_eq!(
+                read_rom_byte(&rom_images_buf, addr, props.board()),
+                expected,
+                "Mismatch at 0x{:04X}",
+                addr
+            );
+        }
+
+        println!("✓ Phase 55 Test 153: Even bytes of an interleaved dump extracted into a 2364");
+    }
+
+    // ========================================================================
+    // TEST 154: Overflowing and zero strides rejected
+    // ========================================================================
+
+    #[test]
+    fn test_phase55_location_stride_rejected() {
+        use onerom_gen::image::Location;
+        use onerom_gen::Error;
+
+        let source = vec![0u8; 16384];
+
+        // No stride is contiguous
+        let location = Location {
+            start: 4096,
+            length: 8192,
+            stride: None,
+        };
+        assert_eq!(
+            location.extract(0, &source).expect("Extract failed").as_ref(),
+            &source[4096..12288]
+        );
+
+        // The last odd byte of the dump is just within bounds
+        let location = Location {
+            start: 1,
+            length: 8192,
+            stride: Some(2),
+        };
+        assert_eq!(location.extract(0, &source).expect("Extract failed").len(), 8192);
+
+        // One more byte isn't
+        let location = Location {
+            start: 1,
+            length: 8193,
+            stride: Some(2),
+        };
+        let result = location.extract(0, &source);
+        assert!(
+            matches!(
+                result,
+                Err(Error::ImageTooSmall {
+                    expected: 16386,
+                    actual: 16384,
+                    ..
+                })
+            ),
+            "Expected ImageTooSmall, got {:?}",
+            result
+        );
+
+        // start + (length - 1) * stride overflows
+        let location = Location {
+            start: 1,
+            length: 3,
+            stride: Some(usize::MAX / 2 + 1),
+        };
+        let result = location.extract(3, &source);
+        assert!(
+            matches!(result, Err(Error::BadLocation { id: 3, .. })),
+            "Expected BadLocation, got {:?}",
+            result
+        );
+
+        // Zero stride
+        let location = Location {
+            start: 0,
+            length: 8192,
+            stride: Some(0),
+        };
+        let result = location.extract(0, &source);
+        assert!(
+            matches!(result, Err(Error::BadLocation { id: 0, .. })),
+            "Expected BadLocation, got {:?}",
+            result
+        );
+
+        // Zero stride is also rejected in configs
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 55 zero stride",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "interleaved.rom",
+                    "type": "2364",
+                    "cs1": "active_low",
+                    "location": { "start": 0, "length": 8192, "stride": 0 }
+                }]
+            }]
+        }"#;
+        let result = Builder::from_json(FW_VER, MCU_FAM, json);
+        assert!(
+            matches!(result, Err(Error::InvalidConfig { .. })),
+            "Expected InvalidConfig, got {:?}",
+            result.err()
+        );
+
+        println!("✓ Phase 55 Test 154: Overflowing and zero strides rejected");
+    }
+
+    // ========================================================================
+    // PHASE 56: Firmware Override Reporting
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 155: Build report lists each set's active firmware overrides
+    // ========================================================================
+
+    #[test]
+    fn test_phase56_report_active_overrides() {
+        use onerom_gen::firmware::{DebugConfig, FirmwareConfig, LedConfig};
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 56 active overrides",
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+                },
+                {
+                    "type": "single",
+                    "chips": [{ "file": "b.rom", "type": "2364", "cs1": "active_low" }],
+                    "firmware_overrides": {
+                        "led": { "enabled": false },
+                        "swd": { "swd_enabled": true }
+                    }
+                }
+            ]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        for id in 0..2 {
+            builder
+                .add_file(FileData {
+                    id,
+                    data: create_test_rom_data(8192, 0x11),
+                })
+                .expect("Failed to add file");
+        }
+
+        let (_metadata_buf, _rom_images_buf, report) = builder
+            .build_with_report(default_fw_props_060())
+            .expect("Build failed");
+        assert!(report.chip_sets[0].firmware_overrides.is_empty());
+        assert_eq!(report.chip_sets[1].firmware_overrides, vec!["led", "swd"]);
+
+        // Directly
+        let overrides = FirmwareConfig {
+            ice: None,
+            fire: None,
+            led: Some(LedConfig { enabled: true }),
+            swd: Some(DebugConfig { swd_enabled: false }),
+            serve_alg_params: None,
+            serve_alg: None,
+        };
+        assert_eq!(overrides.active_overrides(), vec!["led", "swd"]);
+
+        let overrides = FirmwareConfig {
+            led: None,
+            swd: None,
+            ..overrides
+        };
+        assert!(overrides.active_overrides().is_empty());
+
+        println!("✓ Phase 56 Test 155: Build report lists each set's active firmware overrides");
+    }
+
+    // ========================================================================
+    // PHASE 57: Building Into Caller Buffers
+    // ========================================================================
+
+    // Helper: Builder with two single sets, files loaded
+    fn build_into_builder() -> Builder {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 57 build into",
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+                },
+                {
+                    "type": "single",
+                    "chips": [{ "file": "b.rom", "type": "2332", "cs1": "active_low", "cs2": "active_high" }]
+                }
+            ]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x5A),
+            })
+            .expect("Failed to add file");
+        builder
+            .add_file(FileData {
+                id: 1,
+                data: create_test_rom_data(4096, 0xA5),
+            })
+            .expect("Failed to add file");
+        builder
+    }
+
+    // ========================================================================
+    // TEST 156: Exact fit and oversized buffers match build()
+    // ========================================================================
+
+    #[test]
+    fn test_phase57_build_into_exact_fit() {
+        let builder = build_into_builder();
+        let props = default_fw_props();
+        let (metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+
+        let (metadata_len, rom_images_len) =
+            builder.output_sizes(&props).expect("Failed to get output sizes");
+        assert_eq!(metadata_len, metadata_buf.len());
+        assert_eq!(rom_images_len, rom_images_buf.len());
+
+        // Exact fit
+        let mut metadata_out = vec![0u8; metadata_len];
+        let mut images_out = vec![0u8; rom_images_len];
+        let written = builder
+            .build_into(props, &mut metadata_out, &mut images_out)
+            .expect("Build into failed");
+        assert_eq!(written, (metadata_len, rom_images_len));
+        assert_eq!(metadata_out, metadata_buf);
+        assert_eq!(images_out, rom_images_buf);
+
+        // Oversized buffers are only written up to the sizes returned
+        let mut metadata_out = vec![0x42u8; metadata_len + 16];
+        let mut images_out = vec![0x42u8; rom_images_len + 16];
+        let written = builder
+            .build_into(props, &mut metadata_out, &mut images_out)
+            .expect("Build into failed");
+        assert_eq!(written, (metadata_len, rom_images_len));
+        assert_eq!(&metadata_out[..metadata_len], metadata_buf.as_slice());
+        assert_eq!(&images_out[..rom_images_len], rom_images_buf.as_slice());
+        assert!(metadata_out[metadata_len..].iter().all(|&b| b == 0x42));
+        assert!(images_out[rom_images_len..].iter().all(|&b| b == 0x42));
+
+        println!("✓ Phase 57 Test 156: Exact fit and oversized buffers match build()");
+    }
+
+    // ========================================================================
+    // TEST 157: Too small buffers rejected
+    // ========================================================================
+
+    #[test]
+    fn test_phase57_build_into_too_small() {
+        use onerom_gen::Error;
+
+        let builder = build_into_builder();
+        let props = default_fw_props();
+        let (metadata_len, rom_images_len) =
+            builder.output_sizes(&props).expect("Failed to get output sizes");
+
+        let mut metadata_out = vec![0u8; metadata_len - 1];
+        let mut images_out = vec![0u8; rom_images_len];
+        let result = builder.build_into(props, &mut metadata_out, &mut images_out);
+        assert!(
+            matches!(
+                result,
+                Err(Error::BufferTooSmall { expected, actual, .. })
+                    if expected == metadata_len && actual == metadata_len - 1
+            ),
+            "Expected BufferTooSmall for metadata, got {:?}",
+            result
+        );
+
+        let mut metadata_out = vec![0u8; metadata_len];
+        let mut images_out = vec![0u8; rom_images_len - 1];
+        let result = builder.build_into(props, &mut metadata_out, &mut images_out);
+        assert!(
+            matches!(
+                result,
+                Err(Error::BufferTooSmall { expected, actual, .. })
+                    if expected == rom_images_len && actual == rom_images_len - 1
+            ),
+            "Expected BufferTooSmall for images, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 57 Test 157: Too small buffers rejected");
+    }
+
+    // ========================================================================
+    // PHASE 58: Output Size Pre-flight
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 158: Output sizes match built buffers
+    // ========================================================================
+
+    #[test]
+    fn test_phase58_output_sizes_match_build() {
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 58 output sizes",
+            "labels": true,
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "kernal.bin", "label": "KERNAL", "type": "2364", "cs1": "active_low" }]
+                },
+                {
+                    "type": "multi",
+                    "chips": [
+                        { "file": "basic.bin", "type": "2364", "cs1": "active_low" },
+                        { "file": "char.bin", "type": "2332", "cs1": "active_low" }
+                    ]
+                },
+                {
+                    "type": "single",
+                    "chips": [{ "type": "6116" }]
+                }
+            ]
+        }"#;
+
+        // Boot logging (filenames) and 0.6.0 (labels, extended sets) change
+        // the metadata size
+        for props in [default_fw_props(), fw_props_with_logging(), default_fw_props_060()] {
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+            for (id, size) in [(0, 8192), (1, 8192), (2, 4096)] {
+                builder
+                    .add_file(FileData {
+                        id,
+                        data: create_test_rom_data(size, 0x33),
+                    })
+                    .expect("Failed to add file");
+            }
+
+            let sizes = builder.output_sizes(&props).expect("Failed to get output sizes");
+            let (metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+            assert_eq!(
+                sizes,
+                (metadata_buf.len(), rom_images_buf.len()),
+                "Sizes mismatch for {:?}",
+                props.version()
+            );
+
+            // The RAM set has no image
+            assert_eq!(sizes.1, 16384 + 65536);
+        }
+
+        println!("✓ Phase 58 Test 158: Output sizes match built buffers");
+    }
+
+    // ========================================================================
+    // TEST 159: Missing files rejected
+    // ========================================================================
+
+    #[test]
+    fn test_phase58_output_sizes_missing_file() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 58 missing file",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+        let builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+
+        let result = builder.output_sizes(&default_fw_props());
+        assert!(
+            matches!(result, Err(Error::MissingFile { id: 0 })),
+            "Expected MissingFile, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 58 Test 159: Missing files rejected");
+    }
+
+    // ========================================================================
+    // PHASE 59: RAM Initial Images
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 160: RAM served from an initial image, typed as RAM in metadata
+    // ========================================================================
+
+    #[test]
+    fn test_phase59_ram_initial_image() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::Metadata;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 59 RAM initial image",
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "@builtin:ramp2k", "type": "6116" }]
+                },
+                {
+                    "type": "single",
+                    "chips": [{ "type": "6116" }]
+                }
+            ]
+        }"#;
+
+        let builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        let props = default_fw_props();
+        let board = props.board();
+        let flash_base = board.mcu_family().get_flash_base();
+        let (metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+
+        // Only the initialised RAM has an image
+        assert_eq!(rom_images_buf.len(), 16384);
+        for addr in 0..2048 {
+            assert_eq!(
+                read_rom_byte(&rom_images_buf, addr, board),
+                addr as u8,
+                "Mismatch at 0x{:03X}",
+                addr
+            );
+        }
+
+        // Both are RAM in the metadata
+        let parsed =
+            Metadata::parse(&metadata_buf, flash_base, false).expect("Failed to parse metadata");
+        assert_eq!(parsed.chip_sets.len(), 2);
+        for set in &parsed.chip_sets {
+            assert_eq!(set.chips[0].chip_type, ChipType::Chip6116);
+        }
+        assert!(parsed.chip_sets[0].data_ptr.is_some());
+        assert!(parsed.chip_sets[1].data_ptr.is_none());
+
+        println!("✓ Phase 59 Test 160: RAM served from an initial image, typed as RAM in metadata");
+    }
+
+    // ========================================================================
+    // PHASE 60: Error Messages
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 161: Errors display human readable messages naming ids and sizes
+    // ========================================================================
+
+    #[test]
+    fn test_phase60_error_display() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 60 error messages",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "test.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0xAA),
+            })
+            .expect("Failed to add file");
+
+        // Errors returned by the builder
+        let err = builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0xAA),
+            })
+            .expect_err("Duplicate file should be rejected");
+        assert_eq!(err.to_string(), "File 0 has already been added");
+
+        let err = builder
+            .add_file(FileData {
+                id: 1,
+                data: create_test_rom_data(8192, 0xAA),
+            })
+            .expect_err("Out of range file should be rejected");
+        assert_eq!(
+            err.to_string(),
+            "File 1 is out of range, configuration has 1 files"
+        );
+
+        // Directly constructed errors
+        let err = Error::ImageTooSmall {
+            index: 3,
+            expected: 8192,
+            actual: 4096,
+        };
+        assert_eq!(
+            err.to_string(),
+            "ROM image at index 3 is 4096 bytes but chip expects 8192"
+        );
+
+        let err = Error::FirmwareTooOld {
+            version: FirmwareVersion::new(0, 5, 1, 0),
+            minimum: FirmwareVersion::new(0, 6, 0, 0),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Firmware version 0.5.1 is too old, at least 0.6.0 is required"
+        );
+
+        let err = Error::UnsupportedChipType {
+            chip_type: ChipType::Chip2364,
+        };
+        assert_eq!(err.to_string(), "Chip type 2364 is not supported");
+
+        // Usable as a std error
+        #[cfg(feature = "std")]
+        {
+            let boxed: Box<dyn std::error::Error> = Box::new(Error::NoChips);
+            assert_eq!(boxed.to_string(), "Chip set contains no chips");
+        }
+
+        println!("✓ Phase 60 Test 161: Errors display human readable messages naming ids and sizes");
+    }
+
+    // ========================================================================
+    // PHASE 61: Metadata CRC
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 162: Metadata CRC written for 0.6.3 firmware onwards, and detects
+    // corruption
+    // ========================================================================
+
+    #[test]
+    fn test_phase61_metadata_crc() {
+        use onerom_gen::{Error, METADATA_VERSION, MIN_METADATA_CRC_VERSION, Metadata};
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 61 metadata CRC",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "test.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+
+        let build = |version: FirmwareVersion| {
+            let mut builder =
+                Builder::from_json(version, MCU_FAM, json).expect("Failed to parse JSON");
+            builder
+                .add_file(FileData {
+                    id: 0,
+                    data: create_test_rom_data(8192, 0xAA),
+                })
+                .expect("Failed to add file");
+            let props = FirmwareProperties::new(
+                version,
+                Board::Ice24UsbH,
+                McuVariant::F411RE,
+                ServeAlg::Default,
+                false,
+            )
+            .unwrap();
+            builder.build(props).expect("Build failed").0
+        };
+        let flash_base = Board::Ice24UsbH.mcu_family().get_flash_base();
+
+        // Firmware supporting the CRC gets version 2 metadata, with a valid CRC
+        let mut metadata_buf = build(MIN_METADATA_CRC_VERSION);
+        let parsed =
+            Metadata::parse(&metadata_buf, flash_base, false).expect("Failed to parse metadata");
+        assert_eq!(parsed.version, METADATA_VERSION);
+        assert_eq!(parsed.version, 2);
+        assert!(Metadata::verify_crc(&metadata_buf).expect("Failed to verify CRC"));
+
+        // Flipping any metadata byte fails verification
+        let offset = metadata_buf.len() - 1;
+        metadata_buf[offset] ^= 0x01;
+        let result = Metadata::verify_crc(&metadata_buf);
+        assert!(
+            matches!(result, Err(Error::MetadataCrcMismatch { .. })),
+            "Expected MetadataCrcMismatch, got {:?}",
+            result
+        );
+
+        // Rewriting the CRC makes the modified metadata valid again
+        Metadata::write_crc(&mut metadata_buf).expect("Failed to write CRC");
+        assert!(Metadata::verify_crc(&metadata_buf).expect("Failed to verify CRC"));
+
+        // Older firmware gets version 1 metadata, with no CRC to verify
+        let metadata_buf = build(FW_VER);
+        let parsed =
+            Metadata::parse(&metadata_buf, flash_base, false).expect("Failed to parse metadata");
+        assert_eq!(parsed.version, 1);
+        assert!(!Metadata::verify_crc(&metadata_buf).expect("Failed to verify CRC"));
+        assert!(matches!(
+            Metadata::write_crc(&mut metadata_buf.clone()),
+            Err(Error::UnsupportedConfigVersion { version: 1 })
+        ));
+
+        println!("✓ Phase 61 Test 162: Metadata CRC written for 0.6.3 firmware onwards, and detects corruption");
+    }
+
+    // ========================================================================
+    // PHASE 62: Mixed Multi Chip Sets
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 163: Compatible mixed Chip types served, impossible mixes rejected
+    // ========================================================================
+
+    #[test]
+    fn test_phase62_mixed_multi_chip_set() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, ChipSetType, Error};
+
+        let board = Board::Ice24UsbH;
+        let version = FirmwareVersion::new(0, 6, 0, 0);
+
+        // A 2316 and a 2332 share CS1, and neither's address lines use CS1,
+        // X1 or X2
+        let images = [
+            create_test_rom_data(ChipType::Chip2316.size_bytes(), 0x16),
+            create_test_rom_data(ChipType::Chip2332.size_bytes(), 0x32),
+        ];
+        let chips = vec![
+            create_chip(0, ChipType::Chip2316, &images[0]),
+            create_chip(1, ChipType::Chip2332, &images[1]),
+        ];
+        let chip_set = ChipSet::new(0, ChipSetType::Multi, ServeAlg::Default, chips, None)
+            .expect("Failed to create Chip set");
+        chip_set
+            .validate(&board, &version)
+            .expect("2316 and 2332 should be valid together");
+
+        // Each Chip is recovered intact from the flash image
+        let flash_image = chip_set.to_flash_image(&board, false).unwrap();
+        let recovered = chip_set
+            .from_flash_image(&flash_image, &board, false)
+            .expect("Failed to reverse flash image");
+        assert_eq!(recovered.len(), 2);
+        assert_eq!(recovered[0], images[0]);
+        assert_eq!(recovered[1], images[1]);
+
+        // 24 and 28 pin Chips can't share a socket
+        let chips = vec![
+            create_chip(0, ChipType::Chip2364, &create_test_rom_data(8192, 0x64)),
+            create_chip(1, ChipType::Chip2764, &create_test_rom_data(8192, 0x27)),
+        ];
+        let result = ChipSet::new(0, ChipSetType::Multi, ServeAlg::Default, chips, None);
+        assert!(
+            matches!(result, Err(Error::IncompatibleMultiChip { id: 1, .. })),
+            "Expected IncompatibleMultiChip, got {:?}",
+            result
+        );
+
+        // A 2716 has no CS1 line, so can't be selected alongside a 2364
+        let chips = vec![
+            create_chip(0, ChipType::Chip2364, &create_test_rom_data(8192, 0x64)),
+            create_chip(1, ChipType::Chip2716, &create_test_rom_data(2048, 0x27)),
+        ];
+        let chip_set = ChipSet::new(0, ChipSetType::Multi, ServeAlg::Default, chips, None)
+            .expect("Failed to create Chip set");
+        let result = chip_set.validate(&board, &version);
+        assert!(
+            matches!(result, Err(Error::IncompatibleMultiChip { id: 1, .. })),
+            "Expected IncompatibleMultiChip, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 62 Test 163: Compatible mixed Chip types served, impossible mixes rejected");
+    }
+
+    // ========================================================================
+    // PHASE 63: Chip Image Unscrambling
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 164: Built ROM image unscrambled back to the source data
+    // ========================================================================
+
+    #[test]
+    fn test_phase63_unscramble_image() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{Chip, CsConfig};
+
+        // Same configuration and data as Phase 7 Test 1
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 63 unscramble test",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "test.rom",
+                    "type": "2364",
+                    "cs1": "active_low"
+                }]
+            }]
+        }"#;
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        let test_data: Vec<u8> = (0..8192).map(|addr| addr as u8).collect();
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: test_data.clone(),
+            })
+            .expect("Failed to add file");
+
+        let props = default_fw_props();
+        let board = props.board();
+        let (_metadata_buf, rom_images_buf) = builder.build(props).expect("Build failed");
+
+        // The Chip provides the layout only
+        let chip = Chip::placeholder(
+            0,
+            "test.rom".to_string(),
+            None,
+            &ChipType::Chip2364,
+            CsConfig::new(Some(CsLogic::ActiveLow), None, None),
+        );
+        let image_size = 16384;
+        let unscrambled = chip
+            .unscramble_image(&board, &rom_images_buf[..image_size])
+            .expect("Failed to unscramble image");
+        assert_eq!(unscrambled, test_data);
+
+        // A Chip with an image unscrambles the same way
+        let chip = create_chip(0, ChipType::Chip2364, &test_data);
+        let unscrambled = chip
+            .unscramble_image(&board, &rom_images_buf[..image_size])
+            .expect("Failed to unscramble image");
+        assert_eq!(unscrambled, test_data);
+
+        // Wrong sized physical images are rejected
+        assert!(chip.unscramble_image(&board, &rom_images_buf[..image_size - 1]).is_err());
+
+        println!("✓ Phase 63 Test 164: Built ROM image unscrambled back to the source data");
+    }
+
+    // ========================================================================
+    // PHASE 64: Out of Bounds Addresses
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 165: Addresses outside a Chip set's image return an error
+    // ========================================================================
+
+    #[test]
+    fn test_phase64_address_out_of_bounds() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::Error;
+        use onerom_gen::image::{ChipSet, ChipSetType};
+
+        // 24 pin STM32F4 board, so single Chip set images are 16KB
+        let board = Board::Ice24UsbH;
+
+        let data = create_test_rom_data(8192, 0xAA);
+        let chip = create_chip(0, ChipType::Chip2364, &data);
+        let chip_set = ChipSet::new(0, ChipSetType::Single, ServeAlg::Default, vec![chip], None)
+            .expect("Failed to create Chip set");
+
+        assert!(chip_set.get_byte(16383, &board, false).is_ok());
+        match chip_set.get_byte(16384, &board, false) {
+            Err(Error::AddressOutOfBounds { address, chip_size }) => {
+                assert_eq!(address, 16384);
+                assert_eq!(chip_size, 16384);
+            }
+            other => panic!("Expected AddressOutOfBounds, got {:?}", other),
+        }
+
+        // A 28 pin Chip paired with a 24 pin board - the top of the Chip is
+        // outside the board's image
+        let data = create_test_rom_data(65536, 0x55);
+        let chip = create_chip(0, ChipType::Chip27512, &data);
+        let chip_set = ChipSet::new(0, ChipSetType::Single, ServeAlg::Default, vec![chip], None)
+            .expect("Failed to create Chip set");
+        let result = chip_set.get_byte(0xFFFF, &board, false);
+        assert!(
+            matches!(
+                result,
+                Err(Error::AddressOutOfBounds {
+                    address: 0xFFFF,
+                    chip_size: 16384
+                })
+            ),
+            "Expected AddressOutOfBounds, got {:?}",
+            result
+        );
+
+        // Bus trace values are masked to the image, so stay in bounds
+        assert!(chip_set.serve_trace(&[0x1234], &board).is_ok());
+
+        println!("✓ Phase 64 Test 165: Addresses outside a Chip set's image return an error, not a panic");
+    }
+
+    // ========================================================================
+    // PHASE 65: Independent X1/X2 Jumper Pulls
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 166: Banks selected with X1 and X2 pulling in opposite directions
+    // ========================================================================
+
+    #[test]
+    fn test_phase65_independent_x_jumper_pulls() {
+        use onerom_gen::banked_chip_index;
+
+        const X1: u8 = 14;
+        const X2: u8 = 15;
+        let x1 = 1 << X1;
+        let x2 = 1 << X2;
+
+        // X1 pulls high, X2 pulls low - so X1 selects when high, and X2 when
+        // low
+        let banks = [(x2, 0), (x1 | x2, 1), (0, 2), (x1, 3)];
+        for (address, bank) in banks {
+            for low_bits in [0, 0x1234, 0x3FFF] {
+                assert_eq!(
+                    banked_chip_index(address | low_bits, X1, X2, 1, 0),
+                    bank,
+                    "Address {:#06X}",
+                    address | low_bits
+                );
+            }
+        }
+
+        // Matching pulls behave as before - pulled high is uninverted, pulled
+        // low inverts both lines
+        for address in [0, x1, x2, x1 | x2] {
+            let uninverted = ((address >> X1) & 1) | (((address >> X2) & 1) << 1);
+            assert_eq!(banked_chip_index(address, X1, X2, 1, 1), uninverted);
+            assert_eq!(banked_chip_index(address, X1, X2, 0, 0), 3 - uninverted);
+        }
+
+        // Each bank is selected by exactly one X1/X2 combination
+        let mut seen = [false; 4];
+        for address in [0, x1, x2, x1 | x2] {
+            let bank = banked_chip_index(address, X1, X2, 1, 0);
+            assert!(!seen[bank], "Bank {bank} selected twice");
+            seen[bank] = true;
+        }
+
+        // Board pulls are used for banked serving
+        let board = Board::Ice24UsbH;
+        assert_eq!(
+            banked_chip_index(
+                0,
+                board.bit_x1(),
+                board.bit_x2(),
+                board.x1_jumper_pull(),
+                board.x2_jumper_pull()
+            ),
+            3
+        );
+
+        println!("✓ Phase 65 Test 166: Banks selected with X1 and X2 pulling in opposite directions");
+    }
+
+    // ========================================================================
+    // PHASE 66: Serve Algorithm Parameter Validation
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 167: serve_alg_params checked against the serving algorithm
+    // ========================================================================
+
+    #[test]
+    fn test_phase66_serve_alg_param_count() {
+        use onerom_gen::Error;
+
+        fn build(serve_alg: &str, num_params: usize) -> Result<(), Error> {
+            let params = vec!["1"; num_params].join(", ");
+            let json = format!(
+                r#"{{
+                "version": 1,
+                "description": "Phase 66 serve_alg_params test",
+                "chip_sets": [{{
+                    "type": "single",
+                    "serve_alg": "{serve_alg}",
+                    "chips": [{{
+                        "file": "test.rom",
+                        "type": "2332",
+                        "cs1": "active_low",
+                        "cs2": "active_low"
+                    }}],
+                    "firmware_overrides": {{
+                        "serve_alg_params": {{
+                            "params": [{params}]
+                        }}
+                    }}
+                }}]
+            }}"#
+            );
+
+            let mut builder = Builder::from_json(FW_VER, MCU_FAM, &json)?;
+            builder.add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(4096, 0xAA),
+            })?;
+            builder.build(default_fw_props()).map(|_| ())
+        }
+
+        assert_eq!(ServeAlg::Default.expected_param_count(), Some(1..=ServeAlg::MAX_PARAMS));
+        assert_eq!(ServeAlg::AddrOnCs.expected_param_count(), Some(1..=ServeAlg::MAX_PARAMS));
+
+        // Matching param sets - the PIO firmware applies them whatever the
+        // serving algorithm
+        build("default", 5).expect("5 params should be accepted");
+        build("default", ServeAlg::MAX_PARAMS).expect("64 params should be accepted");
+        for serve_alg in ["two_cs_one_addr", "addr_on_cs"] {
+            build(serve_alg, 5)
+                .unwrap_or_else(|e| panic!("5 params should be accepted for {serve_alg}: {e:?}"));
+        }
+
+        // Mismatching param sets
+        match build("default", ServeAlg::MAX_PARAMS + 1) {
+            Err(Error::InvalidConfig { error }) => {
+                assert!(error.contains("Default"), "{error}");
+                assert!(error.contains("65"), "{error}");
+            }
+            other => panic!("Expected InvalidConfig, got {:?}", other),
+        }
+        match build("addr_on_cs", ServeAlg::MAX_PARAMS + 1) {
+            Err(Error::InvalidConfig { error }) => {
+                assert!(error.contains("AddrOnCs"), "{error}");
+                assert!(error.contains("65"), "{error}");
+            }
+            other => panic!("Expected InvalidConfig, got {:?}", other),
+        }
+
+        println!("✓ Phase 66 Test 167: serve_alg_params checked against the serving algorithm");
+    }
+
+    // ========================================================================
+    // PHASE 67: Config Layering
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 168: Overlay config merged onto a shared base config
+    // ========================================================================
+
+    #[test]
+    fn test_phase67_config_merge() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{Config, Error};
+
+        let base = r#"{
+            "version": 1,
+            "name": "Shared base",
+            "description": "Base config",
+            "categories": ["base"],
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "kernal.rom", "type": "2364", "cs1": "active_low" }]
+                },
+                {
+                    "type": "single",
+                    "chips": [{ "file": "basic.rom", "type": "2364", "cs1": "active_low" }]
+                }
+            ]
+        }"#;
+
+        // Redefines set 1, inheriting set 0
+        let overlay = r#"{
+            "description": "Overlay config",
+            "categories": ["overlay"],
+            "chip_sets": [
+                null,
+                {
+                    "type": "single",
+                    "chips": [{
+                        "file": "char.rom",
+                        "type": "2332",
+                        "cs1": "active_low",
+                        "cs2": "active_high"
+                    }]
+                }
+            ]
+        }"#;
+
+        let config = Config::merge_json(base, overlay).expect("Failed to merge configs");
+        assert_eq!(config.version, 1);
+        assert_eq!(config.name.as_deref(), Some("Shared base"));
+        assert_eq!(config.description, "Overlay config");
+        assert_eq!(config.categories, Some(vec!["overlay".to_string()]));
+        assert_eq!(config.chip_sets.len(), 2);
+        assert_eq!(config.chip_sets[0].chips[0].file, "kernal.rom");
+        assert_eq!(config.chip_sets[0].chips[0].chip_type, ChipType::Chip2364);
+        assert_eq!(config.chip_sets[1].chips[0].file, "char.rom");
+        assert_eq!(config.chip_sets[1].chips[0].chip_type, ChipType::Chip2332);
+
+        // The combined config builds
+        let mut builder = Builder::from_json_with_base(FW_VER, MCU_FAM, base, overlay)
+            .expect("Failed to create builder from merged config");
+        assert_eq!(builder.config().chip_sets.len(), 2);
+        for (id, size) in [(0, 8192), (1, 4096)] {
+            builder
+                .add_file(FileData {
+                    id,
+                    data: create_test_rom_data(size, id as u8),
+                })
+                .expect("Failed to add file");
+        }
+        builder.build(default_fw_props()).expect("Merged config build failed");
+
+        // Overlay sets beyond the base's are appended, and null clears an
+        // optional field
+        let overlay = r#"{
+            "name": null,
+            "chip_sets": [
+                null,
+                null,
+                {
+                    "type": "single",
+                    "chips": [{ "file": "extra.rom", "type": "2364", "cs1": "active_low" }]
+                }
+            ]
+        }"#;
+        let config = Config::merge_json(base, overlay).expect("Failed to merge configs");
+        assert_eq!(config.name, None);
+        assert_eq!(config.description, "Base config");
+        assert_eq!(config.chip_sets.len(), 3);
+        assert_eq!(config.chip_sets[1].chips[0].file, "basic.rom");
+        assert_eq!(config.chip_sets[2].chips[0].file, "extra.rom");
+
+        // A null set with nothing to inherit is rejected
+        let overlay = r#"{ "chip_sets": [null, null, null] }"#;
+        assert!(matches!(
+            Config::merge_json(base, overlay),
+            Err(Error::InvalidConfig { .. })
+        ));
+
+        println!("✓ Phase 67 Test 168: Overlay config merged onto a shared base config");
+    }
+
+    // ========================================================================
+    // PHASE 68: File Spec Sizes
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 169: File specs report the sizes each size handling accepts
+    // ========================================================================
+
+    #[test]
+    fn test_phase68_file_spec_sizes() {
+        fn create_builder(size_handling: &str) -> Builder {
+            let json = format!(
+                r#"{{
+                "version": 1,
+                "description": "Phase 68 file spec size test",
+                "chip_sets": [{{
+                    "type": "single",
+                    "chips": [{{
+                        "file": "test.rom",
+                        "type": "2364",
+                        "cs1": "active_low",
+                        "size_handling": "{size_handling}"
+                    }}]
+                }}]
+            }}"#
+            );
+            Builder::from_json(FW_VER, MCU_FAM, &json).expect("Failed to parse JSON")
+        }
+
+        // Expected size, then whether each file size is accepted
+        let sizes = [0, 2048, 3000, 4096, 8192, 16384];
+        let cases = [
+            ("none", 8192, [false, false, false, false, true, false]),
+            ("duplicate", 8192, [false, true, false, true, false, false]),
+            ("truncate", 8192, [false, false, false, false, false, true]),
+            ("pad", 8192, [true, true, true, true, false, false]),
+            ("mirror", 4096, [false, false, false, true, false, false]),
+        ];
+
+        for (size_handling, expected_size, accepted) in cases {
+            let specs = create_builder(size_handling).file_specs();
+            assert_eq!(specs.len(), 1);
+            let spec = &specs[0];
+            assert_eq!(spec.expected_size(), expected_size, "{size_handling}");
+
+            for (&size, &accepted) in sizes.iter().zip(accepted.iter()) {
+                assert_eq!(
+                    spec.accepts_size(size),
+                    accepted,
+                    "{size_handling} with file size {size}"
+                );
+
+                // The builder agrees
+                if size > 0 {
+                    let mut builder = create_builder(size_handling);
+                    builder
+                        .add_file(FileData {
+                            id: spec.id,
+                            data: create_test_rom_data(size, 0xAA),
+                        })
+                        .expect("Failed to add file");
+                    assert_eq!(
+                        builder.build(default_fw_props()).is_ok(),
+                        accepted,
+                        "Builder disagrees for {size_handling} with file size {size}"
+                    );
+                }
+            }
+        }
+
+        println!("✓ Phase 68 Test 169: File specs report the sizes each size handling accepts");
+    }
+
+    // ========================================================================
+    // PHASE 69: Build Report Data CRCs
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 170: Chip set data CRCs reported, and the same across boards
+    // ========================================================================
+
+    #[test]
+    fn test_phase69_report_data_crc() {
+        use onerom_gen::crc32;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 69 data CRC test",
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "kernal.rom", "type": "2364", "cs1": "active_low" }]
+                },
+                {
+                    "type": "banked",
+                    "chips": [
+                        { "file": "bank0.rom", "type": "2364", "cs1": "active_low" },
+                        { "file": "bank1.rom", "type": "2364", "cs1": "active_low" }
+                    ]
+                }
+            ]
+        }"#;
+        let files: Vec<Vec<u8>> = (0..3)
+            .map(|id| (0..8192).map(|addr| (addr as u8) ^ (id * 0x55)).collect())
+            .collect();
+
+        let mut reports = Vec::new();
+        let mut images = Vec::new();
+        for (board, mcu_variant) in [
+            (Board::Ice24G, McuVariant::F411RE),
+            (Board::Ice24UsbH, McuVariant::F411RE),
+            (Board::Fire24UsbB, McuVariant::RP2350),
+        ] {
+            let mut builder = Builder::from_json(FW_VER, board.mcu_family(), json)
+                .expect("Failed to parse JSON");
+            for (id, data) in files.iter().enumerate() {
+                builder
+                    .add_file(FileData {
+                        id,
+                        data: data.clone(),
+                    })
+                    .expect("Failed to add file");
+            }
+
+            let props =
+                FirmwareProperties::new(FW_VER, board, mcu_variant, ServeAlg::Default, false)
+                    .unwrap();
+            let (_metadata_buf, rom_images_buf, report) =
+                builder.build_with_report(props).expect("Build failed");
+            reports.push(report);
+            images.push(rom_images_buf);
+        }
+
+        // CRCs are over the logical data - each Chip in turn for banked sets
+        let crcs: Vec<Option<u32>> =
+            reports[0].chip_sets.iter().map(|set| set.data_crc32).collect();
+        assert_eq!(crcs[0], Some(crc32(&files[0])));
+        assert_eq!(crcs[1], Some(crc32(&[files[1].clone(), files[2].clone()].concat())));
+        assert_ne!(crcs[0], crcs[1]);
+
+        // The boards arrange the images differently, but report the same CRCs
+        assert_ne!(images[1], images[2]);
+        for report in &reports[1..] {
+            let other: Vec<Option<u32>> = report.chip_sets.iter().map(|set| set.data_crc32).collect();
+            assert_eq!(other, crcs);
+        }
+
+        println!("✓ Phase 69 Test 170: Chip set data CRCs reported, and the same across boards");
+    }
+
+    // ========================================================================
+    // PHASE 70: File Manifests
+    // ========================================================================
+
+    // ========================================================================
+    // TEST 171: Concatenated file split between Chips by a manifest
+    // ========================================================================
+
+    #[test]
+    fn test_phase70_file_manifest() {
+        use onerom_gen::{Error, FileManifest, ManifestEntry, crc32};
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 70 manifest test",
+            "chip_sets": [
+                { "type": "single", "chips": [{ "file": "roms.bin", "type": "2364", "cs1": "active_low" }] },
+                { "type": "single", "chips": [{ "file": "roms.bin", "type": "2364", "cs1": "active_low" }] },
+                { "type": "single", "chips": [{ "file": "roms.bin", "type": "2364", "cs1": "active_low" }] }
+            ]
+        }"#;
+
+        // One 24KB blob containing three 8KB ROMs
+        let blob: Vec<u8> = (0..3)
+            .flat_map(|rom| create_test_rom_data(8192, 0x11 * (rom + 1)))
+            .collect();
+        let manifest = |ranges: &[(usize, usize)]| FileManifest {
+            entries: ranges
+                .iter()
+                .enumerate()
+                .map(|(chip_index, &(offset, length))| ManifestEntry {
+                    chip_index,
+                    offset,
+                    length,
+                })
+                .collect(),
+            allow_overlap: false,
+        };
+        let file = || FileData {
+            id: 0,
+            data: blob.clone(),
+        };
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        assert_eq!(builder.file_specs().len(), 1);
+        builder
+            .add_file_with_manifest(file(), &manifest(&[(0, 8192), (8192, 8192), (16384, 8192)]))
+            .expect("Failed to add file with manifest");
+        let (_metadata_buf, _rom_images_buf, report) =
+            builder.build_with_report(default_fw_props()).expect("Build failed");
+        for (set, rom) in report.chip_sets.iter().zip(blob.chunks(8192)) {
+            assert_eq!(set.data_crc32, Some(crc32(rom)));
+        }
+
+        // Ranges beyond the end of the file are rejected
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        let result = builder.add_file_with_manifest(
+            file(),
+            &manifest(&[(0, 8192), (8192, 8192), (16385, 8192)]),
+        );
+        assert!(matches!(result, Err(Error::BadLocation { id: 2, .. })), "{result:?}");
+
+        // Overlapping ranges are rejected, unless allowed
+        let overlapping = manifest(&[(0, 8192), (4096, 8192), (16384, 8192)]);
+        let result = builder.add_file_with_manifest(file(), &overlapping);
+        assert!(matches!(result, Err(Error::BadLocation { id: 1, .. })), "{result:?}");
+        let overlapping = FileManifest {
+            allow_overlap: true,
+            ..overlapping
+        };
+        builder
+            .add_file_with_manifest(file(), &overlapping)
+            .expect("Failed to add file with overlapping manifest");
+        builder.build(default_fw_props()).expect("Build failed");
+
+        println!("✓ Phase 70 Test 171: Concatenated file split between Chips by a manifest");
+    }
+
+    // ============================================================================
+    // PHASE 71: Board Pin Map Tables
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 172: Board pin map collects address, data, X and Chip control lines
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase71_pin_map_table() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::BoardPinMap;
+
+        let board = Board::Ice24UsbH;
+        let table = board.pin_map_table();
+        assert_eq!(table.board, board);
+
+        // Address and data lines follow the board's schematic order
+        assert_eq!(table.addr.len(), board.addr_pins().len());
+        assert_eq!(table.addr[0].name, "A0");
+        assert_eq!(table.addr[0].gpio, board.addr_pins()[0]);
+        assert_eq!(table.data.len(), 8);
+        for (line, mapping) in table.data.iter().enumerate() {
+            assert_eq!(mapping.gpio, board.data_pins()[line]);
+            assert_eq!(mapping.bit, mapping.gpio);
+        }
+
+        // X lines
+        let x_names: Vec<&str> = table.x.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(x_names, vec!["X1", "X2"]);
+        assert_eq!(table.x[0].gpio, board.pin_x1());
+        assert_eq!(table.x[0].bit, board.bit_x1());
+
+        // Every supported Chip type is listed
+        assert_eq!(table.chip_types.len(), board.supported_chip_types().len());
+        let chip_map = |chip_type: ChipType| {
+            table
+                .chip_types
+                .iter()
+                .find(|c| c.chip_type == chip_type)
+                .expect("Chip type missing from pin map")
+        };
+
+        // 2364 uses CS1 only, and doesn't move any address lines
+        let map_2364 = chip_map(ChipType::Chip2364);
+        assert_eq!(map_2364.control.len(), 1);
+        assert_eq!(map_2364.control[0].name, "CS1");
+        assert_eq!(map_2364.control[0].gpio, board.pin_cs1(ChipType::Chip2364));
+        assert!(map_2364.addr_overrides.is_empty());
+
+        // 2732 swaps A11 and A12 relative to the board layout
+        let map_2732 = chip_map(ChipType::Chip2732);
+        let names: Vec<&str> = map_2732
+            .addr_overrides
+            .iter()
+            .map(|m| m.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["A11", "A12"]);
+        assert_eq!(map_2732.addr_overrides[0].bit, table.addr[12].bit);
+        assert_eq!(map_2732.addr_overrides[1].bit, table.addr[11].bit);
+
+        // Data lines on GPIOs 16-23 still occupy bits 0-7 of the served byte
+        let fire = Board::Fire24A.pin_map_table();
+        assert_eq!(fire.data[0].gpio, 16);
+        assert_eq!(fire.data[0].bit, 0);
+        assert_eq!(fire.data[7].bit, 7);
+
+        // Displays as a table
+        let text = table.to_string();
+        assert!(text.contains(&format!("Board: {}", board.name())));
+        assert!(text.contains("A12"));
+        assert!(text.contains("overrides board layout"));
+
+        println!("✓ Phase 71 Test 172: Board pin map collects address, data, X and Chip control lines");
+    }
+
+    // ============================================================================
+    // PHASE 72: Chip Data Lines
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 173: Chip types needing more data lines than the board has rejected
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase72_data_lines() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, ChipSetType, Error};
+
+        assert_eq!(ChipType::Chip2364.num_data_lines(), 8);
+        assert_eq!(ChipType::Chip27C400.num_data_lines(), 16);
+
+        let single = |chip_type: ChipType, data: &[u8]| {
+            ChipSet::new(
+                0,
+                ChipSetType::Single,
+                ServeAlg::Default,
+                vec![create_chip(0, chip_type, data)],
+                None,
+            )
+            .expect("Failed to create Chip set")
+        };
+
+        // 8-bit Chip on an 8 data line board
+        let board = Board::Ice24UsbH;
+        assert_eq!(board.data_pins().len(), 8);
+        let chip_set = single(ChipType::Chip2364, &create_test_rom_data(8192, 0xAA));
+        chip_set
+            .validate(&board, &FW_VER)
+            .expect("8-bit Chip set should validate on an 8 data line board");
+
+        // 16-bit Chip on a 16 data line board
+        let rom_data = create_16_bit_rom_data(ChipType::Chip27C400.size_bytes());
+        let chip_set = single(ChipType::Chip27C400, &rom_data);
+        assert_eq!(Board::Fire40A.data_pins().len(), 16);
+        chip_set
+            .validate(&Board::Fire40A, &FW_VER)
+            .expect("16-bit Chip set should validate on a 16 data line board");
+
+        // 16-bit Chip on an 8 data line board is a clear error, rather than a
+        // failure when mangling the image
+        let result = chip_set.validate(&board, &FW_VER);
+        match &result {
+            Err(Error::TooFewDataLines {
+                chip_type,
+                required,
+                available,
+            }) => {
+                assert_eq!(*chip_type, ChipType::Chip27C400);
+                assert_eq!(*required, 16);
+                assert_eq!(*available, 8);
+            }
+            other => panic!("Expected TooFewDataLines, got {:?}", other),
+        }
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("27C400"), "{message}");
+        assert!(message.contains("16 data lines"), "{message}");
+
+        println!("✓ Phase 72 Test 173: Chip types needing more data lines than the board has rejected");
+    }
+
+    // ============================================================================
+    // PHASE 73: Removing and Replacing Files
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 174: Replaced file changes the built image, and a removed file is
+    // missing
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase73_remove_replace_file() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 73 remove and replace files",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{
+                    "file": "test.rom",
+                    "type": "2364",
+                    "cs1": "active_low"
+                }]
+            }]
+        }"#;
+        let file = |fill_byte| FileData {
+            id: 0,
+            data: create_test_rom_data(8192, fill_byte),
+        };
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+
+        // Neither works before the file has been added
+        let result = builder.replace_file(file(0xBB));
+        assert!(matches!(result, Err(Error::MissingFile { id: 0 })), "{result:?}");
+        let result = builder.remove_file(0);
+        assert!(matches!(result, Err(Error::MissingFile { id: 0 })), "{result:?}");
+
+        builder.add_file(file(0xAA)).expect("Failed to add file");
+        let (_metadata_buf, original_images) =
+            builder.build(default_fw_props()).expect("Build failed");
+
+        // Replacing the file changes the built image
+        builder.replace_file(file(0xBB)).expect("Failed to replace file");
+        let (_metadata_buf, replaced_images) =
+            builder.build(default_fw_props()).expect("Build failed");
+        assert_ne!(original_images, replaced_images);
+
+        // Removing it leaves the file missing at build time
+        builder.remove_file(0).expect("Failed to remove file");
+        let result = builder.build(default_fw_props());
+        assert!(matches!(result, Err(Error::MissingFile { id: 0 })), "{result:?}");
+
+        // ... and it can then be added again
+        builder.add_file(file(0xAA)).expect("Failed to re-add file");
+        let (_metadata_buf, readded_images) =
+            builder.build(default_fw_props()).expect("Build failed");
+        assert_eq!(original_images, readded_images);
+
+        println!("✓ Phase 73 Test 174: Replaced file changes the built image, and a removed file is missing");
+    }
+
+    // ============================================================================
+    // PHASE 74: Chip Set Descriptions
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 175: Single, banked and multi Chip sets described in one line
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase74_describe_chip_sets() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{Chip, ChipSet, ChipSetType, CsConfig, SizeHandling};
+
+        let rom_data = |chip_type: ChipType| create_test_rom_data(chip_type.size_bytes(), 0xAA);
+        let chips = |chip_types: &[ChipType]| -> Vec<Chip> {
+            chip_types
+                .iter()
+                .enumerate()
+                .map(|(index, &chip_type)| create_chip(index, chip_type, &rom_data(chip_type)))
+                .collect()
+        };
+
+        // Single Chip, CS1 only
+        let chip_set = ChipSet::new(
+            0,
+            ChipSetType::Single,
+            ServeAlg::Default,
+            chips(&[ChipType::Chip2364]),
+            None,
+        )
+        .expect("Failed to create single Chip set");
+        assert_eq!(
+            chip_set.describe(),
+            "Single set #0: 2364, CS1 active low, Default"
+        );
+
+        // Single Chip with further CS lines
+        let chip = Chip::from_raw_rom_image(
+            0,
+            "chip0.rom".to_string(),
+            None,
+            Some(&rom_data(ChipType::Chip2332)),
+            vec![0u8; ChipType::Chip2332.size_bytes()],
+            &ChipType::Chip2332,
+            CsConfig::new(Some(CsLogic::ActiveLow), Some(CsLogic::ActiveHigh), None),
+            &SizeHandling::None,
+            None,
+            None,
+            false,
+        )
+        .expect("Failed to create Chip");
+        let chip_set = ChipSet::new(3, ChipSetType::Single, ServeAlg::Default, vec![chip], None)
+            .expect("Failed to create single Chip set");
+        assert_eq!(
+            chip_set.describe(),
+            "Single set #3: 2332, CS1 active low, CS2 active high, Default"
+        );
+
+        // Banked Chips of the same type are counted
+        let chip_set = ChipSet::new(
+            1,
+            ChipSetType::Banked,
+            ServeAlg::AddrOnCs,
+            chips(&[ChipType::Chip2364; 4]),
+            None,
+        )
+        .expect("Failed to create banked Chip set");
+        assert_eq!(
+            chip_set.describe(),
+            "Banked set #1: 4× 2364, CS1 active low, AddrOnCs"
+        );
+
+        // Multi Chips of differing types are listed
+        let chip_set = ChipSet::new(
+            2,
+            ChipSetType::Multi,
+            ServeAlg::Default,
+            chips(&[ChipType::Chip2316, ChipType::Chip2332]),
+            None,
+        )
+        .expect("Failed to create multi Chip set");
+        assert_eq!(
+            chip_set.describe(),
+            "Multi set #2: 2316+2332, CS1 active low, AddrOnAnyCs"
+        );
+
+        println!("✓ Phase 74 Test 175: Single, banked and multi Chip sets described in one line");
+    }
+
+    // ============================================================================
+    // PHASE 75: Explicit CE/OE Chips
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 176: CE/OE Chips marked in metadata, distinct from CS1 active low
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase75_ce_oe_metadata() {
+        use onerom_gen::{Error, Metadata};
+
+        const CS_CE_OE: u8 = 3;
+
+        let json_for = |chip: &str| {
+            format!(
+                r#"{{
+                    "version": 1,
+                    "description": "Phase 75 CE/OE",
+                    "chip_sets": [{{ "type": "single", "chips": [{chip}] }}]
+                }}"#
+            )
+        };
+
+        // Builds a single Chip config, returning the raw Chip metadata and the
+        // parsed Chip
+        let build = |chip: &str, size: usize| {
+            let mut builder = Builder::from_json(FW_VER, MCU_FAM, &json_for(chip))
+                .expect("Failed to parse JSON");
+            builder
+                .add_file(FileData {
+                    id: 0,
+                    data: create_test_rom_data(size, 0xAA),
+                })
+                .expect("Failed to add file");
+            let props = default_fw_props();
+            let flash_base = props.board().mcu_family().get_flash_base();
+            let metadata_flash_start = flash_base + METADATA_FLASH_OFFSET;
+            let (metadata_buf, _rom_images_buf) = builder.build(props).expect("Build failed");
+
+            let header = MetadataHeader::parse(&metadata_buf);
+            let chip_set_offset = (header.chip_sets_ptr - metadata_flash_start) as usize;
+            let chip_set = RomSetStruct::parse(&metadata_buf, chip_set_offset);
+            let rom_array_offset = (chip_set.chips_ptr - metadata_flash_start) as usize;
+            let rom_info_ptr = u32::from_le_bytes(
+                metadata_buf[rom_array_offset..rom_array_offset + 4]
+                    .try_into()
+                    .unwrap(),
+            );
+            let rom_info =
+                RomInfoStruct::parse(&metadata_buf, (rom_info_ptr - metadata_flash_start) as usize);
+
+            let parsed = Metadata::parse(&metadata_buf, flash_base, false)
+                .expect("Failed to parse metadata");
+            let parsed_chip = parsed.chip_sets[0].chips[0].clone();
+            (
+                [rom_info.cs1_state, rom_info.cs2_state, rom_info.cs3_state],
+                parsed_chip,
+            )
+        };
+
+        // Explicit CE/OE - CE and OE active low, marked as CE/OE in CS3
+        let (cs_states, parsed) = build(
+            r#"{ "file": "a.rom", "type": "2716", "cs": "ce_oe" }"#,
+            2048,
+        );
+        assert_eq!(
+            cs_states,
+            [
+                CsLogic::ActiveLow.c_enum_val(),
+                CsLogic::ActiveLow.c_enum_val(),
+                CS_CE_OE
+            ]
+        );
+        assert!(parsed.ce_oe);
+        assert_eq!(parsed.cs1, CsLogic::ActiveLow);
+        assert_eq!(parsed.cs3, None);
+
+        // Implicit CE/OE, with no CS lines given, is the same
+        let (implicit_cs_states, _parsed) = build(r#"{ "file": "a.rom", "type": "2716" }"#, 2048);
+        assert_eq!(implicit_cs_states, cs_states);
+
+        // A CS1 active low Chip is not mistaken for CE/OE
+        let (cs_states, parsed) = build(
+            r#"{ "file": "a.rom", "type": "2364", "cs1": "active_low" }"#,
+            8192,
+        );
+        assert_eq!(cs_states[2], CsLogic::Ignore.c_enum_val());
+        assert!(!parsed.ce_oe);
+
+        // CE/OE can't be requested for CS Chip types, or combined with CS
+        // lines
+        for chip in [
+            r#"{ "file": "a.rom", "type": "2364", "cs": "ce_oe" }"#,
+            r#"{ "file": "a.rom", "type": "2716", "cs": "ce_oe", "cs1": "active_low" }"#,
+        ] {
+            let result = Builder::from_json(FW_VER, MCU_FAM, &json_for(chip));
+            assert!(
+                matches!(result, Err(Error::InvalidConfig { .. })),
+                "Expected InvalidConfig for {chip}, got {:?}",
+                result.err()
+            );
+        }
+
+        println!("✓ Phase 75 Test 176: CE/OE Chips marked in metadata, distinct from CS1 active low");
+    }
+
+    // ============================================================================
+    // PHASE 76: Board Multi Set Limits
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 177: Multi sets larger than the board can select rejected cleanly
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase76_board_multi_set_limits() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, Error};
+
+        // A board with CS1, X1 and X2 selects up to 3 Chips
+        let board = Board::Ice24UsbH;
+        assert_eq!(board.max_chips_in_multi_set(), 3);
+        let chip_set = ChipSet::diagnostic_multi(0, &ChipType::Chip2364, 3, CsLogic::ActiveLow)
+            .expect("3 Chip multi set should be accepted");
+        chip_set
+            .validate_multi_addressing(&board)
+            .expect("3 Chip multi set should validate");
+
+        // ... so 4 Chips are rejected up front
+        let result = ChipSet::diagnostic_multi(0, &ChipType::Chip2364, 4, CsLogic::ActiveLow);
+        assert!(
+            matches!(
+                result,
+                Err(Error::TooManyChips {
+                    expected: 3,
+                    actual: 4
+                })
+            ),
+            "Expected TooManyChips error, got {:?}",
+            result
+        );
+
+        // A board without X lines can't select a second Chip
+        let board = Board::Ice24D;
+        assert_eq!(board.max_chips_in_multi_set(), 1);
+        let chip_set = ChipSet::diagnostic_multi(0, &ChipType::Chip2364, 2, CsLogic::ActiveLow)
+            .expect("Failed to create Chip set");
+        let result = chip_set.validate_multi_addressing(&board);
+        assert!(
+            matches!(
+                result,
+                Err(Error::TooManyChips {
+                    expected: 1,
+                    actual: 2
+                })
+            ),
+            "Expected TooManyChips error, got {:?}",
+            result
+        );
+
+        // The same error is returned when building, rather than a panic
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 76 multi set on a board without X lines",
+            "chip_sets": [{
+                "type": "multi",
+                "chips": [
+                    { "file": "a.rom", "type": "2364", "cs1": "active_low" },
+                    { "file": "b.rom", "type": "2364", "cs1": "active_low" }
+                ]
+            }]
+        }"#;
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        for id in 0..2 {
+            builder
+                .add_file(FileData {
+                    id,
+                    data: create_test_rom_data(8192, id as u8),
+                })
+                .expect("Failed to add file");
+        }
+        let props =
+            FirmwareProperties::new(FW_VER, board, McuVariant::F411RE, ServeAlg::Default, false)
+                .unwrap();
+        let result = builder.build(props);
+        assert!(
+            matches!(
+                result,
+                Err(Error::TooManyChips {
+                    expected: 1,
+                    actual: 2
+                })
+            ),
+            "Expected TooManyChips error, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 76 Test 177: Multi sets larger than the board can select rejected cleanly");
+    }
+
+    // ============================================================================
+    // PHASE 77: Serving Algorithm Precedence
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 178: Overrides, then config, then default serving algorithm used,
+    // with multi sets coerced
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase77_serve_alg_precedence() {
+        use onerom_gen::builder::FirmwareConfig;
+        use onerom_gen::{ChipSet, ChipSetType, Error};
+
+        let overrides = FirmwareConfig {
+            ice: None,
+            fire: None,
+            led: None,
+            swd: None,
+            serve_alg_params: None,
+            serve_alg: Some(ServeAlg::TwoCsOneAddr),
+        };
+        let single = ChipSetType::Single;
+
+        // Nothing specified - the default is used
+        let serve_alg = ChipSet::resolve_serve_alg(&single, None, None, ServeAlg::Default);
+        assert_eq!(serve_alg.unwrap(), ServeAlg::Default);
+
+        // Config beats the default
+        let serve_alg =
+            ChipSet::resolve_serve_alg(&single, None, Some(ServeAlg::AddrOnCs), ServeAlg::Default);
+        assert_eq!(serve_alg.unwrap(), ServeAlg::AddrOnCs);
+
+        // Overrides beat the config
+        let serve_alg = ChipSet::resolve_serve_alg(
+            &single,
+            Some(&overrides),
+            Some(ServeAlg::AddrOnCs),
+            ServeAlg::Default,
+        );
+        assert_eq!(serve_alg.unwrap(), ServeAlg::TwoCsOneAddr);
+
+        // Multi sets are always coerced, whatever was specified
+        let multi_overrides = FirmwareConfig {
+            serve_alg: Some(ServeAlg::AddrOnCs),
+            ..overrides.clone()
+        };
+        let serve_alg = ChipSet::resolve_serve_alg(
+            &ChipSetType::Multi,
+            Some(&multi_overrides),
+            Some(ServeAlg::TwoCsOneAddr),
+            ServeAlg::Default,
+        );
+        assert_eq!(serve_alg.unwrap(), ServeAlg::AddrOnAnyCs);
+
+        // An impossible algorithm is rejected, even if the config's is fine
+        let bad_overrides = FirmwareConfig {
+            serve_alg: Some(ServeAlg::AddrOnAnyCs),
+            ..overrides.clone()
+        };
+        let result = ChipSet::resolve_serve_alg(
+            &ChipSetType::Banked,
+            Some(&bad_overrides),
+            Some(ServeAlg::AddrOnCs),
+            ServeAlg::Default,
+        );
+        assert!(
+            matches!(
+                result,
+                Err(Error::InvalidServeAlg {
+                    serve_alg: ServeAlg::AddrOnAnyCs,
+                    ..
+                })
+            ),
+            "Expected InvalidServeAlg error, got {:?}",
+            result
+        );
+
+        // ChipSet::new() applies the overrides' algorithm too
+        let chip_type = onerom_config::chip::ChipType::Chip2332;
+        let chips = vec![create_chip(0, chip_type, &create_test_rom_data(4096, 0x11))];
+        let chip_set = ChipSet::new(0, single, ServeAlg::AddrOnCs, chips, Some(overrides))
+            .expect("Failed to create Chip set");
+        assert_eq!(chip_set.serve_alg(), ServeAlg::TwoCsOneAddr);
+
+        // Building rejects an impossible overrides algorithm cleanly
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 77 impossible serving algorithm override",
+            "chip_sets": [{
+                "type": "single",
+                "serve_alg": "addr_on_cs",
+                "firmware_overrides": { "serve_alg": "addr_on_any_cs" },
+                "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+        let props = default_fw_props();
+        let result = Builder::from_json(FW_VER, MCU_FAM, json).and_then(|mut builder| {
+            builder.add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x22),
+            })?;
+            builder.build(props).map(|_| ())
+        });
+        assert!(
+            matches!(
+                result,
+                Err(Error::InvalidServeAlg {
+                    serve_alg: ServeAlg::AddrOnAnyCs,
+                    ..
+                })
+            ),
+            "Expected InvalidServeAlg error, got {:?}",
+            result
+        );
+
+        println!("✓ Phase 77 Test 178: Overrides, then config, then default serving algorithm used, with multi sets coerced");
+    }
+
+    // ============================================================================
+    // PHASE 78: Flash Capacity
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 179: ROM images exceeding the MCU's flash rejected, and fitting ones
+    // built
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase78_rom_images_exceed_flash() {
+        use onerom_gen::{Error, FIRMWARE_SIZE, MAX_METADATA_LEN};
+
+        // Each single 24 pin set takes a 16KB image on STM32F4
+        let build = |num_sets: usize, mcu: McuVariant| {
+            let chip_sets = vec![
+                r#"{ "type": "single", "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }] }"#;
+                num_sets
+            ];
+            let json = format!(
+                r#"{{ "version": 1, "description": "Phase 78 flash capacity", "chip_sets": [{}] }}"#,
+                chip_sets.join(", ")
+            );
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, &json).expect("Failed to parse JSON");
+            for id in 0..num_sets {
+                builder
+                    .add_file(FileData {
+                        id,
+                        data: create_test_rom_data(8192, id as u8),
+                    })
+                    .expect("Failed to add file");
+            }
+            let props =
+                FirmwareProperties::new(FW_VER, Board::Ice24UsbH, mcu, ServeAlg::Default, false)
+                    .unwrap();
+            builder.build(props)
+        };
+
+        // A 128KB F401RB has room for 4 images after the firmware and metadata
+        let available = McuVariant::F401RB.flash_storage_bytes() - FIRMWARE_SIZE - MAX_METADATA_LEN;
+        assert_eq!(available, 4 * 16384);
+        let (_, rom_data) = build(4, McuVariant::F401RB).expect("4 images should fit");
+        assert_eq!(rom_data.len(), available);
+
+        // ... but not 5
+        let result = build(5, McuVariant::F401RB);
+        assert!(
+            matches!(
+                result,
+                Err(Error::RomImagesExceedFlash {
+                    required: 81920,
+                    available: 65536
+                })
+            ),
+            "Expected RomImagesExceedFlash error, got {:?}",
+            result
+        );
+
+        // ... which fit on a larger MCU
+        let (_, rom_data) = build(5, McuVariant::F411RE).expect("5 images should fit");
+        assert_eq!(rom_data.len(), 5 * 16384);
+
+        println!("✓ Phase 78 Test 179: ROM images exceeding the MCU's flash rejected, and fitting ones built");
+    }
+
+    // ============================================================================
+    // PHASE 79: Config Descriptions
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 180: Description set on the builder round trips through the metadata
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase79_description_round_trip() {
+        use onerom_gen::Metadata;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 79 description",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x42),
+            })
+            .expect("Failed to add file");
+        assert_eq!(builder.description(), "Phase 79 description");
+
+        let description = builder.description().to_string();
+
+        let props = default_fw_props_060();
+        let flash_base = props.board().mcu_family().get_flash_base();
+        let mut parse_description = |description: String| {
+            builder.set_description(description);
+            let (metadata_buf, _) = builder.build(props).expect("Build failed");
+            Metadata::verify_crc(&metadata_buf).expect("CRC should cover the description");
+            Metadata::parse(&metadata_buf, flash_base, false)
+                .expect("Failed to parse metadata")
+                .description
+        };
+
+        // The config's description is written by default
+        assert_eq!(
+            parse_description(description),
+            Some("Phase 79 description".to_string())
+        );
+
+        // ... and can be replaced
+        let description = "C64 kernal ✓".to_string();
+        assert_eq!(parse_description(description.clone()), Some(description));
+
+        // The longest description which fits
+        let description = "a".repeat(127);
+        assert_eq!(parse_description(description.clone()), Some(description));
+
+        // Empty and overlong descriptions aren't written, parsing as absent,
+        // as for older metadata
+        assert_eq!(parse_description(String::new()), None);
+        assert_eq!(parse_description("a".repeat(128)), None);
+
+        println!("✓ Phase 79 Test 180: Description set on the builder round trips through the metadata");
+    }
+
+    // ============================================================================
+    // PHASE 80: Reproducible Builds
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 181: Build output is byte-identical whatever order files are added in
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase80_build_independent_of_add_order() {
+        // Single, banked and multi sets, with boot logging so filenames are
+        // included too
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 80 reproducible builds",
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "single.rom", "type": "2332", "cs1": "active_low", "cs2": "active_high" }]
+                },
+                {
+                    "type": "banked",
+                    "chips": [
+                        { "file": "bank0.rom", "type": "2364", "cs1": "active_low" },
+                        { "file": "bank1.rom", "type": "2364", "cs1": "active_low" }
+                    ]
+                },
+                {
+                    "type": "multi",
+                    "chips": [
+                        { "file": "multi0.rom", "type": "2364", "cs1": "active_low" },
+                        { "file": "multi1.rom", "type": "2364", "cs1": "active_low" }
+                    ]
+                }
+            ]
+        }"#;
+        let sizes = [4096, 8192, 8192, 8192, 8192];
+        let props = FirmwareProperties::new(
+            FirmwareVersion::new(0, 6, 0, 0),
+            Board::Ice24UsbH,
+            McuVariant::F411RE,
+            ServeAlg::Default,
+            true,
+        )
+        .unwrap();
+
+        let build = |order: &[usize]| {
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+            for &id in order {
+                builder
+                    .add_file(FileData {
+                        id,
+                        data: create_test_rom_data(sizes[id], 0x10 * id as u8),
+                    })
+                    .expect("Failed to add file");
+            }
+            builder.build(props).expect("Build failed")
+        };
+
+        let (metadata, images) = build(&[0, 1, 2, 3, 4]);
+        for order in [[4, 3, 2, 1, 0], [2, 0, 4, 1, 3], [3, 4, 0, 2, 1]] {
+            let (other_metadata, other_images) = build(&order);
+            assert_eq!(
+                other_metadata, metadata,
+                "Metadata differs when files added in order {order:?}"
+            );
+            assert_eq!(
+                other_images, images,
+                "Images differ when files added in order {order:?}"
+            );
+        }
+
+        // ... and the same builder builds identically each time
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        for (id, size) in sizes.into_iter().enumerate() {
+            builder
+                .add_file(FileData {
+                    id,
+                    data: create_test_rom_data(size, 0x10 * id as u8),
+                })
+                .expect("Failed to add file");
+        }
+        assert_eq!(
+            builder.build(props).expect("Build failed"),
+            (metadata.clone(), images.clone())
+        );
+        assert_eq!(
+            builder.build(props).expect("Build failed"),
+            (metadata, images)
+        );
+
+        println!("✓ Phase 80 Test 181: Build output is byte-identical whatever order files are added in");
+    }
+
+    // ============================================================================
+    // PHASE 81: Error Codes
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 182: Each error variant has a unique, stable numeric code
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase81_error_codes() {
+        use onerom_gen::{ERROR_CODES, Error};
+
+        // Codes and names are unique, and codes non-zero
+        let mut codes: Vec<_> = ERROR_CODES.iter().map(|(code, _)| *code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), ERROR_CODES.len(), "Duplicate error codes");
+        assert!(!codes.contains(&0), "Error codes must be non-zero");
+        let mut names: Vec<_> = ERROR_CODES.iter().map(|(_, name)| *name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), ERROR_CODES.len(), "Duplicate error names");
+
+        // Errors return the code listed for their variant
+        let variant = |error: &Error| {
+            format!("{:?}", error)
+                .split([' ', '{', '('])
+                .next()
+                .unwrap()
+                .to_string()
+        };
+        let errors = [
+            Error::RightSize { size: 8192 },
+            Error::NoChips,
+            Error::InvalidConfig {
+                error: "bad".to_string(),
+            },
+            Error::MissingFile { id: 3 },
+            Error::RomImagesExceedFlash {
+                required: 2,
+                available: 1,
+            },
+            Error::Base16,
+        ];
+        for error in &errors {
+            let listed = ERROR_CODES
+                .iter()
+                .find(|(_, name)| *name == variant(error))
+                .unwrap_or_else(|| panic!("{error:?} is missing from ERROR_CODES"));
+            assert_eq!(error.code(), listed.0, "Wrong code for {error:?}");
+        }
+
+        // Codes are a contract with C callers, so must never change
+        let stable = [
+            ("RightSize", 1),
+            ("NoChips", 7),
+            ("InvalidConfig", 14),
+            ("MissingFile", 18),
+            ("RomImagesExceedFlash", 40),
+            ("Base16", 44),
+        ];
+        for (name, code) in stable {
+            assert!(
+                ERROR_CODES.contains(&(code, name)),
+                "{name} should have code {code}"
+            );
+        }
+        let codes: Vec<_> = errors.iter().map(Error::code).collect();
+        assert_eq!(codes, [1, 7, 14, 18, 40, 44]);
+
+        println!("✓ Phase 81 Test 182: Each error variant has a unique, stable numeric code");
+    }
+
+    // ============================================================================
+    // PHASE 82: Variant Builds
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 183: Variants build with their own tweaks, sharing files, and
+    // identical outputs are reported
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase82_build_variants() {
+        use onerom_gen::Error;
+        use onerom_gen::builder::VariantSpec;
+        use onerom_gen::firmware::{FirmwareConfig, LedConfig};
+        use std::collections::BTreeMap;
+
+        // Labels enabled, so label tweaks reach the metadata, and the first
+        // image padded, so fill byte tweaks reach the images
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 82 variant builds",
+            "labels": true,
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "kernal.rom", "type": "2364", "cs1": "active_low", "size_handling": "pad", "label": "Kernal" }]
+                },
+                {
+                    "type": "single",
+                    "chips": [{ "file": "basic.rom", "type": "2364", "cs1": "active_low", "label": "Basic" }]
+                }
+            ]
+        }"#;
+        let props = default_fw_props_060();
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+
+        // Files must all be added before building any variant
+        let result = builder.build_variants(props, &[VariantSpec::default()]);
+        assert!(
+            matches!(result, Err(Error::MissingFile { id: 0 })),
+            "Expected MissingFile, got {:?}",
+            result
+        );
+
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(4096, 0x11),
+            })
+            .expect("Failed to add file");
+        builder
+            .add_file(FileData {
+                id: 1,
+                data: create_test_rom_data(8192, 0x22),
+            })
+            .expect("Failed to add file");
+
+        let variant = |name: &str| VariantSpec {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let serial_0001 = VariantSpec {
+            description: Some("Unit 0001".to_string()),
+            labels: BTreeMap::from([(0, "Kernal 0001".to_string())]),
+            ..variant("0001")
+        };
+        let variants = [
+            serial_0001.clone(),
+            VariantSpec {
+                description: Some("Unit 0002".to_string()),
+                ..variant("0002")
+            },
+            // Same tweaks as 0001
+            VariantSpec {
+                name: "0001 again".to_string(),
+                ..serial_0001
+            },
+            VariantSpec {
+                fill_bytes: BTreeMap::from([(0, 0xFF)]),
+                ..variant("blank fill")
+            },
+            variant("untweaked"),
+            // Different tweaks, but the label is unchanged
+            VariantSpec {
+                labels: BTreeMap::from([(1, "Basic".to_string())]),
+                ..variant("same label")
+            },
+            VariantSpec {
+                firmware_overrides: BTreeMap::from([(
+                    1,
+                    FirmwareConfig {
+                        ice: None,
+                        fire: None,
+                        led: Some(LedConfig { enabled: false }),
+                        swd: None,
+                        serve_alg_params: None,
+                        serve_alg: None,
+                    },
+                )]),
+                ..variant("no LED")
+            },
+        ];
+
+        let (outputs, report) = builder
+            .build_variants_with_report(props, &variants)
+            .expect("Variant build failed");
+        assert_eq!(outputs.len(), variants.len());
+        for ((spec, _, _), variant) in outputs.iter().zip(&variants) {
+            assert_eq!(spec, variant, "Variants should be returned in order");
+        }
+        assert_eq!(
+            report.identical_to,
+            [None, None, Some(0), None, None, Some(4), None]
+        );
+
+        // Without tweaks, a variant builds as the builder does
+        let (metadata, images) = builder.build(props).expect("Build failed");
+        assert_eq!((&outputs[4].1, &outputs[4].2), (&metadata, &images));
+
+        // Each tweak reaches the build
+        let contains = |buf: &[u8], text: &str| {
+            buf.windows(text.len())
+                .any(|window| window == text.as_bytes())
+        };
+        assert!(contains(&outputs[0].1, "Unit 0001"));
+        assert!(contains(&outputs[0].1, "Kernal 0001"));
+        assert!(contains(&outputs[1].1, "Unit 0002"));
+        assert!(!contains(&outputs[1].1, "Kernal 0001"));
+        assert_ne!(outputs[3].2, images, "Fill byte should change the images");
+        assert_ne!(
+            outputs[6].1, metadata,
+            "Overrides should change the metadata"
+        );
+
+        // ... and tweaks don't leak into the builder itself
+        assert_eq!(
+            builder.build(props).expect("Build failed"),
+            (metadata, images)
+        );
+
+        // The build is the same as building the tweaked config directly
+        let json_0002 = json.replace("Phase 82 variant builds", "Unit 0002");
+        let mut direct =
+            Builder::from_json(FW_VER, MCU_FAM, &json_0002).expect("Failed to parse JSON");
+        direct
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(4096, 0x11),
+            })
+            .expect("Failed to add file");
+        direct
+            .add_file(FileData {
+                id: 1,
+                data: create_test_rom_data(8192, 0x22),
+            })
+            .expect("Failed to add file");
+        assert_eq!(
+            direct.build(props).expect("Build failed"),
+            (outputs[1].1.clone(), outputs[1].2.clone())
+        );
+
+        // Tweaks must refer to Chips and chip sets which exist
+        let bad_variants = [
+            VariantSpec {
+                labels: BTreeMap::from([(2, "Missing".to_string())]),
+                ..variant("bad label")
+            },
+            VariantSpec {
+                fill_bytes: BTreeMap::from([(5, 0xFF)]),
+                ..variant("bad fill byte")
+            },
+            VariantSpec {
+                firmware_overrides: BTreeMap::from([(
+                    2,
+                    variants[6].firmware_overrides[&1].clone(),
+                )]),
+                ..variant("bad overrides")
+            },
+        ];
+        for bad in bad_variants {
+            let result = builder.build_variants(props, &[variant("good"), bad.clone()]);
+            assert!(
+                matches!(result, Err(Error::InvalidConfig { .. })),
+                "Expected InvalidConfig for {:?}, got {:?}",
+                bad.name,
+                result
+            );
+        }
+
+        println!("✓ Phase 82 Test 183: Variants build with their own tweaks, sharing files, and identical outputs are reported");
+    }
+
+    // ============================================================================
+    // PHASE 83: Chip Pin Counts
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 184: Chip types with the wrong pin count for the board are rejected
+    // precisely
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase83_wrong_chip_pins() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::Error;
+
+        // A 28-pin 27128 following a 24-pin 2364, for a 24-pin board
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 83 chip pins",
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+                },
+                {
+                    "type": "single",
+                    "chips": [{ "file": "b.rom", "type": "27128" }]
+                }
+            ]
+        }"#;
+        let props = default_fw_props_060();
+        assert_eq!(props.board().chip_pins(), 24);
+
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x11),
+            })
+            .expect("Failed to add file");
+        builder
+            .add_file(FileData {
+                id: 1,
+                data: create_test_rom_data(16384, 0x22),
+            })
+            .expect("Failed to add file");
+
+        let check = |result: Result<(), Error>| {
+            let error = result.expect_err("Expected WrongChipPins");
+            assert!(
+                matches!(
+                    error,
+                    Error::WrongChipPins {
+                        id: 1,
+                        chip_type: ChipType::Chip27128,
+                        board_pins: 24,
+                    }
+                ),
+                "Expected WrongChipPins, got {:?}",
+                error
+            );
+            assert_eq!(
+                error.to_string(),
+                "Chip 1 is a 27128 (28-pin) part, but the board has a 24-pin socket"
+            );
+            assert_eq!(error.code(), 45);
+        };
+        check(builder.build_validation(&props));
+        check(builder.build(props).map(|_| ()));
+
+        // Replacing the 27128 with a 24-pin type builds
+        let json = json.replace(
+            r#""type": "27128""#,
+            r#""type": "2364", "cs1": "active_low""#,
+        );
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, &json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x11),
+            })
+            .expect("Failed to add file");
+        builder
+            .add_file(FileData {
+                id: 1,
+                data: create_test_rom_data(8192, 0x22),
+            })
+            .expect("Failed to add file");
+        builder.build(props).expect("Build failed");
+
+        println!("✓ Phase 83 Test 184: Chip types with the wrong pin count for the board are rejected precisely");
+    }
+
+    // ============================================================================
+    // PHASE 84: Strict Truncation
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 185: Strict truncation allows an all-fill tail, and rejects a tail
+    // containing data
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase84_strict_truncation() {
+        use onerom_gen::Error;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 84 strict truncation",
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low", "size_handling": "truncate", "strict_truncate": true }]
+                }
+            ]
+        }"#;
+        let props = default_fw_props_060();
+
+        // A 16KB file whose upper half is the given tail
+        let build = |json: &str, tail: Vec<u8>| {
+            let mut data = create_test_rom_data(8192, 0x11);
+            data.extend(tail);
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+            builder
+                .add_file(FileData { id: 0, data })
+                .expect("Failed to add file");
+            builder.build(props)
+        };
+        let mut data_tail = vec![0xAA; 8192];
+        data_tail[4096] = 0x55;
+
+        // A tail entirely of the default fill byte is discarded
+        build(json, vec![0xAA; 8192]).expect("All-fill tail should truncate");
+
+        // A tail containing data is rejected
+        let error = build(json, data_tail.clone()).expect_err("Expected TruncationLosesData");
+        assert!(
+            matches!(
+                error,
+                Error::TruncationLosesData {
+                    id: 0,
+                    dropped_bytes: 8192,
+                }
+            ),
+            "Expected TruncationLosesData, got {:?}",
+            error
+        );
+        assert_eq!(error.code(), 46);
+
+        // The tail is compared against the Chip's fill byte, if specified
+        let json_ff = json.replace(
+            r#""strict_truncate": true"#,
+            r#""strict_truncate": true, "fill_byte": 255"#,
+        );
+        build(&json_ff, vec![0xFF; 8192]).expect("All-fill tail should truncate");
+        assert!(matches!(
+            build(&json_ff, vec![0xAA; 8192]),
+            Err(Error::TruncationLosesData { .. })
+        ));
+
+        // Without strict truncation, the data is silently discarded
+        let json_lenient = json.replace(r#", "strict_truncate": true"#, "");
+        build(&json_lenient, data_tail).expect("Lenient truncation should build");
+
+        println!("✓ Phase 84 Test 185: Strict truncation allows an all-fill tail, and rejects a tail containing data");
+    }
+
+    // ============================================================================
+    // PHASE 85: Chip Set Introspection
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 186: Parsed Chip sets are reported, with their types, before files
+    // are added
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase85_chip_sets() {
+        use onerom_gen::ChipSetType;
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 85 chip sets",
+            "chip_sets": [
+                {
+                    "type": "single",
+                    "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+                },
+                {
+                    "type": "banked",
+                    "chips": [
+                        { "file": "b.rom", "type": "2364", "cs1": "active_low" },
+                        { "file": "c.rom", "type": "2364", "cs1": "active_low" }
+                    ]
+                },
+                {
+                    "type": "multi",
+                    "no_chip_byte": 170,
+                    "chips": [
+                        { "file": "d.rom", "type": "2364", "cs1": "active_low" },
+                        { "file": "e.rom", "type": "2364", "cs1": "active_low" }
+                    ]
+                }
+            ]
+        }"#;
+        let props = default_fw_props_060();
+        let builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+
+        // No files have been added, but the sets are still reported
+        let chip_sets = builder.chip_sets(&props).expect("Failed to get chip sets");
+        assert_eq!(chip_sets.len(), 3);
+        let set_types: Vec<_> = chip_sets.iter().map(|set| set.set_type.clone()).collect();
+        assert_eq!(
+            set_types,
+            [ChipSetType::Single, ChipSetType::Banked, ChipSetType::Multi]
+        );
+        let chip_counts: Vec<_> = chip_sets.iter().map(|set| set.chips().len()).collect();
+        assert_eq!(chip_counts, [1, 2, 2]);
+
+        // Chips are numbered across sets, as in the config
+        let indexes: Vec<_> = chip_sets
+            .iter()
+            .flat_map(|set| set.chips().iter().map(|chip| chip.index()))
+            .collect();
+        assert_eq!(indexes, [0, 1, 2, 3, 4]);
+
+        // Multi sets are always served with AddrOnAnyCs
+        assert_eq!(chip_sets[2].serve_alg(), ServeAlg::AddrOnAnyCs);
+
+        // Set options are applied as when building
+        assert_eq!(chip_sets[2].no_chip_byte(), 0xAA);
+        assert_eq!(
+            chip_sets[1].describe(),
+            format!(
+                "Banked set #1: 2× 2364, CS1 active low, {:?}",
+                chip_sets[1].serve_alg()
+            )
+        );
+
+        println!("✓ Phase 85 Test 186: Parsed Chip sets are reported, with their types, before files are added");
+    }
+
+    // ============================================================================
+    // PHASE 86: Inverted Data Lines
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 187: A board with an inverted D7 serves it inverted, and reads back
+    // correctly
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase86_inverted_data_lines() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, ChipSetType, Error};
+
+        let board = Board::Ice24UsbH;
+        assert!(board.data_inverted_pins().is_empty());
+        let logical: Vec<u8> = (0..8192u32).map(|i| ((i * 31) ^ (i >> 8)) as u8).collect();
+        let create_set = || {
+            let chips = vec![create_chip(0, ChipType::Chip2364, &logical)];
+            ChipSet::new(0, ChipSetType::Single, ServeAlg::Default, chips, None)
+                .expect("Failed to create Chip set")
+        };
+        let plain = create_set();
+
+        // Simulate a board variant whose D7 line is inverted
+        let d7_pin = board.data_pins()[7];
+        let mut inverted = create_set();
+        inverted
+            .set_data_inverted_pins(&board, vec![d7_pin])
+            .expect("Failed to set inverted data pins");
+        assert_eq!(inverted.data_inverted_pins(&board), [d7_pin]);
+        inverted
+            .validate(&board, &FW_VER)
+            .expect("Validation failed");
+
+        // Only the physical bit for D7 differs from the plain image
+        let plain_image = plain.to_flash_image(&board, false).unwrap();
+        let inverted_image = inverted.to_flash_image(&board, false).unwrap();
+        let d7_bit = 1u8 << (d7_pin % 8);
+        for (plain, inverted) in plain_image.iter().zip(&inverted_image) {
+            assert_eq!(plain ^ inverted, d7_bit);
+        }
+
+        // And the logical image reads back correctly
+        let recovered = inverted
+            .from_flash_image(&inverted_image, &board, false)
+            .expect("Failed to reverse flash image");
+        assert_eq!(recovered, [logical.clone()]);
+        let bus_values = [0, 1, 0x1234, 0x1FFF];
+        assert_eq!(
+            inverted.serve_trace(&bus_values, &board).unwrap(),
+            plain.serve_trace(&bus_values, &board).unwrap()
+        );
+
+        // Inverted pins must be data pins
+        let mut bad = create_set();
+        let not_data = (0..32u8)
+            .find(|pin| !board.data_pins().contains(pin))
+            .unwrap();
+        assert!(matches!(
+            bad.set_data_inverted_pins(&board, vec![not_data]),
+            Err(Error::InvalidDataPinMap { .. })
+        ));
+
+        println!("✓ Phase 86 Test 187: A board with an inverted D7 serves it inverted, and reads back correctly");
+    }
+
+    // ============================================================================
+    // PHASE 87: Metadata Notes
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 188: Note set on the builder round trips through the metadata, and
+    // overlong notes are rejected
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase87_note_round_trip() {
+        use onerom_gen::{Error, MAX_NOTE_LEN, Metadata};
+
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 87 note",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x42),
+            })
+            .expect("Failed to add file");
+        assert_eq!(builder.note(), None);
+
+        let props = default_fw_props_060();
+        let flash_base = props.board().mcu_family().get_flash_base();
+        let parse = |builder: &Builder| {
+            let (metadata_buf, _) = builder.build(props).expect("Build failed");
+            Metadata::verify_crc(&metadata_buf).expect("CRC should cover the note");
+            Metadata::parse(&metadata_buf, flash_base, false).expect("Failed to parse metadata")
+        };
+
+        // No note by default
+        assert_eq!(parse(&builder).note, None);
+
+        // A note round trips, alongside the description
+        let note = "2026-10-16 3f2a9c1 asset #42 ✓".to_string();
+        builder.set_note(note.clone()).expect("Failed to set note");
+        assert_eq!(builder.note(), Some(note.as_str()));
+        let parsed = parse(&builder);
+        assert_eq!(parsed.note, Some(note.clone()));
+        assert_eq!(parsed.description, Some("Phase 87 note".to_string()));
+
+        // The longest note which fits
+        let longest = "a".repeat(MAX_NOTE_LEN);
+        builder
+            .set_note(longest.clone())
+            .expect("Failed to set note");
+        assert_eq!(parse(&builder).note, Some(longest.clone()));
+
+        // Overlong notes are rejected, leaving the existing note
+        let error = builder
+            .set_note("a".repeat(MAX_NOTE_LEN + 1))
+            .expect_err("Expected NoteTooLong");
+        match error {
+            Error::NoteTooLong { len, max } => {
+                assert_eq!(len, MAX_NOTE_LEN + 1);
+                assert_eq!(max, MAX_NOTE_LEN);
+            }
+            _ => panic!("Expected NoteTooLong, got {:?}", error),
+        }
+        assert_eq!(error.code(), 47);
+        assert!(builder.set_note("a\0b".to_string()).is_err());
+        assert_eq!(parse(&builder).note, Some(longest));
+
+        // An empty note clears it
+        builder
+            .set_note(String::new())
+            .expect("Failed to clear note");
+        assert_eq!(builder.note(), None);
+        assert_eq!(parse(&builder).note, None);
+
+        println!("✓ Phase 87 Test 188: Note set on the builder round trips through the metadata, and overlong notes are rejected");
+    }
+
+    // ============================================================================
+    // PHASE 88: Address Line Maps
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 189: A Chip set's address line map permutes the image, and must be a
+    // permutation
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase88_addr_line_map() {
+        use onerom_config::chip::ChipType;
+        use onerom_gen::{ChipSet, ChipSetType, Error};
+
+        let board = Board::Ice24UsbH;
+        let logical: Vec<u8> = (0..8192u32).map(|i| ((i * 31) ^ (i >> 8)) as u8).collect();
+        let create_set = |chip_type: ChipType| {
+            let size = chip_type.size_bytes();
+            let chips = vec![create_chip(0, chip_type, &logical[..size])];
+            ChipSet::new(0, ChipSetType::Single, ServeAlg::Default, chips, None)
+                .expect("Failed to create Chip set")
+        };
+
+        // Swap A0 and A1
+        let mut swap_map: Vec<usize> = (0..13).collect();
+        swap_map.swap(0, 1);
+        let plain = create_set(ChipType::Chip2364);
+        let mut swapped = create_set(ChipType::Chip2364);
+        swapped
+            .set_addr_line_map(swap_map.clone())
+            .expect("Failed to set address line map");
+        swapped
+            .validate(&board, &FW_VER)
+            .expect("Validation failed");
+
+        // Reading the swapped image as if unswapped finds each byte at its
+        // address with A0 and A1 exchanged
+        let flash_image = swapped.to_flash_image(&board, false).unwrap();
+        let recovered = plain
+            .from_flash_image(&flash_image, &board, false)
+            .expect("Failed to reverse flash image");
+        let swap =
+            |address: usize| (address & !0b11) | ((address & 0b01) << 1) | ((address & 0b10) >> 1);
+        for (address, &byte) in recovered[0].iter().enumerate() {
+            assert_eq!(byte, logical[swap(address)], "Address {address:#06X}");
+        }
+        assert_ne!(recovered[0], logical);
+
+        // ... and the swapped set reads its own image back unchanged
+        let recovered = swapped
+            .from_flash_image(&flash_image, &board, false)
+            .expect("Failed to reverse flash image");
+        assert_eq!(recovered, [logical.clone()]);
+
+        // The map composes with the 2732's swapped A11 and A12 - the identity
+        // map changes nothing
+        let plain_2732 = create_set(ChipType::Chip2732);
+        let mut identity_2732 = create_set(ChipType::Chip2732);
+        identity_2732
+            .set_addr_line_map((0..12).collect())
+            .expect("Failed to set address line map");
+        assert_eq!(
+            identity_2732.to_flash_image(&board, false).unwrap(),
+            plain_2732.to_flash_image(&board, false).unwrap()
+        );
+
+        // Maps must be a permutation of the Chip's address lines
+        let mut bad = create_set(ChipType::Chip2364);
+        let mut duplicate = swap_map.clone();
+        duplicate[0] = 0;
+        for map in [swap_map[..12].to_vec(), duplicate, (1..14).collect()] {
+            assert!(
+                matches!(
+                    bad.set_addr_line_map(map.clone()),
+                    Err(Error::InvalidAddrLineMap { id: 0, .. })
+                ),
+                "{map:?}"
+            );
+        }
+
+        // Configured through the chip set config
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 88 address line map",
+            "chip_sets": [{
+                "type": "single",
+                "addr_line_map": [1, 0, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+                "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+        let props = default_fw_props_060();
+        let build = |json: &str| {
+            let mut builder =
+                Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+            builder
+                .add_file(FileData {
+                    id: 0,
+                    data: logical.clone(),
+                })
+                .expect("Failed to add file");
+            builder.build(props)
+        };
+        let (_, rom_images) = build(json).expect("Build failed");
+        assert_eq!(&rom_images[..flash_image.len()], flash_image.as_slice());
+
+        let json = json.replace("[1, 0, 2,", "[1, 1, 2,");
+        let error = build(&json).expect_err("Expected InvalidAddrLineMap");
+        assert!(
+            matches!(error, Error::InvalidAddrLineMap { id: 0, .. }),
+            "Expected InvalidAddrLineMap, got {:?}",
+            error
+        );
+        assert_eq!(error.code(), 48);
+
+        println!("✓ Phase 88 Test 189: A Chip set's address line map permutes the image, and must be a permutation");
+    }
+
+    // ============================================================================
+    // PHASE 89: Serving Algorithm Resolution
+    // ============================================================================
+
+    // ----------------------------------------------------------------------------
+    // TEST 190: Default resolves to the concrete algorithm in the metadata and
+    // build report
+    // ----------------------------------------------------------------------------
+    #[test]
+    fn test_phase89_serve_alg_resolution() {
+        use onerom_gen::{ChipSetType, Metadata, ResolveServeAlg};
+
+        // Default is AddrOnCs for single and banked sets, and multi-ROM sets
+        // always run AddrOnAnyCs
+        for set_type in [ChipSetType::Single, ChipSetType::Banked] {
+            assert_eq!(ServeAlg::Default.resolve(&set_type), ServeAlg::AddrOnCs);
+            assert_eq!(ServeAlg::AddrOnCs.resolve(&set_type), ServeAlg::AddrOnCs);
+            assert_eq!(
+                ServeAlg::TwoCsOneAddr.resolve(&set_type),
+                ServeAlg::TwoCsOneAddr
+            );
+        }
+        for serve_alg in [
+            ServeAlg::Default,
+            ServeAlg::TwoCsOneAddr,
+            ServeAlg::AddrOnCs,
+        ] {
+            assert_eq!(
+                serve_alg.resolve(&ChipSetType::Multi),
+                ServeAlg::AddrOnAnyCs
+            );
+        }
+
+        // A single set built with the Default algorithm records AddrOnCs
+        let json = r#"{
+            "version": 1,
+            "description": "Phase 89 serve alg",
+            "chip_sets": [{
+                "type": "single",
+                "chips": [{ "file": "a.rom", "type": "2364", "cs1": "active_low" }]
+            }]
+        }"#;
+        let mut builder = Builder::from_json(FW_VER, MCU_FAM, json).expect("Failed to parse JSON");
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: create_test_rom_data(8192, 0x42),
+            })
+            .expect("Failed to add file");
+
+        let props = default_fw_props_060();
+        assert_eq!(props.serve_alg(), ServeAlg::Default);
+        let (metadata_buf, _, report) = builder.build_with_report(props).expect("Build failed");
+        assert_eq!(report.chip_sets[0].serve_alg, ServeAlg::AddrOnCs);
+
+        let flash_base = props.board().mcu_family().get_flash_base();
+        let parsed =
+            Metadata::parse(&metadata_buf, flash_base, false).expect("Failed to parse metadata");
+        assert_eq!(parsed.chip_sets[0].serve_alg, ServeAlg::AddrOnCs);
+        assert_eq!(parsed.chip_sets[0].serve_alg.c_enum_value(), 1);
+
+        println!("✓ Phase 89 Test 190: Default resolves to the concrete algorithm in the metadata and build report");
+    }
 }
@@ -12,7 +12,12 @@ use alloc::vec;
 use embassy_time::{Duration, Timer};
 use static_cell::make_static;
 
-use onerom_protocol::lab::{Command, LabRomEntry, Response};
+use onerom_protocol::lab::{Command, LabRomEntry, Response, SetSettleDelay};
+#[cfg(feature = "usb")]
+use onerom_protocol::lab::{LabRomDumpHeader, LabRomDumpStatus};
+
+#[cfg(feature = "usb")]
+use onerom_database::checksum;
 
 use crate::Rom;
 use crate::info::LAB_RAM_INFO;
@@ -132,7 +137,7 @@ impl Control {
         }
     }
 
-    async fn handle_command(&mut self, command: Command, _data: &[u8]) {
+    async fn handle_command(&mut self, command: Command, data: &[u8]) {
         debug!("Handling command: {command:?}");
         match command {
             Command::Ping => {
@@ -163,10 +168,93 @@ impl Control {
                 self.send_response_no_data(Response::Error);
                 todo!();
             }
+            Command::QaResults => {
+                debug!("QaResults command received");
+                #[cfg(feature = "qa")]
+                {
+                    match self.rom.lab_qa_results().to_buffer() {
+                        Ok(buf) => self.send_response_data(&buf),
+                        Err(e) => {
+                            error!("Failed to build QA results response: {e:?}");
+                            self.send_response_no_data(Response::Error);
+                        }
+                    }
+                }
+                #[cfg(not(feature = "qa"))]
+                {
+                    warn!("QA results not available - built without the qa feature");
+                    self.send_response_no_data(Response::Error);
+                }
+            }
+            Command::DumpRom => {
+                debug!("DumpRom command received");
+                let response = self.dump_rom().await;
+                self.send_response_no_data(response);
+                debug!("DumpRom command complete");
+            }
+            Command::SetSettleDelay => {
+                debug!("SetSettleDelay command received");
+                let result = SetSettleDelay::from_buffer(data)
+                    .map_err(|e| warn!("Invalid SetSettleDelay command: {e:?}"))
+                    .and_then(|cmd| {
+                        self.rom.set_settle_us(cmd.settle_us).map_err(|_| {
+                            warn!(
+                                "Settle delay {}us exceeds maximum {}us",
+                                cmd.settle_us,
+                                Rom::MAX_SETTLE_US
+                            )
+                        })
+                    });
+                match result {
+                    Ok(()) => {
+                        info!("Settle delay set to {}us", self.rom.settle_us());
+                        self.send_response_no_data(Response::SettleDelaySet);
+                    }
+                    Err(()) => self.send_response_no_data(Response::Error),
+                }
+            }
             Command::Unknown => info!("Unknown command received, ignoring"),
         }
     }
 
+    // Streams the last read ROM image over USB, framed by a header and
+    // checksum, returning the response to send.
+    #[cfg(feature = "usb")]
+    async fn dump_rom(&self) -> Response {
+        let (status, image) = match self.rom.image() {
+            Some(image) => (LabRomDumpStatus::Data, image),
+            None => (LabRomDumpStatus::NoData, &[][..]),
+        };
+        let header = LabRomDumpHeader {
+            status,
+            length: image.len() as u32,
+        };
+        let mut header_buf = [0u8; LabRomDumpHeader::size()];
+        header
+            .to_bytes(&mut header_buf)
+            .expect("ROM dump header buffer too small");
+        let sum: u32 = checksum(image);
+
+        if !crate::usb::send_frame(&[&header_buf, image, &sum.to_le_bytes()]).await {
+            warn!("Failed to stream ROM image over USB - is a host connected?");
+            return Response::Error;
+        }
+
+        match status {
+            LabRomDumpStatus::Data => Response::RomDumped,
+            LabRomDumpStatus::NoData => {
+                info!("No ROM image to dump - read the ROM first");
+                Response::NoData
+            }
+        }
+    }
+
+    #[cfg(not(feature = "usb"))]
+    async fn dump_rom(&self) -> Response {
+        warn!("ROM image dump not available - built without the usb feature");
+        Response::Error
+    }
+
     fn send_response_no_data(&mut self, response: Response) {
         let mut buf = [0u8; Response::size()];
         response.to_bytes(&mut buf);
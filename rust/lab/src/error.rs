@@ -10,4 +10,6 @@ pub enum Error {
     Address,
     /// Buffer size too small
     Buffer,
+    /// Settle delay too long
+    Settle,
 }
@@ -10,6 +10,8 @@ use log::{debug, error, info, trace, warn};
 use onerom_database::{RomEntry, RomType};
 
 use crate::rom::Id as RomId;
+#[cfg(feature = "qa")]
+use crate::rom::QaResults;
 
 pub fn log_good_rom_match(entry: &RomEntry) {
     info!("ROM match found:");
@@ -37,6 +39,25 @@ pub fn log_rom_id(id: &RomId) {
     info!("  SHA1:        {}", hex::encode(id.sha1()));
 }
 
+#[cfg(feature = "qa")]
+pub fn log_qa_results(results: &QaResults, settle_us: u32) {
+    info!(
+        "QA reads:    {} passed, {} failed",
+        results.successes(),
+        results.failures()
+    );
+    info!("  Settle:    {settle_us}us");
+    for mismatch in results.mismatches() {
+        info!(
+            "  Mismatch:  {:#07x} expected {:#04x} read {:#04x} (bits {:#04x})",
+            mismatch.address,
+            mismatch.expected,
+            mismatch.actual,
+            mismatch.expected ^ mismatch.actual
+        );
+    }
+}
+
 #[allow(dead_code)]
 pub fn dump_buf(buf: &[u8]) {
     for (i, chunk) in buf.chunks(16).enumerate() {
@@ -110,7 +110,9 @@ async fn main(_spawner: Spawner) {
         );
     }
 
-    // Collate the address and data pins
+    // Collate the address and data pins.  For 24 pin ROMs, A13 is the 2364
+    // CS line.
+    #[cfg(feature = "pin-24")]
     let addr_pins = [
         Flex::new(p.PC5),
         Flex::new(p.PC4),
@@ -127,6 +129,33 @@ async fn main(_spawner: Spawner) {
         Flex::new(p.PC9),
         Flex::new(p.PC10), // 2364 CS pin, set as "A13"
     ];
+
+    // ice-28-a.  For 28 pin ROMs, OE and CE, which are the 23128's CS2 and CS1
+    // lines, are driven separately from A0-A15.
+    #[cfg(feature = "pin-28")]
+    let addr_pins = [
+        Flex::new(p.PC5),
+        Flex::new(p.PC4),
+        Flex::new(p.PC6),
+        Flex::new(p.PC7),
+        Flex::new(p.PC3),
+        Flex::new(p.PC2),
+        Flex::new(p.PC1),
+        Flex::new(p.PC0),
+        Flex::new(p.PC9),
+        Flex::new(p.PC10),
+        Flex::new(p.PC12),
+        Flex::new(p.PC11),
+        Flex::new(p.PC8),
+        Flex::new(p.PC13),
+        Flex::new(p.PB0), // A14, the 23128's CS3 line
+        Flex::new(p.PB1), // A15
+    ];
+    #[cfg(feature = "pin-28")]
+    let select_pins = [
+        Flex::new(p.PC14), // OE
+        Flex::new(p.PC15), // CE
+    ];
     let data_pins = [
         Flex::new(p.PA7),
         Flex::new(p.PA6),
@@ -139,7 +168,10 @@ async fn main(_spawner: Spawner) {
     ];
 
     // Create the ROM object
+    #[cfg(feature = "pin-24")]
     let mut rom = Rom::new(addr_pins, data_pins);
+    #[cfg(feature = "pin-28")]
+    let mut rom = Rom::new(addr_pins, select_pins, data_pins);
     unsafe {
         LAB_RAM_INFO.rom_data = rom.buf.as_ptr() as *const core::ffi::c_void;
     }
@@ -151,16 +183,21 @@ async fn main(_spawner: Spawner) {
         control.run().await;
     }
 
-    #[cfg(feature = "qa")]
+    // With control, reads are triggered and QA results retrieved over the
+    // control channel instead
+    #[cfg(all(feature = "qa", not(feature = "control")))]
     {
         info!("QA mode");
         #[cfg(feature = "usb")]
         info!("Press `d` to enter DFU mode");
         loop {
             #[cfg(not(feature = "usb"))]
-            match rom.read_rom().await {
-                Some(_) => info!("ROM read successfully"),
-                None => info!("Failed to read ROM"),
+            {
+                match rom.read_rom().await {
+                    Some(_) => info!("ROM read successfully"),
+                    None => info!("Failed to read ROM"),
+                }
+                logs::log_qa_results(rom.qa_results(), rom.settle_us());
             }
             #[cfg(feature = "usb")]
             match embassy_time::with_timeout(embassy_time::Duration::from_secs(5), usb::recv_key())
@@ -204,3 +241,6 @@ async fn main(_spawner: Spawner) {
 
 #[cfg(all(feature = "control", feature = "oneshot"))]
 compile_error!("Features 'control' and 'oneshot' are mutually exclusive");
+
+#[cfg(all(feature = "pin-24", feature = "pin-28"))]
+compile_error!("Features 'pin-24' and 'pin-28' are mutually exclusive");
@@ -101,9 +101,9 @@ async fn main(_spawner: Spawner) {
         Flex::new(p.PIN_15),
         Flex::new(p.PIN_10),
         Flex::new(p.PIN_16),
-        Flex::new(p.PIN_8),
-        Flex::new(p.PIN_9),
     ];
+    // OE and CE, which are the 23128's CS2 and CS1 lines
+    let select_pins = [Flex::new(p.PIN_8), Flex::new(p.PIN_9)];
     let data_pins = [
         Flex::new(p.PIN_7),
         Flex::new(p.PIN_6),
@@ -116,7 +116,7 @@ async fn main(_spawner: Spawner) {
     ];
 
     // Create the ROM object
-    let mut rom = Rom::new(addr_pins, data_pins);
+    let mut rom = Rom::new(addr_pins, select_pins, data_pins);
     unsafe {
         LAB_RAM_INFO.rom_data = rom.buf.as_ptr() as *const core::ffi::c_void;
     }
@@ -128,7 +128,9 @@ async fn main(_spawner: Spawner) {
         control.run().await;
     }
 
-    #[cfg(feature = "qa")]
+    // With control, reads are triggered and QA results retrieved over the
+    // control channel instead
+    #[cfg(all(feature = "qa", not(feature = "control")))]
     {
         info!("QA mode");
         loop {
@@ -136,6 +138,7 @@ async fn main(_spawner: Spawner) {
                 Some(_) => info!("ROM read successfully"),
                 None => info!("Failed to read ROM"),
             }
+            logs::log_qa_results(rom.qa_results(), rom.settle_us());
             //embassy_time::Timer::after_secs(5).await;
         }
     }
@@ -164,3 +167,6 @@ async fn main(_spawner: Spawner) {
 
 #[cfg(all(feature = "control", feature = "oneshot"))]
 compile_error!("Features 'control' and 'oneshot' are mutually exclusive");
+
+#[cfg(all(feature = "pin-24", feature = "pin-28"))]
+compile_error!("Features 'pin-24' and 'pin-28' are mutually exclusive");
@@ -15,7 +15,12 @@ use embassy_stm32::gpio::{Flex, Pull, Speed};
 use embassy_time::{Duration, Instant, Timer};
 
 use onerom_database::{CsActive, RomEntry, RomType, checksum, identify_rom, sha1_digest};
+#[cfg(feature = "qa")]
+use onerom_protocol::lab::LabQaMismatch;
+#[cfg(all(feature = "qa", feature = "control"))]
+use onerom_protocol::lab::LabQaResults;
 
+use crate::Error;
 use crate::logs::{log_bad_rom_match, log_good_rom_match, log_rom_id};
 
 /// Identification information for a particular ROM type.  Includes:
@@ -76,32 +81,140 @@ pub struct Matches {
     ids: [Id; RomType::all().len()],
 }
 
+/// Results of repeated ROM reads, in QA mode.
+///
+/// Counts the reads which did and didn't match a database entry.  Each read
+/// is also compared with the last successful read, and the first differing
+/// byte at each address recorded - pointing at any failing address or data
+/// lines.  As the heap is only 1KB, up to [`QaResults::MAX_MISMATCHES`]
+/// addresses are recorded.
+#[cfg(feature = "qa")]
+#[derive(Debug)]
+pub struct QaResults {
+    successes: u32,
+    failures: u32,
+    mismatches: Vec<LabQaMismatch>,
+
+    // Whether the ROM image buffer holds a successful read, to compare the
+    // next read against
+    reference: bool,
+}
+
+#[cfg(feature = "qa")]
+impl QaResults {
+    /// Maximum number of addresses with mismatching bytes recorded
+    pub const MAX_MISMATCHES: usize = 16;
+
+    fn new() -> Self {
+        Self {
+            successes: 0,
+            failures: 0,
+            mismatches: Vec::with_capacity(Self::MAX_MISMATCHES),
+            reference: false,
+        }
+    }
+
+    /// Returns the number of reads which matched a database entry
+    pub fn successes(&self) -> u32 {
+        self.successes
+    }
+
+    /// Returns the number of reads which didn't match a database entry
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Returns the recorded mismatches, in the order they were found
+    pub fn mismatches(&self) -> &[LabQaMismatch] {
+        &self.mismatches
+    }
+
+    // Checks a byte just read against the last successful read at the same
+    // address, recording it if it's the first mismatch at that address.
+    #[inline]
+    fn check_byte(&mut self, address: usize, expected: u8, actual: u8) {
+        if !self.reference || expected == actual {
+            return;
+        }
+        let address = address as u32;
+        if self.mismatches.len() < Self::MAX_MISMATCHES
+            && !self.mismatches.iter().any(|m| m.address == address)
+        {
+            self.mismatches.push(LabQaMismatch {
+                address,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    // Records the outcome of a complete read.
+    fn record_read(&mut self, success: bool) {
+        if success {
+            self.successes = self.successes.wrapping_add(1);
+        } else {
+            self.failures = self.failures.wrapping_add(1);
+        }
+        self.reference = success;
+    }
+}
+
 /// Object representing the ROM.  Used to read and detect connected ROMs.
 pub struct Rom {
     address: AddressLines,
     data: DataLines,
     pub buf: [u8; 1 << AddressLines::NUM_ADDR_LINES],
+    // OE and CE levels the image buffer was last read with
+    #[cfg(feature = "pin-28")]
+    select: usize,
     matches: Option<Matches>,
     last_read_duration: Option<Duration>,
+    settle_us: u32,
+    #[cfg(feature = "qa")]
+    qa: QaResults,
 }
 
 impl Rom {
+    /// Default delay between setting the address and sampling the data, in
+    /// microseconds.  28 pin ROMs are read with CS toggled between reads, so
+    /// need time to settle after being selected.
+    #[cfg(feature = "pin-24")]
+    pub const DEFAULT_SETTLE_US: u32 = 0;
+    #[cfg(feature = "pin-28")]
+    pub const DEFAULT_SETTLE_US: u32 = 1;
+
+    /// Maximum settle delay, in microseconds
+    pub const MAX_SETTLE_US: u32 = 1000;
+
     /// Creates a new ROM object.
     ///
     /// Arguments:
-    /// - `addr_pins`: An array of the physical pins connected to A0-A13.  A13
-    ///   is actually the 2364 chip select pin.
+    /// - `addr_pins`: An array of the physical pins connected to A0-A13 for 24
+    ///   pin ROMs, where A13 is actually the 2364 chip select pin, or A0-A15
+    ///   for 28 pin ROMs.
+    /// - `select_pins`: For 28 pin ROMs, the physical pins connected to OE and
+    ///   CE, in that order.
     /// - `data_pins`: An array of the physical pins connected to D0-D7.
     pub fn new(
         addr_pins: [Flex<'static>; AddressLines::NUM_ADDR_LINES],
+        #[cfg(feature = "pin-28")] select_pins: [Flex<'static>; AddressLines::NUM_SELECT_LINES],
         data_pins: [Flex<'static>; DataLines::NUM_DATA_LINES],
     ) -> Self {
         Self {
-            address: AddressLines { address: addr_pins },
+            address: AddressLines {
+                address: addr_pins,
+                #[cfg(feature = "pin-28")]
+                select: select_pins,
+            },
             data: DataLines::new(data_pins),
             buf: [0u8; 1 << AddressLines::NUM_ADDR_LINES],
+            #[cfg(feature = "pin-28")]
+            select: 0,
             matches: None,
             last_read_duration: None,
+            settle_us: Self::DEFAULT_SETTLE_US,
+            #[cfg(feature = "qa")]
+            qa: QaResults::new(),
         }
     }
 
@@ -112,8 +225,46 @@ impl Rom {
         self.data.init();
     }
 
-    // Reads the ROM data without any delays between settings address lines
-    // and reading the data.
+    /// Returns the delay between setting the address and sampling the data
+    /// when reading the ROM, in microseconds
+    pub fn settle_us(&self) -> u32 {
+        self.settle_us
+    }
+
+    /// Sets the delay between setting the address and sampling the data when
+    /// reading the ROM, in microseconds.  Slow ROMs, such as NMOS mask ROMs,
+    /// may need a longer delay to be read reliably.
+    ///
+    /// Returns [`Error::Settle`] if the delay exceeds
+    /// [`Self::MAX_SETTLE_US`].
+    #[allow(dead_code)]
+    pub fn set_settle_us(&mut self, settle_us: u32) -> Result<(), Error> {
+        if settle_us > Self::MAX_SETTLE_US {
+            return Err(Error::Settle);
+        }
+        self.settle_us = settle_us;
+        Ok(())
+    }
+
+    // Waits for the data lines to settle after setting the address
+    #[inline]
+    async fn settle(&self) {
+        if self.settle_us > 0 {
+            Timer::after_micros(self.settle_us as u64).await;
+        }
+    }
+
+    // Stores a byte read from the ROM in the image buffer.  In QA mode, first
+    // checks it against the last successful read.
+    #[inline]
+    fn store(&mut self, address: usize, byte: u8) {
+        #[cfg(feature = "qa")]
+        self.qa.check_byte(address, self.buf[address], byte);
+        self.buf[address] = byte;
+    }
+
+    // Reads the ROM data without any delays between setting address lines
+    // and reading the data, other than the settle delay.
     #[allow(dead_code)]
     async fn read_fast(&mut self) {
         let max_addr = 1 << AddressLines::NUM_ADDR_LINES;
@@ -124,7 +275,9 @@ impl Rom {
         // Now read the ROM
         for ii in 0..max_addr {
             self.address.set(ii);
-            self.buf[ii] = self.data.read();
+            self.settle().await;
+            let byte = self.data.read();
+            self.store(ii, byte);
         }
         self.address.init();
 
@@ -132,22 +285,36 @@ impl Rom {
         self.last_read_duration = Some(end - start);
     }
 
-    #[allow(dead_code)]
-    async fn read_with_cs_toggle(&mut self) {
+    // Reads the ROM data with OE and CE at the given levels - bit 0 is OE and
+    // bit 1 CE - driving them to the opposite levels between reads.
+    #[cfg(feature = "pin-28")]
+    async fn read_with_cs_toggle(&mut self, select: usize) {
         let max_addr = 1 << AddressLines::NUM_ADDR_LINES;
+        let deselect = !select & ((1 << AddressLines::NUM_SELECT_LINES) - 1);
+
+        // The last read is only a reference for QA if read with the same
+        // levels
+        #[cfg(feature = "qa")]
+        if select != self.select {
+            self.qa.reference = false;
+        }
+
         let start = Instant::now();
 
         for ii in 0..max_addr {
-            // Set CS inactive (all high or whatever makes it inactive)
-            self.address.set(0x3FFFF); // All CS lines inactive
+            // Deselect the ROM
+            self.address.select(deselect);
             Timer::after_micros(1).await;
 
-            // Set to target address with CS active
+            // Set to target address, and select the ROM
             self.address.set(ii);
-            Timer::after_micros(1).await;
-            self.buf[ii] = self.data.read();
+            self.address.select(select);
+            self.settle().await;
+            let byte = self.data.read();
+            self.store(ii, byte);
         }
         self.address.init();
+        self.select = select;
 
         let end = Instant::now();
         self.last_read_duration = Some(end - start);
@@ -160,7 +327,8 @@ impl Rom {
         let max_addr = 1 << AddressLines::NUM_ADDR_LINES;
         assert!(self.buf.len() == max_addr);
 
-        self.address.set(0x3FFF); // Set all address lines high
+        // Set all address lines high
+        self.address.set((1 << AddressLines::NUM_ADDR_LINES) - 1);
         Timer::after_micros(1).await;
 
         let start = Instant::now();
@@ -169,7 +337,8 @@ impl Rom {
         for ii in 0..max_addr {
             self.address.set(ii);
             Timer::after_micros(1).await;
-            self.buf[ii] = self.data.read();
+            let byte = self.data.read();
+            self.store(ii, byte);
             Timer::after_micros(1).await;
         }
         self.address.init();
@@ -178,14 +347,16 @@ impl Rom {
         self.last_read_duration = Some(end - start);
     }
 
-    // Identifies the read ROM image.  Must only be called after either
-    // `Self::read_fast()` or `Self::read_slow()`.
+    // Identifies the read ROM image.  Must only be called after one of the
+    // read functions, with the OE and CE levels the image was read with (0
+    // for 24 pin ROMs).
     //
-    // The existing ROM image buffer contains a 16KB image, based on the
-    // entire address space, including all CS lines, being enumerated.  This
-    // function takes that image, and turns it into an equivalent ROM image
-    // for every supported ROM type (including each possible chip select
-    // configuration) in turn.
+    // The existing ROM image buffer contains an image based on the entire
+    // address space, including all CS lines other than OE and CE, being
+    // enumerated.  This function takes that image, and turns it into an
+    // equivalent ROM image for every supported ROM type (including each
+    // possible chip select configuration) selected by those OE and CE levels
+    // in turn.
     //
     // It then attempted to match each image against the database.  Hence, it
     // should detect any of the different ROM types, if any of them match.
@@ -196,12 +367,7 @@ impl Rom {
     // - the actual bytes data (and hence SHA1/checksum) was a match, but the
     //   actually CS cofiguration required to serve it differed from that in
     //   the database (this is a "bad" match).
-    fn id(&mut self) {
-        // Scratch buffer
-        let mut buf = [0u8; RomType::max_size()];
-
-        let mut matches = Matches::default();
-
+    fn id(&self, select: usize, matches: &mut Matches) {
         for (ii, rom_type) in RomType::all().iter().enumerate() {
             #[cfg(feature = "pin-24")]
             if rom_type.rom_pins() == 28 {
@@ -212,18 +378,21 @@ impl Rom {
                 continue;
             }
 
-            // Build a temporary copy of the ROM data, based on this particular
-            // ROM type and CS behaviour
-            let size = rom_type.size();
-            #[allow(clippy::needless_range_loop)]
-            for jj in 0..size {
-                let addr = jj | rom_type.cs_active_mask();
-                buf[jj] = self.buf[addr];
+            // Skip ROM types which need different OE and CE levels
+            let cs_active_mask = rom_type.cs_active_mask();
+            if cs_active_mask >> AddressLines::NUM_ADDR_LINES != select {
+                continue;
             }
 
-            // Now use this copy to get the checksum/SHA1 digest
-            let sum: u32 = checksum(&buf[0..size]);
-            let sha1 = sha1_digest(&buf[0..size]);
+            // This ROM type's image, based on its CS behaviour.  Its CS lines
+            // are all above its address lines, so the image is contiguous.
+            let size = rom_type.size();
+            let base = cs_active_mask & ((1 << AddressLines::NUM_ADDR_LINES) - 1);
+            let buf = &self.buf[base..base + size];
+
+            // Get the checksum/SHA1 digest
+            let sum: u32 = checksum(buf);
+            let sha1 = sha1_digest(buf);
             let (mut good, mut bad) = identify_rom(rom_type, sum, sha1);
             info!(
                 "sum and sha1 for {}: {:#010X}, {}",
@@ -238,11 +407,34 @@ impl Rom {
                 rom_type: *rom_type,
                 sum,
                 sha1,
-                all_zeros: buf[0..size].iter().all(|&b| b == 0),
-                all_ones: buf[0..size].iter().all(|&b| b == 0xFF),
+                all_zeros: buf.iter().all(|&b| b == 0),
+                all_ones: buf.iter().all(|&b| b == 0xFF),
             };
         }
+    }
 
+    // Reads a 28 pin ROM with each combination of OE and CE levels in turn,
+    // identifying the ROM types each selects.  Starts with the levels the
+    // image buffer was last read with, and stops at the first levels giving a
+    // good match, so in QA mode each read is compared with the last
+    // successful one.
+    #[cfg(feature = "pin-28")]
+    async fn detect_28(&mut self) {
+        let mut matches = Matches::default();
+        let first = self.select;
+        let start = Instant::now();
+
+        for ii in 0..(1 << AddressLines::NUM_SELECT_LINES) {
+            let select = first ^ ii;
+            self.read_with_cs_toggle(select).await;
+            self.id(select, &mut matches);
+            if !matches.good.is_empty() {
+                break;
+            }
+        }
+
+        let end = Instant::now();
+        self.last_read_duration = Some(end - start);
         self.matches = Some(matches);
     }
 
@@ -253,10 +445,14 @@ impl Rom {
     /// do the read of the ROM.
     pub async fn detect(&mut self) {
         #[cfg(feature = "pin-24")]
-        self.read_fast().await;
+        {
+            self.read_fast().await;
+            let mut matches = Matches::default();
+            self.id(0, &mut matches);
+            self.matches = Some(matches);
+        }
         #[cfg(feature = "pin-28")]
-        self.read_with_cs_toggle().await;
-        self.id();
+        self.detect_28().await;
     }
 
     /// Returns last read duration
@@ -282,6 +478,32 @@ impl Rom {
         self.matches.as_ref().map(|m| &m.ids)
     }
 
+    /// Returns the last read ROM image - the whole address space, including
+    /// all CS lines other than OE and CE - or `None` if the ROM hasn't been
+    /// read yet.
+    #[allow(dead_code)]
+    pub fn image(&self) -> Option<&[u8]> {
+        self.matches.as_ref().map(|_| &self.buf[..])
+    }
+
+    /// Returns the results of all reads so far
+    #[cfg(feature = "qa")]
+    pub fn qa_results(&self) -> &QaResults {
+        &self.qa
+    }
+
+    /// Returns the results of all reads so far, with the current settle
+    /// delay, to send over the control channel
+    #[cfg(all(feature = "qa", feature = "control"))]
+    pub fn lab_qa_results(&self) -> LabQaResults {
+        LabQaResults {
+            successes: self.qa.successes,
+            failures: self.qa.failures,
+            settle_us: self.settle_us,
+            mismatches: self.qa.mismatches.clone(),
+        }
+    }
+
     /// Reads all the data from the ROM and tries to match it.
     ///
     /// Returns the first RomEntry found, if multiple exist.  Does not return
@@ -292,7 +514,11 @@ impl Rom {
         info!("Reading ROM...");
         self.detect().await;
         let dur = self.last_read_duration().unwrap();
-        debug!("Read took {}us", dur.as_micros());
+        debug!(
+            "Read took {}us, settle delay {}us",
+            dur.as_micros(),
+            self.settle_us
+        );
 
         // Output any good matches
         let good_matches = self.good_matches().unwrap();
@@ -330,6 +556,7 @@ impl Rom {
             } else {
                 info!("No matches found in database - ROM information follows:");
                 for id in ids {
+                    #[cfg(feature = "pin-24")]
                     if id.rom_type.rom_pins() == 28 {
                         continue;
                     }
@@ -344,29 +571,47 @@ impl Rom {
             }
         }
 
-        if !good_matches.is_empty() {
+        let result = if !good_matches.is_empty() {
             Some(good_matches[0].clone())
         } else {
             None
-        }
+        };
+
+        #[cfg(feature = "qa")]
+        self.qa.record_read(result.is_some());
+
+        result
     }
 }
 
 // Address - and CS lines - for the ROM object to use.
 struct AddressLines {
-    // Array of GPIOs corresponding to A0, A2, ... A13.
-    // A13 is the address line used for 2364's CS line.
+    // Array of GPIOs corresponding to A0, A1, ... A13 for 24 pin ROMs, where
+    // A13 is the address line used for 2364's CS line.  For 28 pin ROMs, A0,
+    // A1, ... A15.
     address: [Flex<'static>; Self::NUM_ADDR_LINES],
+
+    // For 28 pin ROMs, GPIOs corresponding to OE and CE, the 23128's CS2 and
+    // CS1 lines.  These are driven separately from the address lines, so the
+    // image buffer only covers A0-A15.
+    #[cfg(feature = "pin-28")]
+    select: [Flex<'static>; Self::NUM_SELECT_LINES],
 }
 
 impl AddressLines {
     #[cfg(feature = "pin-24")]
     const NUM_ADDR_LINES: usize = 14;
     #[cfg(feature = "pin-28")]
-    const NUM_ADDR_LINES: usize = 18;
+    const NUM_ADDR_LINES: usize = 16;
+    #[cfg(feature = "pin-28")]
+    const NUM_SELECT_LINES: usize = 2;
 
     fn init(&mut self) {
-        for pin in self.address.iter_mut() {
+        #[cfg(feature = "pin-24")]
+        let pins = self.address.iter_mut();
+        #[cfg(feature = "pin-28")]
+        let pins = self.address.iter_mut().chain(self.select.iter_mut());
+        for pin in pins {
             #[cfg(not(feature = "rp2350"))]
             pin.set_as_input(Pull::None);
             #[cfg(feature = "rp2350")]
@@ -382,12 +627,27 @@ impl AddressLines {
         assert!(address < (1 << Self::NUM_ADDR_LINES));
 
         // Set address pins as outputs and drive them
-        for (i, pin) in self.address.iter_mut().enumerate() {
+        Self::drive(&mut self.address, address);
+    }
+
+    // Drives OE (bit 0) and CE (bit 1) to the given levels
+    #[cfg(feature = "pin-28")]
+    #[inline]
+    fn select(&mut self, select: usize) {
+        assert!(select < (1 << Self::NUM_SELECT_LINES));
+        Self::drive(&mut self.select, select);
+    }
+
+    // Sets the pins as outputs and drives each to the corresponding bit of
+    // value
+    #[inline]
+    fn drive(pins: &mut [Flex<'static>], value: usize) {
+        for (i, pin) in pins.iter_mut().enumerate() {
             #[cfg(not(feature = "rp2350"))]
             pin.set_as_output(Speed::High);
             #[cfg(feature = "rp2350")]
             pin.set_as_output();
-            if address & (1 << i) != 0 {
+            if value & (1 << i) != 0 {
                 pin.set_high();
             } else {
                 pin.set_low();
@@ -7,12 +7,15 @@
 #![allow(static_mut_refs)]
 
 use alloc::string::String;
+use core::sync::atomic::{AtomicBool, Ordering};
 use embassy_executor::Spawner;
+use embassy_futures::select::{Either, select};
 use embassy_stm32::peripherals::{self, USB_OTG_FS};
 use embassy_stm32::usb::{Config, DmPin, DpPin, Driver};
 use embassy_stm32::{Peri, bind_interrupts, usb};
 use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::channel::Channel;
+use embassy_time::{Duration, with_timeout};
 use embassy_usb::class::cdc_acm::{CdcAcmClass, Receiver, Sender, State};
 use embassy_usb::{Builder, Config as UsbConfig, UsbDevice};
 use log::{LevelFilter, Log, Metadata, Record};
@@ -34,6 +37,36 @@ static LOG_CHANNEL: Channel<CriticalSectionRawMutex, String, 8> = Channel::new()
 // Channel for key-presses from USB
 static KEY_CHANNEL: Channel<CriticalSectionRawMutex, u8, 8> = Channel::new();
 
+// Channel for frames of data streamed over USB, such as ROM image dumps
+static DATA_CHANNEL: Channel<CriticalSectionRawMutex, DataPacket, 4> = Channel::new();
+
+// Whether a host is connected to the CDC ACM interface
+static CONNECTED: AtomicBool = AtomicBool::new(false);
+
+// Size of packets streamed over USB - the CDC ACM max packet size
+const PACKET_SIZE: usize = 64;
+
+// How long to wait for each packet of a frame to be accepted or to arrive,
+// before giving up on the frame
+const FRAME_TIMEOUT: Duration = Duration::from_secs(1);
+
+// A packet of a frame streamed over USB.  `last` marks the end of the frame.
+struct DataPacket {
+    data: [u8; PACKET_SIZE],
+    len: usize,
+    last: bool,
+}
+
+impl DataPacket {
+    const fn new() -> Self {
+        Self {
+            data: [0; PACKET_SIZE],
+            len: 0,
+            last: false,
+        }
+    }
+}
+
 pub struct Usb {
     cdc_acm: CdcAcmClass<'static, Driver<'static, USB_OTG_FS>>,
     usb_device: UsbDevice<'static, Driver<'static, USB_OTG_FS>>,
@@ -100,12 +133,45 @@ async fn usb_task(mut usb_device: UsbDevice<'static, Driver<'static, USB_OTG_FS>
 async fn logger(mut sender: Sender<'static, Driver<'static, USB_OTG_FS>>) -> ! {
     loop {
         sender.wait_connection().await;
+
+        // Discard any part of a frame queued before the host connected
+        DATA_CHANNEL.clear();
+        CONNECTED.store(true, Ordering::Relaxed);
+
         loop {
-            let msg = recv_log().await;
-            if sender.write_packet(msg.as_bytes()).await.is_err() {
-                break;
+            match select(recv_log(), DATA_CHANNEL.receive()).await {
+                Either::First(msg) => {
+                    if sender.write_packet(msg.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+                Either::Second(packet) => {
+                    if write_frame(&mut sender, packet).await.is_err() {
+                        break;
+                    }
+                }
             }
         }
+
+        CONNECTED.store(false, Ordering::Relaxed);
+    }
+}
+
+// Writes a frame, starting with the given packet, without interleaving any
+// log messages.  Gives up on the frame if the rest of it doesn't arrive.
+async fn write_frame(
+    sender: &mut Sender<'static, Driver<'static, USB_OTG_FS>>,
+    mut packet: DataPacket,
+) -> Result<(), embassy_usb::driver::EndpointError> {
+    loop {
+        sender.write_packet(&packet.data[..packet.len]).await?;
+        if packet.last {
+            return Ok(());
+        }
+        packet = match with_timeout(FRAME_TIMEOUT, DATA_CHANNEL.receive()).await {
+            Ok(packet) => packet,
+            Err(_) => return Ok(()),
+        };
     }
 }
 
@@ -161,6 +227,43 @@ pub async fn recv_key() -> u8 {
     KEY_CHANNEL.receive().await
 }
 
+/// Streams a frame made up of the given parts over USB.  The frame is
+/// written without any log messages interleaved.
+///
+/// Returns `false` if no host is connected, or the host stops reading the
+/// frame.
+pub async fn send_frame(parts: &[&[u8]]) -> bool {
+    if !CONNECTED.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    let mut remaining: usize = parts.iter().map(|part| part.len()).sum();
+    let mut packet = DataPacket::new();
+    for part in parts {
+        let mut part = *part;
+        while !part.is_empty() {
+            let len = (PACKET_SIZE - packet.len).min(part.len());
+            packet.data[packet.len..packet.len + len].copy_from_slice(&part[..len]);
+            packet.len += len;
+            remaining -= len;
+            part = &part[len..];
+
+            if packet.len == PACKET_SIZE || remaining == 0 {
+                packet.last = remaining == 0;
+                if with_timeout(FRAME_TIMEOUT, DATA_CHANNEL.send(packet))
+                    .await
+                    .is_err()
+                {
+                    return false;
+                }
+                packet = DataPacket::new();
+            }
+        }
+    }
+
+    true
+}
+
 const BOOTLOADER_MAGIC: u32 = 0x1234567F;
 const MAGIC_ADDR: u32 = 0x2001_0000;
 
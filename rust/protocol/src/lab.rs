@@ -37,6 +37,18 @@ pub enum Command {
     /// Data follows - see [`GetRawData`].
     GetRawData = 0x0000_0002,
 
+    /// Retrieve the results of ROM reads so far, in QA mode.  No data
+    /// follows.  Responded to with [`LabQaResults`].
+    QaResults = 0x0000_0003,
+
+    /// Stream the last read ROM image over USB, framed as described by
+    /// [`LabRomDumpHeader`].  No data follows.
+    DumpRom = 0x0000_0004,
+
+    /// Set the delay between setting the address and sampling the data when
+    /// reading the ROM.  Data follows - see [`SetSettleDelay`].
+    SetSettleDelay = 0x0000_0005,
+
     /// Unknown command, do not use.  No data follows.
     Unknown = 0xFFFF_FFFF,
 }
@@ -46,6 +58,9 @@ impl From<u32> for Command {
         match value {
             0x0000_0000 => Command::Ping,
             0x0000_0001 => Command::ReadRom,
+            0x0000_0003 => Command::QaResults,
+            0x0000_0004 => Command::DumpRom,
+            0x0000_0005 => Command::SetSettleDelay,
 
             _ => Command::Unknown,
         }
@@ -86,6 +101,24 @@ pub enum Response {
     /// ROM (probably) connected but not recognised.  No data follows
     RomNotRecognised = 0x0000_0002,
 
+    /// QaResults successful response.  Following this word are the QA
+    /// results.  See [`LabQaResults`].
+    /// - 32-bit count of successful reads, little endian encoded
+    /// - 32-bit count of failed reads, little endian encoded
+    /// - 32-bit settle delay used for reads, in microseconds, little endian
+    ///   encoded
+    /// - 32-bit count of mismatches, little endian encoded
+    /// - Each mismatch - 32-bit address, little endian encoded, followed by
+    ///   the expected and actual bytes
+    QaResults = 0x0000_0003,
+
+    /// DumpRom successful response - the ROM image has been streamed over
+    /// USB.  No data follows.
+    RomDumped = 0x0000_0004,
+
+    /// SetSettleDelay successful response.  No data follows.
+    SettleDelaySet = 0x0000_0005,
+
     /// One ROM Lab hit an error
     Error = 0x8000_0000,
 
@@ -93,6 +126,11 @@ pub enum Response {
     /// unrecognised
     NoRom = 0x8000_0001,
 
+    /// No ROM image to dump, as the ROM hasn't been read yet.  A frame with
+    /// [`LabRomDumpStatus::NoData`] is still streamed over USB.  No data
+    /// follows.
+    NoData = 0x8000_0002,
+
     Unknown = 0xFFFF_FFFF,
 }
 
@@ -113,8 +151,12 @@ impl From<u32> for Response {
             0x0000_0000 => Response::Pong,
             0x0000_0001 => Response::RomEntry,
             0x0000_0002 => Response::RomNotRecognised,
+            0x0000_0003 => Response::QaResults,
+            0x0000_0004 => Response::RomDumped,
+            0x0000_0005 => Response::SettleDelaySet,
             0x8000_0000 => Response::Error,
             0x8000_0001 => Response::NoRom,
+            0x8000_0002 => Response::NoData,
             _ => Response::Unknown,
         }
     }
@@ -167,6 +209,45 @@ impl GetRawData {
     }
 }
 
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct SetSettleDelay {
+    /// Delay between setting the address and sampling the data, in
+    /// microseconds
+    pub settle_us: u32,
+}
+
+impl SetSettleDelay {
+    const fn binary_size() -> usize {
+        4
+    }
+
+    pub fn from_buffer(buf: &[u8]) -> Result<Self, Error> {
+        if buf.len() < Self::binary_size() {
+            return Err(Error::BufferTooSmall);
+        }
+        let settle_us = u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
+
+        Ok(Self { settle_us })
+    }
+
+    pub fn to_buffer(&self) -> Result<Vec<u8>, Error> {
+        let mut pos = 0;
+
+        let size = Self::binary_size() + Command::size();
+        let mut buf = vec![0; size];
+
+        // Write Command code
+        let cmd_u32 = Command::SetSettleDelay as u32;
+        buf[pos..pos + 4].copy_from_slice(&cmd_u32.to_le_bytes());
+        pos += 4;
+
+        // Write the delay (little endian)
+        buf[pos..pos + 4].copy_from_slice(&self.settle_us.to_le_bytes());
+
+        Ok(buf)
+    }
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct LabRomType {
     #[serde(rename = "ROM Type")]
@@ -397,3 +478,198 @@ impl From<RomEntry> for LabRomEntry {
         }
     }
 }
+
+/// A byte read differently from the last successful read of the ROM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LabQaMismatch {
+    #[serde(rename = "Address")]
+    pub address: u32,
+    #[serde(rename = "Expected")]
+    pub expected: u8,
+    #[serde(rename = "Actual")]
+    pub actual: u8,
+}
+
+impl LabQaMismatch {
+    const fn binary_size() -> usize {
+        4 + 1 + 1
+    }
+}
+
+/// Response data for QaResults
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LabQaResults {
+    #[serde(rename = "Successes")]
+    pub successes: u32,
+    #[serde(rename = "Failures")]
+    pub failures: u32,
+    #[serde(rename = "Settle Delay (us)")]
+    pub settle_us: u32,
+    #[serde(rename = "Mismatches")]
+    pub mismatches: Vec<LabQaMismatch>,
+}
+
+impl LabQaResults {
+    /// Get QaResults from the appropriate response
+    pub fn from_buffer(buf: &[u8]) -> Result<Self, Error> {
+        let mut pos = 0;
+
+        // Get Response code
+        if buf.len() < 4 {
+            return Err(Error::BufferTooSmall);
+        }
+        let rsp_u32 = u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
+        let response: Response = rsp_u32.into();
+        pos += 4;
+        if response != Response::QaResults {
+            warn!("Unexpected response code for QaResults: {rsp_u32:#010X} {response:?}");
+            return Err(Error::InvalidResponse);
+        }
+
+        // Parse the success and failure counts, settle delay and mismatch
+        // count (little endian)
+        if buf.len() < pos + 16 {
+            warn!("Buffer too short for QA counts");
+            return Err(Error::BufferTooSmall);
+        }
+        let read_u32 =
+            |pos: usize| u32::from_le_bytes([buf[pos], buf[pos + 1], buf[pos + 2], buf[pos + 3]]);
+        let successes = read_u32(pos);
+        let failures = read_u32(pos + 4);
+        let settle_us = read_u32(pos + 8);
+        let count = read_u32(pos + 12) as usize;
+        pos += 16;
+
+        // Parse the mismatches
+        if buf.len() < pos + count * LabQaMismatch::binary_size() {
+            warn!("Buffer too short for {count} QA mismatches");
+            return Err(Error::BufferTooSmall);
+        }
+        let mut mismatches = Vec::with_capacity(count);
+        for _ in 0..count {
+            mismatches.push(LabQaMismatch {
+                address: read_u32(pos),
+                expected: buf[pos + 4],
+                actual: buf[pos + 5],
+            });
+            pos += LabQaMismatch::binary_size();
+        }
+
+        Ok(Self {
+            successes,
+            failures,
+            settle_us,
+            mismatches,
+        })
+    }
+
+    fn buf_size(&self) -> usize {
+        Response::size() + 16 + self.mismatches.len() * LabQaMismatch::binary_size()
+    }
+
+    pub fn to_buffer(&self) -> Result<Vec<u8>, Error> {
+        let mut pos = 0;
+
+        let size = self.buf_size();
+        let mut buf = vec![0; size];
+
+        // Write Response code
+        let rsp_u32 = Response::QaResults as u32;
+        buf[pos..pos + 4].copy_from_slice(&rsp_u32.to_le_bytes());
+        pos += 4;
+
+        // Write the success and failure counts, settle delay and mismatch
+        // count (little endian)
+        let count = u32::try_from(self.mismatches.len()).map_err(|_| Error::InvalidData)?;
+        for value in [self.successes, self.failures, self.settle_us, count] {
+            buf[pos..pos + 4].copy_from_slice(&value.to_le_bytes());
+            pos += 4;
+        }
+
+        // Write the mismatches
+        for mismatch in &self.mismatches {
+            buf[pos..pos + 4].copy_from_slice(&mismatch.address.to_le_bytes());
+            buf[pos + 4] = mismatch.expected;
+            buf[pos + 5] = mismatch.actual;
+            pos += LabQaMismatch::binary_size();
+        }
+
+        Ok(buf)
+    }
+}
+
+/// Status of a ROM image dump streamed over USB
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[repr(u32)]
+pub enum LabRomDumpStatus {
+    /// The ROM image follows the header
+    Data = 0x0000_0000,
+
+    /// The ROM hasn't been read yet, so no image follows the header
+    NoData = 0x0000_0001,
+}
+
+/// Header of a ROM image dump streamed over USB.
+///
+/// The dump is framed as:
+/// - This header - [`LabRomDumpHeader::MAGIC`], then the 32-bit status and
+///   32-bit length of the image, little endian encoded
+/// - The ROM image, `length` bytes
+/// - 32-bit wrapping checksum of the ROM image, little endian encoded
+///
+/// The header is followed by an empty image and a zero checksum if the
+/// status is [`LabRomDumpStatus::NoData`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LabRomDumpHeader {
+    #[serde(rename = "Status")]
+    pub status: LabRomDumpStatus,
+    #[serde(rename = "Length")]
+    pub length: u32,
+}
+
+impl LabRomDumpHeader {
+    /// Magic marking the start of a dump, distinguishing it from log output
+    /// on the same USB interface
+    pub const MAGIC: [u8; 4] = *b"ORLD";
+
+    /// Size of the header in bytes
+    pub const fn size() -> usize {
+        4 + 4 + 4
+    }
+
+    /// Size of the checksum following the image, in bytes
+    pub const fn trailer_size() -> usize {
+        4
+    }
+
+    pub fn from_buffer(buf: &[u8]) -> Result<Self, Error> {
+        if buf.len() < Self::size() {
+            return Err(Error::BufferTooSmall);
+        }
+        if buf[0..4] != Self::MAGIC {
+            warn!("ROM dump header magic not found");
+            return Err(Error::InvalidData);
+        }
+        let status = match u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]) {
+            0x0000_0000 => LabRomDumpStatus::Data,
+            0x0000_0001 => LabRomDumpStatus::NoData,
+            status => {
+                warn!("Unknown ROM dump status: {status:#010X}");
+                return Err(Error::InvalidData);
+            }
+        };
+        let length = u32::from_le_bytes([buf[8], buf[9], buf[10], buf[11]]);
+
+        Ok(Self { status, length })
+    }
+
+    pub fn to_bytes(&self, buf: &mut [u8]) -> Result<(), Error> {
+        if buf.len() < Self::size() {
+            return Err(Error::BufferTooSmall);
+        }
+        buf[0..4].copy_from_slice(&Self::MAGIC);
+        buf[4..8].copy_from_slice(&(self.status as u32).to_le_bytes());
+        buf[8..12].copy_from_slice(&self.length.to_le_bytes());
+        Ok(())
+    }
+}
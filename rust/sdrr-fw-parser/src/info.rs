@@ -8,6 +8,7 @@
 
 use deku::prelude::*;
 
+use onerom_config::chip::ChipType;
 use onerom_config::fw::FirmwareVersion;
 use onerom_config::hw::{Board, Model};
 use onerom_config::mcu::Variant as McuVariant;
@@ -85,6 +86,15 @@ pub struct SdrrInfo {
     /// Whether explicit metadata is included
     pub metadata_present: bool,
 
+    /// Version of onerom-gen which generated the metadata, if recorded
+    #[serde(default)]
+    pub generator_version: Option<GeneratorVersion>,
+
+    /// Note stamped into the metadata when it was built, such as a build
+    /// date or asset tag, if recorded
+    #[serde(default)]
+    pub metadata_note: Option<String>,
+
     /// Decoded hardware information
     pub version: FirmwareVersion,
     pub board: Option<Board>,
@@ -96,6 +106,71 @@ pub struct SdrrInfo {
 }
 
 impl SdrrInfo {
+    /// Classifies the version of onerom-gen which generated this image's
+    /// metadata against the version used by this tool.  See
+    /// [`GeneratorVersion::compatibility()`].
+    pub fn tool_compatibility(&self) -> Compatibility {
+        match &self.generator_version {
+            Some(version) => version.compatibility(),
+            None => Compatibility::Unknown,
+        }
+    }
+
+    /// Checks whether this image's ROM sets can be served by the given
+    /// board - that every ROM type is supported, and that each multi-ROM or
+    /// banked set is supported and has no more ROMs than the board's X lines
+    /// allow.  Used to warn before flashing an image built for a different,
+    /// but similar, board.
+    ///
+    /// Returns a description of every problem found.
+    pub fn fits_board(&self, board: &Board) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        let num_x = [board.pin_x1(), board.pin_x2()]
+            .iter()
+            .filter(|&&pin| pin != 255)
+            .count();
+
+        for (ii, set) in self.rom_sets.iter().enumerate() {
+            for rom in set.roms.iter() {
+                let supported = ChipType::try_from_str(&format!("{}", rom.rom_type))
+                    .is_some_and(|chip_type| board.supports_chip_type(chip_type));
+                if !supported {
+                    problems.push(format!(
+                        "ROM set {ii}: {} ROMs not supported by board {}",
+                        rom.rom_type,
+                        board.name()
+                    ));
+                }
+            }
+
+            let count = set.rom_count as usize;
+            if count > 1 {
+                let (kind, supported, max) = if set.serve == SdrrServe::AddrOnAnyCs {
+                    ("multi-ROM", board.supports_multi_chip_sets(), 1 + num_x)
+                } else {
+                    ("banked", board.supports_banked_roms(), 1 << num_x)
+                };
+                if !supported {
+                    problems.push(format!(
+                        "ROM set {ii}: {kind} sets not supported by board {}",
+                        board.name()
+                    ));
+                } else if count > max {
+                    problems.push(format!(
+                        "ROM set {ii}: {count} ROMs in {kind} set, board {} supports at most {max}",
+                        board.name()
+                    ));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
     /// Demangles a byte from the physical pin representation to the logical
     /// representation which is served on D0-D7.  Use when looking up a byte
     /// from the ROM image data to get the "real" byte.
@@ -375,6 +450,270 @@ impl SdrrInfo {
     }
 }
 
+impl SdrrInfo {
+    /// Version of the JSON emitted by [`Self::to_stable_json()`], included
+    /// as its `info_version` key.
+    ///
+    /// Bumped whenever a key is removed or renamed, or its meaning changes.
+    /// Adding keys does not change the version.
+    pub const INFO_VERSION: u32 = 1;
+
+    /// Returns the stable subset of this information - see
+    /// [`StableSdrrInfo`].
+    pub fn to_stable(&self) -> StableSdrrInfo {
+        StableSdrrInfo {
+            info_version: Self::INFO_VERSION,
+            firmware_version: format!(
+                "{}.{}.{}",
+                self.major_version, self.minor_version, self.patch_version
+            ),
+            build_number: self.build_number,
+            build_date: self.build_date.clone(),
+            commit: String::from_utf8_lossy(&self.commit)
+                .trim_end_matches('\0')
+                .into(),
+            hw_rev: self.hw_rev.clone(),
+            board: self.board.map(|board| board.name().into()),
+            model: self.model.map(|model| model.name().into()),
+            mcu_variant: self.mcu_variant.map(|variant| format!("{variant}")),
+            freq_mhz: self.freq,
+            overclock: self.overclock,
+            swd_enabled: self.swd_enabled,
+            boot_logging_enabled: self.boot_logging_enabled,
+            metadata_present: self.metadata_present,
+            generator_version: self.generator_version.map(|version| format!("{version}")),
+            metadata_note: self.metadata_note.clone(),
+            rom_sets: self
+                .rom_sets
+                .iter()
+                .enumerate()
+                .map(|(index, set)| StableRomSet {
+                    index,
+                    serve: format!("{}", set.serve),
+                    size: set.size,
+                    roms: set
+                        .roms
+                        .iter()
+                        .map(|rom| StableRom {
+                            rom_type: format!("{}", rom.rom_type),
+                            cs1: format!("{}", rom.cs1_state),
+                            cs2: format!("{}", rom.cs2_state),
+                            cs3: format!("{}", rom.cs3_state),
+                            filename: rom.filename.clone(),
+                            source_size: rom.source_size,
+                            source_checksum: rom.source_checksum,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            parse_errors: self
+                .parse_errors
+                .iter()
+                .map(|error| format!("{error}"))
+                .collect(),
+        }
+    }
+
+    /// Returns a documented, ordered subset of this information as pretty
+    /// printed JSON, which tooling can depend on.  See [`StableSdrrInfo`]
+    /// for the keys, and [`Self::INFO_VERSION`] for how they are versioned.
+    ///
+    /// Unlike serializing `SdrrInfo` directly, the keys and their order don't
+    /// change as this crate's internal structures do.
+    pub fn to_stable_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(&self.to_stable())
+            .map_err(|e| format!("Failed to serialize firmware information: {e}"))
+    }
+}
+
+/// Stable subset of [`SdrrInfo`], emitted by
+/// [`SdrrInfo::to_stable_json()`].
+///
+/// Keys are emitted in the order declared here.  Enumerated values are
+/// emitted as their display strings.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StableSdrrInfo {
+    /// Version of this structure - [`SdrrInfo::INFO_VERSION`]
+    pub info_version: u32,
+
+    /// Firmware version, as "major.minor.patch"
+    pub firmware_version: String,
+
+    /// Firmware build number
+    pub build_number: u16,
+
+    /// Firmware build date, if it could be parsed
+    pub build_date: Option<String>,
+
+    /// Git commit the firmware was built from
+    pub commit: String,
+
+    /// Hardware revision string, if it could be parsed
+    pub hw_rev: Option<String>,
+
+    /// Board name, such as "ice-24-usb-h", if decoded
+    pub board: Option<String>,
+
+    /// Model, "Fire" or "Ice", if decoded
+    pub model: Option<String>,
+
+    /// MCU variant, if decoded
+    pub mcu_variant: Option<String>,
+
+    /// Target clock frequency, in MHz
+    pub freq_mhz: u16,
+
+    /// Whether overclocking is enabled
+    pub overclock: bool,
+
+    /// Whether SWD is enabled
+    pub swd_enabled: bool,
+
+    /// Whether boot logging is enabled
+    pub boot_logging_enabled: bool,
+
+    /// Whether explicit ROM metadata is included
+    pub metadata_present: bool,
+
+    /// Version of onerom-gen which generated the metadata, as
+    /// "major.minor.patch", if recorded
+    pub generator_version: Option<String>,
+
+    /// Note stamped into the metadata when it was built, if recorded
+    pub metadata_note: Option<String>,
+
+    /// ROM sets, in firmware order
+    pub rom_sets: Vec<StableRomSet>,
+
+    /// Descriptions of any errors encountered parsing the firmware
+    pub parse_errors: Vec<String>,
+}
+
+/// Stable subset of [`SdrrRomSet`] - see [`StableSdrrInfo`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StableRomSet {
+    /// Index of the set, from 0
+    pub index: usize,
+
+    /// Serving algorithm
+    pub serve: String,
+
+    /// Size of the set's ROM image data in bytes
+    pub size: u32,
+
+    /// ROMs in the set
+    pub roms: Vec<StableRom>,
+}
+
+/// Stable subset of [`SdrrRomInfo`] - see [`StableSdrrInfo`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct StableRom {
+    /// ROM type, such as "2364"
+    pub rom_type: String,
+
+    /// CS1 line state
+    pub cs1: String,
+
+    /// CS2 line state
+    pub cs2: String,
+
+    /// CS3 line state
+    pub cs3: String,
+
+    /// Filename used to create the ROM image, if present
+    pub filename: Option<String>,
+
+    /// Size of the source file, if present
+    pub source_size: Option<u32>,
+
+    /// CRC-32 of the source file, if present
+    pub source_checksum: Option<u32>,
+}
+
+/// Version of onerom-gen which generated an image's metadata
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub struct GeneratorVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+}
+
+impl GeneratorVersion {
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Returns the version of onerom-gen used by this tool
+    pub fn tool() -> Self {
+        let mut parts = onerom_gen::crate_version()
+            .split(['.', '-', '+'])
+            .map(|part| part.parse::<u16>().unwrap_or(0));
+        Self::new(
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+        )
+    }
+
+    /// Classifies this generator version against the version used by this
+    /// tool.
+    pub fn compatibility(&self) -> Compatibility {
+        self.compatibility_with(&Self::tool())
+    }
+
+    /// Classifies this generator version against the given tool version.
+    pub fn compatibility_with(&self, tool: &GeneratorVersion) -> Compatibility {
+        match tool.cmp(self) {
+            core::cmp::Ordering::Equal => Compatibility::Same,
+            core::cmp::Ordering::Less => Compatibility::OlderTool,
+            core::cmp::Ordering::Greater => Compatibility::NewerTool,
+        }
+    }
+
+    /// Returns whether this tool should be able to fully understand an image
+    /// generated by this generator version - i.e. the image was not generated
+    /// by a newer version than this tool uses.
+    pub fn is_compatible(&self) -> bool {
+        self.compatibility().is_compatible()
+    }
+}
+
+impl core::fmt::Display for GeneratorVersion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// How the version of onerom-gen which generated an image compares to that
+/// used by this tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Compatibility {
+    /// Generated by the same version as this tool uses
+    Same,
+
+    /// This tool is older than the generator - the image may contain
+    /// metadata this tool doesn't understand
+    OlderTool,
+
+    /// This tool is newer than the generator
+    NewerTool,
+
+    /// The image doesn't record its generator version
+    Unknown,
+}
+
+impl Compatibility {
+    /// Returns `false` only if the image is known to be generated by a newer
+    /// version than this tool uses.
+    pub fn is_compatible(&self) -> bool {
+        !matches!(self, Compatibility::OlderTool)
+    }
+}
+
 /// Extra information about this One ROM
 ///
 /// Reflects `sdrr_extra_info` from `sdrr/include/config_base.h`
@@ -434,6 +773,11 @@ pub struct SdrrRomSet {
 
     /// Firmware configuration overrides for this ROM set, if any.
     pub firmware_overrides: Option<FirmwareConfig>,
+
+    /// Short human readable signature of this set's content, if present.
+    /// Only written when boot logging is enabled.
+    #[serde(default)]
+    pub signature: Option<String>,
 }
 
 /// Information about a single ROM in an SDRR firmware
@@ -455,6 +799,16 @@ pub struct SdrrRomInfo {
 
     /// The filename used to create the ROM image (if present in the firmware)
     pub filename: Option<String>,
+
+    /// The size of the source file used to create the ROM image (if present
+    /// in the firmware)
+    #[serde(default)]
+    pub source_size: Option<u32>,
+
+    /// The CRC-32 of the source file used to create the ROM image (if present
+    /// in the firmware)
+    #[serde(default)]
+    pub source_checksum: Option<u32>,
 }
 
 /// SDRR pin configuration
@@ -593,3 +947,226 @@ impl SdrrPins {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generator_version_compatibility() {
+        let tool = GeneratorVersion::new(0, 6, 2);
+
+        let cases = [
+            (GeneratorVersion::new(0, 6, 2), Compatibility::Same),
+            (GeneratorVersion::new(0, 6, 3), Compatibility::OlderTool),
+            (GeneratorVersion::new(1, 0, 0), Compatibility::OlderTool),
+            (GeneratorVersion::new(0, 6, 1), Compatibility::NewerTool),
+            (GeneratorVersion::new(0, 5, 9), Compatibility::NewerTool),
+        ];
+        for (generator, expected) in cases {
+            assert_eq!(
+                generator.compatibility_with(&tool),
+                expected,
+                "generator {generator}, tool {tool}"
+            );
+        }
+
+        assert_eq!(GeneratorVersion::tool().compatibility(), Compatibility::Same);
+        assert!(GeneratorVersion::tool().is_compatible());
+
+        assert!(Compatibility::Same.is_compatible());
+        assert!(Compatibility::NewerTool.is_compatible());
+        assert!(Compatibility::Unknown.is_compatible());
+        assert!(!Compatibility::OlderTool.is_compatible());
+    }
+
+    // Minimal parsed image containing the given ROM sets
+    fn info_with_rom_sets(rom_sets: Vec<SdrrRomSet>) -> SdrrInfo {
+        SdrrInfo {
+            major_version: 0,
+            minor_version: 6,
+            patch_version: 0,
+            build_number: 0,
+            commit: [0; 8],
+            stm_line: McuLine::F411,
+            stm_storage: McuStorage::StorageC,
+            freq: 0,
+            overclock: false,
+            swd_enabled: true,
+            preload_image_to_ram: false,
+            bootloader_capable: false,
+            status_led_enabled: false,
+            boot_logging_enabled: false,
+            mco_enabled: false,
+            rom_set_count: rom_sets.len() as u8,
+            count_rom_access: false,
+            boot_config: [0; 4],
+            build_date: None,
+            hw_rev: None,
+            rom_sets,
+            pins: None,
+            parse_errors: Vec::new(),
+            extra_info: None,
+            metadata_present: true,
+            generator_version: None,
+            metadata_note: None,
+            version: FirmwareVersion::new(0, 6, 0, 0),
+            board: Some(Board::Ice24UsbH),
+            model: None,
+            mcu_variant: None,
+            runtime_info_ptr: 0,
+        }
+    }
+
+    fn rom_set(rom_type: SdrrRomType, count: u8, serve: SdrrServe) -> SdrrRomSet {
+        let rom = SdrrRomInfo {
+            rom_type,
+            cs1_state: SdrrCsState::ActiveLow,
+            cs2_state: SdrrCsState::NotUsed,
+            cs3_state: SdrrCsState::NotUsed,
+            filename: None,
+            source_size: None,
+            source_checksum: None,
+        };
+        SdrrRomSet {
+            data_ptr: 0,
+            size: 0,
+            roms: vec![rom; count as usize],
+            rom_count: count,
+            serve,
+            multi_rom_cs1_state: SdrrCsState::ActiveLow,
+            firmware_overrides: None,
+            signature: None,
+        }
+    }
+
+    #[test]
+    fn test_fits_board() {
+        let info = info_with_rom_sets(vec![
+            rom_set(SdrrRomType::Rom2364, 1, SdrrServe::AddrOnCs),
+            rom_set(SdrrRomType::Rom2364, 3, SdrrServe::AddrOnAnyCs),
+        ]);
+
+        // Built for a multi-capable board
+        assert_eq!(info.fits_board(&Board::Ice24UsbH), Ok(()));
+
+        // Ice 24 D has no X lines, so can't serve the multi-ROM set
+        let problems = info.fits_board(&Board::Ice24D).unwrap_err();
+        assert_eq!(problems.len(), 1, "{problems:?}");
+        assert!(problems[0].starts_with("ROM set 1: multi-ROM"), "{problems:?}");
+
+        // Too many ROMs in a multi-ROM set for the board's X lines
+        let info = info_with_rom_sets(vec![rom_set(
+            SdrrRomType::Rom2364,
+            4,
+            SdrrServe::AddrOnAnyCs,
+        )]);
+        assert!(info.fits_board(&Board::Ice24UsbH).is_err());
+
+        // 28 pin ROMs on a 24 pin board
+        let info = info_with_rom_sets(vec![rom_set(
+            SdrrRomType::Rom27512,
+            1,
+            SdrrServe::AddrOnCs,
+        )]);
+        assert!(info.fits_board(&Board::Ice24UsbH).is_err());
+    }
+
+    #[test]
+    fn test_stable_json() {
+        let mut info =
+            info_with_rom_sets(vec![rom_set(SdrrRomType::Rom2364, 1, SdrrServe::AddrOnCs)]);
+        info.commit = *b"abc1234\0";
+        info.generator_version = Some(GeneratorVersion::new(0, 6, 2));
+
+        let json = info.to_stable_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        // Pin the top-level keys - changing these requires bumping
+        // INFO_VERSION
+        let mut keys: Vec<&str> = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(|key| key.as_str())
+            .collect();
+        keys.sort();
+        let mut expected = vec![
+            "info_version",
+            "firmware_version",
+            "build_number",
+            "build_date",
+            "commit",
+            "hw_rev",
+            "board",
+            "model",
+            "mcu_variant",
+            "freq_mhz",
+            "overclock",
+            "swd_enabled",
+            "boot_logging_enabled",
+            "metadata_present",
+            "generator_version",
+            "metadata_note",
+            "rom_sets",
+            "parse_errors",
+        ];
+        expected.sort();
+        assert_eq!(keys, expected);
+
+        // Keys are emitted in declaration order
+        let positions: Vec<usize> = ["\"info_version\"", "\"firmware_version\"", "\"rom_sets\""]
+            .iter()
+            .map(|key| json.find(key).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]), "{json}");
+
+        assert_eq!(value["info_version"], SdrrInfo::INFO_VERSION);
+        assert_eq!(value["firmware_version"], "0.6.0");
+        assert_eq!(value["commit"], "abc1234");
+        assert_eq!(value["board"], Board::Ice24UsbH.name());
+        assert_eq!(value["generator_version"], "0.6.2");
+        assert_eq!(value["rom_sets"][0]["rom_type"], "2364");
+
+        let stable: StableSdrrInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(stable, info.to_stable());
+    }
+
+    #[test]
+    fn test_metadata_header_generator_version() {
+        use crate::parsing::OneRomMetadataHeaderInternal;
+
+        let mut buf = [0xFFu8; 256];
+        buf[0..16].copy_from_slice(b"ONEROM_METADATA\0");
+        buf[16..20].copy_from_slice(&1u32.to_le_bytes());
+
+        // Older metadata has padding where the generator version now goes
+        let (_, header) = OneRomMetadataHeaderInternal::from_bytes((&buf, 0)).unwrap();
+        assert_eq!(header.generator_version(), None);
+
+        buf[28..30].copy_from_slice(&0u16.to_le_bytes());
+        buf[30..32].copy_from_slice(&6u16.to_le_bytes());
+        buf[32..34].copy_from_slice(&2u16.to_le_bytes());
+        let (_, header) = OneRomMetadataHeaderInternal::from_bytes((&buf, 0)).unwrap();
+        assert_eq!(header.generator_version(), Some(GeneratorVersion::new(0, 6, 2)));
+    }
+
+    #[test]
+    fn test_metadata_header_note() {
+        use crate::parsing::OneRomMetadataHeaderInternal;
+
+        let mut buf = [0xFFu8; 256];
+        buf[0..16].copy_from_slice(b"ONEROM_METADATA\0");
+        buf[16..20].copy_from_slice(&2u32.to_le_bytes());
+
+        // Older metadata, and metadata without a note, has padding here
+        let (_, header) = OneRomMetadataHeaderInternal::from_bytes((&buf, 0)).unwrap();
+        assert_eq!(header.note(), None);
+
+        let note = b"asset 0042";
+        buf[172..172 + note.len()].copy_from_slice(note);
+        buf[172 + note.len()] = 0;
+        let (_, header) = OneRomMetadataHeaderInternal::from_bytes((&buf, 0)).unwrap();
+        assert_eq!(header.note().as_deref(), Some("asset 0042"));
+    }
+}
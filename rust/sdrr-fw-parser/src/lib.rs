@@ -37,6 +37,7 @@ use esp_println as _;
 use airfrog_rpc::io::Reader;
 use onerom_config::fw::FirmwareVersion;
 use onerom_config::hw::Board;
+use onerom_config::mcu::Family as McuFamily;
 use onerom_config::mcu::Variant as McuVariant;
 
 /// Maximum SDRR firmware versions supported by this version of`sdrr-fw-parser`
@@ -61,7 +62,9 @@ use core::fmt;
 #[allow(unused_imports)]
 use log::{debug, error, info, trace, warn};
 
+pub use info::{Compatibility, GeneratorVersion};
 pub use info::{Sdrr, SdrrExtraInfo, SdrrInfo, SdrrPins, SdrrRomInfo, SdrrRomSet, SdrrRuntimeInfo};
+pub use info::{StableRom, StableRomSet, StableSdrrInfo};
 pub use lab::{LabFlash, LabParser, LabRam, OneRomLab};
 pub use types::{
     McuLine, McuStorage, SdrrAddress, SdrrCsSet, SdrrCsState, SdrrLogicalAddress, SdrrMcuPort,
@@ -158,6 +161,7 @@ pub struct Parser<'a, R: Reader> {
     reader: &'a mut R,
     base_flash_address: u32,
     base_ram_address: u32,
+    family: Option<McuFamily>,
 }
 
 impl<'a, R: Reader> Parser<'a, R> {
@@ -189,6 +193,7 @@ impl<'a, R: Reader> Parser<'a, R> {
             reader,
             base_flash_address: STM32F4_FLASH_BASE,
             base_ram_address: STM32F4_RAM_BASE,
+            family: None,
         }
     }
 
@@ -211,9 +216,20 @@ impl<'a, R: Reader> Parser<'a, R> {
             reader,
             base_flash_address,
             base_ram_address,
+            family: None,
         }
     }
 
+    /// Set the MCU family of the firmware, skipping auto-detection.
+    ///
+    /// By default the parser switches to the RP2350 flash base address if
+    /// the header reports an RP2350.  When the family is set, the base
+    /// addresses supplied at construction are used as-is - useful for
+    /// partial dumps and non-standard memory maps.
+    pub fn set_family(&mut self, family: McuFamily) {
+        self.family = Some(family);
+    }
+
     // Retrieve the SDRR info header from the firmware.
     async fn retrieve_header(&mut self) -> Result<SdrrInfoHeader, String> {
         // Try to find SDRR info at standard location
@@ -380,9 +396,23 @@ impl<'a, R: Reader> Parser<'a, R> {
         // Update our base address based on the header - before this we don't
         // need to have the correct base_flash_address set.  Base RAM is the
         // same.
-        if header.stm_line == McuLine::Rp2350 {
-            self.base_flash_address = 0x10000000; // RP2350 flash base address
-            self.reader.update_base_address(self.base_flash_address);
+        match self.family {
+            None => {
+                if header.stm_line == McuLine::Rp2350 {
+                    self.base_flash_address = 0x10000000; // RP2350 flash base address
+                    self.reader.update_base_address(self.base_flash_address);
+                }
+            }
+            Some(family) => {
+                let detected = if header.stm_line == McuLine::Rp2350 {
+                    McuFamily::Rp2350
+                } else {
+                    McuFamily::Stm32f4
+                };
+                if family != detected {
+                    warn!("Firmware header reports {}, parsing as {}", detected, family);
+                }
+            }
         }
 
         let mut parse_errors = Vec::new();
@@ -424,6 +454,8 @@ impl<'a, R: Reader> Parser<'a, R> {
         };
 
         // If necessary, parse OneRomMetadataHeader
+        let mut generator_version = None;
+        let mut metadata_note = None;
         let metadata_present = if header.major_version > 0 || header.minor_version > 4 {
             // OneRomMetadataHeader should be parsed for 0.5.0 and above.  Its
             // pointer is actually stored in rom_sets_ptr.
@@ -441,7 +473,10 @@ impl<'a, R: Reader> Parser<'a, R> {
             .await
             {
                 Ok(metadata) => {
-                    if metadata.version == 1 {
+                    generator_version = metadata.generator_version();
+                    metadata_note = metadata.note();
+                    // Version 2 adds a CRC, which is not checked here
+                    if metadata.version == 1 || metadata.version == 2 {
                         if metadata.rom_set_count == 0 {
                             true
                         } else if metadata.rom_sets_ptr > 0 {
@@ -477,29 +512,26 @@ impl<'a, R: Reader> Parser<'a, R> {
             false
         };
 
-        // Parse ROM sets with error collection
-        let rom_sets =
-            match parsing::read_rom_sets(self.reader, &header, self.base_flash_address, &version)
-                .await
-            {
-                Ok(sets) => {
-                    if sets.len() != header.rom_set_count as usize {
-                        parse_errors.push(ParseError::new(
-                            "Rom Sets",
-                            format!(
-                                "Incorrect number of ROM sets found: Found {}, expected {}",
-                                sets.len(),
-                                header.rom_set_count
-                            ),
-                        ));
-                    }
-                    sets
-                }
-                Err(e) => {
-                    parse_errors.push(ParseError::new("ROM Sets", e));
-                    Vec::new()
-                }
-            };
+        // Parse ROM sets with error collection.  Errors are located at the
+        // ROM set, chip or filename which failed.
+        let rom_sets = parsing::read_rom_sets(
+            self.reader,
+            &header,
+            self.base_flash_address,
+            &version,
+            &mut parse_errors,
+        )
+        .await;
+        if rom_sets.len() != header.rom_set_count as usize {
+            parse_errors.push(ParseError::new(
+                "Rom Sets",
+                format!(
+                    "Incorrect number of ROM sets found: Found {}, expected {}",
+                    rom_sets.len(),
+                    header.rom_set_count
+                ),
+            ));
+        }
 
         // Parse pins
         let pins =
@@ -565,6 +597,8 @@ impl<'a, R: Reader> Parser<'a, R> {
             parse_errors,
             extra_info,
             metadata_present,
+            generator_version,
+            metadata_note,
             version,
             board,
             model,
@@ -620,10 +654,11 @@ impl<'a, R: Reader> Parser<'a, R> {
 /// # Examples
 ///
 /// ```rust
-/// # use sdrr_fw_parser::ParseError;
+/// # use sdrr_fw_parser::{ParseError, ParseErrorLocation};
 /// let error = ParseError {
 ///     field: "build_date".to_string(),
 ///     reason: "Invalid pointer: 0xFFFFFFFF".to_string(),
+///     location: ParseErrorLocation::Header,
 /// };
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -646,21 +681,70 @@ pub struct ParseError {
     /// - `"ROM data extends past end of firmware"`
     /// - `"Unsupported ROM type value: 255"`
     pub reason: String,
+
+    /// Where in the firmware the failure occurred.
+    #[serde(default)]
+    pub location: ParseErrorLocation,
 }
 
 impl ParseError {
-    /// Create a new parse error.
+    /// Create a new parse error, located in the firmware's headers.
     pub fn new(field: impl Into<String>, reason: impl Into<String>) -> Self {
         Self {
             field: field.into(),
             reason: reason.into(),
+            location: ParseErrorLocation::Header,
         }
     }
+
+    /// Returns this error with its location set.
+    pub fn with_location(mut self, location: ParseErrorLocation) -> Self {
+        self.location = location;
+        self
+    }
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}: {}", self.field, self.reason)
+        match self.location {
+            ParseErrorLocation::Header => write!(f, "{}: {}", self.field, self.reason),
+            location => write!(f, "{}, {}: {}", self.field, location, self.reason),
+        }
+    }
+}
+
+/// Where in the firmware a [`ParseError`] occurred.
+///
+/// ROM sets and the chips within them are indexed from 0, in firmware order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ParseErrorLocation {
+    /// The firmware's headers, or a structure they point to directly, such
+    /// as the build date or pin configuration
+    #[default]
+    Header,
+
+    /// A ROM set's header, or a structure it points to
+    RomSet(usize),
+
+    /// A chip's ROM info within a ROM set
+    Chip { rom_set: usize, chip: usize },
+
+    /// A chip's filename within a ROM set
+    Filename { rom_set: usize, chip: usize },
+}
+
+impl fmt::Display for ParseErrorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorLocation::Header => write!(f, "header"),
+            ParseErrorLocation::RomSet(rom_set) => write!(f, "rom set {rom_set}"),
+            ParseErrorLocation::Chip { rom_set, chip } => {
+                write!(f, "rom set {rom_set} chip {chip}")
+            }
+            ParseErrorLocation::Filename { rom_set, chip } => {
+                write!(f, "rom set {rom_set} chip {chip} filename")
+            }
+        }
     }
 }
 
@@ -14,7 +14,8 @@ use static_assertions::const_assert_eq;
 use crate::Reader;
 use crate::{MAX_VERSION_MAJOR, MAX_VERSION_MINOR, MAX_VERSION_PATCH};
 use crate::{McuLine, McuStorage, SdrrCsState, SdrrRomType, SdrrServe};
-use crate::{SdrrExtraInfo, SdrrMcuPort, SdrrPins, SdrrRomInfo, SdrrRomSet};
+use crate::{GeneratorVersion, SdrrExtraInfo, SdrrMcuPort, SdrrPins, SdrrRomInfo, SdrrRomSet};
+use crate::{ParseError, ParseErrorLocation};
 
 #[cfg(not(feature = "std"))]
 use alloc::{format, string::String, vec, vec::Vec};
@@ -23,6 +24,9 @@ use alloc::{format, string::String, vec, vec::Vec};
 const MAX_STRING_LEN: usize = 1024;
 const STRING_READ_CHUNK_SIZE: usize = 64;
 
+// Field reported for errors reading ROM sets and their contents
+const ROM_SETS_FIELD: &str = "ROM Sets";
+
 #[derive(Debug, DekuRead, DekuWrite)]
 #[deku(endian = "little", magic = b"sdrr")]
 // Used internally to construct [`SdrrRuntimeInfo`]
@@ -175,7 +179,19 @@ pub(crate) struct OneRomMetadataHeaderInternal {
     pub rom_set_count: u8,
     #[deku(endian = "little")]
     pub rom_sets_ptr: u32,
-    pub _reserved: [u8; 228],
+    #[deku(endian = "little")]
+    pub generator_major: u16,
+    #[deku(endian = "little")]
+    pub generator_minor: u16,
+    #[deku(endian = "little", pad_bytes_after = "2")]
+    pub generator_patch: u16,
+    #[deku(endian = "little")]
+    pub _metadata_len: u32,
+    #[deku(endian = "little")]
+    pub _crc32: u32,
+    pub _description: [u8; 128],
+    pub note: [u8; 64],
+    pub _reserved: [u8; 20],
 }
 
 impl OneRomMetadataHeaderInternal {
@@ -186,6 +202,31 @@ impl OneRomMetadataHeaderInternal {
     pub(crate) const fn size() -> usize {
         Self::ONE_ROM_METADATA_HEADER_SIZE
     }
+
+    // Returns the version of onerom-gen which generated the metadata, if
+    // present.  Older metadata has 0xFF padding here.
+    pub(crate) fn generator_version(&self) -> Option<GeneratorVersion> {
+        if self.generator_major == 0xFFFF {
+            None
+        } else {
+            Some(GeneratorVersion::new(
+                self.generator_major,
+                self.generator_minor,
+                self.generator_patch,
+            ))
+        }
+    }
+
+    // Returns the note stamped into the metadata when it was built, if
+    // present.  Older metadata, and metadata without a note, has 0xFF
+    // padding here.
+    pub(crate) fn note(&self) -> Option<String> {
+        if self.note[0] == 0xFF {
+            return None;
+        }
+        let len = self.note.iter().position(|&b| b == 0)?;
+        Some(String::from_utf8_lossy(&self.note[..len]).into())
+    }
 }
 
 // Information about a specific ROM set
@@ -211,7 +252,13 @@ pub(crate) struct SdrrRomSetHeader {
     #[deku(cond = "*extra_info == 1", endian = "little")]
     pub firmware_overrides_ptr: Option<u32>,
     #[deku(cond = "*extra_info == 1", endian = "little")]
-    pub pad2: Option<[u8; 40]>,
+    pub provenance_ptr: Option<u32>,
+    #[deku(cond = "*extra_info == 1")]
+    pub signature: Option<[u8; 4]>,
+    #[deku(cond = "*extra_info == 1")]
+    pub filenames: Option<u8>,
+    #[deku(cond = "*extra_info == 1", endian = "little")]
+    pub pad2: Option<[u8; 31]>,
 }
 
 impl SdrrRomSetHeader {
@@ -225,6 +272,17 @@ impl SdrrRomSetHeader {
     pub(crate) const fn extra_size() -> usize {
         Self::EXTRA_SIZE
     }
+
+    // Whether this set's ROM infos include filename pointers.  Sets can
+    // override the firmware's boot logging setting - otherwise (and for older
+    // firmware) this follows the firmware.
+    pub(crate) fn filenames_enabled(&self, info_header: &SdrrInfoHeader) -> bool {
+        match self.filenames {
+            Some(1) => true,
+            Some(2) => false,
+            _ => info_header.filenames_enabled(),
+        }
+    }
 }
 
 // Contains information about a specific ROM image
@@ -440,23 +498,30 @@ pub(crate) async fn read_one_rom_metadata_header_info<R: Reader>(
 }
 
 /// Read ROM sets from firmware
+///
+/// Failures are pushed to `parse_errors`, located at the ROM set, chip or
+/// filename concerned.  A ROM set whose header or ROM infos can't be read
+/// ends the read, so the sets returned keep their firmware indices.
 pub(crate) async fn read_rom_sets<R: Reader>(
     reader: &mut R,
     info_header: &SdrrInfoHeader,
     base_addr: u32,
     version: &FirmwareVersion,
-) -> Result<Vec<SdrrRomSet>, String> {
+    parse_errors: &mut Vec<ParseError>,
+) -> Vec<SdrrRomSet> {
     let ptr = info_header.rom_sets_ptr;
     let count = info_header.rom_set_count;
 
     if count == 0 {
-        return Ok(Vec::new());
+        return Vec::new();
     }
 
     if ptr < base_addr {
-        return Err(format!(
-            "ROM set pointer {ptr:#010X} is below base address {base_addr:#010X}"
+        parse_errors.push(ParseError::new(
+            ROM_SETS_FIELD,
+            format!("ROM set pointer {ptr:#010X} is below base address {base_addr:#010X}"),
         ));
+        return Vec::new();
     }
 
     let mut rom_sets = Vec::with_capacity(count as usize);
@@ -464,18 +529,36 @@ pub(crate) async fn read_rom_sets<R: Reader>(
     let mut current_offset = 0u32;
     const MAX_HEADER_SIZE: usize = SdrrRomSetHeader::base_size() + SdrrRomSetHeader::extra_size();
 
-    for i in 0..count {
+    for i in 0..count as usize {
+        let location = ParseErrorLocation::RomSet(i);
         let header_addr = ptr + current_offset;
 
         // Read ROM set header
         let mut header_buf = [0u8; MAX_HEADER_SIZE];
-        reader
-            .read(header_addr, &mut header_buf)
-            .await
-            .map_err(|_| format!("Failed to read ROM set header {i}"))?;
+        if reader.read(header_addr, &mut header_buf).await.is_err() {
+            parse_errors.push(
+                ParseError::new(
+                    ROM_SETS_FIELD,
+                    format!("Failed to read ROM set header at {header_addr:#010X}"),
+                )
+                .with_location(location),
+            );
+            break;
+        }
 
-        let (_, header) = SdrrRomSetHeader::from_bytes((&header_buf, 0))
-            .map_err(|e| format!("Failed to parse ROM set header {i}: {e}"))?;
+        let header = match SdrrRomSetHeader::from_bytes((&header_buf, 0)) {
+            Ok((_, header)) => header,
+            Err(e) => {
+                parse_errors.push(
+                    ParseError::new(
+                        ROM_SETS_FIELD,
+                        format!("Failed to parse ROM set header: {e}"),
+                    )
+                    .with_location(location),
+                );
+                break;
+            }
+        };
         current_offset += SdrrRomSetHeader::base_size() as u32;
         if *version >= FirmwareVersion::new(0, 6, 0, 0) {
             // There are three scenarios here:
@@ -497,11 +580,19 @@ pub(crate) async fn read_rom_sets<R: Reader>(
             && serve_config_ptr != 0xFFFF_FFFF
         {
             let mut buf = [0u8; 64];
-            reader
-                .read(serve_config_ptr, &mut buf)
-                .await
-                .map_err(|_| format!("Failed to read serve_config for ROM set {}", i))?;
-            Some(buf.to_vec())
+            match reader.read(serve_config_ptr, &mut buf).await {
+                Ok(()) => Some(buf.to_vec()),
+                Err(_) => {
+                    parse_errors.push(
+                        ParseError::new(
+                            ROM_SETS_FIELD,
+                            format!("Failed to read serve_config at {serve_config_ptr:#010X}"),
+                        )
+                        .with_location(location),
+                    );
+                    None
+                }
+            }
         } else {
             None
         };
@@ -512,27 +603,44 @@ pub(crate) async fn read_rom_sets<R: Reader>(
             && fw_ptr != 0xFFFF_FFFF
         {
             let mut buf = [0u8; 64];
-            reader
-                .read(fw_ptr, &mut buf)
-                .await
-                .map_err(|_| format!("Failed to read firmware_overrides for ROM set {}", i))?;
-
-            let mut fw_config = FirmwareConfig::from_bytes(&buf).map_err(|e| {
-                format!(
-                    "Failed to parse firmware_overrides for ROM set {}: {}",
-                    i, e
-                )
-            })?;
-            fw_config.serve_alg_params = Some(ServeAlgParams {
-                params: serve_config.clone().unwrap_or_default(),
-            });
-            Some(fw_config)
+            let fw_config = match reader.read(fw_ptr, &mut buf).await {
+                Ok(()) => FirmwareConfig::from_bytes(&buf)
+                    .map_err(|e| format!("Failed to parse firmware_overrides: {e}")),
+                Err(_) => Err(format!(
+                    "Failed to read firmware_overrides at {fw_ptr:#010X}"
+                )),
+            };
+            match fw_config {
+                Ok(mut fw_config) => {
+                    fw_config.serve_alg_params = Some(ServeAlgParams {
+                        params: serve_config.clone().unwrap_or_default(),
+                    });
+                    Some(fw_config)
+                }
+                Err(e) => {
+                    parse_errors.push(ParseError::new(ROM_SETS_FIELD, e).with_location(location));
+                    None
+                }
+            }
         } else {
             None
         };
 
+        // Signature is 4 ASCII hex characters, or 0s if not present
+        let signature = header
+            .signature
+            .filter(|sig| sig.iter().all(|c| c.is_ascii_hexdigit()))
+            .map(|sig| sig.iter().map(|&c| c as char).collect());
+
         // Read ROM infos
-        let roms = read_rom_infos(reader, info_header, &header, base_addr).await?;
+        let roms =
+            match read_rom_infos(reader, info_header, &header, base_addr, i, parse_errors).await {
+                Ok(roms) => roms,
+                Err(e) => {
+                    parse_errors.push(e);
+                    break;
+                }
+            };
 
         // Note: We don't read the ROM data itself - just store where it is
         rom_sets.push(SdrrRomSet {
@@ -543,19 +651,24 @@ pub(crate) async fn read_rom_sets<R: Reader>(
             serve: header.serve,
             multi_rom_cs1_state: header.multi_rom_cs1_state,
             firmware_overrides,
+            signature,
         });
     }
 
-    Ok(rom_sets)
+    rom_sets
 }
 
-// Read ROM info structures
+// Read ROM info structures for ROM set `rom_set`.  Missing provenance and
+// unreadable filenames are pushed to `parse_errors`, while a ROM info which
+// can't be read fails the whole set.
 async fn read_rom_infos<R: Reader>(
     reader: &mut R,
     info_header: &SdrrInfoHeader,
     rom_set_header: &SdrrRomSetHeader,
     base_addr: u32,
-) -> Result<Vec<SdrrRomInfo>, String> {
+    rom_set: usize,
+    parse_errors: &mut Vec<ParseError>,
+) -> Result<Vec<SdrrRomInfo>, ParseError> {
     let ptr = rom_set_header.roms_ptr;
     let count = rom_set_header.rom_count;
 
@@ -564,26 +677,61 @@ async fn read_rom_infos<R: Reader>(
     }
 
     if ptr < base_addr {
-        return Err(format!(
-            "ROM infos pointer {ptr:#010X} is below base address {base_addr:#010X}"
-        ));
+        return Err(ParseError::new(
+            ROM_SETS_FIELD,
+            format!("ROM infos pointer {ptr:#010X} is below base address {base_addr:#010X}"),
+        )
+        .with_location(ParseErrorLocation::RomSet(rom_set)));
+    }
+
+    // Read provenance records if present - 8 bytes (size, checksum) per ROM
+    let mut provenance = vec![(None, None); count as usize];
+    if let Some(provenance_ptr) = rom_set_header.provenance_ptr
+        && provenance_ptr != 0
+        && provenance_ptr != 0xFFFF_FFFF
+    {
+        let mut buf = vec![0u8; count as usize * 8];
+        if reader.read(provenance_ptr, &mut buf).await.is_ok() {
+            for (entry, record) in provenance.iter_mut().zip(buf.chunks_exact(8)) {
+                let size = u32::from_le_bytes([record[0], record[1], record[2], record[3]]);
+                let checksum = u32::from_le_bytes([record[4], record[5], record[6], record[7]]);
+                if size != 0xFFFF_FFFF {
+                    *entry = (Some(size), Some(checksum));
+                }
+            }
+        } else {
+            parse_errors.push(
+                ParseError::new(
+                    ROM_SETS_FIELD,
+                    format!("Failed to read ROM provenance records at {provenance_ptr:#010X}"),
+                )
+                .with_location(ParseErrorLocation::RomSet(rom_set)),
+            );
+        }
     }
 
     let mut rom_infos = Vec::with_capacity(count as usize);
 
-    for i in 0..count {
+    for chip in 0..count as usize {
+        let location = ParseErrorLocation::Chip { rom_set, chip };
+        let (source_size, source_checksum) = provenance[chip];
+
         // Read pointer to ROM info
-        let ptr_addr = ptr + (i as u32 * core::mem::size_of::<u32>() as u32);
+        let ptr_addr = ptr + (chip as u32 * core::mem::size_of::<u32>() as u32);
         let mut ptr_buf = [0u8; core::mem::size_of::<u32>()];
-        reader
-            .read(ptr_addr, &mut ptr_buf)
-            .await
-            .map_err(|_| format!("Failed to read ROM info pointer {}", i))?;
+        reader.read(ptr_addr, &mut ptr_buf).await.map_err(|_| {
+            ParseError::new(
+                ROM_SETS_FIELD,
+                format!("Failed to read ROM info pointer at {ptr_addr:#010X}"),
+            )
+            .with_location(location)
+        })?;
 
         let rom_info_ptr = u32::from_le_bytes(ptr_buf);
 
         // Read the ROM info itself
-        let info_size = if info_header.filenames_enabled() {
+        let filenames_enabled = rom_set_header.filenames_enabled(info_header);
+        let info_size = if filenames_enabled {
             SdrrRomInfoWithLogging::size()
         } else {
             SdrrRomInfoBasic::size()
@@ -592,17 +740,37 @@ async fn read_rom_infos<R: Reader>(
         reader
             .read(rom_info_ptr, &mut info_buf)
             .await
-            .map_err(|_| format!("Failed to read ROM info {}", i))?;
+            .map_err(|_| {
+                ParseError::new(
+                    ROM_SETS_FIELD,
+                    format!("Failed to read ROM info at {rom_info_ptr:#010X}"),
+                )
+                .with_location(location)
+            })?;
 
-        let rom_info = if info_header.filenames_enabled() {
-            let (_, info) = SdrrRomInfoWithLogging::from_bytes((&info_buf, 0))
-                .map_err(|e| format!("Failed to parse ROM info with logging {}: {}", i, e))?;
+        let rom_info = if filenames_enabled {
+            let (_, info) = SdrrRomInfoWithLogging::from_bytes((&info_buf, 0)).map_err(|e| {
+                ParseError::new(
+                    ROM_SETS_FIELD,
+                    format!("Failed to parse ROM info with logging: {e}"),
+                )
+                .with_location(location)
+            })?;
 
-            // This test sets filename to None if pointer invalid (for example 0)
-            let filename = if info.filename_ptr >= base_addr && info.filename_ptr != 0xFFFFFFFF {
-                read_string_at_ptr(reader, info.filename_ptr, base_addr)
-                    .await
-                    .ok()
+            // 0 and 0xFFFFFFFF mean no filename was stored.  Any other
+            // pointer which can't be read is reported, but doesn't fail the
+            // chip.
+            let filename = if info.filename_ptr != 0 && info.filename_ptr != 0xFFFFFFFF {
+                match read_string_at_ptr(reader, info.filename_ptr, base_addr).await {
+                    Ok(filename) => Some(filename),
+                    Err(e) => {
+                        parse_errors.push(
+                            ParseError::new(ROM_SETS_FIELD, e)
+                                .with_location(ParseErrorLocation::Filename { rom_set, chip }),
+                        );
+                        None
+                    }
+                }
             } else {
                 None
             };
@@ -613,10 +781,17 @@ async fn read_rom_infos<R: Reader>(
                 cs2_state: info.cs2_state,
                 cs3_state: info.cs3_state,
                 filename,
+                source_size,
+                source_checksum,
             }
         } else {
-            let (_, info) = SdrrRomInfoBasic::from_bytes((&info_buf, 0))
-                .map_err(|e| format!("Failed to parse ROM info basic {}: {}", i, e))?;
+            let (_, info) = SdrrRomInfoBasic::from_bytes((&info_buf, 0)).map_err(|e| {
+                ParseError::new(
+                    ROM_SETS_FIELD,
+                    format!("Failed to parse ROM info basic: {e}"),
+                )
+                .with_location(location)
+            })?;
 
             SdrrRomInfo {
                 rom_type: info.rom_type,
@@ -624,6 +799,8 @@ async fn read_rom_infos<R: Reader>(
                 cs2_state: info.cs2_state,
                 cs3_state: info.cs3_state,
                 filename: None,
+                source_size,
+                source_checksum,
             }
         };
 
@@ -654,3 +831,186 @@ pub(crate) async fn read_pins<R: Reader>(
         .map_err(|e| format!("Failed to parse pins: {}", e))
         .map(|(_, pins)| pins)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readers::MemoryReader;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+
+    const BASE: u32 = 0x0800_0000;
+
+    // MemoryReader never waits, so a single poll completes the read
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut cx = Context::from_waker(Waker::noop());
+        match pin!(future).poll(&mut cx) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("Read unexpectedly pending"),
+        }
+    }
+
+    fn info_header(rom_set_count: u8, rom_sets_ptr: u32) -> SdrrInfoHeader {
+        SdrrInfoHeader {
+            major_version: 0,
+            minor_version: 5,
+            patch_version: 1,
+            build_number: 0,
+            build_date_ptr: 0,
+            commit: [0; 8],
+            hw_rev_ptr: 0,
+            stm_line: McuLine::F401DE,
+            stm_storage: McuStorage::Storage8,
+            freq: 0,
+            overclock: 0,
+            swd_enabled: 0,
+            preload_image_to_ram: 0,
+            bootloader_capable: 0,
+            status_led_enabled: 0,
+            boot_logging_enabled: 0,
+            mco_enabled: 0,
+            rom_set_count,
+            count_rom_access: 0,
+            rom_sets_ptr,
+            pins_ptr: 0,
+            boot_config: [0; 4],
+            extra_ptr: 0,
+            _post: [0; 4],
+        }
+    }
+
+    fn put_u32(image: &mut [u8], offset: usize, value: u32) {
+        image[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    // Two single ROM sets, with each set's ROM info pointer array, ROM info
+    // (with filename pointer) and filename laid out after the set headers.
+    // Set 1's structures come last, so truncating the image breaks set 1
+    // first.
+    fn rom_sets_image() -> Vec<u8> {
+        let mut image = vec![0u8; 0x160];
+        for (set, set_offset, filename) in [(0, 0x80, b"a.rom"), (1, 0x100, b"b.rom")] {
+            let header = set * SdrrRomSetHeader::base_size();
+            put_u32(&mut image, header + 8, BASE + set_offset as u32);
+            image[header + 12] = 1; // rom_count
+            put_u32(&mut image, set_offset, BASE + set_offset as u32 + 0x10);
+            put_u32(
+                &mut image,
+                set_offset + 0x14,
+                BASE + set_offset as u32 + 0x20,
+            );
+            image[set_offset + 0x20..set_offset + 0x25].copy_from_slice(filename);
+        }
+        image
+    }
+
+    fn read(image: Vec<u8>) -> (Vec<SdrrRomSet>, Vec<ParseError>) {
+        let mut reader = MemoryReader::new(image, BASE);
+        let header = info_header(2, BASE);
+        let version = FirmwareVersion::new(0, 5, 1, 0);
+        let mut parse_errors = Vec::new();
+        let rom_sets = block_on(read_rom_sets(
+            &mut reader,
+            &header,
+            BASE,
+            &version,
+            &mut parse_errors,
+        ));
+        (rom_sets, parse_errors)
+    }
+
+    fn locations(parse_errors: &[ParseError]) -> Vec<ParseErrorLocation> {
+        parse_errors.iter().map(|error| error.location).collect()
+    }
+
+    #[test]
+    fn test_read_rom_sets_located_errors() {
+        // Intact image
+        let (rom_sets, parse_errors) = read(rom_sets_image());
+        assert!(parse_errors.is_empty(), "{parse_errors:?}");
+        assert_eq!(rom_sets.len(), 2);
+        assert_eq!(rom_sets[1].roms[0].filename.as_deref(), Some("b.rom"));
+
+        // Truncated within set 1's filename - the set is kept, without it
+        let mut image = rom_sets_image();
+        image.truncate(0x140);
+        let (rom_sets, parse_errors) = read(image);
+        assert_eq!(rom_sets.len(), 2);
+        assert_eq!(rom_sets[0].roms[0].filename.as_deref(), Some("a.rom"));
+        assert_eq!(rom_sets[1].roms[0].filename, None);
+        assert_eq!(
+            locations(&parse_errors),
+            [ParseErrorLocation::Filename {
+                rom_set: 1,
+                chip: 0
+            }]
+        );
+
+        // Truncated within set 1's ROM info - set 0 is still returned
+        let mut image = rom_sets_image();
+        image.truncate(0x114);
+        let (rom_sets, parse_errors) = read(image);
+        assert_eq!(rom_sets.len(), 1);
+        assert_eq!(
+            locations(&parse_errors),
+            [ParseErrorLocation::Chip {
+                rom_set: 1,
+                chip: 0
+            }]
+        );
+
+        // Truncated before any ROM infos - the first failure ends the read
+        let mut image = rom_sets_image();
+        image.truncate(0x80);
+        let (rom_sets, parse_errors) = read(image);
+        assert!(rom_sets.is_empty());
+        assert_eq!(
+            locations(&parse_errors),
+            [ParseErrorLocation::Chip {
+                rom_set: 0,
+                chip: 0
+            }]
+        );
+
+        // Truncated within the ROM set headers
+        let mut image = rom_sets_image();
+        image.truncate(0x20);
+        let (rom_sets, parse_errors) = read(image);
+        assert!(rom_sets.is_empty());
+        assert_eq!(locations(&parse_errors), [ParseErrorLocation::RomSet(0)]);
+
+        // Set 1's filename pointer is out of range
+        let mut image = rom_sets_image();
+        put_u32(&mut image, 0x114, 0x1234);
+        let (rom_sets, parse_errors) = read(image);
+        assert_eq!(rom_sets.len(), 2);
+        assert_eq!(
+            parse_errors[0].to_string(),
+            "ROM Sets, rom set 1 chip 0 filename: Invalid pointer: 0x00001234"
+        );
+
+        // Set 1's ROM infos pointer is out of range
+        let mut image = rom_sets_image();
+        put_u32(&mut image, 0x18, 0x1234);
+        let (rom_sets, parse_errors) = read(image);
+        assert_eq!(rom_sets.len(), 1);
+        assert_eq!(locations(&parse_errors), [ParseErrorLocation::RomSet(1)]);
+
+        // ROM sets pointer is out of range
+        let mut reader = MemoryReader::new(rom_sets_image(), BASE);
+        let mut parse_errors = Vec::new();
+        let rom_sets = block_on(read_rom_sets(
+            &mut reader,
+            &info_header(2, 0x1234),
+            BASE,
+            &FirmwareVersion::new(0, 5, 1, 0),
+            &mut parse_errors,
+        ));
+        assert!(rom_sets.is_empty());
+        assert_eq!(locations(&parse_errors), [ParseErrorLocation::Header]);
+        assert_eq!(
+            parse_errors[0].to_string(),
+            "ROM Sets: ROM set pointer 0x00001234 is below base address 0x08000000"
+        );
+    }
+}
@@ -395,6 +395,11 @@ pub enum SdrrCsState {
     /// Chip select line is not used
     #[deku(id = "2")]
     NotUsed,
+
+    /// Only used as CS3's state, to mark a chip selected by its CE/OE lines.
+    /// CS1 and CS2's states then give CE and OE's logic.
+    #[deku(id = "3")]
+    CeOe,
 }
 
 impl fmt::Display for SdrrCsState {
@@ -403,6 +408,7 @@ impl fmt::Display for SdrrCsState {
             SdrrCsState::ActiveLow => write!(f, "Active Low"),
             SdrrCsState::ActiveHigh => write!(f, "Active High"),
             SdrrCsState::NotUsed => write!(f, "Not Used"),
+            SdrrCsState::CeOe => write!(f, "CE/OE"),
         }
     }
 }
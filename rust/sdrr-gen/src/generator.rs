@@ -20,6 +20,13 @@ use crate::config::Config;
 use crate::file::{OutType, out_filename};
 use crate::fw::PllConfig;
 
+// Lengths of the byte array fields sdrr-gen fills in onerom_metadata_header_t
+// and sdrr_rom_set_t, from sdrr/include/config_base.h
+const HEADER_DESCRIPTION_LEN: usize = 128;
+const HEADER_NOTE_LEN: usize = 64;
+const HEADER_RESERVED_LEN: usize = 20;
+const ROM_SET_PAD2_LEN: usize = 31;
+
 // Generate all output files
 pub fn generate_files(config: &Config, rom_sets: &[ChipSet]) -> Result<()> {
     // Create output directory if it doesn't exist
@@ -55,6 +62,63 @@ enum FileType {
     Linker,
 }
 
+// Writes the metadata header, pointing to the rom_set array if there are any
+// ROM sets
+fn write_metadata_header(file: &mut impl Write, has_rom_sets: bool) -> Result<()> {
+    writeln!(file, "__attribute__((section(\".metadata.header\")))")?;
+    writeln!(
+        file,
+        "const onerom_metadata_header_t onerom_metadata_header = {{"
+    )?;
+    writeln!(file, "    .magic = \"ONEROM_METADATA\",")?;
+    writeln!(file, "    .version = 1,")?;
+    writeln!(file, "    .rom_set_count = SDRR_NUM_SETS,")?;
+    writeln!(file, "    .pad1 = {{0, 0, 0}},")?;
+    if has_rom_sets {
+        writeln!(file, "    .rom_sets = rom_set,")?;
+    } else {
+        writeln!(file, "    .rom_sets = (void *)0,")?;
+    }
+    // sdrr-gen doesn't record its version, so mark it as not present
+    writeln!(file, "    .generator_major = 0xffff,")?;
+    writeln!(file, "    .generator_minor = 0xffff,")?;
+    writeln!(file, "    .generator_patch = 0xffff,")?;
+    writeln!(file, "    .pad2 = {{0, 0}},")?;
+    // Version 1 metadata has no CRC
+    writeln!(file, "    .metadata_len = 0xffffffff,")?;
+    writeln!(file, "    .crc32 = 0xffffffff,")?;
+    // No description or note, which are all 0xff rather than empty strings
+    for (field, len) in [
+        ("description", HEADER_DESCRIPTION_LEN),
+        ("note", HEADER_NOTE_LEN),
+        ("reserved", HEADER_RESERVED_LEN),
+    ] {
+        write_byte_array(file, "    ", field, len, 0xff)?;
+    }
+    writeln!(file, "}};")?;
+    writeln!(file)?;
+    Ok(())
+}
+
+// Writes a designated initializer for a byte array field, with every byte set
+// to value.  The length must match the field's length in the C struct, as the
+// firmware is built with -Werror, so excess elements fail the build.
+fn write_byte_array(
+    file: &mut impl Write,
+    indent: &str,
+    field: &str,
+    len: usize,
+    value: u8,
+) -> Result<()> {
+    writeln!(file, "{indent}.{field} = {{")?;
+    for start in (0..len).step_by(8) {
+        let bytes = vec![format!("0x{value:02x}"); (len - start).min(8)];
+        writeln!(file, "{indent}    {},", bytes.join(", "))?;
+    }
+    writeln!(file, "{indent}}},")?;
+    Ok(())
+}
+
 fn create_file(output_dir: &Path, filename: &Path, filetype: FileType) -> Result<fs::File> {
     let file_path = output_dir.join(filename);
     let mut file = fs::File::create(&file_path)
@@ -157,31 +221,7 @@ fn generate_roms_implementation_file(
         writeln!(file, "extern const sdrr_rom_set_t rom_set[SDRR_NUM_SETS];")?;
         writeln!(file)?;
     }
-    writeln!(file, "__attribute__((section(\".metadata.header\")))")?;
-    writeln!(
-        file,
-        "const onerom_metadata_header_t onerom_metadata_header = {{"
-    )?;
-    writeln!(file, "    .magic = \"ONEROM_METADATA\",")?;
-    writeln!(file, "    .version = 1,")?;
-    writeln!(file, "    .rom_set_count = SDRR_NUM_SETS,")?;
-    writeln!(file, "    .pad1 = {{0, 0, 0}},")?;
-    if rom_sets.is_empty() {
-        writeln!(file, "    .rom_sets = (void *)0,")?;
-    } else {
-        writeln!(file, "    .rom_sets = rom_set,")?;
-    }
-    writeln!(file, "    .reserved = {{")?;
-    for _ in 0..28 {
-        writeln!(
-            file,
-            "        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,"
-        )?;
-    }
-    writeln!(file, "        0xff, 0xff, 0xff, 0xff,")?; // Last 4 bytes to make 228 total
-    writeln!(file, "    }},")?;
-    writeln!(file, "}};")?;
-    writeln!(file)?;
+    write_metadata_header(&mut file, !rom_sets.is_empty())?;
 
     // Generate filename strings (debug only)
     writeln!(file, "// ROM filenames")?;
@@ -340,14 +380,10 @@ fn generate_roms_implementation_file(
         // Post 0.6.0 firmware additions
         writeln!(file, "        .serve_config = (void *)0,")?;
         writeln!(file, "        .firmware_overrides = (void *)0,")?;
-        writeln!(file, "        .pad2 = {{")?;
-        for _ in 0..5 {
-            writeln!(
-                file,
-                "            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,"
-            )?;
-        }
-        writeln!(file, "        }}")?;
+        writeln!(file, "        .provenance = (void *)0,")?;
+        writeln!(file, "        .signature = {{0}},")?;
+        writeln!(file, "        .filenames = ROM_SET_FILENAMES_DEFAULT,")?;
+        write_byte_array(&mut file, "        ", "pad2", ROM_SET_PAD2_LEN, 0xff)?;
 
         writeln!(file, "    }},")?;
     }
@@ -424,7 +460,9 @@ fn generate_roms_implementation_file(
                 write!(file, "    ")?;
             }
 
-            let byte = rom_set.get_byte(address, board, flip_cs1_x);
+            let byte = rom_set
+                .get_byte(address, board, flip_cs1_x)
+                .map_err(|e| anyhow::anyhow!("In ROM set {}: {e:?}", rom_set.id))?;
             write!(file, "0x{:02x}, ", byte)?;
         }
 
@@ -773,8 +811,14 @@ fn generate_sdrr_config_implementation(filename: &Path, config: &Config) -> Resu
     writeln!(file, "    .x2 = {},", board.pin_x2())?;
     writeln!(file, "    .ce = {ce},")?;
     writeln!(file, "    .oe = {oe},")?;
-    writeln!(file, "    .x_jumper_pull = {},", board.x_jumper_pull())?;
-    writeln!(file, "    .reserved3 = {{0, 0, 0}},")?;
+    // x_jumper_pull is retained for older tools - the firmware uses the
+    // independent X1 and X2 pull directions
+    #[allow(deprecated)]
+    let x_jumper_pull = board.x_jumper_pull();
+    writeln!(file, "    .x_jumper_pull = {x_jumper_pull},")?;
+    writeln!(file, "    .x1_jumper_pull = {},", board.x1_jumper_pull())?;
+    writeln!(file, "    .x2_jumper_pull = {},", board.x2_jumper_pull())?;
+    writeln!(file, "    .reserved3 = {{0}},")?;
     writeln!(file, "    .swclk_sel = {},", board.swclk_sel_pin())?;
     writeln!(file, "    .swdio_sel = {},", board.swdio_sel_pin())?;
     writeln!(
@@ -1104,3 +1148,59 @@ fn generate_platform_ld_script(filename: &Path, config: &Config) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG_BASE_H: &str = include_str!("../../../sdrr/include/config_base.h");
+
+    // Returns the length of a byte array field in a struct in config_base.h
+    fn struct_array_len(struct_name: &str, field: &str) -> usize {
+        let start = CONFIG_BASE_H
+            .find(&format!("typedef struct {struct_name} {{"))
+            .expect("struct not found");
+        let end = start
+            + CONFIG_BASE_H[start..]
+                .find(&format!("}} {struct_name};"))
+                .expect("struct end not found");
+        let body = &CONFIG_BASE_H[start..end];
+        let field_start =
+            body.find(&format!(" {field}[")).expect("field not found") + field.len() + 2;
+        let field_end = field_start + body[field_start..].find(']').unwrap();
+        body[field_start..field_end].parse().unwrap()
+    }
+
+    // Returns the number of bytes emitted for a field's initializer
+    fn emitted_array_len(output: &str, field: &str) -> usize {
+        let start = output
+            .find(&format!(".{field} = {{"))
+            .expect("field not emitted");
+        let end = start + output[start..].find("},").unwrap();
+        output[start..end].matches("0x").count()
+    }
+
+    #[test]
+    fn test_metadata_header_matches_struct() {
+        let mut output = Vec::new();
+        write_metadata_header(&mut output, true).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        for field in ["description", "note", "reserved"] {
+            let len = struct_array_len("onerom_metadata_header_t", field);
+            assert_eq!(emitted_array_len(&output, field), len, "{field}");
+        }
+        assert_eq!(ROM_SET_PAD2_LEN, struct_array_len("sdrr_rom_set_t", "pad2"));
+    }
+
+    #[test]
+    fn test_write_byte_array() {
+        for len in [0, 1, 8, 9, 31] {
+            let mut output = Vec::new();
+            write_byte_array(&mut output, "", "pad", len, 0xff).unwrap();
+            let output = String::from_utf8(output).unwrap();
+            assert_eq!(emitted_array_len(&output, "pad"), len);
+            assert_eq!(output.matches("0xff").count(), len);
+        }
+    }
+}
@@ -444,6 +444,9 @@ fn print_sdrr_info(fw_data: &FirmwareData, args: &Args) {
             };
             println!("  Set type:      {}", set_type);
             println!("  Size:          {} bytes", rom_set.size);
+            if let Some(signature) = &rom_set.signature {
+                println!("  Signature:     {}", signature);
+            }
             println!("  ROM Count:     {}", rom_set.rom_count);
             println!("  Algorithm:     {}", rom_set.serve);
             println!("  Multi-ROM CS1: {}", rom_set.multi_rom_cs1_state);
@@ -504,6 +507,9 @@ fn print_sdrr_info(fw_data: &FirmwareData, args: &Args) {
                     "    Name:        {}",
                     rom.filename.as_deref().unwrap_or("<not present>")
                 );
+                if let (Some(size), Some(checksum)) = (rom.source_size, rom.source_checksum) {
+                    println!("    Source:      {} bytes, CRC-32 0x{:08X}", size, checksum);
+                }
                 println!(
                     "    CS States:   {}/{}/{}",
                     rom.cs1_state, rom.cs2_state, rom.cs3_state
@@ -0,0 +1,288 @@
+// Copyright (C) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT License
+
+//! Analyse firmware comparison methods
+//!
+//! Loads two firmware files and describes what changed between them - for
+//! example, to check what an upgrade will change before flashing it.
+
+use iced::Task;
+use rfd::FileDialog;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+#[allow(unused_imports)]
+use log::{debug, error, info, trace, warn};
+use sdrr_fw_parser::{SdrrInfo, SdrrRomInfo};
+
+use crate::analyse::extract::{extract_rom_image, rom_set_data};
+use crate::analyse::file::load_file_async;
+use crate::analyse::{Analyse, AnalyseState, Message};
+use crate::app::AppMessage;
+
+/// A loaded and parsed firmware file, to be compared
+#[derive(Debug, Clone)]
+pub struct CompareFile {
+    path: PathBuf,
+    info: SdrrInfo,
+    data: Vec<u8>,
+}
+
+impl CompareFile {
+    fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.display().to_string())
+    }
+}
+
+/// Show the file chooser for the two firmware files to compare
+pub fn compare_file_chooser() -> Task<AppMessage> {
+    Task::perform(
+        async {
+            FileDialog::new()
+                .set_title("Select two firmware files to compare")
+                .add_filter("firmware", &["bin"])
+                .pick_files()
+        },
+        |paths| Message::CompareFilesSelected(paths).into(),
+    )
+}
+
+/// Load and parse both files to compare, based on user selection
+pub fn load_compare_files(analyse: &mut Analyse, paths: Option<Vec<PathBuf>>) -> Task<AppMessage> {
+    let Some(paths) = paths else {
+        // User cancelled file selection, just ignore
+        return Task::none();
+    };
+    let Ok([a, b]) = <[PathBuf; 2]>::try_from(paths) else {
+        analyse.analysis_content = "Select exactly two firmware files to compare".to_string();
+        return Task::none();
+    };
+
+    let start_analysis_task = analyse.start_analysis(AnalyseState::Loading);
+    let load_files_task = Task::perform(
+        async move {
+            let a = load_compare_file(a).await?;
+            let b = load_compare_file(b).await?;
+            Ok((a, b))
+        },
+        |result| Message::CompareLoaded(result).into(),
+    );
+    Task::batch([start_analysis_task, load_files_task])
+}
+
+async fn load_compare_file(path: PathBuf) -> Result<CompareFile, String> {
+    let (info, data) = load_file_async(path.clone())
+        .await
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(CompareFile { path, info, data })
+}
+
+/// Both files to compare have been loaded (or failed to) - render the
+/// comparison
+pub fn compare_loaded(analyse: &mut Analyse, result: Result<(CompareFile, CompareFile), String>) {
+    analyse.analysis_content = match result {
+        Ok((a, b)) => compare(&a, &b),
+        Err(e) => format!(
+            "Error loading/parsing file:\n- {e}\n---\nAre you sure both are valid One ROM firmware .bin files?"
+        ),
+    };
+    analyse.state = AnalyseState::Idle;
+}
+
+// Renders the differences between two firmware files.  Lines for fields
+// which differ are marked with `*`.
+fn compare(a: &CompareFile, b: &CompareFile) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Comparing firmware files");
+    let _ = writeln!(out, "------------------------");
+    let _ = writeln!(out, "A: {}", a.name());
+    let _ = writeln!(out, "B: {}", b.name());
+    for (label, file) in [("A", a), ("B", b)] {
+        if !file.info.parse_errors.is_empty() {
+            let _ = writeln!(
+                out,
+                "{label} parsed with {} errors - load it as a File for details",
+                file.info.parse_errors.len()
+            );
+        }
+    }
+    let _ = writeln!(out);
+
+    // Flag metadata differences first, as they change how the rest of the
+    // images are laid out
+    let (a_info, b_info) = (&a.info, &b.info);
+    if a_info.metadata_present != b_info.metadata_present {
+        let with = if a_info.metadata_present { "A" } else { "B" };
+        let _ = writeln!(
+            out,
+            "!!! Metadata version mismatch - only {with} has explicit metadata !!!\n"
+        );
+    } else if a_info.generator_version != b_info.generator_version {
+        let _ = writeln!(
+            out,
+            "!!! Metadata version mismatch - generated by onerom-gen {} and {} !!!\n",
+            generator(a_info),
+            generator(b_info)
+        );
+    }
+
+    field(&mut out, "Firmware", version(a_info), version(b_info));
+    field(&mut out, "Board", board(a_info), board(b_info));
+    field(&mut out, "MCU", mcu(a_info), mcu(b_info));
+    field(&mut out, "Metadata", metadata(a_info), metadata(b_info));
+    field(&mut out, "Generator", generator(a_info), generator(b_info));
+    field(&mut out, "Note", note(a_info), note(b_info));
+    field(
+        &mut out,
+        "ROM sets",
+        a_info.rom_sets.len().to_string(),
+        b_info.rom_sets.len().to_string(),
+    );
+
+    let num_sets = a_info.rom_sets.len().max(b_info.rom_sets.len());
+    for set in 0..num_sets {
+        let _ = writeln!(out);
+        let (a_set, b_set) = match (a_info.rom_sets.get(set), b_info.rom_sets.get(set)) {
+            (Some(a_set), Some(b_set)) => (a_set, b_set),
+            (Some(_), None) => {
+                let _ = writeln!(out, "* ROM set {set}: only in A");
+                continue;
+            }
+            _ => {
+                let _ = writeln!(out, "* ROM set {set}: only in B");
+                continue;
+            }
+        };
+
+        let _ = writeln!(out, "ROM set {set}:");
+        field(
+            &mut out,
+            "  Serve",
+            a_set.serve.to_string(),
+            b_set.serve.to_string(),
+        );
+        field(
+            &mut out,
+            "  ROMs",
+            a_set.roms.len().to_string(),
+            b_set.roms.len().to_string(),
+        );
+        let num_roms = a_set.roms.len().max(b_set.roms.len());
+        for rom in 0..num_roms {
+            field(
+                &mut out,
+                &format!("  Chip {rom}"),
+                chip(a_set.roms.get(rom)),
+                chip(b_set.roms.get(rom)),
+            );
+        }
+        let _ = writeln!(out, "  Image:       {}", compare_images(a, b, set));
+    }
+
+    out
+}
+
+// Writes a field's values, marking it if they differ
+fn field(out: &mut String, label: &str, a: String, b: String) {
+    let label = format!("{label}:");
+    let _ = if a == b {
+        writeln!(out, "  {label:<12} {a}")
+    } else {
+        writeln!(out, "* {label:<12} {a} -> {b}")
+    };
+}
+
+fn version(info: &SdrrInfo) -> String {
+    format!(
+        "{}.{}.{} (build {})",
+        info.version.major(),
+        info.version.minor(),
+        info.version.patch(),
+        info.version.build()
+    )
+}
+
+fn board(info: &SdrrInfo) -> String {
+    match (&info.board, &info.hw_rev) {
+        (Some(board), _) => board.name().to_string(),
+        (None, Some(hw_rev)) => format!("unknown ({hw_rev})"),
+        (None, None) => "unknown".to_string(),
+    }
+}
+
+fn mcu(info: &SdrrInfo) -> String {
+    info.mcu_variant
+        .map_or_else(|| "unknown".to_string(), |mcu| mcu.to_string())
+}
+
+fn metadata(info: &SdrrInfo) -> String {
+    if info.metadata_present {
+        "present".to_string()
+    } else {
+        "none".to_string()
+    }
+}
+
+fn generator(info: &SdrrInfo) -> String {
+    info.generator_version
+        .as_ref()
+        .map_or_else(|| "unknown".to_string(), |version| version.to_string())
+}
+
+fn note(info: &SdrrInfo) -> String {
+    info.metadata_note
+        .clone()
+        .unwrap_or_else(|| "none".to_string())
+}
+
+fn chip(rom: Option<&SdrrRomInfo>) -> String {
+    match rom {
+        Some(rom) => format!(
+            "{}, CS1 {}, CS2 {}, CS3 {}",
+            rom.rom_type, rom.cs1_state, rom.cs2_state, rom.cs3_state
+        ),
+        None => "none".to_string(),
+    }
+}
+
+// Compares a ROM set's images.  Single ROM sets are unscrambled first, so
+// images built for different boards can be compared.  Otherwise the images
+// are compared as stored, which is only meaningful for the same board.
+fn compare_images(a: &CompareFile, b: &CompareFile, set: usize) -> String {
+    let a_logical = extract_rom_image(&a.info, &a.data, set);
+    let b_logical = extract_rom_image(&b.info, &b.data, set);
+    if let (Ok(a_image), Ok(b_image)) = (&a_logical, &b_logical) {
+        return compare_bytes(a_image, b_image);
+    }
+
+    if a.info.board.is_some() && a.info.board == b.info.board {
+        match (
+            rom_set_data(&a.info, &a.data, set),
+            rom_set_data(&b.info, &b.data, set),
+        ) {
+            (Ok(a_image), Ok(b_image)) => {
+                format!("{} (compared as stored)", compare_bytes(a_image, b_image))
+            }
+            (Err(e), _) | (_, Err(e)) => format!("not compared - {e}"),
+        }
+    } else {
+        match (a_logical, b_logical) {
+            (Err(e), _) | (_, Err(e)) => format!("not compared - {e}"),
+            (Ok(_), Ok(_)) => unreachable!(),
+        }
+    }
+}
+
+fn compare_bytes(a: &[u8], b: &[u8]) -> String {
+    if a.len() != b.len() {
+        return format!("differ - {} vs {} bytes", a.len(), b.len());
+    }
+    match a.iter().zip(b).filter(|(a, b)| a != b).count() {
+        0 => "identical".to_string(),
+        count => format!("differ - {count} of {} bytes", a.len()),
+    }
+}
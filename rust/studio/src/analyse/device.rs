@@ -9,12 +9,13 @@ use iced::Task;
 use log::{debug, error, info, trace, warn};
 #[allow(unused_imports)]
 use onerom_config::fw::FirmwareVersion;
-use onerom_config::mcu::Variant as McuVariant;
+use onerom_config::mcu::{Family, Variant as McuVariant};
+use onerom_gen::BoardPinMap;
 use sdrr_fw_parser::{Parser, SdrrInfo, readers::MemoryReader};
 
 use crate::analyse::{Analyse, AnalyseState, FW_VERSION_METADATA, Message};
 use crate::app::AppMessage;
-use crate::device::{Address, Client, Message as DeviceMessage};
+use crate::device::{Address, Client, Message as DeviceMessage, ReadLength};
 use crate::hw::HardwareInfo;
 use crate::studio::Message as StudioMessage;
 
@@ -29,7 +30,8 @@ pub enum DetectState {
     Fire,
 
     /// Re-reading device flash after initial read, used when firmware is pre-
-    /// v0.5.0 and more than 64KB of flash is needed to parse fully.
+    /// v0.5.0 and more than 64KB of flash is needed to parse fully, or when
+    /// RP2350 ROM images extend beyond the initial 64KB read.
     Reread(McuVariant, FirmwareVersion),
 
     /// Detection complete
@@ -130,6 +132,17 @@ pub async fn handle_device_data(data: Vec<u8>) -> AppMessage {
         }
         let (info, data) = info.unwrap();
 
+        // RP2350 images with many ROM sets can extend beyond the initial
+        // read, so re-read enough to cover all of the ROM images
+        if let Some(mcu) = info.mcu_variant
+            && mcu.family() == Family::Rp2350
+            && let Some(bytes) = rom_images_end_bytes(&info, mcu)
+            && bytes > data_len
+        {
+            debug!("RP2350 ROM images extend to {bytes} bytes, re-reading");
+            return Message::RereadDevice(mcu, info.version, ReadLength::Words(bytes / 4)).into();
+        }
+
         if info.version >= FW_VERSION_METADATA || info.parse_errors.is_empty() {
             // Firmware is v0.5.0 or later, so 64KB read is sufficient, or
             // we parsed everything OK anyway
@@ -148,11 +161,34 @@ pub async fn handle_device_data(data: Vec<u8>) -> AppMessage {
         }
         let mcu = info.mcu_variant.unwrap();
 
-        // Ready to re-read full flash
-        Message::RereadDevice(mcu, info.version).into()
+        // Ready to re-read full flash.  We now have the MCU variant, so can
+        // assume the full flash size - but the device will query the actual
+        // size where it can, in case the decoded MCU is wrong.
+        let length = ReadLength::FullFlash {
+            assumed_bytes: mcu.flash_storage_bytes(),
+        };
+        Message::RereadDevice(mcu, info.version, length).into()
     }
 }
 
+// Number of bytes from the start of flash needed to cover all of the ROM
+// images, rounded up to a whole word.  If the ROM set headers themselves
+// couldn't be read, the whole flash is needed.  Capped at the flash size, in
+// case of corrupt pointers.
+fn rom_images_end_bytes(info: &SdrrInfo, mcu: McuVariant) -> Option<usize> {
+    let flash_bytes = mcu.flash_storage_bytes();
+    if info.rom_sets.is_empty() {
+        return (info.rom_set_count > 0).then_some(flash_bytes);
+    }
+
+    let base = mcu.family().get_flash_base();
+    info.rom_sets
+        .iter()
+        .map(|set| set.data_ptr.saturating_add(set.size).saturating_sub(base) as usize)
+        .max()
+        .map(|end| end.next_multiple_of(4).min(flash_bytes))
+}
+
 /// Flash firmware to device
 pub fn flash_firmware(analyse: &mut Analyse) -> Task<AppMessage> {
     // Check if busy
@@ -182,6 +218,7 @@ pub fn flash_firmware(analyse: &mut Analyse) -> Task<AppMessage> {
 
         // Update state
         analyse.state = AnalyseState::Flashing;
+        analyse.flash_progress = None;
         analyse.analysis_content = format!("Flashing {filename:?} to device...");
 
         // Send flash message to device module
@@ -199,6 +236,15 @@ pub fn flash_firmware(analyse: &mut Analyse) -> Task<AppMessage> {
     }
 }
 
+/// Handle firmware flash progress message
+pub fn firmware_flash_progress(analyse: &mut Analyse, fraction: f32) {
+    if analyse.state == AnalyseState::Flashing {
+        analyse.flash_progress = Some(fraction);
+    } else {
+        debug!("Ignoring flash progress received while not flashing");
+    }
+}
+
 /// Handle firmware flash complete message
 pub fn firmware_flash_complete(analyse: &mut Analyse, result: Result<(), String>) {
     // Check state
@@ -213,6 +259,7 @@ pub fn firmware_flash_complete(analyse: &mut Analyse, result: Result<(), String>
 
     // Update state
     analyse.state = AnalyseState::Idle;
+    analyse.flash_progress = None;
 
     // Update analysis content based on result
     match result {
@@ -273,7 +320,7 @@ pub fn detect_device(analyse: &mut Analyse, err: Option<String>) -> Task<AppMess
         client: Client::Analyse,
         hw_info,
         address: detect_state.flash_base(),
-        words: 65536 / 4,
+        length: ReadLength::Words(65536 / 4),
     }));
 
     // Chain the two tasks together
@@ -286,28 +333,20 @@ pub fn reread_device(
     analyse: &mut Analyse,
     mcu: McuVariant,
     fw_version: FirmwareVersion,
+    length: ReadLength,
 ) -> AppMessage {
     // Indicate we're rereading
-    debug!(
-        "Re-reading full flash for MCU variant {} with fw v{}.{}.{}",
-        mcu,
-        fw_version.major(),
-        fw_version.minor(),
-        fw_version.patch()
-    );
-    analyse.analysis_content += &format!(
-        "\nRe-reading full flash from {mcu} based device with firmware v{}.{}.{}...",
-        fw_version.major(),
-        fw_version.minor(),
-        fw_version.patch()
-    );
+    let amount = match &length {
+        ReadLength::FullFlash { .. } => "full flash".to_string(),
+        ReadLength::Words(words) => format!("{}KB of flash", (words * 4).div_ceil(1024)),
+    };
+    debug!("Re-reading {amount} for MCU variant {mcu} with fw v{fw_version}");
+    analyse.analysis_content +=
+        &format!("\nRe-reading {amount} from {mcu} based device with firmware v{fw_version}...");
     analyse.state = AnalyseState::Detecting(DetectState::Reread(mcu.clone(), fw_version.clone()));
 
-    // Build the message re-read the flash (and re-parse).  We now have the
-    // MCU variant, so can get the full flash size - this is what we need to
-    // read.
+    // Build the message re-read the flash (and re-parse)
     let address = Address::Absolute(mcu.family().get_flash_base());
-    let words = mcu.flash_storage_bytes() / 4;
     let hw_info = HardwareInfo {
         board: None,
         model: None,
@@ -319,7 +358,7 @@ pub fn reread_device(
         client: Client::Analyse,
         hw_info,
         address,
-        words,
+        length,
     }
     .into()
 }
@@ -344,6 +383,26 @@ pub fn file_device_loaded(
                 Err(e) => format!("Error serializing info to JSON: {}", e),
             };
 
+            // Show any note stamped into the metadata, such as an asset tag
+            if let Some(note) = &info.metadata_note {
+                analyse.analysis_content += &format!("\n---\nNote: {note}\n");
+            }
+
+            // List any parse errors readably, with where each occurred
+            if !info.parse_errors.is_empty() {
+                analyse.analysis_content += "\n---\nParse errors:";
+                for error in &info.parse_errors {
+                    analyse.analysis_content += &format!("\n- {error}");
+                }
+                analyse.analysis_content += "\n";
+            }
+
+            // Follow with the board's pin map, to help debug wrong bytes
+            // being served
+            if let Some(board) = info.board {
+                analyse.analysis_content += &format!("\n---\n{}", board.pin_map_table());
+            }
+
             // Store firmware info and file contents
             analyse.fw_info = Some(info);
             analyse.file_contents = if is_file { Some(data) } else { None };
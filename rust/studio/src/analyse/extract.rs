@@ -0,0 +1,161 @@
+// Copyright (C) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT License
+
+//! Analyse ROM extraction methods
+//!
+//! Reverses the address and data scrambling applied to a ROM image when
+//! building firmware, so an individual ROM can be saved back out of a loaded
+//! firmware file - for example, to compare against a known-good dump.
+
+use iced::Task;
+use rfd::FileDialog;
+use std::path::PathBuf;
+
+#[allow(unused_imports)]
+use log::{debug, error, info, trace, warn};
+use onerom_config::chip::ChipType;
+use onerom_gen::{Chip, CsConfig, CsLogic};
+use sdrr_fw_parser::{SdrrCsState, SdrrInfo};
+
+use crate::analyse::{Analyse, Message};
+use crate::app::AppMessage;
+
+/// A ROM set within the loaded firmware, as shown in the extraction pick list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RomSetIndex(pub usize);
+
+impl std::fmt::Display for RomSetIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ROM Set {}", self.0)
+    }
+}
+
+/// Extract ROM requested - show the save dialog
+pub fn extract_rom(analyse: &mut Analyse) -> Task<AppMessage> {
+    if analyse.state.is_busy() {
+        warn!(
+            "Cannot extract ROM - Analyse tab is busy ({})",
+            analyse.state
+        );
+        return Task::none();
+    }
+    let Some(set) = analyse.extract_set else {
+        warn!("Extract ROM requested with no ROM set selected");
+        return Task::none();
+    };
+
+    // Default to naming the ROM after the firmware file
+    let stem = analyse
+        .fw_file
+        .as_ref()
+        .and_then(|path| path.file_stem())
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "onerom".to_string());
+    let filename = format!("{stem}-set{set}.rom");
+    Task::future(extract_rom_async(filename))
+}
+
+// Async function to show the save file dialog
+async fn extract_rom_async(filename: String) -> AppMessage {
+    let dialog = FileDialog::new()
+        .set_title("Save ROM Image")
+        .set_file_name(filename)
+        .add_filter("ROM Images", &["rom", "bin"])
+        .set_directory(".");
+    let path = dialog.save_file();
+    Message::ExtractRomFilename(path).into()
+}
+
+/// Extract ROM filename selected (or cancelled) - unscramble and save the
+/// ROM image
+pub fn extract_rom_filename(analyse: &mut Analyse, path: Option<PathBuf>) -> Task<AppMessage> {
+    let Some(path) = path else {
+        debug!("Extract ROM cancelled");
+        return Task::none();
+    };
+
+    let result = match (
+        &analyse.fw_info,
+        &analyse.file_contents,
+        analyse.extract_set,
+    ) {
+        (Some(info), Some(data), Some(set)) => extract_rom_image(info, data, set)
+            .and_then(|image| std::fs::write(&path, image).map_err(|e| e.to_string())),
+        _ => Err("No firmware file loaded".to_string()),
+    };
+
+    match result {
+        Ok(()) => {
+            debug!("Extracted ROM set {:?} to {path:?}", analyse.extract_set);
+            analyse.analysis_content += &format!("\nExtracted ROM image to {path:?}\n");
+        }
+        Err(e) => {
+            warn!("Failed to extract ROM: {e}");
+            analyse.analysis_content += &format!("\nFailed to extract ROM:\n- {e}\n");
+        }
+    }
+    Task::none()
+}
+
+// Recover the logical ROM image for the given ROM set from the firmware
+// file's contents.  Only single ROM sets are supported.
+pub fn extract_rom_image(info: &SdrrInfo, data: &[u8], set: usize) -> Result<Vec<u8>, String> {
+    let board = info
+        .board
+        .ok_or("Firmware board unknown - cannot unscramble ROM")?;
+    let rom_set = info
+        .rom_sets
+        .get(set)
+        .ok_or_else(|| format!("ROM set {set} not present in firmware"))?;
+    let [rom] = rom_set.roms.as_slice() else {
+        return Err(format!(
+            "ROM set {set} contains {} ROMs - only single ROM sets can be extracted",
+            rom_set.roms.len()
+        ));
+    };
+    let chip_type = ChipType::try_from_str(&rom.rom_type.to_string())
+        .ok_or_else(|| format!("Unsupported ROM type {}", rom.rom_type))?;
+    let physical = rom_set_data(info, data, set)?;
+
+    let cs_config = if rom.cs3_state == SdrrCsState::CeOe {
+        CsConfig::CeOe
+    } else {
+        CsConfig::new(
+            cs_logic(rom.cs1_state),
+            cs_logic(rom.cs2_state),
+            cs_logic(rom.cs3_state),
+        )
+    };
+    let chip = Chip::placeholder(set, String::new(), None, &chip_type, cs_config);
+    chip.unscramble_image(&board, physical)
+        .map_err(|e| e.to_string())
+}
+
+// Returns the given ROM set's image, as stored (scrambled) in the firmware
+// file's contents.
+pub fn rom_set_data<'a>(info: &SdrrInfo, data: &'a [u8], set: usize) -> Result<&'a [u8], String> {
+    let board = info
+        .board
+        .ok_or("Firmware board unknown - cannot locate ROM set data")?;
+    let rom_set = info
+        .rom_sets
+        .get(set)
+        .ok_or_else(|| format!("ROM set {set} not present in firmware"))?;
+
+    // The firmware file starts at the flash base
+    let start = rom_set
+        .data_ptr
+        .checked_sub(board.mcu_family().get_flash_base())
+        .ok_or("ROM set data outside of flash")? as usize;
+    data.get(start..start + rom_set.size as usize)
+        .ok_or_else(|| format!("ROM set {set} data extends beyond the end of the file"))
+}
+
+fn cs_logic(state: SdrrCsState) -> Option<CsLogic> {
+    match state {
+        SdrrCsState::ActiveLow => Some(CsLogic::ActiveLow),
+        SdrrCsState::ActiveHigh => Some(CsLogic::ActiveHigh),
+        SdrrCsState::NotUsed | SdrrCsState::CeOe => None,
+    }
+}
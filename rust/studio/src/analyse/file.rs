@@ -44,7 +44,7 @@ pub fn load_file(analyse: &mut Analyse, path: Option<PathBuf>) -> Task<AppMessag
 }
 
 // Actual file load routine
-async fn load_file_async(path: PathBuf) -> Result<(SdrrInfo, Vec<u8>), String> {
+pub async fn load_file_async(path: PathBuf) -> Result<(SdrrInfo, Vec<u8>), String> {
     // Check we have a valid file
     if path.exists() && path.is_file() {
         // Read in the file
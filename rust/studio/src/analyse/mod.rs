@@ -7,7 +7,9 @@
 //! Analyse parses a device or firmware file's contents and displays
 //! information about it.
 
+mod compare;
 mod device;
+mod extract;
 mod file;
 mod msg;
 mod view;
@@ -38,6 +40,7 @@ pub enum Source {
     Device,
     #[default]
     File,
+    Compare,
 }
 
 impl std::fmt::Display for Source {
@@ -45,6 +48,7 @@ impl std::fmt::Display for Source {
         match self {
             Source::Device => write!(f, "Device"),
             Source::File => write!(f, "File"),
+            Source::Compare => write!(f, "Compare"),
         }
     }
 }
@@ -112,6 +116,12 @@ pub struct Analyse {
 
     // Loaded firmware file contents
     file_contents: Option<Vec<u8>>,
+
+    // Progress of the current flash operation, if reported
+    flash_progress: Option<f32>,
+
+    // ROM set selected for extraction
+    extract_set: Option<usize>,
 }
 
 impl Default for Analyse {
@@ -123,6 +133,8 @@ impl Default for Analyse {
             fw_info: Default::default(),
             fw_file: Default::default(),
             file_contents: Default::default(),
+            flash_progress: Default::default(),
+            extract_set: Default::default(),
         }
     }
 }
@@ -167,6 +179,7 @@ impl Analyse {
         self.analysis_content += &self.state.content().to_string();
         self.fw_info = None;
         self.file_contents = None;
+        self.extract_set = None;
         self.clear_hw_info()
     }
 
@@ -14,20 +14,25 @@ use onerom_config::fw::FirmwareVersion;
 use onerom_config::mcu::Variant as McuVariant;
 use sdrr_fw_parser::SdrrInfo;
 
+use crate::analyse::compare::{
+    CompareFile, compare_file_chooser, compare_loaded, load_compare_files,
+};
 use crate::analyse::device::{
-    detect_device, file_device_loaded, firmware_flash_complete, flash_firmware, handle_device_data,
-    reread_device,
+    detect_device, file_device_loaded, firmware_flash_complete, firmware_flash_progress,
+    flash_firmware, handle_device_data, reread_device,
 };
+use crate::analyse::extract::{extract_rom, extract_rom_filename};
 use crate::analyse::file::{fw_file_chooser, load_file};
 use crate::analyse::{Analyse, Source};
 use crate::app::AppMessage;
+use crate::device::ReadLength;
 use crate::studio::RuntimeInfo;
 
 /// Analyse tab messages
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub enum Message {
-    /// Source (File/Device) selected
+    /// Source (File/Device/Compare) selected
     SourceSelected(Source),
 
     // Handle file
@@ -35,17 +40,28 @@ pub enum Message {
     FileSelected(Option<PathBuf>),
     FileLoaded(Result<(SdrrInfo, Vec<u8>), String>),
 
+    // Handle comparing files
+    SelectCompareFiles,
+    CompareFilesSelected(Option<Vec<PathBuf>>),
+    CompareLoaded(Result<(CompareFile, CompareFile), String>),
+
     // Handle device
     DetectDevice,
     DeviceLoaded(Result<(SdrrInfo, Vec<u8>), String>),
     DeviceData(Vec<u8>),
     ReadFailed(String),
-    RereadDevice(McuVariant, FirmwareVersion),
+    RereadDevice(McuVariant, FirmwareVersion, ReadLength),
 
     // Handle flashing
     FlashFirmware,
+    FlashProgress(f32),
     FlashComplete(Result<(), String>),
 
+    // Handle ROM extraction
+    ExtractSetSelected(usize),
+    ExtractRom,
+    ExtractRomFilename(Option<PathBuf>),
+
     // Progress tick
     ProgressTick,
 }
@@ -57,13 +73,20 @@ impl std::fmt::Display for Message {
             Message::SelectFile => write!(f, "SelectFile"),
             Message::FileSelected(_) => write!(f, "FileSelected(...)"),
             Message::FileLoaded(_) => write!(f, "FileLoaded(...)"),
+            Message::SelectCompareFiles => write!(f, "SelectCompareFiles"),
+            Message::CompareFilesSelected(_) => write!(f, "CompareFilesSelected(...)"),
+            Message::CompareLoaded(_) => write!(f, "CompareLoaded(...)"),
             Message::DetectDevice => write!(f, "DetectDevice"),
             Message::DeviceLoaded(_) => write!(f, "DeviceLoaded(...)"),
             Message::DeviceData(_) => write!(f, "DeviceData(...)"),
             Message::ReadFailed(err) => write!(f, "ReadFailed({err})"),
-            Message::RereadDevice(_, _) => write!(f, "RereadDevice"),
+            Message::RereadDevice(_, _, length) => write!(f, "RereadDevice({length})"),
             Message::FlashFirmware => write!(f, "FlashFirmware"),
+            Message::FlashProgress(fraction) => write!(f, "FlashProgress({fraction})"),
             Message::FlashComplete(_) => write!(f, "FlashComplete(...)"),
+            Message::ExtractSetSelected(set) => write!(f, "ExtractSetSelected({set})"),
+            Message::ExtractRom => write!(f, "ExtractRom"),
+            Message::ExtractRomFilename(path) => write!(f, "ExtractRomFilename({path:?})"),
             Message::ProgressTick => write!(f, "ProgressTick"),
         }
     }
@@ -99,6 +122,24 @@ pub fn message(
             file_device_loaded(analyse, result, true)
         }
 
+        // Handle compare operations
+        Message::SelectCompareFiles => {
+            debug!("Selecting firmware files to compare");
+            compare_file_chooser()
+        }
+        Message::CompareFilesSelected(paths) => {
+            debug!("Firmware files selected for comparison: {:?}", paths);
+            load_compare_files(analyse, paths)
+        }
+        Message::CompareLoaded(result) => {
+            debug!(
+                "Firmware files for comparison loaded: {}",
+                if result.is_ok() { "OK" } else { "Error" }
+            );
+            compare_loaded(analyse, result);
+            Task::none()
+        }
+
         // Handle device operations
         Message::DetectDevice => {
             debug!("Starting device detection");
@@ -122,15 +163,9 @@ pub fn message(
             // Move onto trying to detect next device type
             detect_device(analyse, Some(err))
         }
-        Message::RereadDevice(mcu, fw_version) => {
-            debug!(
-                "Re-reading device flash for MCU variant {} with fw v{}.{}.{}",
-                mcu,
-                fw_version.major(),
-                fw_version.minor(),
-                fw_version.patch()
-            );
-            Task::done(reread_device(analyse, mcu, fw_version))
+        Message::RereadDevice(mcu, fw_version, length) => {
+            debug!("Re-reading device flash for MCU variant {mcu} with fw v{fw_version}");
+            Task::done(reread_device(analyse, mcu, fw_version, length))
         }
 
         // Handle flashing
@@ -138,6 +173,10 @@ pub fn message(
             debug!("Flashing firmware to device");
             flash_firmware(analyse)
         }
+        Message::FlashProgress(fraction) => {
+            firmware_flash_progress(analyse, fraction);
+            Task::none()
+        }
         Message::FlashComplete(result) => {
             debug!(
                 "Firmware flash complete: {}",
@@ -147,6 +186,18 @@ pub fn message(
             Task::none()
         }
 
+        // Handle ROM extraction
+        Message::ExtractSetSelected(set) => {
+            debug!("ROM set {set} selected for extraction");
+            analyse.extract_set = Some(set);
+            Task::none()
+        }
+        Message::ExtractRom => {
+            debug!("Extracting ROM");
+            extract_rom(analyse)
+        }
+        Message::ExtractRomFilename(path) => extract_rom_filename(analyse, path),
+
         // Handle progress tick
         Message::ProgressTick => {
             analyse.progress_tick();
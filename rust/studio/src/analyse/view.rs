@@ -12,6 +12,7 @@ use log::{debug, error, info, trace, warn};
 #[allow(unused_imports)]
 use onerom_config::fw::FirmwareVersion;
 
+use crate::analyse::extract::RomSetIndex;
 use crate::analyse::{Analyse, AnalyseState, Message, Source};
 use crate::app::AppMessage;
 use crate::device::Device;
@@ -25,9 +26,12 @@ pub const ANALYSIS_TEXT_DEFAULT: &str = "No firmware analysed";
 // Button names
 const DEVICE_BUTTON_NAME: &str = "Device";
 const FILE_BUTTON_NAME: &str = "File";
+const COMPARE_BUTTON_NAME: &str = "Compare";
 const SOURCE_DEVICE_BUTTON_NAME: &str = "Detect Device";
 const SOURCE_FILE_BUTTON_NAME: &str = "Select File";
+const SOURCE_COMPARE_BUTTON_NAME: &str = "Select Files";
 const FLASH_BUTTON_NAME: &str = "Flash";
+const EXTRACT_BUTTON_NAME: &str = "Extract ROM";
 
 /// Main analyse tab view
 pub fn view<'a>(
@@ -74,38 +78,27 @@ fn top_level_buttons<'a>(analyse: &'a Analyse, device: &'a Device) -> Element<'a
 
 // Firmware source selection buttons
 fn fw_source_buttons<'a>(analyse: &'a Analyse) -> Element<'a, AppMessage> {
-    // Determine button states based on selected tab
-    let is_file_selected = matches!(analyse.selected_source_tab, Source::File);
-
-    // Enable the file button only if not already selected and not busy
-    let file_message = if is_file_selected {
-        None
-    } else {
-        if analyse.state.is_idle() {
-            Some(Message::SourceSelected(Source::File).into())
-        } else {
-            None
-        }
-    };
-
-    // Enable the device button only if not already selected and not busy
-    let device_message = if is_file_selected {
-        if analyse.state.is_idle() {
-            Some(Message::SourceSelected(Source::Device).into())
+    // Enable each button only if its source is not already selected and not
+    // busy
+    let source_button = |name: &'a str, source: Source| {
+        let is_selected = analyse.selected_source_tab == source;
+        let message = if !is_selected && analyse.state.is_idle() {
+            Some(Message::SourceSelected(source).into())
         } else {
             None
-        }
-    } else {
-        None
+        };
+        Style::text_button_small(name, message, is_selected)
     };
 
     // Create the buttons
-    let file_button = Style::text_button_small(FILE_BUTTON_NAME, file_message, is_file_selected);
-    let device_button =
-        Style::text_button_small(DEVICE_BUTTON_NAME, device_message, !is_file_selected);
+    let file_button = source_button(FILE_BUTTON_NAME, Source::File);
+    let device_button = source_button(DEVICE_BUTTON_NAME, Source::Device);
+    let compare_button = source_button(COMPARE_BUTTON_NAME, Source::Compare);
 
     // Stick them in a row
-    row![file_button, device_button].spacing(20).into()
+    row![file_button, device_button, compare_button]
+        .spacing(20)
+        .into()
 }
 
 // Firmware source control buttons - on the right, select file, etc
@@ -113,16 +106,24 @@ fn fw_source_control<'a>(analyse: &'a Analyse, device: &'a Device) -> Element<'a
     // Show appropriate control based on selected source
     let source_button = match analyse.selected_source_tab {
         Source::Device => fw_source_device_control(analyse, device),
-        Source::File => fw_source_file_control(analyse),
+        Source::File => {
+            fw_source_file_control(analyse, SOURCE_FILE_BUTTON_NAME, Message::SelectFile)
+        }
+        Source::Compare => fw_source_file_control(
+            analyse,
+            SOURCE_COMPARE_BUTTON_NAME,
+            Message::SelectCompareFiles,
+        ),
     };
 
     // Create an empty row
     let row = row![];
 
-    // Show flash file if on file source tab, and always the buttons for this
-    // source
+    // Show ROM extraction and flash file if on file source tab, and always
+    // the buttons for this source
     if analyse.selected_source_tab == Source::File && analyse.file_contents.is_some() {
-        row.push(flash_file_button(analyse, device))
+        row.push(extract_rom_controls(analyse))
+            .push(flash_file_button(analyse, device))
     } else {
         row
     }
@@ -187,15 +188,50 @@ fn flash_file_button<'a>(analyse: &'a Analyse, device: &'a Device) -> Button<'a,
 
     // Button content changes based on state
     let content = if analyse.state.is_idle() {
-        FLASH_BUTTON_NAME
+        FLASH_BUTTON_NAME.to_string()
     } else {
-        "Flashing..."
+        match analyse.flash_progress {
+            Some(fraction) => format!("Flashing... {:.0}%", fraction * 100.0),
+            None => "Flashing...".to_string(),
+        }
     };
 
     // Create the button
     Style::text_button_small(content, message, highlighted)
 }
 
+// ROM set pick list and extract button
+fn extract_rom_controls<'a>(analyse: &'a Analyse) -> Element<'a, AppMessage> {
+    let num_sets = analyse
+        .fw_info
+        .as_ref()
+        .map_or(0, |info| info.rom_sets.len());
+    if num_sets == 0 {
+        return row![].into();
+    }
+
+    // Ignore pick list selection while busy
+    let options: Vec<RomSetIndex> = (0..num_sets).map(RomSetIndex).collect();
+    let selected = analyse.extract_set.map(RomSetIndex);
+    let pick_list = if analyse.state.is_idle() {
+        Style::pick_list_small(options, selected, |set: RomSetIndex| {
+            Message::ExtractSetSelected(set.0).into()
+        })
+    } else {
+        Style::pick_list_small(options, selected, |_| AppMessage::Nop)
+    }
+    .placeholder("ROM Set");
+
+    let ready = analyse.state.is_idle() && analyse.extract_set.is_some();
+    let message = ready.then(|| Message::ExtractRom.into());
+    let button = Style::text_button_small(EXTRACT_BUTTON_NAME, message, ready);
+
+    row![pick_list, button]
+        .spacing(10)
+        .align_y(iced::alignment::Vertical::Center)
+        .into()
+}
+
 // Device source control button
 fn fw_source_device_control<'a>(
     analyse: &'a Analyse,
@@ -227,18 +263,22 @@ fn fw_source_device_control<'a>(
     Style::text_button_small(content, message, highlighted)
 }
 
-// File source control button
-fn fw_source_file_control<'a>(analyse: &'a Analyse) -> Button<'a, AppMessage> {
+// File and compare source control button
+fn fw_source_file_control<'a>(
+    analyse: &'a Analyse,
+    name: &'a str,
+    message: Message,
+) -> Button<'a, AppMessage> {
     // Only enable this button if file not being loaded
     let file_control_message = if analyse.state.is_idle() {
-        Some(Message::SelectFile.into())
+        Some(message.into())
     } else {
         None
     };
 
     // Button content changes based on state
     let content = if analyse.state != AnalyseState::Loading {
-        SOURCE_FILE_BUTTON_NAME
+        name
     } else {
         "Loading..."
     };
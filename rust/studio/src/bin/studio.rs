@@ -17,8 +17,12 @@ use onerom_studio::style;
 
 // Main - application entry point
 fn main() -> iced::Result {
-    // Initialize logging
-    log::init_logging();
+    // Initialize logging, at any level given on the command line
+    let level = log::level_from_args(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        None
+    });
+    log::init_logging(level);
 
     // Run the application
     iced::application("One ROM Studio", App::update, App::view)
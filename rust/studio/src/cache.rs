@@ -0,0 +1,311 @@
+// Copyright (C) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT License
+
+//! On-disk cache of downloaded firmware releases, ROM configs and their
+//! manifests, so images can still be built on flaky or offline connections.
+//!
+//! Published firmware releases never change, so are served from the cache
+//! without touching the network.  Manifests and configs can be updated, so
+//! are always downloaded when possible, with the cache used as a fallback.
+
+#[allow(unused_imports)]
+use log::{debug, error, info, trace, warn};
+use std::fs;
+use std::path::PathBuf;
+
+use onerom_config::fw::FirmwareVersion;
+use onerom_config::hw::Board;
+use onerom_config::mcu::Variant as McuVariant;
+use onerom_fw::net::Releases;
+
+/// Where downloaded data was served from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Served {
+    /// Downloaded from the network
+    Network,
+
+    /// Served from the on-disk cache
+    Cache,
+
+    /// A local file, or built in Studio - neither downloaded nor cached
+    Local,
+}
+
+impl std::fmt::Display for Served {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Served::Network => write!(f, "network"),
+            Served::Cache => write!(f, "cache"),
+            Served::Local => write!(f, "local"),
+        }
+    }
+}
+
+/// Identifies an item in the cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheKey {
+    /// Firmware release for a board and MCU
+    Firmware {
+        board: Board,
+        mcu: McuVariant,
+        version: FirmwareVersion,
+    },
+
+    /// ROM config, by its partial URL from the configs manifest
+    Config { url: String },
+
+    /// Firmware releases manifest
+    ReleasesManifest,
+
+    /// ROM configs manifest
+    ConfigManifest,
+}
+
+impl CacheKey {
+    /// Returns the item's path, relative to the cache's root
+    pub fn path(&self) -> PathBuf {
+        match self {
+            CacheKey::Firmware {
+                board,
+                mcu,
+                version,
+            } => [
+                "firmware".to_string(),
+                board.name().to_ascii_lowercase(),
+                mcu.to_string().to_ascii_lowercase(),
+                Releases::version_str(version),
+                "firmware.bin".to_string(),
+            ]
+            .iter()
+            .collect(),
+            CacheKey::Config { url } => {
+                // Keep the URL's directory structure, but nothing which
+                // could escape the cache
+                let mut path = PathBuf::from("configs");
+                for segment in url.split('/') {
+                    if segment.is_empty() || segment == "." || segment == ".." {
+                        continue;
+                    }
+                    let segment: String = segment
+                        .chars()
+                        .map(|c| {
+                            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                                c
+                            } else {
+                                '_'
+                            }
+                        })
+                        .collect();
+                    path.push(segment);
+                }
+                path
+            }
+            CacheKey::ReleasesManifest => PathBuf::from("releases.json"),
+            CacheKey::ConfigManifest => PathBuf::from("configs.json"),
+        }
+    }
+}
+
+/// On-disk cache.  Failures to read or write the cache are logged, and
+/// otherwise treated as misses - the cache is never required.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    root: PathBuf,
+}
+
+impl Cache {
+    /// Create a cache rooted at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Returns Studio's cache, under the platform's cache directory
+    pub fn studio() -> Option<Self> {
+        directories::ProjectDirs::from("org", "onerom", "studio")
+            .map(|dirs| Self::new(dirs.cache_dir().join("downloads")))
+    }
+
+    /// Returns the cached item, if present
+    pub fn get(&self, key: &CacheKey) -> Option<Vec<u8>> {
+        let file_path = self.root.join(key.path());
+        match fs::read(&file_path) {
+            Ok(data) => {
+                trace!("Cache hit for {file_path:?}");
+                Some(data)
+            }
+            Err(e) => {
+                trace!("Cache miss for {file_path:?}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Stores an item, replacing any existing one
+    pub fn put(&self, key: &CacheKey, data: &[u8]) {
+        let file_path = self.root.join(key.path());
+        if let Some(parent) = file_path.parent()
+            && let Err(e) = fs::create_dir_all(parent)
+        {
+            warn!("Failed to create cache directory {parent:?}: {e}");
+            return;
+        }
+
+        // Write to a temporary file first, so an interrupted write can't
+        // leave a truncated item to be served later
+        let tmp_path = file_path.with_extension("tmp");
+        let result = fs::write(&tmp_path, data).and_then(|_| fs::rename(&tmp_path, &file_path));
+        if let Err(e) = result {
+            warn!("Failed to write cache file {file_path:?}: {e}");
+            let _ = fs::remove_file(&tmp_path);
+        }
+    }
+
+    /// Serves an item which never changes once published, such as a firmware
+    /// release.  The cache is checked first, and `download` only called on a
+    /// miss, its result being stored.
+    pub async fn cached_first<E, F>(
+        cache: Option<&Self>,
+        key: &CacheKey,
+        download: F,
+    ) -> Result<(Vec<u8>, Served), E>
+    where
+        F: Future<Output = Result<Vec<u8>, E>>,
+    {
+        if let Some(data) = cache.and_then(|cache| cache.get(key)) {
+            debug!("Serving {:?} from cache", key.path());
+            return Ok((data, Served::Cache));
+        }
+
+        let data = download.await?;
+        if let Some(cache) = cache {
+            cache.put(key, &data);
+        }
+        Ok((data, Served::Network))
+    }
+
+    /// Serves an item which can change, such as a manifest.  `download` is
+    /// always tried first, and its result stored, with the cache only used if
+    /// it fails.
+    pub async fn network_first<E, F>(
+        cache: Option<&Self>,
+        key: &CacheKey,
+        download: F,
+    ) -> Result<(Vec<u8>, Served), E>
+    where
+        F: Future<Output = Result<Vec<u8>, E>>,
+    {
+        match download.await {
+            Ok(data) => {
+                if let Some(cache) = cache {
+                    cache.put(key, &data);
+                }
+                Ok((data, Served::Network))
+            }
+            Err(e) => match cache.and_then(|cache| cache.get(key)) {
+                Some(data) => {
+                    info!("Download failed, using cached {:?}", key.path());
+                    Ok((data, Served::Cache))
+                }
+                None => Err(e),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cache operations never wait, so a single poll completes them
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        match std::pin::pin!(future).poll(&mut cx) {
+            std::task::Poll::Ready(output) => output,
+            std::task::Poll::Pending => panic!("Cache operation unexpectedly pending"),
+        }
+    }
+
+    #[test]
+    fn test_cache_key_paths() {
+        let firmware = CacheKey::Firmware {
+            board: Board::Ice24UsbH,
+            mcu: McuVariant::F411RE,
+            version: FirmwareVersion::new(0, 6, 1, 7),
+        };
+        let expected: PathBuf = ["firmware", "ice-24-usb-h", "f411re", "0.6.1", "firmware.bin"]
+            .iter()
+            .collect();
+        assert_eq!(firmware.path(), expected);
+
+        // Build numbers don't distinguish releases
+        let other_build = CacheKey::Firmware {
+            board: Board::Ice24UsbH,
+            mcu: McuVariant::F411RE,
+            version: FirmwareVersion::new(0, 6, 1, 0),
+        };
+        assert_eq!(other_build.path(), firmware.path());
+
+        let config = CacheKey::Config {
+            url: "c64/kernal v3.json".to_string(),
+        };
+        let expected: PathBuf = ["configs", "c64", "kernal_v3.json"].iter().collect();
+        assert_eq!(config.path(), expected);
+
+        // Nothing escapes the cache
+        let config = CacheKey::Config {
+            url: "../../etc//passwd".to_string(),
+        };
+        let expected: PathBuf = ["configs", "etc", "passwd"].iter().collect();
+        assert_eq!(config.path(), expected);
+
+        assert_ne!(
+            CacheKey::ReleasesManifest.path(),
+            CacheKey::ConfigManifest.path()
+        );
+    }
+
+    #[test]
+    fn test_cache_hit_and_miss() {
+        let dir = std::env::temp_dir().join(format!("onerom-cache-{}", std::process::id()));
+        let cache = Cache::new(&dir);
+        let key = CacheKey::Firmware {
+            board: Board::Ice24UsbH,
+            mcu: McuVariant::F411RE,
+            version: FirmwareVersion::new(0, 6, 1, 0),
+        };
+        let ok = |data: &[u8]| std::future::ready(Ok::<_, String>(data.to_vec()));
+        let failed = || std::future::ready(Err::<Vec<u8>, _>("offline".to_string()));
+
+        // Miss - downloaded, then stored
+        assert_eq!(cache.get(&key), None);
+        let result = block_on(Cache::cached_first(Some(&cache), &key, ok(b"v1")));
+        assert_eq!(result, Ok((b"v1".to_vec(), Served::Network)));
+        assert_eq!(cache.get(&key), Some(b"v1".to_vec()));
+
+        // Hit - served from the cache, without downloading
+        let result = block_on(Cache::cached_first(Some(&cache), &key, failed()));
+        assert_eq!(result, Ok((b"v1".to_vec(), Served::Cache)));
+
+        // Network first - a successful download replaces the cached item
+        let key = CacheKey::ReleasesManifest;
+        let result = block_on(Cache::network_first(Some(&cache), &key, failed()));
+        assert_eq!(result, Err("offline".to_string()));
+        let result = block_on(Cache::network_first(Some(&cache), &key, ok(b"v1")));
+        assert_eq!(result, Ok((b"v1".to_vec(), Served::Network)));
+        let result = block_on(Cache::network_first(Some(&cache), &key, ok(b"v2")));
+        assert_eq!(result, Ok((b"v2".to_vec(), Served::Network)));
+
+        // ... and the cache is used when the download fails
+        let result = block_on(Cache::network_first(Some(&cache), &key, failed()));
+        assert_eq!(result, Ok((b"v2".to_vec(), Served::Cache)));
+
+        // Without a cache, downloads are passed straight through
+        let result = block_on(Cache::cached_first(None, &key, ok(b"v3")));
+        assert_eq!(result, Ok((b"v3".to_vec(), Served::Network)));
+        let result = block_on(Cache::network_first(None, &key, failed()));
+        assert_eq!(result, Err("offline".to_string()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -9,6 +9,7 @@ use log::{debug, error, info, trace, warn};
 use std::path::PathBuf;
 
 use crate::app::AppMessage;
+use crate::cache::{Cache, CacheKey, Served};
 use crate::studio::Message as StudioMessage;
 use crate::{ManifestType, PathType};
 use crate::{app_manifest, internal_error};
@@ -98,22 +99,34 @@ impl ConfigManifest {
         &self.configs
     }
 
-    /// Create ConfigManifest from network manifest
+    /// Create ConfigManifest from network manifest, falling back to the
+    /// last manifest downloaded if the network is unavailable
     ///
     /// Adds the special "Select Local File" entry, and config, if a local file
-    pub async fn from_network_async(selected: Option<SelectedConfig>) -> Result<Self, String> {
-        // Get the manifest from the network
-        let url = Self::manifest_url();
-        let response = reqwest::get(&url)
-            .await
-            .map_err(|e| format!("Network error fetching Configs manifest:\n  - {e}"))?;
-        let text = response
-            .text()
-            .await
-            .map_err(|e| format!("Network error reading Configs manifest:\n  - {e}"))?;
+    pub async fn from_network_async(
+        selected: Option<SelectedConfig>,
+    ) -> Result<(Self, Served), String> {
+        // Get the manifest from the network, only caching it if valid
+        let download = async {
+            let url = Self::manifest_url();
+            let response = reqwest::get(&url)
+                .await
+                .map_err(|e| format!("Network error fetching Configs manifest:\n  - {e}"))?;
+            let text = response
+                .text()
+                .await
+                .map_err(|e| format!("Network error reading Configs manifest:\n  - {e}"))?;
+            Self::from_json(text.clone())?;
+            Ok::<_, String>(text.into_bytes())
+        };
+        let cache = Cache::studio();
+        let (json, served) =
+            Cache::network_first(cache.as_ref(), &CacheKey::ConfigManifest, download).await?;
 
         // Construct from JSON
-        let mut manifest = Self::from_json(text)?;
+        let json = String::from_utf8(json)
+            .map_err(|e| format!("Cached Configs manifest is not valid UTF-8:\n  - {e}"))?;
+        let mut manifest = Self::from_json(json)?;
 
         // Add the special entries
         manifest.add_special();
@@ -125,7 +138,7 @@ impl ConfigManifest {
             manifest.internal_configs.insert(0, selected.config.clone());
         }
 
-        Ok(manifest)
+        Ok((manifest, served))
     }
 
     pub fn update_local_file(&mut self, config: Config) {
@@ -343,14 +356,19 @@ impl Config {
     }
 }
 
-/// Async download of a network config
+/// Async download of a network config, falling back to the last copy
+/// downloaded if the network is unavailable
 pub async fn download_config_async(config: Config) -> AppMessage {
     // Download the config
     assert!(config.is_network());
     let url = config.url().unwrap();
     trace!("Downloading config from URL: {url}");
-    match get_config_from_partial_url(url).await {
-        Ok(data) => StudioMessage::ConfigLoaded(Ok(config.with_data(data))).into(),
+    let cache = Cache::studio();
+    let key = CacheKey::Config { url: url.clone() };
+    match Cache::network_first(cache.as_ref(), &key, get_config_from_partial_url(url)).await {
+        Ok((data, served)) => {
+            StudioMessage::ConfigLoaded(Ok((config.with_data(data), served))).into()
+        }
         Err(e) => {
             let log = format!("Failed to download config from {url}: {e}");
             warn!("{log}");
@@ -365,7 +383,9 @@ pub fn load_config_file(config: Config) -> AppMessage {
     assert!(config.is_file());
     if let Config::File { filename } = &config {
         match std::fs::read(filename) {
-            Ok(data) => StudioMessage::ConfigLoaded(Ok(config.with_data(data))).into(),
+            Ok(data) => {
+                StudioMessage::ConfigLoaded(Ok((config.with_data(data), Served::Local))).into()
+            }
             Err(e) => {
                 let log = format!("Failed to read config file {}: {e}", filename.display());
                 warn!("{log}");
@@ -435,5 +455,9 @@ pub fn generate_built_config(config: Config) -> AppMessage {
 }}"#
     );
 
-    StudioMessage::ConfigLoaded(Ok(config.with_data(json.as_bytes().to_vec()))).into()
+    StudioMessage::ConfigLoaded(Ok((
+        config.with_data(json.as_bytes().to_vec()),
+        Served::Local,
+    )))
+    .into()
 }
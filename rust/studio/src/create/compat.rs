@@ -0,0 +1,135 @@
+// Copyright (C) 2026 Piers Finlayson <piers@piers.rocks>
+//
+// MIT License
+
+//! Config/firmware compatibility checking
+//!
+//! Checks, before a build is attempted, whether the selected firmware release
+//! supports every feature the selected ROM config requires.
+
+#[allow(unused_imports)]
+use log::{debug, error, info, trace, warn};
+
+use onerom_config::fw::FirmwareVersion;
+use onerom_gen::Config as GenConfig;
+use onerom_gen::builder::MAX_SUPPORTED_FIRMWARE_VERSION;
+
+use crate::studio::RuntimeInfo;
+
+/// Whether the selected config can be built for the selected firmware
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Config or firmware not yet selected or loaded
+    Unknown,
+    Compatible,
+    Incompatible(String),
+}
+
+impl Compatibility {
+    /// Checks a raw JSON config against a firmware version
+    pub fn check(config: &[u8], version: &FirmwareVersion) -> Self {
+        if *version > MAX_SUPPORTED_FIRMWARE_VERSION {
+            return Compatibility::Incompatible(format!(
+                "Firmware {} is newer than supported",
+                version_str(version)
+            ));
+        }
+
+        let config: GenConfig = match serde_json::from_slice(config) {
+            Ok(config) => config,
+            Err(e) => return Compatibility::Incompatible(format!("Invalid config: {e}")),
+        };
+
+        match config.unsupported_features(version).first() {
+            None => Compatibility::Compatible,
+            Some(feature) => Compatibility::Incompatible(format!(
+                "Config uses {}, which requires firmware {} or later",
+                feature.name(),
+                version_str(&feature.min_firmware_version())
+            )),
+        }
+    }
+
+    /// Checks the currently selected config against the currently selected
+    /// firmware release.
+    pub fn from_runtime_info(runtime_info: &RuntimeInfo) -> Self {
+        let Some(release) = runtime_info.selected_firmware() else {
+            return Compatibility::Unknown;
+        };
+        let Some(config) = runtime_info.selected_config() else {
+            return Compatibility::Unknown;
+        };
+        if config.data.is_empty() {
+            return Compatibility::Unknown;
+        }
+
+        match release.firmware_version() {
+            Ok(version) => Self::check(&config.data, &version),
+            Err(_) => Compatibility::Incompatible(format!(
+                "Unrecognised firmware version {}",
+                release.version
+            )),
+        }
+    }
+
+    /// Whether a build should be allowed.  Unknown is allowed, so any
+    /// problem is reported by the build itself.
+    pub fn can_build(&self) -> bool {
+        !matches!(self, Compatibility::Incompatible(_))
+    }
+
+    /// Indicator text to display
+    pub fn indicator(&self) -> Option<String> {
+        match self {
+            Compatibility::Unknown => None,
+            Compatibility::Compatible => Some("Compatible".to_string()),
+            Compatibility::Incompatible(reason) => Some(format!("Incompatible: {reason}")),
+        }
+    }
+}
+
+fn version_str(version: &FirmwareVersion) -> String {
+    format!(
+        "v{}.{}.{}",
+        version.major(),
+        version.minor(),
+        version.patch()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OVERRIDES_CONFIG: &str = r#"{
+        "version": 1,
+        "description": "Overrides",
+        "chip_sets": [{
+            "type": "single",
+            "chips": [{ "file": "test.rom", "type": "2364", "cs1": "active_low" }],
+            "firmware_overrides": { "led": { "enabled": false } }
+        }]
+    }"#;
+
+    #[test]
+    fn test_compatible_indicator() {
+        let version = FirmwareVersion::new(0, 6, 0, 0);
+        let compat = Compatibility::check(OVERRIDES_CONFIG.as_bytes(), &version);
+        assert_eq!(compat, Compatibility::Compatible);
+        assert!(compat.can_build());
+        assert_eq!(compat.indicator().as_deref(), Some("Compatible"));
+    }
+
+    #[test]
+    fn test_incompatible_indicator() {
+        let version = FirmwareVersion::new(0, 5, 1, 0);
+        let compat = Compatibility::check(OVERRIDES_CONFIG.as_bytes(), &version);
+        assert!(!compat.can_build());
+        assert_eq!(
+            compat.indicator().as_deref(),
+            Some(
+                "Incompatible: Config uses firmware overrides, which requires firmware v0.6.0 or later"
+            )
+        );
+    }
+}
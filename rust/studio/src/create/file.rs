@@ -13,6 +13,7 @@ use std::path::PathBuf;
 use onerom_config::chip::CHIP_TYPES;
 
 use crate::app::AppMessage;
+use crate::cache::Served;
 use crate::config::Config;
 use crate::create::{Create, Message, State};
 use crate::studio::{Image, Message as StudioMessage, RuntimeInfo};
@@ -86,7 +87,11 @@ pub fn save_firmware_filename(
         // Kick off the async save operation
         create.set_display_content(format!("Saving firmware to {filename:?}..."));
         let images = runtime_info.image().cloned();
-        Task::future(save_firmware_filename_async(filename, images))
+        Task::future(save_firmware_filename_async(
+            filename,
+            images,
+            create.save_sidecar,
+        ))
     } else {
         // User cancelled
         debug!("Save firmware cancelled by user");
@@ -100,6 +105,7 @@ pub fn save_firmware_filename(
 async fn save_firmware_filename_async(
     filename: Option<PathBuf>,
     image: Option<Image>,
+    save_sidecar: bool,
 ) -> AppMessage {
     // Handle errors
     if image.is_none() {
@@ -121,6 +127,16 @@ async fn save_firmware_filename_async(
         }
         Err(e) => {
             error!("Error saving firmware image to {filename:?}: {e}");
+            return Message::SaveFirmwareComplete.into();
+        }
+    }
+
+    // Write the provenance sidecar alongside, if requested
+    if save_sidecar {
+        match image.sidecar().map(|sidecar| sidecar.save(&filename)) {
+            Some(Ok(path)) => debug!("Build provenance saved to {path:?}"),
+            Some(Err(e)) => error!("Error saving build provenance: {e}"),
+            None => warn!("No build provenance available to save"),
         }
     }
 
@@ -142,6 +158,13 @@ pub fn save_firmware_complete(create: &mut Create) -> Task<AppMessage> {
     Task::none()
 }
 
+/// Handle the save build provenance option being toggled
+pub fn save_sidecar_toggled(create: &mut Create, save_sidecar: bool) -> Task<AppMessage> {
+    debug!("Save build provenance: {save_sidecar}");
+    create.save_sidecar = save_sidecar;
+    Task::none()
+}
+
 /// Handle a ROM config being selected
 pub fn config_selected(create: &mut Create, config: Config) -> Task<AppMessage> {
     debug!("Config selected: {}", config);
@@ -156,18 +179,16 @@ pub fn config_selected(create: &mut Create, config: Config) -> Task<AppMessage>
             Task::future(select_local_config_file())
         }
         Config::BuildConfig => {
-            // Get the selected rom pins from the selected hardware
-            let rom_pins: u8 = create
-                .selected_hw_info
-                .board
-                .map_or(24, |board| board.chip_pins());
-
-            // build a vec of rom_types that match the selected rom pins
-            let rom_types = CHIP_TYPES
-                .iter()
-                .filter(|rt| rt.chip_pins() == rom_pins)
-                .cloned()
-                .collect::<Vec<_>>();
+            // Get the rom_types the selected hardware can host, defaulting
+            // to 24 pin rom_types
+            let rom_types = match create.selected_hw_info.board {
+                Some(board) => board.supported_chip_types(),
+                None => CHIP_TYPES
+                    .iter()
+                    .filter(|rt| rt.chip_pins() == 24)
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            };
 
             create.state = State::UserBuilding {
                 valid_rom_types: rom_types,
@@ -204,7 +225,7 @@ async fn select_local_config_file() -> AppMessage {
 pub fn config_loaded(
     create: &mut Create,
     runtime_info: &RuntimeInfo,
-    result: Result<(), String>,
+    result: Result<Served, String>,
 ) -> Task<AppMessage> {
     debug!("Config loaded");
 
@@ -218,20 +239,25 @@ pub fn config_loaded(
 
     // Log and display result
     match result {
-        Ok(()) => {
+        Ok(served) => {
             debug!(
-                "Config {} loaded successfully.",
+                "Config {} loaded successfully ({served}).",
                 runtime_info
                     .selected_config()
                     .map(|c| c.name())
                     .unwrap_or("none".to_string())
             );
             create.display_content += &format!(
-                "\n\nConfig {} loaded successfully.",
+                "\n\nConfig {} loaded successfully{}.",
                 runtime_info
                     .selected_config()
                     .map(|c| c.name())
-                    .unwrap_or("none".to_string())
+                    .unwrap_or("none".to_string()),
+                if served == Served::Cache {
+                    " from cache, as the network is unavailable"
+                } else {
+                    ""
+                }
             );
         }
         Err(e) => {
@@ -70,6 +70,7 @@ pub fn flash_firmware(create: &mut Create, runtime_info: &RuntimeInfo) -> Task<A
         {
             Some(fw) => {
                 create.state = State::Flashing;
+                create.flash_progress = None;
                 create.set_display_content("Flashing firmware...");
                 Task::done(
                     DeviceMessage::FlashFirmware {
@@ -91,6 +92,16 @@ pub fn flash_firmware(create: &mut Create, runtime_info: &RuntimeInfo) -> Task<A
     }
 }
 
+/// Handle a progress update from a flash firmware operation
+pub fn flash_progress(create: &mut Create, fraction: f32) -> Task<AppMessage> {
+    if create.is_flashing() {
+        create.flash_progress = Some(fraction);
+    } else {
+        debug!("Ignoring flash progress received while not flashing");
+    }
+    Task::none()
+}
+
 /// Handle the result of a flash firmware operation
 pub fn flash_firmware_result(create: &mut Create, result: Result<(), String>) -> Task<AppMessage> {
     debug!(
@@ -108,6 +119,7 @@ pub fn flash_firmware_result(create: &mut Create, result: Result<(), String>) ->
 
     // Update state to idle
     create.state = State::Idle;
+    create.flash_progress = None;
 
     // Handle result
     match result {
@@ -7,9 +7,11 @@
 //! Creates firmware images for selected hardware and configuration.
 
 mod build;
+mod compat;
 mod file;
 mod hw;
 mod msg;
+mod sidecar;
 mod view;
 
 use iced::{Element, Subscription, Task};
@@ -29,6 +31,7 @@ use crate::style::Style;
 
 pub use build::Active;
 pub use msg::Message;
+pub use sidecar::Sidecar;
 
 /// Create tab internal state
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -68,6 +71,8 @@ pub struct Create {
     mcu_variants: Option<Vec<McuVariant>>,
     display_content: String,
     state: State,
+    save_sidecar: bool,
+    flash_progress: Option<f32>,
 }
 
 impl Create {
@@ -15,14 +15,16 @@ use onerom_config::chip::ChipType;
 use onerom_fw::net::Release;
 
 use crate::app::AppMessage;
+use crate::cache::Served;
 use crate::config::Config;
 use crate::create::Create;
 use crate::create::build::{build_image, build_image_result, build_json_config_from_state};
 use crate::create::file::{
     config_loaded, config_selected, save_firmware, save_firmware_complete, save_firmware_filename,
+    save_sidecar_toggled,
 };
 use crate::create::hw::{
-    detect_hardware, detected_hardware_info, flash_firmware, flash_firmware_result,
+    detect_hardware, detected_hardware_info, flash_firmware, flash_firmware_result, flash_progress,
 };
 use crate::studio::RuntimeInfo;
 use crate::task_from_msg;
@@ -38,7 +40,7 @@ pub enum Message {
     ModelSelected(Model),
     McuSelected(McuVariant),
     ReleaseSelected(Release),
-    ReleaseDowloaded(Result<(), String>),
+    ReleaseDowloaded(Result<Served, String>),
 
     // Detect hardware button operation.
     // Information can be detected via connected device using Analyse tab
@@ -51,7 +53,7 @@ pub enum Message {
 
     // ROM config has been selected via pick list
     ConfigSelected(Config),
-    ConfigLoaded(Result<(), String>),
+    ConfigLoaded(Result<Served, String>),
 
     // Build image
     BuildImage,
@@ -66,8 +68,12 @@ pub enum Message {
     SaveFirmwareFilename(Option<PathBuf>),
     SaveFirmwareComplete,
 
+    // Whether to save a build provenance sidecar alongside the firmware image
+    SaveSidecarToggled(bool),
+
     // Flash firmware
     FlashFirmware,
+    FlashProgress(f32),
     FlashFirmwareResult(Result<(), String>),
 
     // Progress tick from subscription during operation
@@ -106,7 +112,7 @@ pub fn message(create: &mut Create, runtime_info: &RuntimeInfo, msg: Message) ->
         Message::ReleaseDowloaded(result) => {
             debug!("Firmware release downloaded");
             match result {
-                Ok(()) => debug!("Release download succeeded"),
+                Ok(served) => debug!("Release download succeeded ({served})"),
                 Err(e) => warn!("Release download failed: {e}"),
             }
             Task::none()
@@ -143,9 +149,11 @@ pub fn message(create: &mut Create, runtime_info: &RuntimeInfo, msg: Message) ->
             save_firmware_filename(create, runtime_info, filename)
         }
         Message::SaveFirmwareComplete => save_firmware_complete(create),
+        Message::SaveSidecarToggled(save) => save_sidecar_toggled(create, save),
 
         // Flash firmware
         Message::FlashFirmware => flash_firmware(create, runtime_info),
+        Message::FlashProgress(fraction) => flash_progress(create, fraction),
         Message::FlashFirmwareResult(result) => flash_firmware_result(create, result),
 
         // Progress tick from subscription during operation
@@ -184,7 +192,7 @@ impl std::fmt::Display for Message {
                 write!(f, "ReleaseSelected({})", release.version)
             }
             Message::ReleaseDowloaded(result) => match result {
-                Ok(()) => write!(f, "ReleaseDowloaded(Ok)"),
+                Ok(served) => write!(f, "ReleaseDowloaded(Ok({served}))"),
                 Err(e) => write!(f, "ReleaseDowloaded(Err({e}))"),
             },
 
@@ -196,7 +204,7 @@ impl std::fmt::Display for Message {
 
             Message::ConfigSelected(name) => write!(f, "ConfigSelected({})", name),
             Message::ConfigLoaded(result) => match result {
-                Ok(()) => write!(f, "ConfigLoaded(Ok)"),
+                Ok(served) => write!(f, "ConfigLoaded(Ok({served}))"),
                 Err(e) => write!(f, "ConfigLoaded(Err({e}))"),
             },
 
@@ -210,8 +218,10 @@ impl std::fmt::Display for Message {
                 write!(f, "SaveFirmwareFilename({:?})", filename)
             }
             Message::SaveFirmwareComplete => write!(f, "SaveFirmwareComplete"),
+            Message::SaveSidecarToggled(save) => write!(f, "SaveSidecarToggled({save})"),
 
             Message::FlashFirmware => write!(f, "FlashFirmware"),
+            Message::FlashProgress(fraction) => write!(f, "FlashProgress({fraction})"),
             Message::FlashFirmwareResult(result) => {
                 write!(f, "FlashFirmwareResult({:?})", result)
             }
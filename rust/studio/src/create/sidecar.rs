@@ -0,0 +1,188 @@
+// Copyright (C) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT License
+
+//! Build provenance sidecar
+//!
+//! Optionally saved as a `.json` file alongside a firmware image, recording
+//! the config, firmware and hardware used, the source files' checksums, and
+//! the output image's checksum, so builds can be reproduced and audited.
+
+use std::path::{Path, PathBuf};
+
+use onerom_config::fw::{FirmwareProperties, FirmwareVersion};
+use onerom_gen::{BuildReport, Builder, crc32};
+
+/// A source file used in a build
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SidecarSource {
+    /// Filename or URL of the file, as given in the config
+    pub source: String,
+
+    /// Path within an archive the file was extracted from, if any
+    pub extract: Option<String>,
+
+    /// Size of the file in bytes
+    pub size: u32,
+
+    /// CRC-32 of the file
+    pub checksum: u32,
+}
+
+/// Provenance of a built firmware image
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Sidecar {
+    /// Config the image was built from
+    pub config: serde_json::Value,
+
+    /// Firmware version the image was built for
+    pub firmware_version: FirmwareVersion,
+
+    /// Board the image was built for
+    pub board: String,
+
+    /// MCU variant the image was built for
+    pub mcu: String,
+
+    /// Source files loaded for the build
+    pub sources: Vec<SidecarSource>,
+
+    /// Flash layout of the built metadata and ROM images
+    pub report: BuildReport,
+
+    /// Size of the full output image in bytes
+    pub image_size: usize,
+
+    /// CRC-32 of the full output image
+    pub image_checksum: u32,
+}
+
+impl Sidecar {
+    /// Creates a sidecar for a build.  `image` is the full image, as saved.
+    pub fn new(
+        config: &str,
+        props: &FirmwareProperties,
+        builder: &Builder,
+        report: BuildReport,
+        image: &[u8],
+    ) -> Result<Self, String> {
+        let config = serde_json::from_str(config).map_err(|e| format!("Invalid config: {e}"))?;
+
+        let sources = builder
+            .file_specs()
+            .into_iter()
+            .map(|spec| {
+                let provenance = builder
+                    .file_provenance(spec.id)
+                    .ok_or_else(|| format!("File {} not loaded", spec.source))?;
+                Ok(SidecarSource {
+                    source: spec.source,
+                    extract: spec.extract,
+                    size: provenance.size,
+                    checksum: provenance.checksum,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            config,
+            firmware_version: props.version(),
+            board: props.board().name().to_string(),
+            mcu: props.mcu_variant().to_string(),
+            sources,
+            report,
+            image_size: image.len(),
+            image_checksum: crc32(image),
+        })
+    }
+
+    /// Returns the sidecar filename for a saved image - the image filename
+    /// with a `.json` extension.
+    pub fn path(image_path: &Path) -> PathBuf {
+        image_path.with_extension("json")
+    }
+
+    /// Writes the sidecar alongside the saved image at `image_path`,
+    /// returning the sidecar's filename.
+    pub fn save(&self, image_path: &Path) -> Result<PathBuf, String> {
+        let path = Self::path(image_path);
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize sidecar: {e}"))?;
+        std::fs::write(&path, json)
+            .map_err(|e| format!("Failed to write sidecar to {path:?}: {e}"))?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use onerom_config::fw::ServeAlg;
+    use onerom_config::hw::Board;
+    use onerom_config::mcu::Variant as McuVariant;
+    use onerom_gen::FileData;
+
+    #[test]
+    fn test_sidecar_matches_saved_image() {
+        let config = r#"{
+            "version": 1,
+            "description": "Sidecar test",
+            "chip_sets": [
+                { "type": "single", "chips": [{ "file": "kernal.bin", "type": "2364", "cs1": "active_low" }] },
+                { "type": "single", "chips": [{ "file": "@builtin:ramp8k", "type": "2364", "cs1": "active_low" }] }
+            ]
+        }"#;
+        let props = FirmwareProperties::new(
+            FirmwareVersion::new(0, 6, 0, 0),
+            Board::Ice24UsbH,
+            McuVariant::F411RE,
+            ServeAlg::Default,
+            false,
+        )
+        .unwrap();
+
+        let mut builder =
+            Builder::from_json(props.version(), props.mcu_variant().family(), config).unwrap();
+        let kernal = vec![0x42u8; 8192];
+        builder
+            .add_file(FileData {
+                id: 0,
+                data: kernal.clone(),
+            })
+            .unwrap();
+        let (metadata, roms, report) = builder.build_with_report(props).unwrap();
+        let image = [metadata, roms].concat();
+
+        let sidecar = Sidecar::new(config, &props, &builder, report, &image).unwrap();
+
+        // Save the image and its sidecar, as Studio does
+        let dir = std::env::temp_dir().join(format!("onerom-sidecar-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let image_path = dir.join("onerom.bin");
+        std::fs::write(&image_path, &image).unwrap();
+        let sidecar_path = sidecar.save(&image_path).unwrap();
+        assert_eq!(sidecar_path, dir.join("onerom.json"));
+
+        let saved_image = std::fs::read(&image_path).unwrap();
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&sidecar_path).unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(saved["config"]["description"], "Sidecar test");
+        assert_eq!(saved["board"], props.board().name());
+        assert_eq!(saved["mcu"], props.mcu_variant().to_string());
+        assert!(saved["firmware_version"].is_object());
+        assert_eq!(saved["report"]["chip_sets"].as_array().unwrap().len(), 2);
+
+        // Only the loaded file is a source - the built-in is generated
+        let sources = saved["sources"].as_array().unwrap();
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0]["source"], "kernal.bin");
+        assert_eq!(sources[0]["size"], 8192);
+        assert_eq!(sources[0]["checksum"], crc32(&kernal));
+
+        // Output checksum matches the saved image
+        assert_eq!(saved["image_size"], saved_image.len());
+        assert_eq!(saved["image_checksum"], crc32(&saved_image));
+    }
+}
@@ -4,7 +4,7 @@
 
 //! Create view methods
 
-use iced::widget::{Column, Container, Row, Space, column, row};
+use iced::widget::{Column, Container, Row, Space, checkbox, column, row};
 use iced::{Element, Length};
 #[allow(unused_imports)]
 use log::{debug, error, info, trace, warn};
@@ -13,6 +13,7 @@ use onerom_config::hw::MODELS;
 
 use crate::config::Config;
 use crate::create::{Create, Message};
+use crate::create::compat::Compatibility;
 use crate::device::Device;
 use crate::studio::RuntimeInfo;
 use crate::style::Style;
@@ -374,14 +375,27 @@ fn build_section<'a>(
     } else {
         "Build Image".to_string()
     };
-    let (on_press, highlighted) = if create.is_busy() {
+    // Grey out the build button if the selected firmware can't serve the
+    // selected config
+    let compatibility = Compatibility::from_runtime_info(runtime_info);
+    let (on_press, highlighted) = if create.is_busy() || !compatibility.can_build() {
         (None, false)
     } else {
         (Some(Message::BuildImage.into()), true)
     };
     let build_button = Style::text_button_small(content, on_press, highlighted);
 
-    let button_row = row![build_button].spacing(20);
+    let mut button_row = row![build_button]
+        .spacing(20)
+        .align_y(iced::alignment::Vertical::Center);
+    if let Some(indicator) = compatibility.indicator() {
+        let colour = if compatibility.can_build() {
+            Style::COLOUR_INFO
+        } else {
+            Style::COLOUR_ERROR
+        };
+        button_row = button_row.push(Style::text_small(indicator).color(colour));
+    }
 
     // Only show Save and Flash buttons if images exist _and_ not in
     // the process of building one.
@@ -397,9 +411,18 @@ fn build_section<'a>(
             "Save Firmware".to_string()
         };
         let save_button = Style::text_button_small(save_content, on_press, highlighted);
+        let mut sidecar_checkbox = checkbox("Save build record", create.save_sidecar)
+            .text_size(Style::FONT_SIZE_SMALL);
+        if !create.is_busy() {
+            sidecar_checkbox =
+                sidecar_checkbox.on_toggle(|save| Message::SaveSidecarToggled(save).into());
+        }
 
         let flash_content = if create.is_flashing() {
-            "Flashing...".to_string()
+            match create.flash_progress {
+                Some(fraction) => format!("Flashing... {:.0}%", fraction * 100.0),
+                None => "Flashing...".to_string(),
+            }
         } else {
             "Flash Firmware".to_string()
         };
@@ -412,6 +435,7 @@ fn build_section<'a>(
 
         button_row
             .push(Space::with_width(Length::Fill))
+            .push(sidecar_checkbox)
             .push(save_button)
             .push(flash_button)
     } else {
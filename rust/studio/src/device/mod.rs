@@ -9,15 +9,21 @@
 
 mod msg;
 mod probe;
+mod target;
 mod usb;
 mod view;
 
+use futures::SinkExt;
+use futures::channel::mpsc;
 use futures::stream::{self, Stream, StreamExt};
 use iced::widget::Column;
 use iced::{Element, Subscription, Task};
 #[allow(unused_imports)]
 use log::{debug, error, info, trace, warn};
 use onerom_config::Model;
+use onerom_config::mcu::Family;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::app::AppMessage;
 use crate::hw::HardwareInfo;
@@ -26,6 +32,7 @@ use crate::studio::RuntimeInfo;
 use crate::style::Style;
 pub use msg::Message;
 use probe::ProbeType;
+use target::{ProbeTarget, TargetOverride};
 use usb::UsbDeviceType;
 
 /// At startup we want to check USB devices, then probe devices, so any
@@ -54,6 +61,66 @@ impl std::fmt::Display for Client {
     }
 }
 
+// Number of progress updates which may be queued before further updates are
+// dropped
+const FLASH_PROGRESS_CHANNEL_SIZE: usize = 32;
+
+/// Reports progress of a flash operation back to the client, as
+/// Message::FlashProgress.
+#[derive(Debug, Clone)]
+pub struct FlashProgress {
+    client: Client,
+    sender: mpsc::Sender<AppMessage>,
+}
+
+impl FlashProgress {
+    fn new(client: Client, sender: mpsc::Sender<AppMessage>) -> Self {
+        Self { client, sender }
+    }
+
+    /// Report progress, as a fraction between 0.0 and 1.0.  Updates are
+    /// dropped, rather than blocking the flash operation, if the UI is
+    /// falling behind.
+    pub fn report(&mut self, fraction: f32) {
+        let msg = Message::FlashProgress(self.client.clone(), fraction.clamp(0.0, 1.0)).into();
+        if let Err(e) = self.sender.try_send(msg) {
+            trace!("Dropped flash progress update: {e}");
+        }
+    }
+}
+
+/// Allows an in-flight device operation to be cancelled.  Operations check
+/// the token at safe boundaries - between read chunks or flash chunks - and
+/// return early, with an error, once it has been cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Request cancellation of the operation
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// Error reported when a read is cancelled
+const READ_CANCELLED: &str = "Read cancelled";
+
+// Error reported when a flash is cancelled before anything was written
+const FLASH_CANCELLED: &str = "Flash cancelled before writing firmware";
+
+// Error reported when a flash is cancelled part way through.  The device has
+// been erased and/or partially written, so won't boot until reflashed.
+fn flash_cancelled_partial(written: usize, total: usize) -> String {
+    format!(
+        "Flash cancelled after writing {written} of {total} bytes - device firmware is incomplete and must be reflashed"
+    )
+}
+
 /// Addressing modes for device read/write
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Address {
@@ -143,6 +210,62 @@ impl Address {
     }
 }
 
+/// Amount of memory to read from a device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadLength {
+    /// A fixed number of 32-bit words
+    Words(usize),
+
+    /// The device's entire flash.  The size is queried from the device where
+    /// possible, falling back to `assumed_bytes` (typically from the MCU
+    /// variant decoded from the firmware) where it is not.
+    FullFlash { assumed_bytes: usize },
+}
+
+impl std::fmt::Display for ReadLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadLength::Words(words) => write!(f, "Words({words})"),
+            ReadLength::FullFlash { assumed_bytes } => {
+                write!(f, "FullFlash(assumed={assumed_bytes} bytes)")
+            }
+        }
+    }
+}
+
+impl ReadLength {
+    /// Number of 32-bit words to read, given the flash size queried from the
+    /// device, if any.
+    pub fn words(&self, queried_bytes: Option<usize>) -> usize {
+        match self {
+            ReadLength::Words(words) => *words,
+            ReadLength::FullFlash { assumed_bytes } => {
+                select_flash_bytes(queried_bytes, *assumed_bytes) / 4
+            }
+        }
+    }
+
+    /// Whether this read needs the device's flash size
+    pub fn is_full_flash(&self) -> bool {
+        matches!(self, ReadLength::FullFlash { .. })
+    }
+}
+
+/// Select the flash size to read, preferring the size queried from the
+/// device over the assumed size.  A queried size of zero is treated as not
+/// queryable.
+pub fn select_flash_bytes(queried_bytes: Option<usize>, assumed_bytes: usize) -> usize {
+    match queried_bytes {
+        Some(bytes) if bytes > 0 => {
+            if bytes != assumed_bytes {
+                debug!("Device reports {bytes} bytes of flash, {assumed_bytes} bytes assumed");
+            }
+            bytes
+        }
+        _ => assumed_bytes,
+    }
+}
+
 /// Device state
 #[derive(Debug, Clone)]
 pub struct Device {
@@ -152,6 +275,9 @@ pub struct Device {
     probes: Vec<ProbeType>,
     usb_devices: Vec<UsbDeviceType>,
     operating: Option<Client>,
+    cancel: CancelToken,
+    verify_flash: bool,
+    target_override: TargetOverride,
 }
 
 impl Default for Device {
@@ -163,6 +289,9 @@ impl Default for Device {
             probes: Vec::new(),
             usb_devices: Vec::new(),
             operating: None,
+            cancel: CancelToken::default(),
+            verify_flash: true,
+            target_override: TargetOverride::default(),
         }
     }
 }
@@ -170,7 +299,10 @@ impl Default for Device {
 impl Device {
     /// Instantiation
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            target_override: TargetOverride::load(),
+            ..Self::default()
+        }
     }
 
     /// Is the device ready for operations?
@@ -186,11 +318,33 @@ impl Device {
         self.operating.is_none()
     }
 
+    // Mark an operation as underway for the client, returning a new token
+    // with which it can be cancelled
+    fn start_operation(&mut self, client: Client) -> CancelToken {
+        self.operating = Some(client);
+        self.cancel = CancelToken::default();
+        self.cancel.clone()
+    }
+
+    // Whether the operation underway is being cancelled
+    fn is_cancelling(&self) -> bool {
+        self.is_busy() && self.cancel.is_cancelled()
+    }
+
     // Retrieve selected device
     fn selected(&self) -> &DeviceType {
         &self.selected
     }
 
+    // Retrieve the probe target override, if enabled.  Only applies to debug
+    // probes - USB devices identify their own MCU.
+    fn target_override(&self) -> Result<Option<ProbeTarget>, String> {
+        match self.selected {
+            DeviceType::DebugProbe(_) => self.target_override.target(),
+            DeviceType::Usb(_) | DeviceType::None => Ok(None),
+        }
+    }
+
     /// Main Device Message handling method
     pub fn update(&mut self, runtime_info: &RuntimeInfo, message: Message) -> Task<AppMessage> {
         msg::handle_message(self, runtime_info, message)
@@ -201,13 +355,21 @@ impl Device {
     // - If none exists (now), see if we can auto-select one
     // - If one now exists, and there's no device selected, select the probe
     fn probes_updated(&mut self) {
-        // Check if selected probe is still connected
-        if let Some(old_probe) = &self.selected_probe {
-            if !self.probes.contains(&old_probe) {
-                info!("Selected probe has been disconnected {old_probe}");
-                self.selected_probe = None;
-            } else {
-                trace!("Still connected to probe {old_probe}");
+        // Check if selected probe is still connected.  It is matched by serial
+        // number, and the re-detected details replace the old ones.
+        if let Some(old_probe) = self.selected_probe.clone() {
+            match self.probes.iter().find(|p| p.is_same_probe(&old_probe)) {
+                Some(probe) => {
+                    trace!("Still connected to probe {old_probe}");
+                    if self.selected.probe().as_ref() == Some(&old_probe) {
+                        self.selected = DeviceType::from_probe(probe.clone());
+                    }
+                    self.selected_probe = Some(probe.clone());
+                }
+                None => {
+                    info!("Selected probe has been disconnected {old_probe}");
+                    self.selected_probe = None;
+                }
             }
         }
 
@@ -269,6 +431,12 @@ impl Device {
         if should_clear {
             debug!("Clearing selected device as no longer valid");
             self.selected = DeviceType::None;
+
+            // The operation will fail anyway, so stop it as soon as possible
+            if let Some(client) = &self.operating {
+                warn!("{client} Device disconnected during operation - cancelling");
+                self.cancel.cancel();
+            }
         }
 
         if self.selected.is_none() {
@@ -285,18 +453,26 @@ impl Device {
         }
     }
 
-    // Methods called when device is selected
+    // Methods called when device is selected.  Choosing a probe or USB
+    // device from a pick list targets it for subsequent operations, if that
+    // type of device is in use.
     fn select_device(&mut self, device: DeviceType) -> Task<AppMessage> {
         self.selected = device;
         self.check_selected();
         Task::none()
     }
     fn select_probe(&mut self, probe: ProbeType) -> Task<AppMessage> {
+        if !matches!(self.selected, DeviceType::Usb(_)) {
+            self.selected = DeviceType::from_probe(probe.clone());
+        }
         self.selected_probe = Some(probe);
         self.check_selected();
         Task::none()
     }
     fn select_usb_device(&mut self, usb_device: UsbDeviceType) -> Task<AppMessage> {
+        if !matches!(self.selected, DeviceType::DebugProbe(_)) {
+            self.selected = DeviceType::from_usb(usb_device.clone());
+        }
         self.selected_usb_device = Some(usb_device);
         self.check_selected();
         Task::none()
@@ -376,6 +552,16 @@ impl DeviceType {
         matches!(self, DeviceType::None)
     }
 
+    /// Returns the MCU family of the device, if known without attaching to
+    /// it.  USB devices imply their family from the model, whereas a probe
+    /// may be connected to either family.
+    pub fn mcu_family(&self) -> Option<Family> {
+        match self {
+            DeviceType::Usb(usb_type) => Some(usb_type.model().mcu_family()),
+            DeviceType::DebugProbe(_) | DeviceType::None => None,
+        }
+    }
+
     fn from_probe(info: ProbeType) -> Self {
         DeviceType::DebugProbe(info)
     }
@@ -397,13 +583,70 @@ impl DeviceType {
         client: Client,
         hw_info: HardwareInfo,
         address: Address,
-        words: usize,
+        length: ReadLength,
+        target: Option<ProbeTarget>,
+        cancel: CancelToken,
     ) -> Task<AppMessage> {
-        Task::future(read_async(self.clone(), client, hw_info, address, words))
-    }
-
-    pub fn flash(&self, client: Client, hw_info: HardwareInfo, data: Vec<u8>) -> Task<AppMessage> {
-        Task::future(flash_async(self.clone(), hw_info, client, data))
+        Task::future(read_async(
+            self.clone(),
+            client,
+            hw_info,
+            address,
+            length,
+            target,
+            cancel,
+        ))
+    }
+
+    pub fn flash(
+        &self,
+        client: Client,
+        hw_info: HardwareInfo,
+        data: Vec<u8>,
+        verify: bool,
+        target: Option<ProbeTarget>,
+        cancel: CancelToken,
+    ) -> Task<AppMessage> {
+        // Run as a stream, so progress messages can be sent ahead of the
+        // final result
+        let device = self.clone();
+        Task::stream(iced::stream::channel(
+            FLASH_PROGRESS_CHANNEL_SIZE,
+            move |mut output: mpsc::Sender<AppMessage>| async move {
+                let progress = FlashProgress::new(client.clone(), output.clone());
+                let expected = verify.then(|| data.clone());
+                let mut result = flash_async(
+                    device.clone(),
+                    hw_info,
+                    client.clone(),
+                    data,
+                    progress,
+                    target.clone(),
+                    cancel.clone(),
+                )
+                .await;
+
+                // Only verify if the flash itself succeeded
+                if let Some(expected) = expected
+                    && matches!(
+                        result,
+                        AppMessage::Device(Message::FlashFirmwareResult(_, Ok(())))
+                    )
+                {
+                    result = if cancel.is_cancelled() {
+                        let log = "Verify cancelled - firmware was flashed but not verified";
+                        warn!("{client} {log}");
+                        Message::FlashFirmwareResult(client, Err(log.to_string())).into()
+                    } else {
+                        verify_async(device, hw_info, client, expected, target, cancel).await
+                    };
+                }
+
+                if let Err(e) = output.send(result).await {
+                    warn!("Failed to send flash result: {e}");
+                }
+            },
+        ))
     }
 }
 
@@ -413,13 +656,17 @@ async fn read_async(
     client: Client,
     hw_info: HardwareInfo,
     address: Address,
-    words: usize,
+    length: ReadLength,
+    target: Option<ProbeTarget>,
+    cancel: CancelToken,
 ) -> AppMessage {
     match device {
         DeviceType::DebugProbe(p) => {
-            probe::read_async(p.clone(), client, hw_info, address, words).await
+            probe::read_async(p.clone(), client, hw_info, address, length, target, cancel).await
+        }
+        DeviceType::Usb(u) => {
+            usb::read_async(u.clone(), client, hw_info, address, length, cancel).await
         }
-        DeviceType::Usb(u) => usb::read_async(u.clone(), client, hw_info, address, words).await,
         DeviceType::None => {
             let log = "Attempted to read from None device";
             internal_error!("{log}");
@@ -434,10 +681,23 @@ async fn flash_async(
     hw_info: HardwareInfo,
     client: Client,
     data: Vec<u8>,
+    progress: FlashProgress,
+    target: Option<ProbeTarget>,
+    cancel: CancelToken,
 ) -> AppMessage {
+    // Refuse to flash firmware built for a different MCU family
+    if let Err(log) = check_flash_family(device.mcu_family(), hw_info.mcu_family()) {
+        warn!("{log}");
+        return Message::FlashFirmwareResult(client, Err(log)).into();
+    }
+
     match device {
-        DeviceType::DebugProbe(p) => probe::flash_async(p.clone(), hw_info, client, data).await,
-        DeviceType::Usb(u) => usb::flash_async(u.clone(), hw_info, client, data).await,
+        DeviceType::DebugProbe(p) => {
+            probe::flash_async(p.clone(), hw_info, client, data, progress, target, cancel).await
+        }
+        DeviceType::Usb(u) => {
+            usb::flash_async(u.clone(), hw_info, client, data, progress, cancel).await
+        }
         DeviceType::None => {
             let log = "Attempted to flash None device";
             internal_error!("{log}");
@@ -445,3 +705,169 @@ async fn flash_async(
         }
     }
 }
+
+// Read back the flashed region and compare it to the data written.  Returns
+// a FlashFirmwareResult message.
+async fn verify_async(
+    device: DeviceType,
+    hw_info: HardwareInfo,
+    client: Client,
+    expected: Vec<u8>,
+    target: Option<ProbeTarget>,
+    cancel: CancelToken,
+) -> AppMessage {
+    debug!(
+        "{client} Verifying {} bytes of flashed firmware",
+        expected.len()
+    );
+    // Firmware was written to the override's load address, if there is one
+    let address = match (&device, &target) {
+        (DeviceType::DebugProbe(_), Some(target)) => Address::Absolute(target.load_address),
+        _ => Address::FlashStart,
+    };
+    let base = match &device {
+        DeviceType::Usb(usb) => Some(address.abs_from_usb_device(usb)),
+        _ => address.abs_from_hw_info(&hw_info),
+    };
+    let length = ReadLength::Words(expected.len().div_ceil(4));
+
+    let data = read_async(
+        device,
+        client.clone(),
+        hw_info,
+        address,
+        length,
+        target,
+        cancel,
+    )
+    .await;
+    let result = match data {
+        AppMessage::Device(Message::DeviceData(_, actual)) => {
+            match verify_mismatch(&expected, &actual) {
+                None => Ok(()),
+                Some(offset) => Err(format!(
+                    "Verify mismatch at {:#010X}",
+                    base.unwrap_or_default() + offset as u32
+                )),
+            }
+        }
+        AppMessage::Device(Message::ReadFailed(_, e)) => {
+            Err(format!("Failed to read back firmware to verify: {e}"))
+        }
+        msg => {
+            internal_error!("Unexpected message reading back firmware: {msg:?}");
+            Err("Failed to read back firmware to verify".to_string())
+        }
+    };
+
+    match &result {
+        Ok(()) => debug!("{client} Firmware verified"),
+        Err(e) => warn!("{client} {e}"),
+    }
+    Message::FlashFirmwareResult(client, result).into()
+}
+
+// Returns the offset of the first byte which differs between the data written
+// and that read back, if any.  Data read back short is treated as a mismatch.
+fn verify_mismatch(expected: &[u8], actual: &[u8]) -> Option<usize> {
+    expected
+        .iter()
+        .zip(actual)
+        .position(|(e, a)| e != a)
+        .or_else(|| (actual.len() < expected.len()).then_some(actual.len()))
+}
+
+// Check the firmware's MCU family matches the device's, where both are known
+fn check_flash_family(device: Option<Family>, firmware: Option<Family>) -> Result<(), String> {
+    match (device, firmware) {
+        (Some(device), Some(firmware)) if device != firmware => Err(format!(
+            "Firmware is for {firmware} but the connected device is {device} - refusing to flash"
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_mismatch() {
+        let expected = [0x01, 0x02, 0x03, 0x04, 0x05];
+
+        // Identical, or read back padded to a whole number of words
+        assert_eq!(verify_mismatch(&expected, &expected), None);
+        assert_eq!(
+            verify_mismatch(&expected, &[1, 2, 3, 4, 5, 0xFF, 0xFF, 0xFF]),
+            None
+        );
+
+        // First differing byte is reported
+        assert_eq!(verify_mismatch(&expected, &[1, 2, 0xFF, 4, 0xFF]), Some(2));
+
+        // Short read
+        assert_eq!(verify_mismatch(&expected, &[1, 2, 3]), Some(3));
+    }
+
+    #[test]
+    fn test_cancel_token() {
+        let token = CancelToken::default();
+        let operation = token.clone();
+        assert!(!operation.is_cancelled());
+
+        // Cancellation is seen by the operation's clone of the token
+        token.cancel();
+        assert!(operation.is_cancelled());
+
+        // A new token starts uncancelled
+        assert!(!CancelToken::default().is_cancelled());
+    }
+
+    #[test]
+    fn test_check_flash_family() {
+        // Matching families
+        assert!(check_flash_family(Some(Family::Stm32f4), Some(Family::Stm32f4)).is_ok());
+        assert!(check_flash_family(Some(Family::Rp2350), Some(Family::Rp2350)).is_ok());
+
+        // Mismatched families are refused
+        assert!(check_flash_family(Some(Family::Stm32f4), Some(Family::Rp2350)).is_err());
+        assert!(check_flash_family(Some(Family::Rp2350), Some(Family::Stm32f4)).is_err());
+
+        // Unknown families can't be checked
+        assert!(check_flash_family(None, Some(Family::Rp2350)).is_ok());
+        assert!(check_flash_family(Some(Family::Stm32f4), None).is_ok());
+    }
+
+    #[test]
+    fn test_queried_flash_size_preferred() {
+        // Oversized flash is fully captured
+        assert_eq!(select_flash_bytes(Some(1024 * 1024), 512 * 1024), 1024 * 1024);
+        // Undersized flash isn't over-read
+        assert_eq!(select_flash_bytes(Some(256 * 1024), 512 * 1024), 256 * 1024);
+        // Matching sizes
+        assert_eq!(select_flash_bytes(Some(512 * 1024), 512 * 1024), 512 * 1024);
+    }
+
+    #[test]
+    fn test_assumed_flash_size_fallback() {
+        // Not queryable, e.g. DFU
+        assert_eq!(select_flash_bytes(None, 512 * 1024), 512 * 1024);
+        // Nonsensical queried value
+        assert_eq!(select_flash_bytes(Some(0), 512 * 1024), 512 * 1024);
+    }
+
+    #[test]
+    fn test_read_length_words() {
+        let full = ReadLength::FullFlash {
+            assumed_bytes: 512 * 1024,
+        };
+        assert!(full.is_full_flash());
+        assert_eq!(full.words(None), 512 * 1024 / 4);
+        assert_eq!(full.words(Some(1024 * 1024)), 1024 * 1024 / 4);
+
+        // Fixed length reads ignore any queried size
+        let fixed = ReadLength::Words(65536 / 4);
+        assert!(!fixed.is_full_flash());
+        assert_eq!(fixed.words(Some(1024 * 1024)), 65536 / 4);
+    }
+}
@@ -14,7 +14,7 @@ use crate::app::AppMessage;
 use crate::create::Message as CreateMessage;
 use crate::device::probe::ProbeType;
 use crate::device::usb::UsbDeviceType;
-use crate::device::{Address, Client, Device, DeviceType};
+use crate::device::{Address, Client, Device, DeviceType, ReadLength};
 use crate::hw::HardwareInfo;
 use crate::internal_error;
 use crate::studio::RuntimeInfo;
@@ -44,14 +44,26 @@ pub enum Message {
         hw_info: HardwareInfo,
         data: Vec<u8>,
     },
+    FlashProgress(Client, f32),
     FlashFirmwareResult(Client, Result<(), String>),
 
+    // Cancel the client's in-flight flash or read
+    Cancel(Client),
+
+    // Whether to read back and verify firmware after flashing
+    VerifyFlashToggled(bool),
+
+    // Probe target override enabled, chip ID or load address edited
+    TargetOverrideToggled(bool),
+    TargetChipIdChanged(String),
+    TargetLoadAddressChanged(String),
+
     // Read data from a device
     ReadDevice {
         client: Client,
         hw_info: HardwareInfo,
         address: Address,
-        words: usize,
+        length: ReadLength,
     },
     DeviceData(Client, Vec<u8>),
     ReadFailed(Client, String),
@@ -76,11 +88,11 @@ impl std::fmt::Display for Message {
                 client,
                 hw_info,
                 address,
-                words,
+                length,
             } => {
                 write!(
                     f,
-                    "ReadDevice(client={client}, hw_info={hw_info}, address={address}, words={words})",
+                    "ReadDevice(client={client}, hw_info={hw_info}, address={address}, length={length})",
                 )
             }
             Message::DetectUsbDevices => write!(f, "DetectUsbDevices"),
@@ -103,10 +115,26 @@ impl std::fmt::Display for Message {
                     data.len()
                 )
             }
+            Message::FlashProgress(client, fraction) => {
+                write!(
+                    f,
+                    "FlashProgress(client={client}, {:.0}%)",
+                    fraction * 100.0
+                )
+            }
             Message::FlashFirmwareResult(client, result) => match result {
                 Ok(()) => write!(f, "FlashFirmwareResult(client={client}, Ok)"),
                 Err(e) => write!(f, "FlashFirmwareResult(client={client}, Err: {})", e),
             },
+            Message::Cancel(client) => write!(f, "Cancel(client={client})"),
+            Message::VerifyFlashToggled(verify) => write!(f, "VerifyFlashToggled({verify})"),
+            Message::TargetOverrideToggled(enabled) => {
+                write!(f, "TargetOverrideToggled({enabled})")
+            }
+            Message::TargetChipIdChanged(chip_id) => write!(f, "TargetChipIdChanged({chip_id})"),
+            Message::TargetLoadAddressChanged(address) => {
+                write!(f, "TargetLoadAddressChanged({address})")
+            }
             Message::DeviceData(client, data) => {
                 write!(f, "DeviceData(client={client}, {} bytes)", data.len())
             }
@@ -189,8 +217,26 @@ pub fn handle_message(
             data,
         } => {
             debug!("{client} Flashing firmware");
-            device.operating = Some(client.clone());
-            device.selected.flash(client, hw_info, data)
+            let cancel = device.start_operation(client.clone());
+            let target = match device.target_override() {
+                Ok(target) => target,
+                Err(e) => {
+                    let log = format!("Invalid probe target override: {e}");
+                    warn!("{client} {log}");
+                    return Task::done(Message::FlashFirmwareResult(client, Err(log)).into());
+                }
+            };
+            device
+                .selected
+                .flash(client, hw_info, data, device.verify_flash, target, cancel)
+        }
+        Message::FlashProgress(client, fraction) => {
+            trace!("{client} Flash progress: {:.0}%", fraction * 100.0);
+            let msg = match client {
+                Client::Analyse => AnalyseMessage::FlashProgress(fraction).into(),
+                Client::Create => CreateMessage::FlashProgress(fraction).into(),
+            };
+            Task::done(msg)
         }
         Message::FlashFirmwareResult(client, result) => {
             debug!(
@@ -210,20 +256,66 @@ pub fn handle_message(
             )))
         }
 
+        // The operation stops at its next safe boundary, and its result
+        // (an error) completes it as normal
+        Message::Cancel(client) => {
+            if device.operating.as_ref() == Some(&client) {
+                info!("{client} Cancelling device operation");
+                device.cancel.cancel();
+            } else {
+                debug!("{client} No device operation to cancel");
+            }
+            Task::none()
+        }
+
+        Message::VerifyFlashToggled(verify) => {
+            debug!("Verify after flash: {verify}");
+            device.verify_flash = verify;
+            Task::none()
+        }
+
+        // Probe target override changes are persisted as they are made
+        Message::TargetOverrideToggled(enabled) => {
+            debug!("Probe target override: {enabled}");
+            device.target_override.enabled = enabled;
+            device.target_override.save();
+            Task::none()
+        }
+        Message::TargetChipIdChanged(chip_id) => {
+            device.target_override.chip_id = chip_id;
+            device.target_override.save();
+            Task::none()
+        }
+        Message::TargetLoadAddressChanged(address) => {
+            device.target_override.load_address = address;
+            device.target_override.save();
+            Task::none()
+        }
+
         // Read device request and results
         Message::ReadDevice {
             client,
             hw_info,
             address,
-            words,
+            length,
         } => {
-            debug!("{client} Reading device memory at {address}, {words} words",);
+            debug!("{client} Reading device memory at {address}, {length}",);
             if client != Client::Analyse {
                 internal_error!("Device read requested by unsupported client: {}", client);
                 return Task::none();
             }
-            device.operating = Some(client.clone());
-            device.selected.read(client, hw_info, address, words)
+            let cancel = device.start_operation(client.clone());
+            let target = match device.target_override() {
+                Ok(target) => target,
+                Err(e) => {
+                    let log = format!("Invalid probe target override: {e}");
+                    warn!("{client} {log}");
+                    return Task::done(Message::ReadFailed(client, log).into());
+                }
+            };
+            device
+                .selected
+                .read(client, hw_info, address, length, target, cancel)
         }
         Message::DeviceData(client, data) => {
             debug!("{client} Received device data: {} bytes", data.len());
@@ -8,7 +8,8 @@
 
 #[allow(unused_imports)]
 use log::{debug, error, info, trace, warn};
-use probe_rs::flashing::FlashError;
+use onerom_config::mcu::Family;
+use probe_rs::flashing::{DownloadOptions, FlashError, ProgressEvent};
 use probe_rs::probe::list::Lister;
 use probe_rs::probe::{DebugProbeInfo, WireProtocol};
 use probe_rs::{Core, Error as ProbeError, MemoryInterface, Permissions};
@@ -16,12 +17,23 @@ use std::time::Duration;
 use tokio::task::spawn_blocking;
 
 use crate::app::AppMessage;
-use crate::device::{Address, Client, Message};
+use crate::device::target::ProbeTarget;
+use crate::device::{
+    Address, CancelToken, Client, FLASH_CANCELLED, FlashProgress, Message, READ_CANCELLED,
+    ReadLength,
+};
 use crate::hw::HardwareInfo;
 
 // Time to wait for core halt operations
 const PROBE_CORE_HALT_TIMEOUT: Duration = Duration::from_millis(100);
 
+// Number of 32-bit words read from the device at a time.  Cancellation is
+// checked between each read.
+const PROBE_READ_CHUNK_WORDS: usize = 1024;
+
+// STM32F4 flash size register - 16-bit value containing the flash size in KB
+const STM32F4_FLASH_SIZE_REG: u64 = 0x1FFF_7A22;
+
 /// Retrieve the list of connected debug probes.  Sends
 /// Message::ProbesDetected when done.
 pub async fn get_probe_list_async() -> AppMessage {
@@ -41,14 +53,18 @@ pub struct ProbeType(DebugProbeInfo);
 
 impl std::fmt::Display for ProbeType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // Your custom display logic here
+        // Include the serial number, so identical probes can be told apart
         write!(
             f,
-            "{} ({:04X}:{:04X})",
+            "{} ({:04X}:{:04X}",
             self.0.probe_type(),
             self.0.vendor_id,
             self.0.product_id
-        )
+        )?;
+        if let Some(serial) = self.serial_number() {
+            write!(f, ", {serial}")?;
+        }
+        write!(f, ")")
     }
 }
 
@@ -70,6 +86,21 @@ impl ProbeType {
     pub fn serial_number(&self) -> Option<&str> {
         self.0.serial_number.as_deref()
     }
+
+    /// Whether this is the same physical probe as `other`.  Probes are
+    /// matched by serial number where both have one, as other details may
+    /// change when re-detected.
+    pub fn is_same_probe(&self, other: &ProbeType) -> bool {
+        same_serial(self.serial_number(), other.serial_number()).unwrap_or(self == other)
+    }
+}
+
+// Compare serial numbers, if both are known
+fn same_serial(a: Option<&str>, b: Option<&str>) -> Option<bool> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a == b),
+        _ => None,
+    }
 }
 
 /// Read memory from a device using a probe
@@ -78,44 +109,65 @@ pub async fn read_async(
     client: Client,
     hw_info: HardwareInfo,
     address: Address,
-    words: usize,
+    length: ReadLength,
+    target: Option<ProbeTarget>,
+    cancel: CancelToken,
 ) -> AppMessage {
-    // Get the chip ID
-    let chip_id = match hw_info.mcu_variant {
-        None => "STM32F411RETx".to_string(),
-        Some(mcu) => mcu.chip_id().to_string(),
+    // Get the chip ID, preferring the user's override
+    let chip_id = match (target, hw_info.mcu_variant) {
+        (Some(target), _) => target.chip_id,
+        (None, None) => "STM32F411RETx".to_string(),
+        (None, Some(mcu)) => mcu.chip_id().to_string(),
     };
 
     // Get the absolute address
     let address = if let Some(address) = address.abs_from_hw_info(&hw_info) {
         address
     } else {
-        let log =
-            format!("Failed to resolve address for reading {words} words of memory at {address}");
+        let log = format!("Failed to resolve address for reading {length} of memory at {address}");
         warn!("{log}");
         return Message::ReadFailed(client, log).into();
     };
 
+    let family = hw_info.mcu_variant.map(|mcu| mcu.family());
+    let read_length = length.clone();
     let result = spawn_blocking(move || {
         probe_init_and_operate_on_core(probe.inner().clone(), chip_id, true, |core| {
+            let queried_bytes = if read_length.is_full_flash() {
+                query_flash_bytes(core, family)
+            } else {
+                None
+            };
+            let words = read_length.words(queried_bytes);
             let mut buf = vec![0u32; words];
-            core.read_32(address as u64, &mut buf)?;
+            let mut chunk_address = address as u64;
+            for chunk in buf.chunks_mut(PROBE_READ_CHUNK_WORDS) {
+                if cancel.is_cancelled() {
+                    return Ok(None);
+                }
+                core.read_32(chunk_address, chunk)?;
+                chunk_address += (chunk.len() * 4) as u64;
+            }
             let bytes: Vec<u8> = buf.iter().flat_map(|w| w.to_le_bytes()).collect();
-            Ok(bytes)
+            Ok(Some(bytes))
         })
     })
     .await;
 
     match result {
-        Ok(Ok(bytes)) => Message::DeviceData(client, bytes).into(),
+        Ok(Ok(Some(bytes))) => Message::DeviceData(client, bytes).into(),
+        Ok(Ok(None)) => {
+            warn!("{client} {READ_CANCELLED}");
+            Message::ReadFailed(client, READ_CANCELLED.to_string()).into()
+        }
         Ok(Err(e)) => {
-            let log = format!("Failed to read {words} words of memory at {address:#010X}: {e}");
+            let log = format!("Failed to read {length} of memory at {address:#010X}: {e}");
             warn!("{log}");
             debug!("Precise error: {e:?}");
             Message::ReadFailed(client, log).into()
         }
         Err(e) => {
-            let log = format!("Failed to read {words} words of memory at {address:#010X}: {e}");
+            let log = format!("Failed to read {length} of memory at {address:#010X}: {e}");
             warn!("{log}");
             debug!("Precise error: {e:?}");
             Message::ReadFailed(client, log).into()
@@ -129,17 +181,28 @@ pub async fn flash_async(
     hw_info: HardwareInfo,
     client: Client,
     data: Vec<u8>,
+    progress: FlashProgress,
+    target: Option<ProbeTarget>,
+    cancel: CancelToken,
 ) -> AppMessage {
-    let chip_id = match hw_info.mcu_variant {
-        None => "STM32F411RETx".to_string(),
-        Some(mcu) => mcu.chip_id().to_string(),
-    };
-    let address = match hw_info.mcu_variant {
-        None => 0x08000000,
-        Some(mcu) => mcu.family().get_flash_base(),
+    let (chip_id, address) = match flash_target(&hw_info, target) {
+        Ok(target) => target,
+        Err(log) => {
+            warn!("{log}");
+            return Message::FlashFirmwareResult(client, Err(log)).into();
+        }
     };
-    let result =
-        spawn_blocking(move || probe_flash(probe.inner().clone(), chip_id, address, &data)).await;
+    let result = spawn_blocking(move || {
+        probe_flash(
+            probe.inner().clone(),
+            chip_id,
+            address,
+            &data,
+            progress,
+            cancel,
+        )
+    })
+    .await;
 
     match result {
         Ok(Ok(())) => Message::FlashFirmwareResult(client, Ok(())).into(),
@@ -188,12 +251,56 @@ where
     f(&mut core)
 }
 
-// Helper to open a probe and session, and run a closure
+// Query the flash size from the device, if possible.  Only STM32F4 devices
+// report this, via a register - RP2350 flash is external and its size isn't
+// readable from the core.
+fn query_flash_bytes(core: &mut Core, family: Option<Family>) -> Option<usize> {
+    match family {
+        Some(Family::Stm32f4) => match core.read_word_16(STM32F4_FLASH_SIZE_REG) {
+            Ok(kb) => {
+                debug!("Device reports {kb}KB of flash");
+                Some(kb as usize * 1024)
+            }
+            Err(e) => {
+                debug!("Failed to read flash size register: {e}");
+                None
+            }
+        },
+        _ => None,
+    }
+}
+
+// Select the probe-rs chip ID and flash load address for the firmware's MCU,
+// unless the user has overridden them.  Guessing isn't safe, as flashing to
+// the wrong target either fails cryptically or writes to the wrong address.
+fn flash_target(
+    hw_info: &HardwareInfo,
+    target: Option<ProbeTarget>,
+) -> Result<(String, u32), String> {
+    if let Some(target) = target {
+        debug!(
+            "Using probe target override {} at {:#010X}",
+            target.chip_id, target.load_address
+        );
+        return Ok((target.chip_id, target.load_address));
+    }
+
+    match hw_info.mcu_variant {
+        Some(mcu) => Ok((mcu.chip_id().to_string(), mcu.family().get_flash_base())),
+        None => Err("Firmware MCU variant is unknown - cannot select probe target".to_string()),
+    }
+}
+
+// Helper to open a probe and session, and flash the data.  probe-rs can't
+// interrupt a flash once committed, so cancellation is only possible up to
+// that point.
 fn probe_flash(
     probe: DebugProbeInfo,
     chip_id: String,
     load_address: u32,
     data: &[u8],
+    mut progress: FlashProgress,
+    cancel: CancelToken,
 ) -> Result<(), String> {
     let mut probe = probe.open().map_err(|e| e.to_string())?;
     let probe_name = probe.get_name();
@@ -217,8 +324,30 @@ fn probe_flash(
         .add_data(load_address as u64, &data)
         .map_err(|e| e.to_string())?;
 
+    // Report progress across all of probe-rs' operations (erase, program,
+    // etc), as the total of each is only known once it is added
+    let mut total = 0u64;
+    let mut done = 0u64;
+    let mut options = DownloadOptions::default();
+    options.progress = probe_rs::flashing::FlashProgress::new(move |event| match event {
+        ProgressEvent::AddProgressBar {
+            total: Some(size), ..
+        } => total += size,
+        ProgressEvent::Progress { size, .. } => {
+            done += size;
+            if total > 0 {
+                progress.report(done as f32 / total as f32);
+            }
+        }
+        _ => (),
+    });
+
+    if cancel.is_cancelled() {
+        return Err(FLASH_CANCELLED.to_string());
+    }
+
     trace!("Commit flash loader");
-    match loader.commit(&mut session, probe_rs::flashing::DownloadOptions::default()) {
+    match loader.commit(&mut session, options) {
         Ok(()) => Ok(()),
         Err(e) => {
             match &e {
@@ -229,3 +358,62 @@ fn probe_flash(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use onerom_config::mcu::Variant as McuVariant;
+
+    fn hw_info(mcu_variant: Option<McuVariant>) -> HardwareInfo {
+        HardwareInfo {
+            mcu_variant,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_same_serial() {
+        assert_eq!(same_serial(Some("ABC123"), Some("ABC123")), Some(true));
+        assert_eq!(same_serial(Some("ABC123"), Some("DEF456")), Some(false));
+
+        // Can't be compared without both serial numbers
+        assert_eq!(same_serial(Some("ABC123"), None), None);
+        assert_eq!(same_serial(None, Some("ABC123")), None);
+        assert_eq!(same_serial(None, None), None);
+    }
+
+    #[test]
+    fn test_flash_target_stm32f4() {
+        let (chip_id, address) = flash_target(&hw_info(Some(McuVariant::F446RE)), None).unwrap();
+        assert_eq!(chip_id, "STM32F446RETx");
+        assert_eq!(address, 0x0800_0000);
+    }
+
+    #[test]
+    fn test_flash_target_rp2350() {
+        let (chip_id, address) = flash_target(&hw_info(Some(McuVariant::RP2350)), None).unwrap();
+        assert_eq!(chip_id, "RP235X");
+        assert_eq!(address, 0x1000_0000);
+    }
+
+    #[test]
+    fn test_flash_target_unknown() {
+        assert!(flash_target(&hw_info(None), None).is_err());
+    }
+
+    #[test]
+    fn test_flash_target_override() {
+        let target = ProbeTarget {
+            chip_id: "STM32F405RGTx".to_string(),
+            load_address: 0x0800_4000,
+        };
+
+        // Override takes precedence over the firmware's MCU, and is used
+        // when the MCU is unknown
+        for mcu in [Some(McuVariant::F446RE), Some(McuVariant::RP2350), None] {
+            let (chip_id, address) = flash_target(&hw_info(mcu), Some(target.clone())).unwrap();
+            assert_eq!(chip_id, "STM32F405RGTx");
+            assert_eq!(address, 0x0800_4000);
+        }
+    }
+}
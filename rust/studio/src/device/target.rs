@@ -0,0 +1,163 @@
+// Copyright (C) 2025 Piers Finlayson <piers@piers.rocks>
+//
+// MIT License
+
+//! Probe target override
+//!
+//! The probe-rs chip ID and flash load address are normally derived from the
+//! MCU variant in the firmware.  Users with unusual board variants can
+//! override both explicitly.  The override is persisted in the config
+//! directory.
+
+#[allow(unused_imports)]
+use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const TARGET_OVERRIDE_FILE: &str = "probe-target.json";
+
+/// A probe-rs chip ID and the address to load firmware to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeTarget {
+    pub chip_id: String,
+    pub load_address: u32,
+}
+
+/// User's probe target override, as entered.  Held as strings so partially
+/// entered values can be edited and persisted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetOverride {
+    pub enabled: bool,
+    pub chip_id: String,
+    pub load_address: String,
+}
+
+impl TargetOverride {
+    /// Load the persisted override, or a disabled one if there isn't one
+    pub fn load() -> Self {
+        let Some(file_path) = file_path() else {
+            return Self::default();
+        };
+        let Ok(contents) = fs::read_to_string(&file_path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&contents)
+            .inspect_err(|e| warn!("Failed to parse probe target file {file_path:?}: {e}"))
+            .unwrap_or_default()
+    }
+
+    /// Persist the override
+    pub fn save(&self) {
+        let Some(file_path) = file_path() else {
+            return;
+        };
+
+        let json = match serde_json::to_string_pretty(self) {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Failed to serialize probe target: {e}");
+                return;
+            }
+        };
+
+        // Create parent directory if it doesn't exist
+        if let Some(parent) = file_path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                warn!("Failed to create config directory {parent:?}: {e}");
+                return;
+            }
+        }
+
+        let _ = fs::write(&file_path, json)
+            .inspect_err(|e| warn!("Failed to write probe target to file {file_path:?}: {e}"));
+    }
+
+    /// Returns the overriding probe target, None if the override is disabled,
+    /// or an error describing why the entered values are invalid.
+    pub fn target(&self) -> Result<Option<ProbeTarget>, String> {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        let chip_id = self.chip_id.trim();
+        if chip_id.is_empty() {
+            return Err("Chip ID must not be empty".to_string());
+        }
+        if chip_id.chars().any(char::is_whitespace) {
+            return Err("Chip ID must not contain spaces".to_string());
+        }
+        let load_address = parse_address(&self.load_address)?;
+
+        Ok(Some(ProbeTarget {
+            chip_id: chip_id.to_string(),
+            load_address,
+        }))
+    }
+}
+
+// Parse a load address as hex, with or without a 0x prefix
+fn parse_address(address: &str) -> Result<u32, String> {
+    let address = address.trim();
+    let hex = address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .unwrap_or(address);
+    if hex.is_empty() {
+        return Err("Load address must not be empty".to_string());
+    }
+    u32::from_str_radix(hex, 16).map_err(|_| format!("Invalid load address: {address}"))
+}
+
+fn file_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("org", "onerom", "studio")
+        .map(|dirs| dirs.config_dir().join(TARGET_OVERRIDE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target_override(chip_id: &str, load_address: &str) -> TargetOverride {
+        TargetOverride {
+            enabled: true,
+            chip_id: chip_id.to_string(),
+            load_address: load_address.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_target_override_disabled() {
+        let mut over = target_override("", "");
+        over.enabled = false;
+        assert_eq!(over.target(), Ok(None));
+    }
+
+    #[test]
+    fn test_target_override_valid() {
+        let expected = ProbeTarget {
+            chip_id: "STM32F405RGTx".to_string(),
+            load_address: 0x0800_0000,
+        };
+        for address in ["0x08000000", "0X08000000", "08000000", " 0x8000000 "] {
+            let over = target_override(" STM32F405RGTx ", address);
+            assert_eq!(over.target(), Ok(Some(expected.clone())), "{address}");
+        }
+    }
+
+    #[test]
+    fn test_target_override_invalid() {
+        for (chip_id, address) in [
+            ("", "0x08000000"),
+            ("  ", "0x08000000"),
+            ("STM32 F405", "0x08000000"),
+            ("STM32F405RGTx", ""),
+            ("STM32F405RGTx", "0x"),
+            ("STM32F405RGTx", "0x1_0000"),
+            ("STM32F405RGTx", "0x100000000"),
+        ] {
+            let over = target_override(chip_id, address);
+            assert!(over.target().is_err(), "{chip_id:?}, {address:?}");
+        }
+    }
+}
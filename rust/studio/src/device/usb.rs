@@ -12,9 +12,24 @@ use picoboot::{Picoboot, Target};
 use std::time::Duration;
 
 use crate::app::AppMessage;
-use crate::device::{Address, Client, Message};
+use crate::device::{
+    Address, CancelToken, Client, FLASH_CANCELLED, FlashProgress, Message, READ_CANCELLED,
+    ReadLength, flash_cancelled_partial,
+};
 use crate::hw::HardwareInfo;
 
+// Size of each DFU download when flashing Ice, chosen to give reasonably
+// granular progress updates
+const ICE_DFU_CHUNK_SIZE: usize = 16 * 1024;
+
+// Size of each erase and write when flashing Fire - must be a multiple of
+// the RP2350's 4KB flash sector size
+const FIRE_PICOBOOT_CHUNK_SIZE: usize = 64 * 1024;
+
+// Size of each read from either device type.  Cancellation is checked
+// between each read.
+const USB_READ_CHUNK_SIZE: usize = 64 * 1024;
+
 /// Retrieve the list of connected USB devices.  Sends
 /// Message::UsbDevicesDetected when done.
 pub async fn get_usb_device_list_async() -> AppMessage {
@@ -137,38 +152,57 @@ impl UsbDeviceType {
 
 /// Read memory from a device using USB DFU
 pub async fn read_async(
-    usb_device: UsbDeviceType,
+    mut usb_device: UsbDeviceType,
     client: Client,
     _hw_info: HardwareInfo,
     address: Address,
-    words: usize,
+    length: ReadLength,
+    cancel: CancelToken,
 ) -> AppMessage {
     let address = address.abs_from_usb_device(&usb_device);
 
-    match usb_device {
-        UsbDeviceType::Ice(d) => match d.upload(address, words * 4).await {
-            Ok(data) => Message::DeviceData(client, data).into(),
-            Err(e) => {
-                let log = format!(
-                    "Failed to read {words} words of memory at {address:#010X} from Ice USB ({}): {e}",
-                    d.info(),
-                );
-                warn!("{log}");
-                return Message::ReadFailed(client, log).into();
+    // The flash size can't be queried over DFU/PICOBOOT, so full flash reads
+    // use the assumed size
+    let words = length.words(None);
+    let total = words * 4;
+
+    let mut data = Vec::with_capacity(total);
+    while data.len() < total {
+        if cancel.is_cancelled() {
+            warn!("{client} {READ_CANCELLED}");
+            return Message::ReadFailed(client, READ_CANCELLED.to_string()).into();
+        }
+
+        let chunk_address = address + data.len() as u32;
+        let chunk_len = USB_READ_CHUNK_SIZE.min(total - data.len());
+        let result = match &mut usb_device {
+            UsbDeviceType::Ice(d) => d
+                .upload(chunk_address, chunk_len)
+                .await
+                .map_err(|e| e.to_string()),
+            UsbDeviceType::Fire(p) => p
+                .flash_read(chunk_address, chunk_len as u32)
+                .await
+                .map_err(|e| e.to_string()),
+        };
+        match result {
+            // A short read means the device has no more to return
+            Ok(chunk) if chunk.len() < chunk_len => {
+                data.extend(chunk);
+                break;
             }
-        },
-        UsbDeviceType::Fire(mut p) => match p.flash_read(address, (words * 4) as u32).await {
-            Ok(data) => Message::DeviceData(client, data).into(),
+            Ok(chunk) => data.extend(chunk),
             Err(e) => {
                 let log = format!(
-                    "Failed to read {words} words of memory at {address:#010X} from Fire USB ({}): {e}",
-                    p.info(),
+                    "Failed to read {words} words of memory at {address:#010X} from {usb_device}: {e}",
                 );
                 warn!("{log}");
                 return Message::ReadFailed(client, log).into();
             }
-        },
+        }
     }
+
+    Message::DeviceData(client, data).into()
 }
 
 /// Flash firmware to a device using USB DFU
@@ -177,15 +211,29 @@ pub async fn flash_async(
     _hw_info: HardwareInfo,
     client: Client,
     data: Vec<u8>,
+    progress: FlashProgress,
+    cancel: CancelToken,
 ) -> AppMessage {
     match usb_device {
-        UsbDeviceType::Ice(d) => flash_ice_async(d, client, data).await,
-        UsbDeviceType::Fire(p) => flash_fire_async(p, client, data).await,
+        UsbDeviceType::Ice(d) => flash_ice_async(d, client, data, progress, cancel).await,
+        UsbDeviceType::Fire(p) => flash_fire_async(p, client, data, progress, cancel).await,
     }
 }
 
-async fn flash_ice_async(dfu_device: DfuDevice, client: Client, data: Vec<u8>) -> AppMessage {
+async fn flash_ice_async(
+    dfu_device: DfuDevice,
+    client: Client,
+    data: Vec<u8>,
+    mut progress: FlashProgress,
+    cancel: CancelToken,
+) -> AppMessage {
+    if cancel.is_cancelled() {
+        warn!("{client} {FLASH_CANCELLED}");
+        return Message::FlashFirmwareResult(client, Err(FLASH_CANCELLED.to_string())).into();
+    }
+
     debug!("Erase One ROM USB");
+    progress.report(0.0);
     match dfu_device.mass_erase().await {
         Ok(()) => (),
         Err(e) => {
@@ -194,46 +242,78 @@ async fn flash_ice_async(dfu_device: DfuDevice, client: Client, data: Vec<u8>) -
             return Message::FlashFirmwareResult(client, Err(log)).into();
         }
     }
+
+    // Download in chunks, so progress can be reported
     debug!("Flash firmware to One ROM USB");
-    match dfu_device.download(0x08000000, &data).await {
-        Ok(()) => {
-            debug!(
-                "Successfully flashed firmware onto Ice USB ({})",
-                dfu_device.info()
-            );
-            Message::FlashFirmwareResult(client, Ok(())).into()
+    let flash_base = Model::Ice.mcu_family().get_flash_base();
+    let mut written = 0;
+    for chunk in data.chunks(ICE_DFU_CHUNK_SIZE) {
+        // The device has been erased, so cancelling leaves it unbootable
+        if cancel.is_cancelled() {
+            let log = flash_cancelled_partial(written, data.len());
+            warn!("{client} {log}");
+            return Message::FlashFirmwareResult(client, Err(log)).into();
         }
-        Err(e) => {
+
+        let address = flash_base + written as u32;
+        if let Err(e) = dfu_device.download(address, chunk).await {
             let log = format!(
-                "Failed to flash firmware to Ice USB ({}): {e}",
+                "Failed to flash firmware to Ice USB ({}) at {address:#010X}: {e}",
                 dfu_device.info()
             );
             warn!("{log}");
             return Message::FlashFirmwareResult(client, Err(log)).into();
         }
+        written += chunk.len();
+        progress.report(written as f32 / data.len() as f32);
     }
+
+    debug!(
+        "Successfully flashed firmware onto Ice USB ({})",
+        dfu_device.info()
+    );
+    Message::FlashFirmwareResult(client, Ok(())).into()
 }
 
-async fn flash_fire_async(mut picoboot: Picoboot, client: Client, data: Vec<u8>) -> AppMessage {
+async fn flash_fire_async(
+    mut picoboot: Picoboot,
+    client: Client,
+    data: Vec<u8>,
+    mut progress: FlashProgress,
+    cancel: CancelToken,
+) -> AppMessage {
+    // Erase and write in chunks, so progress can be reported
     debug!("Flash firmware to Fire USB");
-    match picoboot
-        .flash_erase_and_write(picoboot.target().flash_start(), &data)
-        .await
-    {
-        Ok(()) => {
-            debug!(
-                "Successfully flashed firmware onto Fire USB ({})",
-                picoboot.info()
-            );
-            Message::FlashFirmwareResult(client, Ok(())).into()
+    progress.report(0.0);
+    let flash_start = picoboot.target().flash_start();
+    let mut written = 0;
+    for chunk in data.chunks(FIRE_PICOBOOT_CHUNK_SIZE) {
+        if cancel.is_cancelled() {
+            let log = if written == 0 {
+                FLASH_CANCELLED.to_string()
+            } else {
+                flash_cancelled_partial(written, data.len())
+            };
+            warn!("{client} {log}");
+            return Message::FlashFirmwareResult(client, Err(log)).into();
         }
-        Err(e) => {
+
+        let address = flash_start + written as u32;
+        if let Err(e) = picoboot.flash_erase_and_write(address, chunk).await {
             let log = format!(
-                "Failed to flash firmware to Fire USB ({}): {e}",
+                "Failed to flash firmware to Fire USB ({}) at {address:#010X}: {e}",
                 picoboot.info()
             );
             warn!("{log}");
             return Message::FlashFirmwareResult(client, Err(log)).into();
         }
+        written += chunk.len();
+        progress.report(written as f32 / data.len() as f32);
     }
+
+    debug!(
+        "Successfully flashed firmware onto Fire USB ({})",
+        picoboot.info()
+    );
+    Message::FlashFirmwareResult(client, Ok(())).into()
 }
@@ -5,11 +5,11 @@
 //! Device view methods
 //!
 //! Device gets the top right corner of the app, with probe and USB device
-//! pick lists and buttons - and a help icon.
+//! pick lists and buttons, the probe target override - and a help icon.
 
 use iced::alignment::Alignment::Center;
 use iced::alignment::Horizontal;
-use iced::widget::{Button, Column, Space, column, container, row};
+use iced::widget::{Button, Column, Space, checkbox, column, container, row, text_input};
 use iced::{Element, Length};
 #[allow(unused_imports)]
 use log::{debug, error, info, trace, warn};
@@ -43,8 +43,8 @@ pub fn view<'a>(device: &'a Device, style: &'a Style) -> Column<'a, AppMessage>
         .align_x(Horizontal::Center)
 }
 
-fn left_column<'a>(_device: &'a Device, _style: &'a Style) -> Column<'a, AppMessage> {
-    column![
+fn left_column<'a>(device: &'a Device, _style: &'a Style) -> Column<'a, AppMessage> {
+    let mut col = column![
         container(Style::text_small("Probe:"))
             .height(Length::Fixed(25.0))
             .align_y(Center),
@@ -54,9 +54,18 @@ fn left_column<'a>(_device: &'a Device, _style: &'a Style) -> Column<'a, AppMess
         container(Style::text_small("Use:"))
             .height(Length::Fixed(30.0))
             .align_y(Center),
-    ]
-    .spacing(10)
-    .align_x(Horizontal::Right)
+        container(Style::text_small("Target:"))
+            .height(Length::Fixed(25.0))
+            .align_y(Center),
+    ];
+
+    // Keep the labels aligned with the right hand column when the target
+    // override error is shown
+    if target_override_error(device).is_some() {
+        col = col.push(Space::with_height(Length::Fixed(20.0)));
+    }
+
+    col.spacing(10).align_x(Horizontal::Right)
 }
 
 fn right_column<'a>(device: &'a Device, style: &'a Style) -> Column<'a, AppMessage> {
@@ -67,17 +76,42 @@ fn right_column<'a>(device: &'a Device, style: &'a Style) -> Column<'a, AppMessa
     // Create the buttons
     let probe_button = probe_button(device);
     let usb_button = usb_button(device);
-    let rescan_button = rescan_button(device);
+    // Rescan is replaced by Cancel while an operation is underway
+    let rescan_button = if device.is_busy() {
+        cancel_button(device)
+    } else {
+        rescan_button(device)
+    };
+    let verify_checkbox = verify_checkbox(device);
     let help_icon = style.help_icon("Device Help");
 
     // Create the button row
-    let button_row = button_row(vec![probe_button, usb_button, rescan_button], help_icon);
+    let button_row = button_row(
+        vec![probe_button, usb_button, rescan_button],
+        verify_checkbox,
+        help_icon,
+    );
+
+    let mut col = column![
+        probe_list,
+        usb_device_list,
+        button_row,
+        target_override_row(device)
+    ];
+    if let Some(error) = target_override_error(device) {
+        col = col.push(
+            container(Style::text_small(error).color(Style::COLOUR_ERROR))
+                .height(Length::Fixed(20.0))
+                .align_y(Center),
+        );
+    }
 
-    column![probe_list, usb_device_list, button_row,].spacing(10)
+    col.spacing(10)
 }
 
 fn button_row<'a>(
     buttons: Vec<Button<'a, AppMessage>>,
+    verify_checkbox: Element<'a, AppMessage>,
     help_icon: Element<'a, AppMessage>,
 ) -> Element<'a, AppMessage> {
     let mut row = row![];
@@ -86,6 +120,7 @@ fn button_row<'a>(
     }
     row = row
         .push(Space::with_width(Length::Fill))
+        .push(verify_checkbox)
         .push(help_icon)
         .spacing(10)
         .align_y(Center);
@@ -188,6 +223,68 @@ fn rescan_button<'a>(device: &'a Device) -> Button<'a, AppMessage> {
     Style::text_button_small("Rescan", on_press_rescan, highlight_rescan_button)
 }
 
+fn cancel_button<'a>(device: &'a Device) -> Button<'a, AppMessage> {
+    let (content, on_press_cancel) = match &device.operating {
+        Some(client) if !device.is_cancelling() => {
+            ("Cancel", Some(Message::Cancel(client.clone()).into()))
+        }
+        _ => ("Cancelling...", None),
+    };
+
+    let highlight_cancel_button = on_press_cancel.is_some();
+    Style::text_button_small(content, on_press_cancel, highlight_cancel_button)
+}
+
+// Whether to read back and verify firmware after flashing
+fn verify_checkbox(device: &Device) -> Element<'_, AppMessage> {
+    let mut verify_checkbox =
+        checkbox("Verify", device.verify_flash).text_size(Style::FONT_SIZE_SMALL);
+    if !device.is_busy() {
+        verify_checkbox =
+            verify_checkbox.on_toggle(|verify| Message::VerifyFlashToggled(verify).into());
+    }
+    verify_checkbox.into()
+}
+
+// Probe target override - chip ID and load address, used in preference to
+// those derived from the firmware when flashing via a debug probe
+fn target_override_row(device: &Device) -> Element<'_, AppMessage> {
+    let over = &device.target_override;
+    let editable = over.enabled && !device.is_busy();
+
+    let mut override_checkbox =
+        checkbox("Override", over.enabled).text_size(Style::FONT_SIZE_SMALL);
+    let mut chip_id = text_input("Chip ID, e.g. STM32F411RETx", &over.chip_id)
+        .size(Style::FONT_SIZE_SMALL)
+        .width(Length::FillPortion(3));
+    let mut load_address = text_input("Load address, e.g. 0x08000000", &over.load_address)
+        .size(Style::FONT_SIZE_SMALL)
+        .width(Length::FillPortion(2));
+    if !device.is_busy() {
+        override_checkbox =
+            override_checkbox.on_toggle(|enabled| Message::TargetOverrideToggled(enabled).into());
+    }
+    if editable {
+        chip_id = chip_id.on_input(|chip_id| Message::TargetChipIdChanged(chip_id).into());
+        load_address =
+            load_address.on_input(|address| Message::TargetLoadAddressChanged(address).into());
+    }
+
+    container(
+        row![override_checkbox, chip_id, load_address]
+            .spacing(10)
+            .align_y(Center),
+    )
+    .height(Length::Fixed(25.0))
+    .align_y(Center)
+    .into()
+}
+
+// Returns why the enabled probe target override is invalid, if it is
+fn target_override_error(device: &Device) -> Option<String> {
+    device.target_override.target().err()
+}
+
 /// Create the device help overlay
 pub fn help_overlay() -> Element<'static, AppMessage> {
     let main_content = if cfg!(target_os = "windows") {
@@ -10,7 +10,7 @@ use log::{debug, error, info, trace, warn};
 
 use onerom_config::fw::{FirmwareProperties, ServeAlg};
 use onerom_config::hw::{Board, Model};
-use onerom_config::mcu::Variant as McuVariant;
+use onerom_config::mcu::{Family, Variant as McuVariant};
 use onerom_fw::net::Release;
 
 /// Information One ROM about hardware
@@ -32,6 +32,15 @@ impl HardwareInfo {
         self.board.is_some() && self.model.is_some() && self.mcu_variant.is_some()
     }
 
+    /// Returns the MCU family, from the MCU variant if known, otherwise from
+    /// the board or model
+    pub fn mcu_family(&self) -> Option<Family> {
+        self.mcu_variant
+            .map(|mcu| mcu.family())
+            .or_else(|| self.board.map(|board| board.mcu_family()))
+            .or_else(|| self.model.map(|model| model.mcu_family()))
+    }
+
     /// Constructs firmware properties from hardware info and a release.  This
     /// is used when building firmware usig `onerom_gen`.
     pub fn firmware_properties(&self, release: &Release) -> Option<FirmwareProperties> {
@@ -7,6 +7,7 @@
 mod analyse;
 pub mod app;
 mod built;
+mod cache;
 mod config;
 mod create;
 mod device;
@@ -8,6 +8,9 @@
 //!
 //! Sets up a global logger that captures log messages and sends them to
 //! the application's log view.
+//!
+//! The log level can be chosen at startup with `--log-level <level>`, and is
+//! otherwise the level last chosen in the log view, which is persisted.
 
 use chrono::{DateTime, Local};
 use iced::futures::channel::mpsc::{Sender, channel as mpsc_channel};
@@ -17,7 +20,10 @@ use iced::widget::{Column, Space, column, row};
 use iced::{Element, Subscription, Task, clipboard};
 #[allow(unused_imports)]
 use log::{debug, error, info, trace, warn};
+use std::collections::VecDeque;
 use std::env;
+use std::fs;
+use std::path::PathBuf;
 use std::sync::{Mutex, OnceLock};
 use std::time::SystemTime;
 
@@ -28,6 +34,15 @@ use crate::style::Style;
 // Global log sender for logging from anywhere
 static LOG_SENDER: OnceLock<Mutex<Sender<LogEntry>>> = OnceLock::new();
 
+// Log level chosen at startup, used when creating the Log instance
+static INITIAL_LEVEL: OnceLock<Level> = OnceLock::new();
+
+// File the chosen log level is persisted to, within the config directory
+const LOG_LEVEL_FILE: &str = "log-level";
+
+// Command line argument used to choose the log level
+const LOG_LEVEL_ARG: &str = "--log-level";
+
 // Default maximum number of log entries to keep
 const DEFAULT_LOG_ENTRIES: usize = 1024;
 
@@ -48,7 +63,7 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             max_entries: DEFAULT_LOG_ENTRIES,
-            max_level: Level::default(),
+            max_level: INITIAL_LEVEL.get().copied().unwrap_or_default(),
         }
     }
 }
@@ -106,6 +121,14 @@ impl Into<log::LevelFilter> for Level {
 }
 
 impl Level {
+    /// Parse a log level, case insensitively.  Accepts the names used by the
+    /// `log` crate, plus "warning".
+    pub fn try_from_str(s: &str) -> Option<Self> {
+        LOG_LEVELS.into_iter().find(|level| {
+            s.eq_ignore_ascii_case(level.as_str()) || s.eq_ignore_ascii_case(&level.to_string())
+        })
+    }
+
     // Get the string representation of the log level.  Used in the actual log
     const fn as_str(&self) -> &'static str {
         match self {
@@ -246,8 +269,8 @@ pub struct Log {
     // Log configuration
     config: Config,
 
-    // Stored log entries as a list, from oldest to newest
-    entries: Vec<LogEntry>,
+    // Stored log entries as a ring buffer, from oldest to newest
+    entries: VecDeque<LogEntry>,
 }
 
 impl Log {
@@ -260,7 +283,7 @@ impl Log {
     pub fn new() -> Self {
         Self {
             config: Config::default(),
-            entries: Vec::new(),
+            entries: VecDeque::new(),
         }
     }
 
@@ -275,6 +298,7 @@ impl Log {
                 debug!("Max log level changed: {}", level);
                 self.config.max_level = level;
                 log::set_max_level(level.into());
+                save_level(level);
                 Task::none()
             }
             Message::CopyToClipboard => {
@@ -307,7 +331,7 @@ impl Log {
         }
 
         // Add the log entry
-        self.entries.push(log_entry);
+        self.entries.push_back(log_entry);
 
         // Trim the log entries if necessary
         self.trim_entries();
@@ -315,9 +339,8 @@ impl Log {
 
     // Throw away oldest log entries if we have exceeded the max entries
     fn trim_entries(&mut self) {
-        if self.entries.len() > self.config.max_entries {
-            let to_drain = self.entries.len() - self.config.max_entries;
-            self.entries.drain(0..to_drain);
+        while self.entries.len() > self.config.max_entries {
+            self.entries.pop_front();
         }
     }
 
@@ -442,10 +465,71 @@ async fn log_channel(mut sender: Sender<AppMessage>) {
     }
 }
 
-/// Initialize the global logger
-pub fn init_logging() {
+/// Initialize the global logger.
+///
+/// `level` is the log level chosen on the command line, if any - see
+/// [`level_from_args()`].  If not specified, the level last chosen in the
+/// log view is used, otherwise the default.
+pub fn init_logging(level: Option<Level>) {
+    let level = level.or_else(load_level).unwrap_or_default();
+    let _ = INITIAL_LEVEL.set(level);
     log::set_logger(&Logger).unwrap();
-    log::set_max_level(Level::default().into());
+    log::set_max_level(level.into());
+}
+
+/// Get the log level from the command line arguments (excluding the program
+/// name), specified as `--log-level <level>` or `--log-level=<level>`.
+/// Returns `Ok(None)` if not specified, or an error if the level is missing
+/// or invalid.
+pub fn level_from_args<I>(args: I) -> Result<Option<Level>, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let value = if arg == LOG_LEVEL_ARG {
+            args.next()
+                .ok_or_else(|| format!("{LOG_LEVEL_ARG} requires a level"))?
+        } else if let Some(value) = arg.strip_prefix(&format!("{LOG_LEVEL_ARG}=")) {
+            value.to_string()
+        } else {
+            continue;
+        };
+
+        return Level::try_from_str(&value)
+            .map(Some)
+            .ok_or_else(|| format!("Invalid log level {value:?}"));
+    }
+    Ok(None)
+}
+
+// Load the persisted log level, if any
+fn load_level() -> Option<Level> {
+    let contents = fs::read_to_string(level_file_path()?).ok()?;
+    Level::try_from_str(contents.trim())
+}
+
+// Persist the chosen log level
+fn save_level(level: Level) {
+    let Some(file_path) = level_file_path() else {
+        return;
+    };
+
+    // Create parent directory if it doesn't exist
+    if let Some(parent) = file_path.parent() {
+        if let Err(e) = fs::create_dir_all(parent) {
+            warn!("Failed to create config directory {parent:?}: {e}");
+            return;
+        }
+    }
+
+    let _ = fs::write(&file_path, level.as_str())
+        .inspect_err(|e| warn!("Failed to write log level to file {file_path:?}: {e}"));
+}
+
+fn level_file_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("org", "onerom", "studio")
+        .map(|dirs| dirs.config_dir().join(LOG_LEVEL_FILE))
 }
 
 /// Helper macro for internal error
@@ -455,3 +539,65 @@ macro_rules! internal_error {
         log::error!(target: env!("CARGO_CRATE_NAME"), "Internal error: {}\nPlease raise an issue.", format!($($arg)*));
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_level_parsing() {
+        assert_eq!(Level::try_from_str("trace"), Some(Level::Trace));
+        assert_eq!(Level::try_from_str("DEBUG"), Some(Level::Debug));
+        assert_eq!(Level::try_from_str("Info"), Some(Level::Info));
+        assert_eq!(Level::try_from_str("warn"), Some(Level::Warning));
+        assert_eq!(Level::try_from_str("warning"), Some(Level::Warning));
+        assert_eq!(Level::try_from_str("error"), Some(Level::Error));
+        assert_eq!(Level::try_from_str("verbose"), None);
+
+        assert_eq!(level_from_args(args(&[])), Ok(None));
+        assert_eq!(level_from_args(args(&["--other"])), Ok(None));
+        assert_eq!(
+            level_from_args(args(&["--log-level", "debug"])),
+            Ok(Some(Level::Debug))
+        );
+        assert_eq!(
+            level_from_args(args(&["--log-level=trace"])),
+            Ok(Some(Level::Trace))
+        );
+        assert!(level_from_args(args(&["--log-level"])).is_err());
+        assert!(level_from_args(args(&["--log-level", "loud"])).is_err());
+    }
+
+    #[test]
+    fn test_log_ring_buffer() {
+        let mut log = Log {
+            config: Config {
+                max_entries: 3,
+                max_level: Level::Debug,
+            },
+            entries: VecDeque::new(),
+        };
+
+        // Entries below the level aren't recorded
+        log.add_entry(LogEntry::new(Level::Trace, "trace".to_string()));
+        assert!(log.entries.is_empty());
+
+        // Oldest entries are discarded once full
+        for ii in 0..5 {
+            log.add_entry(LogEntry::new(Level::Info, format!("entry {ii}")));
+        }
+        let texts: Vec<&str> = log.entries.iter().map(|e| e.text.as_str()).collect();
+        assert_eq!(texts, ["entry 2", "entry 3", "entry 4"]);
+
+        // Raising the level hides, but keeps, lower level entries
+        log.add_entry(LogEntry::new(Level::Error, "error".to_string()));
+        log.config.max_level = Level::Error;
+        assert_eq!(log.get_visible_logs().len(), 1);
+        assert_eq!(log.entries.len(), 3);
+        assert!(log.serious_errors_occurred());
+    }
+}
@@ -18,11 +18,12 @@ use onerom_gen::{Builder, FIRMWARE_SIZE, MAX_METADATA_LEN};
 use crate::ManifestType;
 use crate::analyse::Analyse;
 use crate::app::AppMessage;
+use crate::cache::{Cache, CacheKey, Served};
 use crate::config::{
     Config, ConfigManifest, SelectedConfig, download_config_async, generate_built_config,
     load_config_file,
 };
-use crate::create::{Create, Message as CreateMessage};
+use crate::create::{Create, Message as CreateMessage, Sidecar};
 use crate::hw::HardwareInfo;
 use crate::log::Log;
 use crate::style::Style;
@@ -37,15 +38,15 @@ pub enum Message {
     TabSelected(StudioTab),
     HardwareInfo(Option<HardwareInfo>),
     FetchReleases,
-    Releases(Releases),
+    Releases(Releases, Served),
     DownloadRelease(Release, Board, McuVariant),
-    ReleaseDownloaded(Result<Vec<u8>, String>),
+    ReleaseDownloaded(Result<(Vec<u8>, Served), String>),
     ReleaseDoesntExist,
     ClearDownloadedRelease,
     FetchConfigs,
-    ConfigManifest(ConfigManifest),
+    ConfigManifest(ConfigManifest, Served),
     LoadConfig(Config),
-    ConfigLoaded(Result<SelectedConfig, String>),
+    ConfigLoaded(Result<(SelectedConfig, Served), String>),
     ClearDownloadedConfig,
     BuildImage(HardwareInfo),
     BuildImageResult(Result<(Image, String), String>),
@@ -65,23 +66,29 @@ impl std::fmt::Display for Message {
             Message::TabSelected(tab) => write!(f, "TabSelected({tab})"),
             Message::HardwareInfo(info) => write!(f, "HardwareInfo({info:?})"),
             Message::FetchReleases => write!(f, "FetchReleases"),
-            Message::Releases(releases) => write!(f, "Releases({})  ", releases.releases_str()),
+            Message::Releases(releases, served) => {
+                write!(f, "Releases({}, {served})", releases.releases_str())
+            }
             Message::DownloadRelease(release, board, mcu) => {
                 write!(f, "DownloadRelease({}, {board}, {mcu})", release.version)
             }
             Message::ReleaseDownloaded(result) => match result {
-                Ok(data) => write!(f, "ReleaseDownloaded({} bytes)", data.len()),
+                Ok((data, served)) => {
+                    write!(f, "ReleaseDownloaded({} bytes, {served})", data.len())
+                }
                 Err(_) => write!(f, "ReleaseDownloaded(Err)"),
             },
             Message::ReleaseDoesntExist => write!(f, "ReleaseDoesntExist"),
             Message::ClearDownloadedRelease => write!(f, "ClearDownloadedRelease"),
             Message::FetchConfigs => write!(f, "FetchConfigs"),
-            Message::ConfigManifest(configs) => {
-                write!(f, "ConfigManifest({})", configs.names_str())
+            Message::ConfigManifest(configs, served) => {
+                write!(f, "ConfigManifest({}, {served})", configs.names_str())
             }
             Message::LoadConfig(config) => write!(f, "LoadConfig({config})"),
             Message::ConfigLoaded(result) => match result {
-                Ok(selected) => write!(f, "ConfigLoaded({} bytes)", selected.data.len()),
+                Ok((selected, served)) => {
+                    write!(f, "ConfigLoaded({} bytes, {served})", selected.data.len())
+                }
                 Err(_) => write!(f, "ConfigLoaded(Err)"),
             },
             Message::ClearDownloadedConfig => write!(f, "ClearDownloadedConfig"),
@@ -155,6 +162,8 @@ pub struct Image {
     metadata: Vec<u8>,
 
     roms: Vec<u8>,
+
+    sidecar: Option<Sidecar>,
 }
 
 impl std::fmt::Display for Image {
@@ -255,6 +264,11 @@ impl Image {
     pub fn full_image_len(&self) -> usize {
         Self::max_firmware_len() + Self::max_metadata_len() + self.roms_len()
     }
+
+    /// Returns the build's provenance, to save alongside the full image
+    pub fn sidecar(&self) -> Option<&Sidecar> {
+        self.sidecar.as_ref()
+    }
 }
 
 /// Network state
@@ -471,8 +485,8 @@ impl Studio {
                 task_from_msg!(CreateMessage::DetectedHardwareInfo)
             }
             Message::FetchReleases => Task::future(Self::fetch_releases_async()),
-            Message::Releases(releases) => {
-                self.download_succeeded();
+            Message::Releases(releases, served) => {
+                self.download_served(served);
                 self.runtime_info.set_releases(releases.clone());
                 Task::done(CreateMessage::ReleasesUpdated.into())
             }
@@ -481,8 +495,13 @@ impl Studio {
             }
             Message::ReleaseDownloaded(result) => {
                 match &result {
-                    Ok(data) => {
-                        self.download_succeeded();
+                    Ok((data, served)) => {
+                        // Firmware releases are served from the cache without
+                        // trying the network, so a cache hit says nothing about
+                        // the network
+                        if *served == Served::Network {
+                            self.download_succeeded();
+                        }
                         self.runtime_info.set_firmware(data.clone());
                     }
                     Err(_) => {
@@ -490,7 +509,7 @@ impl Studio {
                         self.runtime_info.clear_firmware();
                     }
                 };
-                let result = result.map(drop);
+                let result = result.map(|(_, served)| served);
                 task_from_msg!(CreateMessage::ReleaseDowloaded(result))
             }
             Message::ReleaseDoesntExist => {
@@ -507,18 +526,18 @@ impl Studio {
             Message::FetchConfigs => Task::future(Self::fetch_configs_async(
                 self.runtime_info.selected_config().cloned(),
             )),
-            Message::ConfigManifest(configs) => {
-                self.download_succeeded();
+            Message::ConfigManifest(configs, served) => {
+                self.download_served(served);
                 self.runtime_info.set_configs(configs.clone());
                 Task::done(CreateMessage::ConfigsUpdated.into())
             }
             Message::LoadConfig(config) => self.load_config(config),
             Message::ConfigLoaded(result) => {
                 let create_result = match result {
-                    Ok(selected) => {
-                        self.download_succeeded();
+                    Ok((selected, served)) => {
+                        self.download_served(served);
                         self.runtime_info.set_selected_config(selected);
-                        Ok(())
+                        Ok(served)
                     }
                     Err(e) => {
                         self.download_failed();
@@ -564,6 +583,16 @@ impl Studio {
         self.runtime_info.network_online()
     }
 
+    // Updates the network state from where a manifest or config was
+    // served.  These are only served from the cache when downloading fails.
+    fn download_served(&mut self, served: Served) {
+        match served {
+            Served::Network => self.download_succeeded(),
+            Served::Cache => self.download_failed(),
+            Served::Local => (),
+        }
+    }
+
     fn help_pressed(&self) -> Task<AppMessage> {
         Task::none()
     }
@@ -677,8 +706,11 @@ impl Studio {
         };
 
         // Build the image
-        let (metadata, roms) = match builder.build(props) {
-            Ok((md, roms)) => (md, roms),
+        if let Ok((md_len, roms_len)) = builder.output_sizes(&props) {
+            debug!("Building image: metadata={md_len} bytes, roms={roms_len} bytes");
+        }
+        let (metadata, roms, report) = match builder.build_with_report(props) {
+            Ok(built) => built,
             Err(e) => {
                 warn!("Failed to build image: {e:?}");
                 return CreateMessage::BuildImageResult(Err(format!(
@@ -688,12 +720,23 @@ impl Studio {
             }
         };
 
-        // Store image
-        let image = Image {
+        for (ii, set) in report.chip_sets.iter().enumerate() {
+            if !set.firmware_overrides.is_empty() {
+                debug!("Set {ii} overrides: {}", set.firmware_overrides.join(", "));
+            }
+        }
+
+        // Store image, along with its provenance
+        let mut image = Image {
             firmware,
             metadata,
             roms,
+            sidecar: None,
         };
+        match Sidecar::new(&config_str, &props, &builder, report, &image.full_image()) {
+            Ok(sidecar) => image.sidecar = Some(sidecar),
+            Err(e) => warn!("Failed to record build provenance: {e}"),
+        }
         let total_len = image.full_image_len();
         let fw_len = image.firmware_len();
         let md_len = image.metadata_len();
@@ -713,8 +756,22 @@ impl Studio {
         let url = app_manifest()
             .manifest_url(ManifestType::FirmwareRelease)
             .to_string();
-        match Releases::from_network_async_url(&url).await {
-            Ok(releases) => AppMessage::Studio(Message::Releases(releases)),
+
+        // Only cache the manifest if valid
+        let download = async {
+            let json = Releases::manifest_json_async_url(&url).await?;
+            Releases::from_json(&json)?;
+            Ok::<_, onerom_fw::Error>(json.into_bytes())
+        };
+        let cache = Cache::studio();
+        let result = Cache::network_first(cache.as_ref(), &CacheKey::ReleasesManifest, download)
+            .await
+            .and_then(|(json, served)| {
+                let json = String::from_utf8_lossy(&json);
+                Releases::from_json(&json).map(|releases| (releases, served))
+            });
+        match result {
+            Ok((releases, served)) => AppMessage::Studio(Message::Releases(releases, served)),
             Err(e) => {
                 warn!("Failed to fetch releases from network\n  - {e}");
                 Message::DownloadFailed.into()
@@ -724,7 +781,7 @@ impl Studio {
 
     async fn fetch_configs_async(selected: Option<SelectedConfig>) -> AppMessage {
         match ConfigManifest::from_network_async(selected).await {
-            Ok(configs) => AppMessage::Studio(Message::ConfigManifest(configs)),
+            Ok((configs, served)) => AppMessage::Studio(Message::ConfigManifest(configs, served)),
             Err(e) => {
                 warn!("Failed to fetch configs from network\n  - {e}");
                 Message::DownloadFailed.into()
@@ -791,12 +848,21 @@ impl Studio {
         board: Board,
         mcu: McuVariant,
     ) -> AppMessage {
-        // Download the firmware
-        let result = match releases
-            .download_firmware_async(&fw_ver, &board, &mcu)
-            .await
-        {
-            Ok(data) => Ok(data),
+        // Download the firmware, unless already cached
+        let cache = Cache::studio();
+        let key = CacheKey::Firmware {
+            board,
+            mcu,
+            version: fw_ver,
+        };
+        let download = releases.download_firmware_async(&fw_ver, &board, &mcu);
+        let result = match Cache::cached_first(cache.as_ref(), &key, download).await {
+            Ok((data, served)) => {
+                if served == Served::Cache {
+                    info!("Using cached release {fw_ver:?} for {board} {mcu}");
+                }
+                Ok((data, served))
+            }
             Err(onerom_fw::Error::ReleaseNotFound) => {
                 trace!("Release {fw_ver:?} does not exist for {board} {mcu}");
                 return Message::ReleaseDoesntExist.into();
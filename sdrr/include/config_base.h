@@ -46,9 +46,10 @@ typedef struct {
 
     // Chip select lines for supported variants
     //
-    // x_jumper_pull is the direction of pull from the X1/X2 jumper on the
-    // board.  It is 1 if that jumper closing pulls it high, and 0 if it pulls
-    // it low.
+    // x1_jumper_pull and x2_jumper_pull are the directions of pull from the
+    // X1 and X2 jumpers on the board, which may differ.  Each is 1 if that
+    // jumper closing pulls it high, and 0 if it pulls it low.  x_jumper_pull
+    // is 1 only if both are 1, and is retained for older tools.
     //
     // Offset: 36
     // 16 x 1 byte = 16 bytes
@@ -64,7 +65,9 @@ typedef struct {
     uint8_t ce;
     uint8_t oe;
     uint8_t x_jumper_pull;
-    uint8_t reserved3[3];
+    uint8_t x1_jumper_pull;
+    uint8_t x2_jumper_pull;
+    uint8_t reserved3[1];
 
     // As of 0.6.0
     uint8_t swclk_sel;  // SWCLK connected to which sel pin (255 = none)
@@ -428,9 +431,29 @@ typedef struct sdrr_rom_set_t {
     // Pointer to firmware configuration overrides when serving this ROM set.
     const onerom_firmware_overrides_t *firmware_overrides;
 
+    // Pointer to an array of provenance records, one per ROM in this set,
+    // each containing the source file's size and CRC-32 (two uint32_t).  0
+    // if not present.  Not used by the firmware - provided for analysis.
+    const uint32_t *provenance;
+
+    // Short human readable signature of this set's content - 4 ASCII hex
+    // characters, not NUL terminated.  All 0 if not present.  Logged at boot
+    // to help confirm the right ROMs are loaded.
+    const char signature[4];
+
+    // Whether this set's sdrr_rom_info_t structs include the filename field.
+    // Allows boot logging to be enabled for only some sets.  One of:
+    // - ROM_SET_FILENAMES_DEFAULT - included if boot logging is enabled
+    // - ROM_SET_FILENAMES_PRESENT - included
+    // - ROM_SET_FILENAMES_ABSENT - not included, so must not be read
+    const uint8_t filenames;
+
     // Padding to 64 bytes
-    const uint8_t pad2[40];
+    const uint8_t pad2[31];
 } sdrr_rom_set_t;
+#define ROM_SET_FILENAMES_DEFAULT  0
+#define ROM_SET_FILENAMES_PRESENT  1
+#define ROM_SET_FILENAMES_ABSENT   2
 
 // SDRR Runtime Information Structure
 //
@@ -550,7 +573,7 @@ typedef struct onerom_metadata_header_t {
     // Metadata version
     //
     // Offset: 16
-    const uint32_t version; // Metadata version - currently 1
+    const uint32_t version; // Metadata version - 1, or 2 with a CRC
 
     // Number of installed ROM sets
     // 
@@ -563,10 +586,45 @@ typedef struct onerom_metadata_header_t {
     // Offset: 24
     const sdrr_rom_set_t *rom_sets;
 
-    // Reserved for future expansion, preferably set to 0xff.
+    // Version of onerom-gen which generated this metadata.  0xffff if not
+    // present (generated before this was added).
     //
     // Offset: 28
-    const uint8_t reserved[228];
+    const uint16_t generator_major;
+    const uint16_t generator_minor;
+    const uint16_t generator_patch;
+    const uint8_t pad2[2];
+
+    // Length of the metadata, including this header, covered by crc32.
+    // 0xffffffff in version 1 metadata.
+    //
+    // Offset: 36
+    const uint32_t metadata_len;
+
+    // CRC32 (IEEE) of the metadata_len bytes of metadata, excluding this
+    // field.  0xffffffff in version 1 metadata.
+    //
+    // Offset: 40
+    const uint32_t crc32;
+
+    // Null terminated description of the config the metadata was built
+    // from.  0xff padded, and all 0xff if not present (including metadata
+    // generated before this was added).
+    //
+    // Offset: 44
+    const char description[128];
+
+    // Null terminated user note, such as a build date or asset tag, stamped
+    // when the metadata was built.  0xff padded, and all 0xff if not present
+    // (including metadata generated before this was added).
+    //
+    // Offset: 172
+    const char note[64];
+
+    // Reserved for future expansion, preferably set to 0xff.
+    //
+    // Offset: 236
+    const uint8_t reserved[20];
 
 } onerom_metadata_header_t;
 
@@ -64,10 +64,18 @@ _Static_assert(sizeof(chip_type_strings)/sizeof(chip_type_strings[0]) == NUM_CHI
 #endif
 
 // CS state enumeration
+//
+// CS_CE_OE is only used as cs3_state, to mark a chip selected by its CE/OE
+// lines rather than CS lines (27xx EPROMs).  cs1_state and cs2_state then
+// hold CE and OE's logic, which is always CS_ACTIVE_LOW.  Firmware should
+// serve such a chip using CE as CS1 and OE as CS2, and must not treat CS_CE_OE
+// as a CS line state.  CE/OE chip types never check cs3_state, so firmware
+// predating CS_CE_OE serves these chips correctly.
 typedef enum {
     CS_ACTIVE_LOW,
     CS_ACTIVE_HIGH,
     CS_NOT_USED,
+    CS_CE_OE,
 } sdrr_cs_state_t;
 _Static_assert(sizeof(sdrr_cs_state_t) == 1, "sdrr_cs_state_t must be 1 byte");
 
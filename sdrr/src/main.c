@@ -135,7 +135,7 @@ uint8_t metadata_present(const sdrr_info_t *info) {
     }
 
     if (present) {
-        if (metadata->version == 1) {
+        if ((metadata->version == 1) || (metadata->version == 2)) {
             LOG("Metadata v%d", metadata->version);
         } else {
             LOG("!!! Unsupported metadata version: %d", metadata->version);
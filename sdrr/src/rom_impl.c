@@ -258,14 +258,14 @@ static inline void __attribute__((always_inline)) configure_x_pulls(
     } else {
         if (serve_mode != SERVE_ADDR_ON_ANY_CS) {
             // We are serving from a dynamically selected bank of ROMs, using
-            // the X1/X2 pins.  We need to pull the X pins in the opposite
-            // direction to the jumper.
+            // the X1/X2 pins.  We need to pull each X pin in the opposite
+            // direction to its jumper, which may differ between X1 and X2.
             ROM_IMPL_LOG("Serving bank switched images");
-            if (pins->x_jumper_pull == 0) {
-                set_x_pulls(pins, 1, 1);
-            } else {
-                set_x_pulls(pins, 0, 0);
-            }
+            set_x_pulls(
+                pins,
+                pins->x1_jumper_pull == 0,
+                pins->x2_jumper_pull == 0
+            );
         } else {
             // Serving multiple ROM images simultaneously.  In this case we
             // don't care about the board jumpers - they aren't being used.
@@ -316,8 +316,11 @@ void __attribute__((section(".main_loop"), used)) main_loop(
 #ifndef EXECUTE_FROM_RAM
     // We don't copy filenames over in the RAM case, so this won't work - and
     // neither does MAIN_LOOP_LOGGING
-    for (int ii = 0; ii < set->rom_count; ii++) {
-        ROM_IMPL_DEBUG("Serve Chip #%d: %s via mode: %d", ii, set->roms[ii]->filename, serve_mode);
+    // Sets can also omit filenames, in which case the field isn't present
+    if ((set->extra_info != 1) || (set->filenames != ROM_SET_FILENAMES_ABSENT)) {
+        for (int ii = 0; ii < set->rom_count; ii++) {
+            ROM_IMPL_DEBUG("Serve Chip #%d: %s via mode: %d", ii, set->roms[ii]->filename, serve_mode);
+        }
     }
 #endif // EXECUTE_FROM_RAM
 
@@ -741,7 +744,10 @@ void* preload_rom_image(const sdrr_rom_set_t *set) {
 #endif // defined(CCM_RAM_BASE) && !defined(DISABLE_CCM)
 
 #if defined(BOOT_LOGGING)
-    if (set->roms[0]->filename != NULL) {
+    // Sets can omit filenames, in which case the field isn't present
+    uint8_t has_filename = ((set->extra_info != 1) || (set->filenames != ROM_SET_FILENAMES_ABSENT))
+        && (set->roms[0]->filename != NULL);
+    if (has_filename) {
         DEBUG("ROM filename: %s", set->roms[0]->filename);
     }
 #endif // BOOT_LOGGING
@@ -753,7 +759,7 @@ void* preload_rom_image(const sdrr_rom_set_t *set) {
 
 #if defined(BOOT_LOGGING)
     const char *filename = "";
-    if (set->roms[0]->filename != NULL) {
+    if (has_filename) {
         filename = set->roms[0]->filename;
     }
     LOG("ROM %s preloaded to RAM 0x%08X size %d bytes", filename, (uint32_t)img_dst, img_size);
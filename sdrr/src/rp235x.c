@@ -809,7 +809,8 @@ void check_config(
                     LOG("!!! Multi-ROM mode, but pin X1==X2");
                     failed = 1;
                 }
-                if (info->pins->x_jumper_pull > 1) {
+                if ((info->pins->x1_jumper_pull > 1) ||
+                    (info->pins->x2_jumper_pull > 1)) {
                     LOG("!!! X jumper pull value invalid");
                     failed = 1;
                 }
@@ -144,16 +144,29 @@ void log_roms(const onerom_metadata_header_t *metadata_header) {
     for (uint8_t ii = 0; ii < metadata_header->rom_set_count; ii++) {
         const sdrr_rom_set_t *set = (const sdrr_rom_set_t *)(base + (stride * ii));
 
-        LOG("Set #%d: %d ROM(s), size: %d bytes", ii, set->rom_count, set->size);
+        if ((extra_info == 1) && (set->signature[0] != 0)) {
+            LOG("Set #%d [%c%c%c%c]: %d ROM(s), size: %d bytes",
+                ii,
+                set->signature[0], set->signature[1],
+                set->signature[2], set->signature[3],
+                set->rom_count, set->size);
+        } else {
+            LOG("Set #%d: %d ROM(s), size: %d bytes", ii, set->rom_count, set->size);
+        }
         
 #if defined(DEBUG_LOGGING)
         for (uint8_t jj = 0; jj < set->rom_count; jj++) {
             const sdrr_rom_info_t *rom = set->roms[jj];
             const char *rom_type_str = chip_type_strings[rom->rom_type];
 
-            DEBUG("  Chip #%d: %s, %s",
-                jj, rom->filename,
-                rom_type_str);
+            // Sets can omit filenames, in which case the field isn't present
+            if ((extra_info == 1) && (set->filenames == ROM_SET_FILENAMES_ABSENT)) {
+                DEBUG("  Chip #%d: %s", jj, rom_type_str);
+            } else {
+                DEBUG("  Chip #%d: %s, %s",
+                    jj, rom->filename,
+                    rom_type_str);
+            }
         }
 #endif // DEBUG_LOGGING
     }
@@ -359,6 +359,7 @@ const char* cs_state_to_string(int cs_state) {
         case CS_ACTIVE_LOW: return "active_low";
         case CS_ACTIVE_HIGH: return "active_high";
         case CS_NOT_USED: return "not_used";
+        case CS_CE_OE: return "ce_oe";
         default: return "unknown";
     }
 }